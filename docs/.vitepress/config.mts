@@ -126,13 +126,21 @@ export default defineConfig({
           { text: "list", link: "/reference/commands/list" },
           { text: "open", link: "/reference/commands/open" },
           { text: "close", link: "/reference/commands/close" },
+          { text: "attach", link: "/reference/commands/attach" },
+          { text: "rename", link: "/reference/commands/rename" },
+          { text: "switch", link: "/reference/commands/switch" },
+          { text: "pick", link: "/reference/commands/pick" },
           { text: "path", link: "/reference/commands/path" },
+          { text: "send", link: "/reference/commands/send" },
+          { text: "exec", link: "/reference/commands/exec" },
           { text: "dashboard", link: "/reference/commands/dashboard" },
           { text: "init", link: "/reference/commands/init" },
           { text: "claude prune", link: "/reference/commands/claude" },
           { text: "sandbox", link: "/reference/commands/sandbox" },
           { text: "completions", link: "/reference/commands/completions" },
           { text: "docs", link: "/reference/commands/docs" },
+          { text: "doctor", link: "/reference/commands/doctor" },
+          { text: "reconcile", link: "/reference/commands/reconcile" },
         ],
       },
     ],
@@ -0,0 +1,69 @@
+//! Open URLs in the host's default browser.
+
+use anyhow::{Result, bail};
+
+/// Accept only http(s) URLs, so a guest cannot make the host open local
+/// files (`file://`) or hand arbitrary schemes to registered handlers.
+pub fn validate_url(url: &str) -> Result<()> {
+    let scheme_end = url.find("://").unwrap_or(0);
+    let scheme = url[..scheme_end].to_ascii_lowercase();
+    if scheme != "http" && scheme != "https" {
+        bail!("Only http and https URLs can be opened: {}", url);
+    }
+    if url.len() == scheme_end + 3 {
+        bail!("URL is missing a host: {}", url);
+    }
+    if url.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        bail!("URL contains whitespace or control characters: {:?}", url);
+    }
+    Ok(())
+}
+
+/// Open `url` with `open` (macOS) or `xdg-open` (Linux).
+pub fn open(url: &str) -> Result<()> {
+    validate_url(url)?;
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    crate::host_tool::spawn_first_available(
+        &[opener],
+        |_| vec![url.to_string()],
+        None,
+        &format!("No URL opener found. Install {}.", opener),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_http_and_https() {
+        for url in [
+            "https://github.com/login/device",
+            "http://localhost:3000/callback?code=abc",
+            "HTTPS://example.com",
+        ] {
+            assert!(validate_url(url).is_ok(), "{url}");
+        }
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_malformed_urls() {
+        for url in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "ssh://host",
+            "vscode://extension",
+            "github.com",
+            "https://",
+            "https://example.com/a b",
+            "https://example.com\n",
+            "",
+        ] {
+            assert!(validate_url(url).is_err(), "{url:?}");
+        }
+    }
+}
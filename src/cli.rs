@@ -1,8 +1,9 @@
-use crate::command::args::{MultiArgs, PromptArgs, RescueArgs, SetupFlags};
-use crate::{claude, command, config, git, nerdfont};
+use crate::command::args::{MarkdownArgs, MultiArgs, PromptArgs, RescueArgs, SetupFlags};
+use crate::{claude, command, config, git, logger, nerdfont};
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug)]
 struct WorktreeBranchParser;
@@ -171,6 +172,18 @@ impl clap::builder::TypedValueParser for GitBranchParser {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Log more detail and print the log to stderr (-v debug, -vv trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only log warnings and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Also write the log to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -179,11 +192,16 @@ enum Commands {
     Add {
         /// Name of the branch (creates if it doesn't exist) or remote ref (e.g., origin/feature).
         /// When used with --pr, this becomes the custom local branch name.
-        #[arg(required_unless_present_any = ["pr", "auto_name"], value_parser = GitBranchParser::new())]
+        /// When omitted at a terminal, prompts interactively (see --interactive).
+        #[arg(value_parser = GitBranchParser::new())]
         branch_name: Option<String>,
 
+        /// Prompt step-by-step for branch, base, agent, and sandbox
+        #[arg(short = 'i', long, conflicts_with_all = ["branch_name", "pr", "auto_name", "base", "base_pr"])]
+        interactive: bool,
+
         /// Pull request number to checkout
-        #[arg(long, conflicts_with_all = ["base", "auto_name"])]
+        #[arg(long, conflicts_with_all = ["base", "base_pr", "auto_name"])]
         pr: Option<u32>,
 
         /// Generate branch name from prompt using LLM
@@ -194,6 +212,10 @@ enum Commands {
         #[arg(long)]
         base: Option<String>,
 
+        /// Use the head branch of this pull request as the base (for stacked PRs)
+        #[arg(long, value_name = "NUMBER", conflicts_with = "base")]
+        base_pr: Option<u32>,
+
         /// Explicit name for the worktree directory and tmux window (overrides worktree_naming strategy and worktree_prefix)
         #[arg(long)]
         name: Option<String>,
@@ -201,8 +223,9 @@ enum Commands {
         #[command(flatten)]
         prompt: PromptArgs,
 
+        // Boxed to keep `Commands` small
         #[command(flatten)]
-        setup: SetupFlags,
+        setup: Box<SetupFlags>,
 
         #[command(flatten)]
         rescue: RescueArgs,
@@ -237,15 +260,40 @@ enum Commands {
         #[arg(long, short = 'n')]
         new: bool,
 
+        /// Run the setup script again, even if it already ran for this worktree
+        #[arg(long)]
+        rerun_setup: bool,
+
         #[command(flatten)]
         prompt: PromptArgs,
     },
 
+    /// Recreate the tmux window of a worktree whose window was closed
+    Attach {
+        /// Worktree name or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+    },
+
     /// Close a worktree's tmux window (keeps the worktree and branch)
     Close {
-        /// Worktree name (defaults to current directory if omitted)
+        /// Worktree names (defaults to current directory if omitted)
+        #[arg(value_parser = WorktreeHandleParser::new(), num_args = 0..)]
+        names: Vec<String>,
+
+        /// Show what would be closed and stopped without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rename a worktree's branch and its tmux window (keeps the worktree directory)
+    Rename {
+        /// Worktree name or branch
         #[arg(value_parser = WorktreeHandleParser::new())]
-        name: Option<String>,
+        name: String,
+
+        /// New branch name
+        new_branch: String,
     },
 
     /// Merge a branch, then clean up the worktree and tmux window
@@ -318,11 +366,30 @@ enum Commands {
         #[arg(long)]
         pr: bool,
 
+        /// Output as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+
+        /// Only show worktrees without an open window and no commits within
+        /// this duration (e.g. 2w, 30d)
+        #[arg(long, value_name = "DURATION", value_parser = crate::util::parse_age)]
+        stale: Option<std::time::Duration>,
+
         /// Filter by worktree name or branch (supports multiple)
         #[arg(value_parser = WorktreeBranchParser::new())]
         filter: Vec<String>,
     },
 
+    /// Switch to a worktree's open tmux window or session
+    Switch {
+        /// Worktree name or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+    },
+
+    /// Pick a running agent from a filterable list and switch to it
+    Pick,
+
     /// Get the filesystem path of a worktree
     Path {
         /// Worktree name (directory name)
@@ -333,8 +400,8 @@ enum Commands {
     /// Send a prompt or instruction to a running agent
     Send {
         /// Worktree name
-        #[arg(value_parser = WorktreeHandleParser::new())]
-        name: String,
+        #[arg(value_parser = WorktreeHandleParser::new(), required_unless_present = "all")]
+        name: Option<String>,
 
         /// Text to send (reads from --file or stdin if omitted)
         #[arg(conflicts_with = "file")]
@@ -343,6 +410,18 @@ enum Commands {
         /// Read prompt from file
         #[arg(short, long, conflicts_with = "text")]
         file: Option<String>,
+
+        /// Paste the content as one block (multi-line input, read from stdin if no text is given)
+        #[arg(long)]
+        paste: bool,
+
+        /// Send TEXT to every running agent (reads from --file or stdin if omitted)
+        #[arg(long, value_name = "TEXT", num_args = 0..=1, conflicts_with_all = ["name", "text"])]
+        all: Option<Option<String>>,
+
+        /// With --all, list the target agents without sending
+        #[arg(long, requires = "all")]
+        dry_run: bool,
     },
 
     /// Capture terminal output from a running agent
@@ -369,6 +448,10 @@ enum Commands {
         /// Include git info (staged/unstaged changes, unmerged commits)
         #[arg(long)]
         git: bool,
+
+        /// Describe only the pane this command runs in (exits 2 outside a workmux pane)
+        #[arg(long, conflicts_with_all = ["worktrees", "git"])]
+        current: bool,
     },
 
     /// Wait for agents to reach a target status
@@ -413,17 +496,35 @@ enum Commands {
         timeout: Option<u64>,
     },
 
+    /// Run a one-off command in a worktree's sandbox, in the foreground
+    #[command(name = "exec")]
+    SandboxExec {
+        /// Worktree name
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        /// Command to run (everything after --)
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
     /// Generate example .workmux.yaml configuration file
     Init,
 
     /// Set up agent status tracking hooks
     Setup,
 
+    /// Check the environment for common setup problems
+    Doctor,
+
+    /// Remove stored agents whose panes are gone or no longer run the agent
+    Reconcile,
+
     /// Show detailed documentation (renders README.md)
-    Docs,
+    Docs(MarkdownArgs),
 
     /// Show the changelog (what's new in each version)
-    Changelog,
+    Changelog(MarkdownArgs),
 
     /// Show a TUI dashboard of all active workmux agents across all sessions
     Dashboard {
@@ -482,11 +583,58 @@ enum Commands {
     /// Execute a command on the host (used by guest shims)
     #[command(hide = true, name = "host-exec")]
     HostExec {
+        /// Print the command that would be forwarded without running it
+        #[arg(long)]
+        dry_run: bool,
+
         /// Command name and arguments
         #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
         args: Vec<String>,
     },
 
+    /// Send periodic heartbeats to the host supervisor (used inside sandboxes)
+    #[command(hide = true, name = "_heartbeat")]
+    Heartbeat {
+        /// Seconds between heartbeats
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
+
+    /// Open an http(s) URL in the browser, via the host inside a sandbox
+    #[command(hide = true, name = "open-url")]
+    OpenUrl {
+        /// URL to open (http or https only)
+        url: String,
+    },
+
+    /// Copy text (or stdin) to the clipboard, via the host inside a sandbox
+    #[command(hide = true)]
+    Clipboard {
+        /// Text to copy. Reads stdin when omitted.
+        text: Option<String>,
+    },
+
+    /// Send notifications, via the host inside a sandbox
+    #[command(hide = true)]
+    Notify {
+        #[command(subcommand)]
+        command: NotifyCommands,
+    },
+
+    /// Git credential helper that asks the host via RPC (used inside sandboxes)
+    #[command(hide = true, name = "git-credential")]
+    GitCredential {
+        /// Credential helper operation: get, store or erase
+        operation: String,
+    },
+
+    /// Record that a worktree's setup script succeeded (appended to the script)
+    #[command(hide = true, name = "_setup-done")]
+    SetupDone {
+        /// Worktree handle
+        handle: String,
+    },
+
     /// Generate shell completions
     Completions {
         /// The shell to generate completions for
@@ -505,6 +653,10 @@ enum Commands {
     /// Output git branches for shell completion (internal use)
     #[command(hide = true, name = "_complete-git-branches")]
     CompleteGitBranches,
+
+    /// Output running workmux Lima VM names for shell completion (internal use)
+    #[command(hide = true, name = "_complete-vms")]
+    CompleteVms,
 }
 
 #[derive(Subcommand)]
@@ -513,12 +665,27 @@ enum ClaudeCommands {
     Prune,
 }
 
+#[derive(Subcommand)]
+enum NotifyCommands {
+    /// Show a desktop notification (terminal-notifier/osascript or notify-send)
+    Desktop {
+        /// Notification title
+        title: String,
+        /// Notification body
+        body: String,
+    },
+}
+
 /// Check if the command should show the nerdfont setup prompt.
 /// Only commands that display icons should trigger the prompt.
+/// `list --json` is excluded so prompts never mix into machine-readable output.
 fn should_prompt_nerdfont(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Add { .. } | Commands::Init | Commands::Dashboard { .. } | Commands::List { .. }
+        Commands::Add { .. }
+            | Commands::Init
+            | Commands::Dashboard { .. }
+            | Commands::List { json: false, .. }
     )
 }
 
@@ -528,7 +695,7 @@ fn should_prompt_nerdfont(cmd: &Commands) -> bool {
 fn should_prompt_status_setup(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Add { .. } | Commands::Init | Commands::List { .. }
+        Commands::Add { .. } | Commands::Init | Commands::List { json: false, .. }
     )
 }
 
@@ -536,6 +703,19 @@ fn should_prompt_status_setup(cmd: &Commands) -> bool {
 pub fn run() -> Result<()> {
     let cli = Cli::parse();
 
+    let mut log_files: Vec<PathBuf> = cli.log_file.iter().cloned().collect();
+    if let Commands::Sandbox(args) = &cli.command
+        && let Some(path) = command::sandbox::supervisor_log_file(args)
+    {
+        log_files.push(path);
+    }
+    logger::init(&logger::LogOptions {
+        verbose: cli.verbose,
+        quiet: cli.quiet,
+        log_files,
+    })?;
+    tracing::info!(args = ?std::env::args().collect::<Vec<_>>(), "workmux start");
+
     // Always initialize nerdfont setting for prefix consistency across commands.
     // Only prompt interactively for commands that display icons.
     // If config fails to load, skip the nerdfont wizard -- it will be shown on
@@ -569,9 +749,11 @@ pub fn run() -> Result<()> {
     match cli.command {
         Commands::Add {
             branch_name,
+            interactive,
             pr,
             auto_name,
             base,
+            base_pr,
             name,
             prompt,
             setup,
@@ -581,12 +763,14 @@ pub fn run() -> Result<()> {
             session,
         } => command::add::run(
             branch_name.as_deref(),
+            interactive,
             pr,
             auto_name,
             base.as_deref(),
+            base_pr,
             name,
             prompt,
-            setup,
+            *setup,
             rescue,
             multi,
             wait,
@@ -597,9 +781,19 @@ pub fn run() -> Result<()> {
             run_hooks,
             force_files,
             new,
+            rerun_setup,
+            prompt,
+        } => command::open::run(
+            name.as_deref(),
+            run_hooks,
+            force_files,
+            new,
+            rerun_setup,
             prompt,
-        } => command::open::run(name.as_deref(), run_hooks, force_files, new, prompt),
-        Commands::Close { name } => command::close::run(name.as_deref()),
+        ),
+        Commands::Attach { name } => command::attach::run(&name),
+        Commands::Close { names, dry_run } => command::close::run(&names, dry_run),
+        Commands::Rename { name, new_branch } => command::rename::run(&name, &new_branch),
         Commands::Merge {
             name,
             into,
@@ -628,17 +822,46 @@ pub fn run() -> Result<()> {
             force,
             keep_branch,
         } => command::remove::run(names, gone, all, force, keep_branch),
-        Commands::List { pr, filter } => command::list::run(pr, &filter),
+        Commands::List {
+            pr,
+            json,
+            stale,
+            filter,
+        } => command::list::run(pr, json, &filter, stale),
+        Commands::Switch { name } => command::switch::run(&name),
+        Commands::Pick => command::pick::run(),
         Commands::Path { name } => command::path::run(&name),
-        Commands::Send { name, text, file } => {
-            command::send::run(&name, text.as_deref(), file.as_deref())
+        Commands::Send {
+            name,
+            text,
+            file,
+            paste,
+            all,
+            dry_run,
+        } => {
+            if let Some(all_text) = all {
+                if all_text.is_some() && file.is_some() {
+                    anyhow::bail!("--all takes either TEXT or --file, not both");
+                }
+                command::send::run_all(all_text.as_deref(), file.as_deref(), paste, dry_run)
+            } else {
+                let name = name.expect("clap requires a name without --all");
+                command::send::run(&name, text.as_deref(), file.as_deref(), paste)
+            }
         }
         Commands::Capture { name, lines } => command::capture::run(&name, lines),
         Commands::Status {
             worktrees,
             json,
             git,
-        } => command::status::run(&worktrees, json, git),
+            current,
+        } => {
+            if current {
+                command::status::run_current(json)
+            } else {
+                command::status::run(&worktrees, json, git)
+            }
+        }
         Commands::Wait {
             worktrees,
             status,
@@ -652,11 +875,14 @@ pub fn run() -> Result<()> {
             keep,
             timeout,
         } => command::run::run(&name, command, background, keep, timeout),
+        Commands::SandboxExec { name, command } => command::sandbox_exec::run(&name, &command),
         Commands::Exec { run_dir } => command::exec::run(&run_dir),
         Commands::Init => crate::config::Config::init(),
         Commands::Setup => command::setup::run(),
-        Commands::Docs => command::docs::run(),
-        Commands::Changelog => command::changelog::run(),
+        Commands::Doctor => command::doctor::run(),
+        Commands::Reconcile => command::reconcile::run(),
+        Commands::Docs(args) => command::docs::run(&args.into()),
+        Commands::Changelog(args) => command::changelog::run(&args.into()),
         Commands::Dashboard { preview_size, diff } => command::dashboard::run(preview_size, diff),
         Commands::Config(args) => command::config::run(args),
         Commands::Claude { command } => match command {
@@ -667,13 +893,21 @@ pub fn run() -> Result<()> {
         Commands::SetBase { base } => command::set_base::run(&base),
         Commands::LastDone => command::last_done::run(),
         Commands::LastAgent => command::last_agent::run(),
-        Commands::HostExec { args } => {
+        Commands::HostExec { dry_run, args } => {
             let (command, cmd_args) = args
                 .split_first()
                 .ok_or_else(|| anyhow::anyhow!("host-exec requires a command name"))?;
-            let code = command::host_exec::run(command, cmd_args)?;
+            let code = command::host_exec::run(command, cmd_args, dry_run)?;
             std::process::exit(code);
         }
+        Commands::Heartbeat { interval } => command::heartbeat::run(interval),
+        Commands::OpenUrl { url } => command::open_url::run(&url),
+        Commands::Clipboard { text } => command::clipboard::run(text),
+        Commands::Notify { command } => match command {
+            NotifyCommands::Desktop { title, body } => command::notify::desktop(&title, &body),
+        },
+        Commands::GitCredential { operation } => command::git_credential::run(&operation),
+        Commands::SetupDone { handle } => command::setup_done::run(&handle),
         Commands::Completions { shell } => {
             generate_completions(shell);
             Ok(())
@@ -696,6 +930,16 @@ pub fn run() -> Result<()> {
             }
             Ok(())
         }
+        Commands::CompleteVms => {
+            // Fail silently on completion; don't disrupt the user's shell.
+            let instances = crate::sandbox::lima::LimaInstance::list().unwrap_or_default();
+            for vm in instances {
+                if vm.name.starts_with(crate::sandbox::lima::VM_PREFIX) && vm.is_running() {
+                    println!("{}", vm.name);
+                }
+            }
+            Ok(())
+        }
     }
 }
 
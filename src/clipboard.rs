@@ -0,0 +1,50 @@
+//! Copy text to the host's system clipboard.
+
+use anyhow::Result;
+
+/// Clipboard tools to try, in order of preference.
+fn candidates(macos: bool, wayland: bool) -> Vec<&'static str> {
+    if macos {
+        return vec!["pbcopy"];
+    }
+    let mut tools = Vec::new();
+    if wayland {
+        tools.push("wl-copy");
+    }
+    tools.push("xclip");
+    tools
+}
+
+/// Arguments that make `tool` copy its input.
+fn tool_args(tool: &str) -> Vec<String> {
+    match tool {
+        "xclip" => vec!["-selection".to_string(), "clipboard".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Pipe `text` into the first available clipboard tool
+/// (`pbcopy` on macOS, `wl-copy` or `xclip` on Linux).
+pub fn copy(text: &str) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    crate::host_tool::spawn_first_available(
+        &candidates(cfg!(target_os = "macos"), wayland),
+        tool_args,
+        Some(text.as_bytes()),
+        "No clipboard tool found. Install pbcopy, wl-copy, or xclip.",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_per_platform() {
+        assert_eq!(candidates(true, false), vec!["pbcopy"]);
+        assert_eq!(candidates(false, true), vec!["wl-copy", "xclip"]);
+        assert_eq!(candidates(false, false), vec!["xclip"]);
+        assert_eq!(tool_args("xclip"), vec!["-selection", "clipboard"]);
+        assert!(tool_args("pbcopy").is_empty());
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 use std::process::{Command, Output};
+use std::time::Duration;
 use tracing::{debug, trace};
 
+/// Base delay between retries; attempt `n` waits `n` times this long.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(50);
+
 /// A builder for executing shell commands with unified error handling
+#[derive(Clone)]
 pub struct Cmd<'a> {
     command: &'a str,
     args: Vec<&'a str>,
@@ -84,6 +89,39 @@ impl<'a> Cmd<'a> {
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
+    /// Execute the command and parse stdout, retrying while the output is empty
+    /// or `parse` rejects it.
+    ///
+    /// Meant for state that lags behind a just-issued action (e.g., a freshly
+    /// created tab not yet listed). A failing command is returned immediately.
+    /// Attempt `n` is followed by a wait of `delay * n`.
+    pub fn run_and_capture_stdout_with_retry<T>(
+        self,
+        attempts: u32,
+        delay: Duration,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<T> {
+        let attempts = attempts.max(1);
+        for attempt in 1..=attempts {
+            let stdout = self.clone().run_and_capture_stdout()?;
+            if !stdout.is_empty()
+                && let Some(value) = parse(&stdout)
+            {
+                return Ok(value);
+            }
+            if attempt < attempts {
+                debug!(command = self.command, attempt, "cmd:retry unusable output");
+                std::thread::sleep(delay * attempt);
+            }
+        }
+        Err(anyhow!(
+            "No usable output from {} {} after {} attempts",
+            self.command,
+            self.args.join(" "),
+            attempts
+        ))
+    }
+
     /// Execute the command, returning Ok(true) if it succeeds, Ok(false) if it fails
     /// This is useful for commands that are used as checks (e.g., git rev-parse --verify)
     pub fn run_as_check(self) -> Result<bool> {
@@ -135,3 +173,60 @@ pub fn shell_command_with_env(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shell script that logs each run to `log` and prints `ready` from the
+    /// third run on.
+    fn flaky_script(log: &Path) -> String {
+        format!(
+            "echo run >> '{0}'; [ $(wc -l < '{0}') -ge 3 ] && echo ready || true",
+            log.display()
+        )
+    }
+
+    fn runs(log: &Path) -> usize {
+        std::fs::read_to_string(log)
+            .unwrap_or_default()
+            .lines()
+            .count()
+    }
+
+    #[test]
+    fn retry_until_output_is_usable() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("runs");
+        let script = flaky_script(&log);
+        let value = Cmd::new("sh")
+            .args(&["-c", &script])
+            .run_and_capture_stdout_with_retry(5, Duration::ZERO, |out| {
+                (out == "ready").then_some(42)
+            })
+            .unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(runs(&log), 3);
+    }
+
+    #[test]
+    fn retry_gives_up_after_attempts() {
+        let err = Cmd::new("sh")
+            .args(&["-c", "echo not-json"])
+            .run_and_capture_stdout_with_retry(2, Duration::ZERO, |out| out.parse::<u32>().ok())
+            .unwrap_err();
+        assert!(err.to_string().contains("after 2 attempts"));
+    }
+
+    #[test]
+    fn retry_does_not_repeat_failing_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("runs");
+        let script = format!("echo run >> '{}'; exit 1", log.display());
+        let result = Cmd::new("sh")
+            .args(&["-c", &script])
+            .run_and_capture_stdout_with_retry(5, Duration::ZERO, |out| Some(out.to_string()));
+        assert!(result.is_err());
+        assert_eq!(runs(&log), 1);
+    }
+}
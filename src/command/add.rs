@@ -1,6 +1,6 @@
 use crate::config::MuxMode;
 use crate::multiplexer::handle::mode_label;
-use crate::multiplexer::{MuxHandle, create_backend, detect_backend, util::prefixed};
+use crate::multiplexer::{MuxHandle, create_backend, detect_backend};
 use crate::prompt::{Prompt, PromptDocument, foreach_from_frontmatter};
 use crate::spinner;
 use crate::template::{
@@ -72,9 +72,10 @@ fn read_stdin_lines() -> Result<Vec<String>> {
 
 /// Check preconditions for the add command (git repo and multiplexer session).
 /// Returns Ok(()) if all preconditions are met, or an error listing all failures.
-fn check_preconditions() -> Result<()> {
+fn check_preconditions(config: &config::Config) -> Result<()> {
     let is_git = git::is_git_repo()?;
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(config), config);
+    crate::multiplexer::ensure_forced_backend_running(config, mux.as_ref())?;
     let is_mux_running = mux.is_running()?;
 
     if is_git && is_mux_running {
@@ -99,6 +100,7 @@ fn check_preconditions() -> Result<()> {
     if !is_git {
         errors.push("Please run this command from within a git repository.".to_string());
     }
+    errors.push("Run `workmux doctor` to check your setup.".to_string());
 
     Err(anyhow!(errors.join("\n")))
 }
@@ -106,17 +108,29 @@ fn check_preconditions() -> Result<()> {
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     branch_name: Option<&str>,
+    interactive: bool,
     pr: Option<u32>,
     auto_name: bool,
     base: Option<&str>,
+    base_pr: Option<u32>,
     name: Option<String>,
     prompt_args: PromptArgs,
-    setup: SetupFlags,
+    mut setup: SetupFlags,
     rescue: RescueArgs,
-    multi: MultiArgs,
+    mut multi: MultiArgs,
     wait: bool,
     session: bool,
 ) -> Result<()> {
+    // Without a branch name, prompt interactively at a terminal
+    let use_wizard = interactive || (branch_name.is_none() && pr.is_none() && !auto_name);
+    if use_wizard && (crate::sandbox::guest::is_sandbox_guest() || !std::io::stdin().is_terminal())
+    {
+        bail!(
+            "A branch name is required. Pass it as an argument, \
+             or run from a terminal to be prompted (--interactive)."
+        );
+    }
+
     // Inside a sandbox guest, route through RPC to the host supervisor
     if crate::sandbox::guest::is_sandbox_guest() {
         return run_add_via_rpc(
@@ -127,6 +141,7 @@ pub fn run(
             &rescue,
             &multi,
             base,
+            base_pr,
             pr,
             name.as_deref(),
             wait,
@@ -135,13 +150,42 @@ pub fn run(
     }
 
     // Ensure preconditions are met (git repo and multiplexer session)
-    check_preconditions()?;
+    let config = config::Config::load(None)?;
+    check_preconditions(&config)?;
+
+    // Gather answers from the wizard and feed them into the normal flow
+    let wizard = if use_wizard {
+        let answers = super::add_wizard::run(&config)?;
+        if let Some(agent) = &answers.agent {
+            multi.agent = vec![agent.clone()];
+        }
+        setup.sandbox |= answers.sandbox;
+        Some(answers)
+    } else {
+        None
+    };
+    let branch_name = wizard
+        .as_ref()
+        .map(|w| w.branch_name.as_str())
+        .or(branch_name);
+    let base = wizard.as_ref().and_then(|w| w.base.as_deref()).or(base);
 
     // Extract sandbox override before consuming setup flags
-    let sandbox_override = setup.sandbox;
+    let sandbox_override = if setup.no_sandbox {
+        Some(false)
+    } else if setup.sandbox {
+        Some(true)
+    } else {
+        None
+    };
+    let layout = setup.layout.take();
 
     // Load config early to determine mode (CLI flag overrides config)
     let initial_config = config::Config::load(multi.agent.first().map(|s| s.as_str()))?;
+    // Fail on an unknown layout before creating anything
+    if let Some(layout) = &layout {
+        initial_config.clone().apply_layout(layout)?;
+    }
     let mode = if session {
         MuxMode::Session
     } else {
@@ -153,6 +197,14 @@ pub fn run(
     options.focus_window = !setup.background;
     options.open_if_exists = setup.open_if_exists;
     options.mode = mode;
+    options.isolation = setup.isolation.take();
+    options.sandbox_image = setup.image.take();
+    if let Some(cwd) = &setup.cwd {
+        crate::multiplexer::util::check_start_dir(cwd)?;
+    }
+    options.start_dir = setup.cwd.take();
+    options.setup_script = setup.setup_script.take();
+    options.pr_number = pr;
 
     // If using --auto-name and config has auto_name.background = true, run in background
     if auto_name && options.focus_window {
@@ -228,12 +280,15 @@ pub fn run(
             )
         };
 
+    // Resolve --base-pr to the PR's head branch (fetched from its remote)
+    let base_from_pr = base_pr.map(workflow::pr::resolve_pr_base).transpose()?;
+
     // Use the determined branch name and override base if from PR
     let branch_name = &final_branch_name;
     let base = if remote_branch_for_pr.is_some() {
         None
     } else {
-        base
+        base_from_pr.as_deref().or(base)
     };
 
     // Validate --with-changes compatibility
@@ -259,10 +314,19 @@ pub fn run(
     if rescue.with_changes {
         let (mut rescue_config, rescue_location) =
             config::Config::load_with_location(multi.agent.first().map(|s| s.as_str()))?;
-        if sandbox_override {
-            rescue_config.sandbox.enabled = Some(true);
+        if let Some(enabled) = sandbox_override {
+            rescue_config.sandbox.enabled = Some(enabled);
+        }
+        if let Some(layout) = &layout {
+            rescue_config.apply_layout(layout)?;
         }
-        let mux = create_backend(detect_backend());
+        if let Some(isolation) = &options.isolation {
+            rescue_config.sandbox.lima.isolation = Some(isolation.clone());
+        }
+        if let Some(image) = &options.sandbox_image {
+            rescue_config.sandbox.image = Some(image.clone());
+        }
+        let mux = create_backend(detect_backend(&rescue_config), &rescue_config);
         let rescue_context = workflow::WorkflowContext::new(rescue_config, mux, rescue_location)?;
         // Derive handle for rescue flow (uses config for naming strategy/prefix)
         let handle =
@@ -378,6 +442,7 @@ pub fn run(
         deferred_auto_name,
         max_concurrent: multi.max_concurrent,
         sandbox_override,
+        layout: layout.as_deref(),
     };
     plan.execute()
 }
@@ -416,7 +481,7 @@ fn handle_rescue_flow(
     );
 
     if wait {
-        MuxHandle::new(context.mux.as_ref(), mode, &context.prefix, handle).wait_until_closed()?;
+        MuxHandle::new(context.mux.as_ref(), mode, &result.window_name).wait_until_closed()?;
     }
 
     Ok(true)
@@ -505,7 +570,8 @@ struct CreationPlan<'a> {
     wait: bool,
     deferred_auto_name: bool,
     max_concurrent: Option<u32>,
-    sandbox_override: bool,
+    sandbox_override: Option<bool>,
+    layout: Option<&'a str>,
 }
 
 impl<'a> CreationPlan<'a> {
@@ -520,7 +586,8 @@ impl<'a> CreationPlan<'a> {
         }
 
         // Create backend once for all specs
-        let mux = create_backend(detect_backend());
+        let config = config::Config::load(None)?;
+        let mux = create_backend(detect_backend(&config), &config);
 
         // Track targets for --wait (all created windows/sessions)
         let mut created_targets = Vec::new();
@@ -552,8 +619,17 @@ impl<'a> CreationPlan<'a> {
             // Load config for this specific agent to ensure correct agent resolution
             let (mut config, config_location) =
                 config::Config::load_with_location(spec.agent.as_deref())?;
-            if self.sandbox_override {
-                config.sandbox.enabled = Some(true);
+            if let Some(enabled) = self.sandbox_override {
+                config.sandbox.enabled = Some(enabled);
+            }
+            if let Some(layout) = self.layout {
+                config.apply_layout(layout)?;
+            }
+            if let Some(isolation) = &self.options.isolation {
+                config.sandbox.lima.isolation = Some(isolation.clone());
+            }
+            if let Some(image) = &self.options.sandbox_image {
+                config.sandbox.image = Some(image.clone());
             }
 
             // Render prompt first (needed for deferred auto-name)
@@ -594,18 +670,6 @@ impl<'a> CreationPlan<'a> {
             // Create a WorkflowContext for this spec's config (reuse shared mux)
             let context = workflow::WorkflowContext::new(config, mux.clone(), config_location)?;
 
-            // Calculate window name for tracking
-            let full_window_name = prefixed(&context.prefix, &handle);
-
-            if self.wait {
-                created_targets.push(full_window_name.clone());
-            }
-
-            // Track for concurrency control
-            if self.max_concurrent.is_some() {
-                active_targets.push(full_window_name);
-            }
-
             let result = workflow::create(
                 &context,
                 workflow::CreateArgs {
@@ -625,6 +689,16 @@ impl<'a> CreationPlan<'a> {
                 )
             })?;
 
+            // Track the created window by name
+            if self.wait {
+                created_targets.push(result.window_name.clone());
+            }
+
+            // Track for concurrency control
+            if self.max_concurrent.is_some() {
+                active_targets.push(result.window_name.clone());
+            }
+
             if result.post_create_hooks_run > 0 {
                 println!("✓ Setup complete");
             }
@@ -668,6 +742,7 @@ fn run_add_via_rpc(
     rescue: &RescueArgs,
     multi: &MultiArgs,
     base: Option<&str>,
+    base_pr: Option<u32>,
     pr: Option<u32>,
     name: Option<&str>,
     wait: bool,
@@ -680,6 +755,9 @@ fn run_add_via_rpc(
     if base.is_some() {
         bail!("--base is not supported from inside a sandbox");
     }
+    if base_pr.is_some() {
+        bail!("--base-pr is not supported from inside a sandbox");
+    }
     if pr.is_some() {
         bail!("--pr is not supported from inside a sandbox");
     }
@@ -692,6 +770,24 @@ fn run_add_via_rpc(
     if rescue.with_changes {
         bail!("--with-changes is not supported from inside a sandbox");
     }
+    if setup.layout.is_some() {
+        bail!("--layout is not supported from inside a sandbox");
+    }
+    if setup.isolation.is_some() {
+        bail!("--isolation is not supported from inside a sandbox");
+    }
+    if setup.image.is_some() {
+        bail!("--image is not supported from inside a sandbox");
+    }
+    if setup.cwd.is_some() {
+        bail!("--cwd is not supported from inside a sandbox");
+    }
+    if setup.setup_script.is_some() {
+        bail!("--setup is not supported from inside a sandbox");
+    }
+    if setup.no_sandbox {
+        bail!("--no-sandbox is not supported from inside a sandbox");
+    }
     if !multi.agent.is_empty() {
         bail!("--agent is not supported from inside a sandbox (uses host config)");
     }
@@ -747,7 +843,7 @@ fn run_add_via_rpc(
             println!("✓ Spawned agent: {}", display_name);
             Ok(())
         }
-        RpcResponse::Error { message } => {
+        RpcResponse::Error { message, .. } => {
             bail!("Host failed to spawn agent: {}", message)
         }
         other => bail!("Unexpected RPC response: {:?}", other),
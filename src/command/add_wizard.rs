@@ -0,0 +1,232 @@
+//! Interactive wizard for `workmux add --interactive`.
+//!
+//! Prompts step-by-step for the branch name, base branch, agent, and sandbox
+//! mode, then hands the answers back to the normal `add` creation path.
+
+use anyhow::{Result, anyhow, bail};
+use std::io::{BufRead, Write};
+
+use crate::{config, git};
+
+/// Known agent commands offered as suggestions.
+const SUGGESTED_AGENTS: &[&str] = &["claude", "gemini", "codex", "opencode"];
+
+/// Environment the wizard prompts against.
+pub struct WizardContext {
+    /// Local branches offered as bases
+    pub branches: Vec<String>,
+    /// Branch checked out in the current worktree (default base)
+    pub current_branch: Option<String>,
+    /// Agent configured in `.workmux.yaml` (default agent)
+    pub default_agent: Option<String>,
+    /// Whether sandbox mode is already enabled in config
+    pub sandbox_enabled: bool,
+}
+
+impl WizardContext {
+    /// Gather branches and config defaults for the current repository.
+    pub fn load(config: &config::Config) -> Result<Self> {
+        let current_branch = git::get_current_branch()
+            .ok()
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty());
+        Ok(Self {
+            branches: git::list_local_branches()?,
+            current_branch,
+            default_agent: config.agent.clone(),
+            sandbox_enabled: config.sandbox.is_enabled(),
+        })
+    }
+}
+
+/// Creation parameters gathered by the wizard.
+#[derive(Debug, PartialEq)]
+pub struct WizardAnswers {
+    pub branch_name: String,
+    /// Explicit base, or None to branch from the current branch
+    pub base: Option<String>,
+    /// Explicit agent, or None to use the configured default
+    pub agent: Option<String>,
+    /// Whether to force sandbox mode on
+    pub sandbox: bool,
+}
+
+/// Run the wizard on the terminal.
+pub fn run(config: &config::Config) -> Result<WizardAnswers> {
+    let ctx = WizardContext::load(config)?;
+    let stdin = std::io::stdin();
+    prompt_answers(&mut stdin.lock(), &mut std::io::stdout(), &ctx)
+}
+
+/// Read one answer line. Returns an error on EOF so a closed stdin aborts.
+fn read_answer(input: &mut impl BufRead, output: &mut impl Write, prompt: &str) -> Result<String> {
+    write!(output, "{}", prompt)?;
+    output.flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        bail!("Aborted.");
+    }
+    Ok(line.trim().to_string())
+}
+
+/// Prompt for each creation parameter in turn.
+pub fn prompt_answers(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    ctx: &WizardContext,
+) -> Result<WizardAnswers> {
+    // 1. Branch name
+    let branch_name = read_answer(input, output, "Branch name: ")?;
+    if branch_name.is_empty() {
+        bail!("A branch name is required.");
+    }
+    if branch_name.chars().any(char::is_whitespace) {
+        bail!("Branch name cannot contain whitespace: '{}'", branch_name);
+    }
+
+    // 2. Base branch
+    writeln!(output)?;
+    writeln!(output, "Base branch:")?;
+    for (idx, branch) in ctx.branches.iter().enumerate() {
+        let marker = if ctx.current_branch.as_deref() == Some(branch.as_str()) {
+            " (current)"
+        } else {
+            ""
+        };
+        writeln!(output, "  {}. {}{}", idx + 1, branch, marker)?;
+    }
+    let default_base = ctx.current_branch.as_deref().unwrap_or("current branch");
+    let answer = read_answer(
+        input,
+        output,
+        &format!("Enter number or name [{}]: ", default_base),
+    )?;
+    let base = if answer.is_empty() {
+        None
+    } else if let Ok(idx) = answer.parse::<usize>() {
+        let branch = idx
+            .checked_sub(1)
+            .and_then(|i| ctx.branches.get(i))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid selection. Please choose a number between 1 and {}",
+                    ctx.branches.len()
+                )
+            })?;
+        Some(branch.clone())
+    } else {
+        Some(answer)
+    };
+
+    // 3. Agent
+    writeln!(output)?;
+    let default_agent = ctx.default_agent.as_deref().unwrap_or("claude");
+    let answer = read_answer(
+        input,
+        output,
+        &format!(
+            "Agent ({}) [{}]: ",
+            SUGGESTED_AGENTS.join(", "),
+            default_agent
+        ),
+    )?;
+    let agent = Some(answer).filter(|a| !a.is_empty());
+
+    // 4. Sandbox (only asked when config doesn't already enable it)
+    let sandbox = if ctx.sandbox_enabled {
+        writeln!(output, "Sandbox: enabled by config")?;
+        false
+    } else {
+        let answer = read_answer(input, output, "Run in sandbox? [y/N]: ")?;
+        matches!(answer.to_ascii_lowercase().as_str(), "y" | "yes")
+    };
+
+    Ok(WizardAnswers {
+        branch_name,
+        base,
+        agent,
+        sandbox,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ctx() -> WizardContext {
+        WizardContext {
+            branches: vec!["main".to_string(), "develop".to_string()],
+            current_branch: Some("main".to_string()),
+            default_agent: None,
+            sandbox_enabled: false,
+        }
+    }
+
+    fn answers(script: &str, ctx: &WizardContext) -> Result<WizardAnswers> {
+        let mut input = Cursor::new(script.as_bytes().to_vec());
+        let mut output = Vec::new();
+        prompt_answers(&mut input, &mut output, ctx)
+    }
+
+    #[test]
+    fn test_defaults_accepted() {
+        let result = answers("my-feature\n\n\n\n", &ctx()).unwrap();
+        assert_eq!(
+            result,
+            WizardAnswers {
+                branch_name: "my-feature".to_string(),
+                base: None,
+                agent: None,
+                sandbox: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_base_by_number_agent_and_sandbox() {
+        let result = answers("fix-bug\n2\ngemini\ny\n", &ctx()).unwrap();
+        assert_eq!(result.branch_name, "fix-bug");
+        assert_eq!(result.base.as_deref(), Some("develop"));
+        assert_eq!(result.agent.as_deref(), Some("gemini"));
+        assert!(result.sandbox);
+    }
+
+    #[test]
+    fn test_base_by_name() {
+        let result = answers("fix-bug\nrelease/1.0\n\nn\n", &ctx()).unwrap();
+        assert_eq!(result.base.as_deref(), Some("release/1.0"));
+        assert!(!result.sandbox);
+    }
+
+    #[test]
+    fn test_sandbox_not_asked_when_enabled_in_config() {
+        let ctx = WizardContext {
+            sandbox_enabled: true,
+            ..ctx()
+        };
+        // No sandbox answer in the script: EOF would abort if it were asked
+        let result = answers("feature\n\ncodex\n", &ctx).unwrap();
+        assert_eq!(result.agent.as_deref(), Some("codex"));
+        assert!(!result.sandbox);
+    }
+
+    #[test]
+    fn test_empty_branch_name_errors() {
+        let err = answers("\n", &ctx()).unwrap_err();
+        assert!(err.to_string().contains("branch name is required"));
+    }
+
+    #[test]
+    fn test_invalid_base_number_errors() {
+        let err = answers("feature\n5\n", &ctx()).unwrap_err();
+        assert!(err.to_string().contains("between 1 and 2"));
+    }
+
+    #[test]
+    fn test_eof_aborts() {
+        let err = answers("feature\n", &ctx()).unwrap_err();
+        assert!(err.to_string().contains("Aborted"));
+    }
+}
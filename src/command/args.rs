@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::config::IsolationLevel;
+
 #[derive(clap::Args, Debug)]
 pub struct PromptArgs {
     /// Inline prompt text to store in the new worktree
@@ -45,6 +47,30 @@ pub struct SetupFlags {
     /// Enable sandbox mode even when disabled in config
     #[arg(short = 'S', long)]
     pub sandbox: bool,
+
+    /// Disable sandbox mode even when enabled in config
+    #[arg(long, conflicts_with = "sandbox")]
+    pub no_sandbox: bool,
+
+    /// Use a named pane layout from the `layouts` config instead of `panes`
+    #[arg(short = 'l', long)]
+    pub layout: Option<String>,
+
+    /// Override the Lima VM isolation level for this worktree
+    #[arg(long, value_enum)]
+    pub isolation: Option<IsolationLevel>,
+
+    /// Override the sandbox image for this worktree (kept for later `open`s)
+    #[arg(long)]
+    pub image: Option<String>,
+
+    /// Start panes in this subdirectory of the new worktree
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Run this script in the agent pane before the agent (overrides `setup_script`)
+    #[arg(long = "setup", value_name = "SCRIPT")]
+    pub setup_script: Option<String>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -97,3 +123,23 @@ pub struct RescueArgs {
     #[arg(short = 'u', long, requires = "with_changes")]
     pub include_untracked: bool,
 }
+
+#[derive(clap::Args, Debug)]
+pub struct MarkdownArgs {
+    /// Wrap at this many columns instead of the terminal width
+    #[arg(long, value_parser = clap::value_parser!(u16).range(20..))]
+    pub width: Option<u16>,
+
+    /// Render without colors or text styling
+    #[arg(long)]
+    pub no_color: bool,
+}
+
+impl From<MarkdownArgs> for crate::markdown::DisplayOptions {
+    fn from(args: MarkdownArgs) -> Self {
+        Self {
+            width: args.width.map(usize::from),
+            no_color: args.no_color,
+        }
+    }
+}
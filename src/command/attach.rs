@@ -0,0 +1,74 @@
+use crate::multiplexer::handle::mode_label;
+use crate::multiplexer::{MuxHandle, create_backend, detect_backend};
+use crate::workflow::{SetupOptions, WorkflowContext};
+use crate::{config, git, workflow};
+use anyhow::{Context, Result, anyhow, bail};
+
+/// Recreate the window/session of a worktree whose target was closed.
+///
+/// Unlike `open`, this refuses to touch a worktree that still has a target,
+/// so it never silently switches or creates a duplicate.
+pub fn run(name: &str) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
+    let context = WorkflowContext::new(config, mux, config_location)?;
+
+    // Smart resolution: try handle first, then branch name
+    let (worktree_path, _branch) = git::find_worktree(name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    let handle = worktree_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not derive handle from worktree path: {:?}",
+                worktree_path
+            )
+        })?;
+
+    let mode = git::get_worktree_mode(handle);
+    let kind = mode_label(mode);
+    let full_name = context.full_name(handle);
+    if MuxHandle::exists_full(context.mux.as_ref(), mode, &full_name)? {
+        bail!(already_open_message(kind, handle, &full_name));
+    }
+
+    // Existing worktree: skip hooks and file operations, launch the panes
+    let mut options = SetupOptions::new(false, false, true);
+    options.mode = mode;
+
+    let result =
+        workflow::open(handle, &context, options, false).context("Failed to attach to worktree")?;
+
+    println!(
+        "✓ Attached tmux {} for '{}'\n  Worktree: {}",
+        kind,
+        handle,
+        result.worktree_path.display()
+    );
+    Ok(())
+}
+
+/// Error for a worktree whose target is still open.
+fn already_open_message(kind: &str, handle: &str, full_name: &str) -> String {
+    format!(
+        "Worktree '{}' already has an open {} '{}'. Use 'workmux switch {}' to go to it.",
+        handle, kind, full_name, handle
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn already_open_message_suggests_switch() {
+        let message = already_open_message("window", "feat", "wm-feat");
+        assert!(message.contains("open window 'wm-feat'"));
+        assert!(message.ends_with("Use 'workmux switch feat' to go to it."));
+    }
+}
@@ -4,7 +4,8 @@ use crate::multiplexer::{create_backend, detect_backend};
 use crate::workflow;
 
 pub fn run(name: &str, lines: u16) -> Result<()> {
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
     let (_path, agent) = workflow::resolve_worktree_agent(name, mux.as_ref())?;
 
     let output = mux
@@ -2,7 +2,7 @@ use anyhow::Result;
 
 const CHANGELOG: &str = include_str!("../../CHANGELOG.md");
 
-pub fn run() -> Result<()> {
-    crate::markdown::display(CHANGELOG, CHANGELOG);
+pub fn run(options: &crate::markdown::DisplayOptions) -> Result<()> {
+    crate::markdown::display(CHANGELOG, CHANGELOG, options);
     Ok(())
 }
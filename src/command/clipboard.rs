@@ -0,0 +1,27 @@
+//! Copy text to the clipboard, routing through the host inside a sandbox.
+
+use anyhow::{Context, Result};
+use std::io::Read;
+
+use crate::sandbox::rpc::{RpcClient, RpcRequest};
+
+/// Copy `text`, or stdin when no text is given, to the system clipboard.
+pub fn run(text: Option<String>) -> Result<()> {
+    let text = match text {
+        Some(text) => text,
+        None => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read stdin")?;
+            buf
+        }
+    };
+
+    // Inside a sandbox guest, the host supervisor owns the clipboard
+    if crate::sandbox::guest::is_sandbox_guest() {
+        return RpcClient::call_host(&RpcRequest::Clipboard { text }, "copy to the clipboard");
+    }
+
+    crate::clipboard::copy(&text)
+}
@@ -1,17 +1,80 @@
+use crate::config::MuxMode;
 use crate::multiplexer::handle::mode_label;
-use crate::multiplexer::{MuxHandle, create_backend, detect_backend};
+use crate::multiplexer::util::WindowNames;
+use crate::multiplexer::{Multiplexer, MuxHandle, create_backend, detect_backend};
+use crate::sandbox::TeardownPlan;
 use crate::{config, git, sandbox};
 use anyhow::{Context, Result, anyhow};
 
-pub fn run(name: Option<&str>) -> Result<()> {
+/// Close the targets for `names`, or the current worktree when empty.
+///
+/// With several names, a failure doesn't stop the rest; failures are
+/// summarized at the end.
+pub fn run(names: &[String], dry_run: bool) -> Result<()> {
     let config = config::Config::load(None)?;
-    let mux = create_backend(detect_backend());
+    let backend = create_backend(detect_backend(&config), &config);
+    let mux = backend.as_ref();
+
+    if names.len() <= 1 {
+        let name = names.first().map(String::as_str);
+        let pending = close_one(name, dry_run, &config, mux)?;
+        return finish_pending(mux, pending);
+    }
+
+    let mut pending = None;
+    let mut closed = 0;
+    let mut failed: Vec<(String, String)> = Vec::new();
+    for name in names {
+        match close_one(Some(name), dry_run, &config, mux) {
+            Ok(current) => {
+                closed += 1;
+                pending = pending.or(current);
+            }
+            Err(e) => failed.push((name.clone(), e.to_string())),
+        }
+    }
+
+    if !dry_run {
+        println!("\nClosed {} of {} worktree(s)", closed, names.len());
+    }
+    // The current target goes last: closing it may end this process
+    finish_pending(mux, pending)?;
+
+    if !failed.is_empty() {
+        eprintln!("\nFailed to close {} worktree(s):", failed.len());
+        for (name, error) in &failed {
+            eprintln!("  - {}: {}", name, error);
+        }
+        return Err(anyhow!("Some worktrees could not be closed"));
+    }
+
+    Ok(())
+}
+
+/// Schedule the deferred close of the current window/session, if any.
+fn finish_pending(mux: &dyn Multiplexer, pending: Option<(MuxMode, String)>) -> Result<()> {
+    if let Some((mode, full_target_name)) = pending {
+        let delay = std::time::Duration::from_millis(100);
+        MuxHandle::schedule_close_full(mux, mode, &full_target_name, delay)?;
+    }
+    Ok(())
+}
+
+/// Close one worktree's target. Returns the target to close later when it is
+/// the one this command runs in.
+fn close_one(
+    name: Option<&str>,
+    dry_run: bool,
+    config: &config::Config,
+    mux: &dyn Multiplexer,
+) -> Result<Option<(MuxMode, String)>> {
     let prefix = config.window_prefix();
+    let names = WindowNames::load();
 
     // Resolve the handle first to determine target mode
     let resolved_handle = match name {
         Some(h) => h.to_string(),
-        None => super::resolve_name(None)?,
+        None => super::resolve_name(None, config)?,
     };
 
     // Determine if this worktree was created as a session or window
@@ -28,33 +91,59 @@ pub fn run(name: Option<&str>) -> Result<()> {
                     handle
                 )
             })?;
-            let target = MuxHandle::new(mux.as_ref(), mode, prefix, handle);
-            let full = target.full_name();
+            let target = MuxHandle::new(mux, mode, names.full_name(prefix, handle));
             let current = target.current_name()?;
-            let is_current = current.as_deref() == Some(full.as_str());
-            (full, is_current)
+            let is_current = current.as_deref() == Some(target.full_name());
+            (target.full_name().to_string(), is_current)
         }
         None => {
             // No name provided - check if we're in a workmux window/session
-            let target = MuxHandle::new(mux.as_ref(), mode, prefix, &resolved_handle);
+            let target = MuxHandle::new(mux, mode, names.full_name(prefix, &resolved_handle));
             let current_name = target.current_name()?;
             if let Some(current) = current_name {
-                if current.starts_with(prefix) {
+                if names.handle_for(&current, prefix).is_some() {
                     // We're in a workmux target, use it directly
                     (current.clone(), true)
                 } else {
                     // Not in a workmux target, fall back to resolved handle
-                    (target.full_name(), false)
+                    (target.full_name().to_string(), false)
                 }
             } else {
                 // Not in multiplexer, use resolved handle
-                (target.full_name(), false)
+                (target.full_name().to_string(), false)
             }
         }
     };
 
     let kind = mode_label(mode);
-    let target_exists = MuxHandle::exists_full(mux.as_ref(), mode, &full_target_name)?;
+    let target_exists = MuxHandle::exists_full(mux, mode, &full_target_name)?;
+
+    if dry_run {
+        // Mirrors the teardown below
+        let teardown = match names.handle_for(&full_target_name, prefix) {
+            Some(handle) if handle == resolved_handle => sandbox::plan_teardown(&handle, config),
+            Some(handle) => {
+                let record = git::get_worktree_sandbox(&resolved_handle);
+                Ok(TeardownPlan::Containers(sandbox::planned_container_stops(
+                    &handle,
+                    record.as_ref(),
+                    &config.sandbox,
+                )))
+            }
+            None => Ok(TeardownPlan::Containers(Vec::new())),
+        };
+        print!(
+            "{}",
+            dry_run_report(
+                kind,
+                &full_target_name,
+                target_exists,
+                &teardown,
+                is_current_target
+            )
+        );
+        return Ok(None);
+    }
 
     if !target_exists {
         return Err(anyhow!(
@@ -65,19 +154,125 @@ pub fn run(name: Option<&str>) -> Result<()> {
         ));
     }
 
-    // Stop any running containers for this worktree before killing the target.
-    if let Some(handle) = full_target_name.strip_prefix(prefix) {
-        sandbox::stop_containers_for_handle(handle, &config.sandbox);
+    // Tear down the sandbox recorded for this worktree's last launch before
+    // killing the target. A duplicate target (e.g. feature-2) shares the VM with
+    // the original, so only its own containers are stopped.
+    if let Some(handle) = names.handle_for(&full_target_name, prefix) {
+        if handle == resolved_handle {
+            sandbox::teardown_for_handle(&handle, config);
+        } else {
+            let record = git::get_worktree_sandbox(&resolved_handle);
+            sandbox::stop_sandbox_for_handle(&handle, record.as_ref(), &config.sandbox);
+        }
     }
 
     if is_current_target {
-        let delay = std::time::Duration::from_millis(100);
-        MuxHandle::schedule_close_full(mux.as_ref(), mode, &full_target_name, delay)?;
-    } else {
-        MuxHandle::kill_full(mux.as_ref(), mode, &full_target_name)
-            .context("Failed to close target")?;
-        println!("✓ Closed {} '{}' (worktree kept)", kind, full_target_name);
+        return Ok(Some((mode, full_target_name)));
     }
 
-    Ok(())
+    MuxHandle::kill_full(mux, mode, &full_target_name).context("Failed to close target")?;
+    println!("✓ Closed {} '{}' (worktree kept)", kind, full_target_name);
+    Ok(None)
+}
+
+/// Describe what `close` would do for a target, for `--dry-run`.
+fn dry_run_report(
+    kind: &str,
+    full_target_name: &str,
+    exists: bool,
+    teardown: &Result<TeardownPlan>,
+    is_current_target: bool,
+) -> String {
+    let mut out = String::from("DRY RUN: nothing will be closed or stopped\n");
+    out.push_str(&format!("  Target:     {} '{}'\n", kind, full_target_name));
+    out.push_str(&format!(
+        "  Exists:     {}\n",
+        if exists {
+            "yes"
+        } else {
+            "no (close would fail)"
+        }
+    ));
+    match teardown {
+        Ok(TeardownPlan::Containers(containers)) => {
+            let containers = if containers.is_empty() {
+                "none".to_string()
+            } else {
+                containers.join(", ")
+            };
+            out.push_str(&format!("  Containers: {}\n", containers));
+        }
+        Ok(TeardownPlan::Lima(release)) => {
+            let stop = if release.stop {
+                "released, stopped once idle"
+            } else {
+                "released, kept running"
+            };
+            out.push_str(&format!("  Lima VM:    {} ({})\n", release.vm_name, stop));
+        }
+        Err(e) => out.push_str(&format!("  Sandbox:    unknown ({})\n", e)),
+    }
+    if exists {
+        let close = if is_current_target {
+            "delayed (closing the current one)"
+        } else {
+            "immediate"
+        };
+        out.push_str(&format!("  Close:      {}\n", close));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dry_run_report_lists_plan() {
+        let report = dry_run_report(
+            "window",
+            "wm-feature",
+            true,
+            &Ok(TeardownPlan::Containers(vec![
+                "wm-feature-1".to_string(),
+                "wm-feature-2".to_string(),
+            ])),
+            false,
+        );
+        assert!(report.starts_with("DRY RUN"));
+        assert!(report.contains("Target:     window 'wm-feature'"));
+        assert!(report.contains("Exists:     yes"));
+        assert!(report.contains("Containers: wm-feature-1, wm-feature-2"));
+        assert!(report.contains("Close:      immediate"));
+    }
+
+    #[test]
+    fn dry_run_report_missing_target() {
+        let none = Ok(TeardownPlan::Containers(Vec::new()));
+        let report = dry_run_report("session", "wm-feature", false, &none, false);
+        assert!(report.contains("Exists:     no"));
+        assert!(report.contains("Containers: none"));
+        assert!(!report.contains("Close:"));
+
+        let report = dry_run_report("window", "wm-feature", true, &none, true);
+        assert!(report.contains("Close:      delayed"));
+    }
+
+    #[test]
+    fn dry_run_report_lists_lima_release() {
+        let release = sandbox::lima::VmRelease {
+            worktree: "/repo__worktrees/feature".into(),
+            vm_name: "wm-repo-1234abcd".to_string(),
+            stop: true,
+        };
+        let report = dry_run_report(
+            "window",
+            "wm-feature",
+            true,
+            &Ok(TeardownPlan::Lima(release)),
+            false,
+        );
+        assert!(report.contains("Lima VM:    wm-repo-1234abcd (released, stopped once idle)"));
+        assert!(!report.contains("Containers:"));
+    }
 }
@@ -2,9 +2,18 @@
 
 use anyhow::{Context, Result, bail};
 use clap::{Args, Subcommand};
+use console::style;
 use std::fs;
 use std::process::Command;
 
+use crate::config::MuxMode;
+use crate::config::{
+    Config, IsolationLevel, MergeStrategy, SandboxBackend, validate_panes_config,
+    validate_windows_config,
+};
+use crate::multiplexer::agent::resolve_profile;
+use crate::sandbox::KNOWN_AGENTS;
+
 #[derive(Debug, Args)]
 pub struct ConfigArgs {
     #[command(subcommand)]
@@ -17,12 +26,18 @@ pub enum ConfigCommand {
     Edit,
     /// Print the path to the global configuration file
     Path,
+    /// Load the merged configuration and report problems with it
+    Validate,
+    /// Print the merged configuration as YAML, with defaults filled in
+    Show,
 }
 
 pub fn run(args: ConfigArgs) -> Result<()> {
     match args.command {
         ConfigCommand::Edit => run_edit(),
         ConfigCommand::Path => run_path(),
+        ConfigCommand::Validate => run_validate(),
+        ConfigCommand::Show => run_show(),
     }
 }
 
@@ -72,6 +87,165 @@ fn run_path() -> Result<()> {
     Ok(())
 }
 
+fn run_validate() -> Result<()> {
+    let config = match Config::load(None) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("{} {:#}", style("✗").red(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let problems = config_problems(&config);
+    if problems.is_empty() {
+        println!("{} Config is valid", style("✓").green());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{} {}: {}", style("✗").red(), problem.key, problem.message);
+    }
+    std::process::exit(1);
+}
+
+fn run_show() -> Result<()> {
+    let config = resolved(Config::load(None)?);
+    print!("{}", serde_yaml::to_string(&config)?);
+    Ok(())
+}
+
+/// A config problem and the key it was found at.
+#[derive(Debug, PartialEq)]
+struct Problem {
+    key: String,
+    message: String,
+}
+
+impl Problem {
+    fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Cross-field problems that loading the config doesn't catch, because they
+/// only surface once a worktree is created or a sandbox started.
+fn config_problems(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let icons = &config.status_icons;
+    for (key, icon) in [
+        ("status_icons.working", &icons.working),
+        ("status_icons.waiting", &icons.waiting),
+        ("status_icons.done", &icons.done),
+    ] {
+        if icon.as_deref().is_some_and(|icon| icon.trim().is_empty()) {
+            problems.push(Problem::new(key, "must not be empty"));
+        }
+    }
+
+    for (agent, profile) in config.profiles.iter().flatten() {
+        let Some(patterns) = &profile.status_patterns else {
+            continue;
+        };
+        for (status, pattern) in [
+            ("working", &patterns.working),
+            ("waiting", &patterns.waiting),
+            ("done", &patterns.done),
+        ] {
+            if let Some(Err(e)) = pattern.as_deref().map(regex::Regex::new) {
+                problems.push(Problem::new(
+                    format!("profiles.{}.status_patterns.{}", agent, status),
+                    format!("invalid regex: {}", e),
+                ));
+            }
+        }
+    }
+
+    if let Some(panes) = &config.panes
+        && let Err(e) = validate_panes_config(panes)
+    {
+        problems.push(Problem::new("panes", e.to_string()));
+    }
+    if let Some(windows) = &config.windows {
+        if let Err(e) = validate_windows_config(windows) {
+            problems.push(Problem::new("windows", e.to_string()));
+        }
+        if config.mode() != MuxMode::Session {
+            problems.push(Problem::new("windows", "requires mode: session"));
+        }
+    }
+
+    let sandbox = &config.sandbox;
+    if sandbox.is_enabled() {
+        match sandbox.backend() {
+            SandboxBackend::Container => {
+                let agent = sandbox_agent(config);
+                if sandbox.image.is_none() && !KNOWN_AGENTS.contains(&agent) {
+                    problems.push(Problem::new(
+                        "sandbox.image",
+                        format!(
+                            "not set, and agent '{}' has no default image (known agents: {})",
+                            agent,
+                            KNOWN_AGENTS.join(", ")
+                        ),
+                    ));
+                }
+            }
+            SandboxBackend::Lima => {
+                if sandbox.lima.isolation() == IsolationLevel::Shared
+                    && sandbox.lima.projects_dir.is_none()
+                {
+                    problems.push(Problem::new(
+                        "sandbox.lima.projects_dir",
+                        "required when sandbox.lima.isolation is shared",
+                    ));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Canonical name of the agent the sandbox image is picked for.
+fn sandbox_agent(config: &Config) -> &'static str {
+    resolve_profile(Some(config.agent.as_deref().unwrap_or("claude"))).name()
+}
+
+/// The config with every defaulted setting written out explicitly.
+fn resolved(mut config: Config) -> Config {
+    config.window_prefix = Some(config.window_prefix().to_string());
+    config.agent = Some(config.agent.as_deref().unwrap_or("claude").to_string());
+    config.mode = Some(config.mode());
+    config.multiplexer = Some(config.multiplexer());
+    config.merge_strategy = Some(config.merge_strategy.unwrap_or(MergeStrategy::Merge));
+    config.status_format = Some(config.status_format.unwrap_or(true));
+    config.desktop_notifications = Some(config.desktop_notifications());
+    config.env_file = Some(config.env_file().to_string());
+    config.handshake_timeout = Some(config.handshake_timeout().as_secs());
+
+    config.status_icon_theme = Some(config.status_icon_theme.unwrap_or_default());
+    config.status_icons = config.status_icons();
+
+    let agent = sandbox_agent(&config);
+    let sandbox = &mut config.sandbox;
+    sandbox.enabled = Some(sandbox.is_enabled());
+    sandbox.backend = Some(sandbox.backend());
+    if sandbox.backend() == SandboxBackend::Container {
+        sandbox.image = Some(sandbox.resolved_image(agent));
+    } else {
+        let lima = &mut sandbox.lima;
+        lima.isolation = Some(lima.isolation());
+        lima.cpus = Some(lima.cpus());
+        lima.memory = Some(lima.memory().to_string());
+        lima.disk = Some(lima.disk().to_string());
+    }
+
+    config
+}
+
 const DEFAULT_GLOBAL_CONFIG: &str = r#"# workmux global configuration
 # Settings here apply to all projects. Project-specific .workmux.yaml overrides these.
 # See: https://workmux.raine.dev/guide/configuration
@@ -102,4 +276,82 @@ mod tests {
             result.err()
         );
     }
+
+    fn config(yaml: &str) -> Config {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn config_problems_empty_for_defaults() {
+        assert!(config_problems(&Config::default()).is_empty());
+    }
+
+    #[test]
+    fn config_problems_reports_empty_status_icon() {
+        let problems = config_problems(&config("status_icons:\n  waiting: ''\n"));
+        assert_eq!(
+            problems,
+            vec![Problem::new("status_icons.waiting", "must not be empty")]
+        );
+    }
+
+    #[test]
+    fn config_problems_reports_invalid_status_pattern() {
+        let problems = config_problems(&config(
+            "profiles:\n  claude:\n    status_patterns:\n      waiting: '(unclosed'\n      done: 'ok$'\n",
+        ));
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key, "profiles.claude.status_patterns.waiting");
+        assert!(problems[0].message.starts_with("invalid regex"));
+    }
+
+    #[test]
+    fn config_problems_reports_windows_outside_session_mode() {
+        let yaml = "windows:\n  - panes:\n      - command: vim\n";
+        let problems = config_problems(&config(yaml));
+        assert_eq!(
+            problems,
+            vec![Problem::new("windows", "requires mode: session")]
+        );
+        assert!(config_problems(&config(&format!("mode: session\n{}", yaml))).is_empty());
+    }
+
+    #[test]
+    fn config_problems_reports_sandbox_without_image() {
+        let yaml = "agent: aider\nsandbox:\n  enabled: true\n";
+        let problems = config_problems(&config(yaml));
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key, "sandbox.image");
+
+        let with_image = format!("{}  image: my/aider\n", yaml);
+        assert!(config_problems(&config(&with_image)).is_empty());
+        let known_agent = "agent: codex\nsandbox:\n  enabled: true\n";
+        assert!(config_problems(&config(known_agent)).is_empty());
+    }
+
+    #[test]
+    fn config_problems_reports_shared_isolation_without_projects_dir() {
+        let yaml = "sandbox:\n  enabled: true\n  backend: lima\n  lima:\n    isolation: shared\n";
+        let problems = config_problems(&config(yaml));
+        assert_eq!(
+            problems.iter().map(|p| p.key.as_str()).collect::<Vec<_>>(),
+            vec!["sandbox.lima.projects_dir"]
+        );
+    }
+
+    #[test]
+    fn resolved_fills_in_defaults() {
+        let config = resolved(config("window_prefix: 'x-'\n"));
+        assert_eq!(config.window_prefix.as_deref(), Some("x-"));
+        assert_eq!(config.mode, Some(MuxMode::Window));
+        assert_eq!(config.env_file.as_deref(), Some(".workmux.env"));
+        assert_eq!(config.status_icons.done.as_deref(), Some("✅"));
+        assert_eq!(config.sandbox.enabled, Some(false));
+        assert!(
+            config
+                .sandbox
+                .image
+                .is_some_and(|image| image.ends_with(":claude"))
+        );
+    }
 }
@@ -113,7 +113,7 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
         }
         Action::EnterInputMode => {
             if app.table_state.selected().is_some() && !app.agents.is_empty() {
-                app.input_mode = true;
+                app.input_mode = !app.selected_is_dashboard();
             }
             false
         }
@@ -2,26 +2,44 @@
 
 use std::path::Path;
 
+use crate::multiplexer::util::handle_for_name;
+
 /// Extract the worktree name from a window or session name.
 /// Checks window_name first (window mode), then session_name (session mode).
+/// A templated name may not end in the handle, so the worktree directory in
+/// `path` wins when it is under a `__worktrees` directory.
 /// Returns (worktree_name, is_main) where is_main indicates if this is the main worktree.
 pub fn extract_worktree_name(
     session_name: &str,
     window_name: &str,
     window_prefix: &str,
+    path: &Path,
 ) -> (String, bool) {
-    if let Some(stripped) = window_name.strip_prefix(window_prefix) {
-        // Window mode: worktree name is in the window name
-        (stripped.to_string(), false)
-    } else if let Some(stripped) = session_name.strip_prefix(window_prefix) {
-        // Session mode: worktree name is in the session name
-        (stripped.to_string(), false)
+    // Window mode: worktree name is in the window name; session mode: in the
+    // session name
+    let from_name = handle_for_name(window_name, window_prefix)
+        .or_else(|| handle_for_name(session_name, window_prefix));
+    if let Some(handle) = from_name {
+        (worktree_dir_name(path).unwrap_or(handle), false)
     } else {
         // Non-workmux agent - running in main worktree
         ("main".to_string(), true)
     }
 }
 
+/// Name of the worktree directory containing `path`, when it sits directly
+/// under a `project__worktrees` directory.
+fn worktree_dir_name(path: &Path) -> Option<String> {
+    path.ancestors().find_map(|ancestor| {
+        let parent = ancestor.parent()?.file_name()?.to_string_lossy();
+        parent
+            .ends_with("__worktrees")
+            .then(|| ancestor.file_name())
+            .flatten()
+            .map(|name| name.to_string_lossy().to_string())
+    })
+}
+
 /// Extract project name from a worktree path.
 /// Finds the git root (where .git is a directory) or falls back to pattern matching.
 pub fn extract_project_name(path: &Path) -> String {
@@ -80,29 +98,56 @@ mod tests {
 
     #[test]
     fn test_extract_worktree_name_window_mode() {
-        let (name, is_main) = extract_worktree_name("main-session", "workmux:fix-bug", "workmux:");
+        let (name, is_main) = extract_worktree_name(
+            "main-session",
+            "workmux:fix-bug",
+            "workmux:",
+            Path::new("/home/user/myproject"),
+        );
         assert_eq!(name, "fix-bug");
         assert!(!is_main);
     }
 
     #[test]
     fn test_extract_worktree_name_session_mode() {
-        let (name, is_main) = extract_worktree_name("workmux:feature-auth", "zsh", "workmux:");
+        let (name, is_main) = extract_worktree_name(
+            "workmux:feature-auth",
+            "zsh",
+            "workmux:",
+            Path::new("/home/user/myproject"),
+        );
         assert_eq!(name, "feature-auth");
         assert!(!is_main);
     }
 
     #[test]
     fn test_extract_worktree_name_window_preferred_over_session() {
-        let (name, is_main) =
-            extract_worktree_name("workmux:from-session", "workmux:from-window", "workmux:");
+        let (name, is_main) = extract_worktree_name(
+            "workmux:from-session",
+            "workmux:from-window",
+            "workmux:",
+            Path::new("/home/user/myproject"),
+        );
         assert_eq!(name, "from-window");
         assert!(!is_main);
     }
 
+    #[test]
+    fn test_extract_worktree_name_templated_uses_worktree_dir() {
+        let path = Path::new("/home/user/myproject__worktrees/fix-bug/src");
+        let (name, is_main) = extract_worktree_name("main", "workmux:42-fix-bug", "workmux:", path);
+        assert_eq!(name, "fix-bug");
+        assert!(!is_main);
+    }
+
     #[test]
     fn test_extract_worktree_name_main() {
-        let (name, is_main) = extract_worktree_name("other-session", "some-window", "workmux:");
+        let (name, is_main) = extract_worktree_name(
+            "other-session",
+            "some-window",
+            "workmux:",
+            Path::new("/home/user/myproject"),
+        );
         assert_eq!(name, "main");
         assert!(is_main);
     }
@@ -21,6 +21,7 @@ const PR_FETCH_INTERVAL: Duration = Duration::from_secs(30);
 
 use super::agent;
 use super::diff::DiffView;
+use super::refresh::StatusSnapshot;
 use super::settings::{
     load_hide_stale, load_last_pane_id, load_preview_size, save_hide_stale, save_last_pane_id,
     save_preview_size,
@@ -60,6 +61,11 @@ pub struct App {
     pub preview: Option<String>,
     /// Track which pane_id the preview was captured from (to detect selection changes)
     preview_pane_id: Option<String>,
+    /// Channel for periodic preview captures (pane_id, content)
+    preview_rx: mpsc::Receiver<(String, Option<String>)>,
+    preview_tx: mpsc::Sender<(String, Option<String>)>,
+    /// Flag to prevent concurrent preview captures
+    is_preview_capturing: Arc<AtomicBool>,
     /// Input mode: keystrokes are sent directly to the selected agent's pane
     pub input_mode: bool,
     /// Manual scroll offset for the preview (None = auto-scroll to bottom)
@@ -108,6 +114,7 @@ impl App {
         let config = Config::load(None)?;
         let (git_tx, git_rx) = mpsc::channel();
         let (pr_tx, pr_rx) = mpsc::channel();
+        let (preview_tx, preview_rx) = mpsc::channel();
 
         // Get the active pane's directory to indicate the active worktree.
         // Try multiplexer first (handles popup case), fall back to current_dir.
@@ -129,6 +136,11 @@ impl App {
         let hide_stale = load_hide_stale();
         let last_pane_id = load_last_pane_id();
 
+        // Infer status for agents that don't report it (opt-in)
+        if config.dashboard.status_watch() && mux.supports_preview() {
+            super::status_watch::spawn(mux.clone(), config.clone());
+        }
+
         let mut app = Self {
             mux,
             agents: Vec::new(),
@@ -143,6 +155,9 @@ impl App {
             view_mode: ViewMode::default(),
             preview: None,
             preview_pane_id: None,
+            preview_rx,
+            preview_tx,
+            is_preview_capturing: Arc::new(AtomicBool::new(false)),
             input_mode: false,
             preview_scroll: None,
             preview_line_count: 0,
@@ -416,6 +431,52 @@ impl App {
             .and_then(|pane_id| self.mux.capture_pane(pane_id, PREVIEW_LINES));
     }
 
+    /// Capture the preview on a background thread, so a slow `capture_pane`
+    /// doesn't hold up the agent list refresh. Skipped while a capture is
+    /// still running; results are picked up by `consume_preview_updates`.
+    pub fn spawn_preview_capture(&self) {
+        if !self.mux.supports_preview() {
+            return;
+        }
+        let Some(pane_id) = self.preview_pane_id.clone() else {
+            return;
+        };
+        if self
+            .is_preview_capturing
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let mux = self.mux.clone();
+        let tx = self.preview_tx.clone();
+        let is_capturing = self.is_preview_capturing.clone();
+        std::thread::spawn(move || {
+            let content = mux.capture_pane(&pane_id, PREVIEW_LINES);
+            is_capturing.store(false, Ordering::SeqCst);
+            // Ignore send errors (receiver dropped means app is shutting down)
+            let _ = tx.send((pane_id, content));
+        });
+    }
+
+    /// Apply finished background captures that are still for the selection.
+    pub fn consume_preview_updates(&mut self) {
+        while let Ok((pane_id, content)) = self.preview_rx.try_recv() {
+            if self.preview_pane_id.as_deref() == Some(pane_id.as_str()) {
+                self.preview = content;
+            }
+        }
+    }
+
+    /// Statuses of the listed agents, for the refresh schedule.
+    pub fn status_snapshot(&self) -> StatusSnapshot {
+        self.agents
+            .iter()
+            .map(|agent| (agent.pane_id.clone(), agent.status))
+            .collect()
+    }
+
     /// Parse pane_id to a number for proper ordering.
     /// Handles tmux format (%0, %10) and numeric formats (WezTerm, kitty).
     /// Uses u64 since kitty pane IDs can exceed u32 range.
@@ -642,6 +703,27 @@ impl App {
         }
     }
 
+    /// Whether the selected agent's pane is running the dashboard itself,
+    /// e.g. this one. Input mode would type into itself, so it's refused.
+    pub fn selected_is_dashboard(&self) -> bool {
+        let Some(agent) = self
+            .table_state
+            .selected()
+            .and_then(|selected| self.agents.get(selected))
+        else {
+            return false;
+        };
+        let is_dashboard = self.mux.current_pane_id().as_deref() == Some(agent.pane_id.as_str())
+            || self.mux.is_dashboard_pane(&agent.pane_id);
+        if is_dashboard {
+            tracing::warn!(
+                pane_id = agent.pane_id,
+                "dashboard:refusing input mode on a dashboard pane"
+            );
+        }
+        is_dashboard
+    }
+
     /// Send a key to the selected agent's pane
     pub fn send_key_to_selected(&self, key: &str) {
         if let Some(selected) = self.table_state.selected()
@@ -697,12 +779,11 @@ impl App {
         let is_stale = self.is_stale(agent);
 
         // Map status enum to icon and color
+        let icons = self.config.status_icons();
         let (icon, base_color, is_working) = match agent.status {
-            Some(AgentStatus::Working) => (self.config.status_icons.working(), Color::Cyan, true),
-            Some(AgentStatus::Waiting) => {
-                (self.config.status_icons.waiting(), Color::Magenta, false)
-            }
-            Some(AgentStatus::Done) => (self.config.status_icons.done(), Color::Green, false),
+            Some(AgentStatus::Working) => (icons.working(), Color::Cyan, true),
+            Some(AgentStatus::Waiting) => (icons.waiting(), Color::Magenta, false),
+            Some(AgentStatus::Done) => (icons.done(), Color::Green, false),
             None => ("", self.palette.text, false),
         };
 
@@ -727,6 +808,7 @@ impl App {
             &agent_pane.session,
             &agent_pane.window_name,
             self.config.window_prefix(),
+            &agent_pane.path,
         )
     }
 
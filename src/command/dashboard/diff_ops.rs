@@ -8,6 +8,8 @@
 
 use std::io::Write;
 
+use crate::multiplexer::agent;
+
 use super::ansi::parse_ansi_to_lines;
 use super::app::{App, ViewMode};
 use super::diff::{
@@ -354,7 +356,10 @@ impl DiffOps for App {
         );
 
         // Use paste_multiline to properly handle newlines in the message
-        let _ = self.mux.paste_multiline(&diff.pane_id, &message);
+        let input = agent::resolve_input_profile(self.config.agent.as_deref(), &self.config);
+        let _ = self
+            .mux
+            .paste_multiline(&diff.pane_id, &message, input.bracketed_paste);
         // Send an additional Enter to submit the comment to the agent
         let _ = self.mux.send_key(&diff.pane_id, "Enter");
     }
@@ -517,7 +522,7 @@ impl DiffOps for App {
             let _ = self.mux.send_keys_to_agent(
                 &diff.pane_id,
                 self.config.dashboard.commit(),
-                self.config.agent.as_deref(),
+                &agent::resolve_input_profile(self.config.agent.as_deref(), &self.config),
             );
         }
         self.close_diff();
@@ -540,7 +545,7 @@ impl DiffOps for App {
             let _ = self.mux.send_keys_to_agent(
                 &diff.pane_id,
                 self.config.dashboard.merge(),
-                self.config.agent.as_deref(),
+                &agent::resolve_input_profile(self.config.agent.as_deref(), &self.config),
             );
         }
         self.close_diff();
@@ -560,7 +565,7 @@ impl DiffOps for App {
             let _ = self.mux.send_keys_to_agent(
                 &agent.pane_id,
                 self.config.dashboard.commit(),
-                self.config.agent.as_deref(),
+                &agent::resolve_input_profile(self.config.agent.as_deref(), &self.config),
             );
         }
     }
@@ -579,7 +584,7 @@ impl DiffOps for App {
             let _ = self.mux.send_keys_to_agent(
                 &agent.pane_id,
                 self.config.dashboard.merge(),
-                self.config.agent.as_deref(),
+                &agent::resolve_input_profile(self.config.agent.as_deref(), &self.config),
             );
         }
     }
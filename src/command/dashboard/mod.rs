@@ -14,9 +14,11 @@
 //! - `ansi`: ANSI escape sequence parsing and stripping
 //! - `diff`: Diff domain types and helper functions
 //! - `keymap`: Key-to-action mapping per context with help text
+//! - `refresh`: Agent list refresh cadence (fixed or adaptive)
 //! - `settings`: Tmux-persisted dashboard settings
 //! - `sort`: Sort mode enum and tmux persistence
 //! - `spinner`: Spinner animation constants
+//! - `status_watch`: Infers agent status from pane output
 //! - `ui/`: TUI rendering modules
 //!   - `dashboard`: Table, preview, and footer
 //!   - `diff`: Normal diff, patch mode, file list
@@ -30,9 +32,11 @@ mod app;
 mod diff;
 mod diff_ops;
 mod keymap;
+mod refresh;
 mod settings;
 mod sort;
 mod spinner;
+mod status_watch;
 mod ui;
 
 use anyhow::Result;
@@ -53,6 +57,7 @@ use self::actions::apply_action;
 use self::app::{App, ViewMode};
 use self::diff_ops::DiffOps;
 use self::keymap::{Context, action_for_key};
+use self::refresh::RefreshSchedule;
 use self::spinner::SPINNER_FRAME_COUNT;
 use self::ui::ui;
 
@@ -107,7 +112,8 @@ fn handle_mouse_event(app: &mut App, kind: MouseEventKind) {
 }
 
 pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
 
     // Check if multiplexer is running
     if !mux.is_running().unwrap_or(false) {
@@ -142,7 +148,11 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
     // Main loop
     let tick_rate = Duration::from_millis(250);
     let mut last_tick = std::time::Instant::now();
-    let refresh_interval = Duration::from_secs(2);
+    let mut refresh_schedule = RefreshSchedule::new(
+        app.config.dashboard.refresh_interval(),
+        app.config.dashboard.adaptive_refresh(),
+        std::time::Instant::now(),
+    );
     let mut last_refresh = std::time::Instant::now();
     // Preview refreshes more frequently than the agent list
     // Use a faster refresh rate when in input mode for responsive typing feedback
@@ -179,6 +189,9 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
                 continue;
             }
 
+            // The user is active, so keep the list fresh
+            refresh_schedule.reset(std::time::Instant::now());
+
             // Help overlay handling - close on any key if open
             if app.show_help {
                 app.show_help = false;
@@ -213,17 +226,20 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
             app.spinner_frame = (app.spinner_frame + 1) % SPINNER_FRAME_COUNT;
         }
 
-        // Auto-refresh agent list every 2 seconds
-        if last_refresh.elapsed() >= refresh_interval {
+        // Auto-refresh agent list (every 2 seconds unless configured)
+        if last_refresh.elapsed() >= refresh_schedule.interval() {
             app.refresh();
             last_refresh = std::time::Instant::now();
+            refresh_schedule.record(app.status_snapshot(), last_refresh);
         }
 
-        // Auto-refresh preview more frequently for live updates
-        // Uses faster refresh rate in input mode (set at top of loop)
+        // Auto-refresh preview more frequently for live updates, capturing in
+        // the background. Uses faster refresh rate in input mode (set at top
+        // of loop)
+        app.consume_preview_updates();
         if app.mux.supports_preview() && last_preview_refresh.elapsed() >= current_preview_interval
         {
-            app.refresh_preview();
+            app.spawn_preview_capture();
             last_preview_refresh = std::time::Instant::now();
         }
 
@@ -0,0 +1,120 @@
+//! Agent list refresh cadence, optionally backing off while nothing changes.
+
+use std::time::{Duration, Instant};
+
+use crate::multiplexer::AgentStatus;
+
+/// How long statuses must stay unchanged before adaptive refresh slows down.
+const IDLE_AFTER: Duration = Duration::from_secs(30);
+
+/// Slowest adaptive interval, as a multiple of the configured interval.
+const MAX_BACKOFF: u32 = 4;
+
+/// Snapshot of every listed agent's status, compared between refreshes.
+pub type StatusSnapshot = Vec<(String, Option<AgentStatus>)>;
+
+/// When to refresh the agent list next.
+///
+/// With adaptive refresh, the interval doubles on each refresh once no
+/// status has changed for [`IDLE_AFTER`], up to [`MAX_BACKOFF`] times the
+/// configured interval. A status transition or user input returns it to the
+/// configured interval.
+pub struct RefreshSchedule {
+    base: Duration,
+    adaptive: bool,
+    interval: Duration,
+    last_change: Instant,
+    last_snapshot: Option<StatusSnapshot>,
+}
+
+impl RefreshSchedule {
+    pub fn new(base: Duration, adaptive: bool, now: Instant) -> Self {
+        Self {
+            base,
+            adaptive,
+            interval: base,
+            last_change: now,
+            last_snapshot: None,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Record the statuses seen by a refresh and pick the next interval.
+    pub fn record(&mut self, snapshot: StatusSnapshot, now: Instant) {
+        if self.last_snapshot.as_ref() != Some(&snapshot) {
+            self.last_snapshot = Some(snapshot);
+            self.reset(now);
+            return;
+        }
+        if self.adaptive && now.saturating_duration_since(self.last_change) >= IDLE_AFTER {
+            self.interval = (self.interval * 2).min(self.base * MAX_BACKOFF);
+        }
+    }
+
+    /// Go back to the configured interval, e.g. when the user is active.
+    pub fn reset(&mut self, now: Instant) {
+        self.interval = self.base;
+        self.last_change = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(2);
+
+    fn snapshot(status: AgentStatus) -> StatusSnapshot {
+        vec![("%1".to_string(), Some(status))]
+    }
+
+    #[test]
+    fn adaptive_backs_off_when_idle_and_recovers_on_change() {
+        let start = Instant::now();
+        let mut schedule = RefreshSchedule::new(BASE, true, start);
+        schedule.record(snapshot(AgentStatus::Working), start);
+        assert_eq!(schedule.interval(), BASE);
+
+        // Unchanged but not idle long enough yet
+        schedule.record(
+            snapshot(AgentStatus::Working),
+            start + Duration::from_secs(10),
+        );
+        assert_eq!(schedule.interval(), BASE);
+
+        let idle = start + IDLE_AFTER;
+        schedule.record(snapshot(AgentStatus::Working), idle);
+        assert_eq!(schedule.interval(), BASE * 2);
+        for _ in 0..5 {
+            schedule.record(snapshot(AgentStatus::Working), idle);
+        }
+        assert_eq!(schedule.interval(), BASE * MAX_BACKOFF);
+
+        schedule.record(snapshot(AgentStatus::Done), idle);
+        assert_eq!(schedule.interval(), BASE);
+    }
+
+    #[test]
+    fn fixed_interval_without_adaptive() {
+        let start = Instant::now();
+        let mut schedule = RefreshSchedule::new(BASE, false, start);
+        schedule.record(snapshot(AgentStatus::Working), start);
+        schedule.record(snapshot(AgentStatus::Working), start + IDLE_AFTER * 2);
+        assert_eq!(schedule.interval(), BASE);
+    }
+
+    #[test]
+    fn reset_returns_to_base_interval() {
+        let start = Instant::now();
+        let mut schedule = RefreshSchedule::new(BASE, true, start);
+        schedule.record(snapshot(AgentStatus::Waiting), start);
+        schedule.record(snapshot(AgentStatus::Waiting), start + IDLE_AFTER);
+        assert_eq!(schedule.interval(), BASE * 2);
+
+        schedule.reset(start + IDLE_AFTER);
+        assert_eq!(schedule.interval(), BASE);
+    }
+}
@@ -0,0 +1,212 @@
+//! Status watcher: infers agent status from pane output.
+//!
+//! Not every agent reports its status with `workmux set-window-status`.
+//! While the dashboard is open and `dashboard.status_watch` is enabled, a
+//! background thread captures agent panes and matches the agents'
+//! `status_patterns` against their latest output.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use regex::Regex;
+use tracing::{debug, warn};
+
+use super::ansi::strip_ansi_escapes;
+use crate::command::set_window_status::apply_status;
+use crate::config::Config;
+use crate::git;
+use crate::multiplexer::agent::resolve_status_patterns;
+use crate::multiplexer::util::contains_dashboard_ui;
+use crate::multiplexer::{AgentStatus, Multiplexer};
+use crate::state::StateStore;
+
+/// How often agent panes are captured and matched.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Lines captured per pane.
+const CAPTURE_LINES: u16 = 40;
+
+/// Non-empty lines at the bottom of a capture that patterns are matched
+/// against, so a prompt that has scrolled up no longer counts.
+const MATCH_LINES: usize = 15;
+
+/// Run the watcher on a background thread for the rest of the process.
+pub fn spawn(mux: Arc<dyn Multiplexer>, config: Config) {
+    std::thread::spawn(move || {
+        let mut watcher = StatusWatcher::default();
+        loop {
+            watcher.poll(&config, mux.as_ref());
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    });
+}
+
+#[derive(Default)]
+struct StatusWatcher {
+    /// Agent of a pane whose foreground command has no patterns, by pane id.
+    pane_agents: HashMap<String, String>,
+    /// Compiled patterns, by agent command.
+    rules: HashMap<String, Vec<(AgentStatus, Regex)>>,
+}
+
+impl StatusWatcher {
+    /// Capture every agent pane once and apply the status its output implies.
+    fn poll(&mut self, config: &Config, mux: &dyn Multiplexer) {
+        let panes = match mux.get_all_live_pane_info() {
+            Ok(panes) => panes,
+            Err(e) => {
+                debug!(error = %e, "status_watch:failed to list panes");
+                return;
+            }
+        };
+
+        // A pane is watched when its foreground command has status patterns,
+        // built in or from `profiles.<agent>.status_patterns`. Agents that
+        // already report status are watched too, whatever runs in the
+        // foreground (some agents show up as "node" or a version number).
+        let instance = mux.instance_id();
+        let stored: HashMap<String, Option<AgentStatus>> = StateStore::new()
+            .and_then(|store| store.list_all_agents())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|a| a.pane_key.backend == mux.name() && a.pane_key.instance == instance)
+            .map(|a| (a.pane_key.pane_id, a.status))
+            .collect();
+
+        for (pane_id, info) in panes {
+            let command = info.current_command.unwrap_or_default();
+            let agent = if !command.is_empty() && !self.rules_for(&command, config).is_empty() {
+                command
+            } else if stored.contains_key(&pane_id) {
+                self.pane_agents
+                    .entry(pane_id.clone())
+                    .or_insert_with(|| worktree_agent(&info.working_dir, config))
+                    .clone()
+            } else {
+                continue;
+            };
+
+            let rules = self.rules_for(&agent, config);
+            if rules.is_empty() {
+                continue;
+            }
+            let Some(content) = mux.capture_pane(&pane_id, CAPTURE_LINES) else {
+                continue;
+            };
+            let content = strip_ansi_escapes(&content);
+            if contains_dashboard_ui(&content) {
+                continue;
+            }
+
+            let Some(status) = infer_status(rules, &content) else {
+                continue;
+            };
+            if stored.get(&pane_id).copied().flatten() == Some(status) {
+                continue;
+            }
+
+            debug!(pane_id, agent, ?status, "status_watch:status changed");
+            if let Err(e) = apply_status(config, mux, &pane_id, status, Some(&info.working_dir)) {
+                warn!(pane_id, error = %e, "status_watch:failed to set status");
+            }
+        }
+    }
+
+    /// Compiled patterns for an agent. Invalid regexes are skipped with a
+    /// warning, once per agent.
+    fn rules_for(&mut self, agent: &str, config: &Config) -> &[(AgentStatus, Regex)] {
+        self.rules.entry(agent.to_string()).or_insert_with(|| {
+            resolve_status_patterns(Some(agent), config)
+                .into_iter()
+                .filter_map(|(status, pattern)| match Regex::new(&pattern) {
+                    Ok(re) => Some((status, re)),
+                    Err(e) => {
+                        warn!(agent, pattern, error = %e, "status_watch:invalid status pattern");
+                        None
+                    }
+                })
+                .collect()
+        })
+    }
+}
+
+/// The agent a worktree was created with, else the configured agent.
+fn worktree_agent(working_dir: &Path, config: &Config) -> String {
+    git::worktree_handle_for(working_dir)
+        .and_then(|handle| git::get_worktree_agent(&handle))
+        .or_else(|| config.agent.clone())
+        .unwrap_or_else(|| "claude".to_string())
+}
+
+/// First status whose pattern matches the last non-empty lines of `content`.
+fn infer_status(rules: &[(AgentStatus, Regex)], content: &str) -> Option<AgentStatus> {
+    let lines: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let tail = lines[lines.len().saturating_sub(MATCH_LINES)..].join("\n");
+    rules
+        .iter()
+        .find(|(_, re)| re.is_match(&tail))
+        .map(|(status, _)| *status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<(AgentStatus, Regex)> {
+        vec![
+            (
+                AgentStatus::Waiting,
+                Regex::new(r"Do you want to proceed\?").unwrap(),
+            ),
+            (
+                AgentStatus::Done,
+                Regex::new(r"(?m)^Task complete$").unwrap(),
+            ),
+            (
+                AgentStatus::Working,
+                Regex::new("esc to interrupt").unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn infer_status_uses_precedence() {
+        let content = "✻ Thinking… (esc to interrupt)\n\nDo you want to proceed?\n❯ 1. Yes\n";
+        assert_eq!(infer_status(&rules(), content), Some(AgentStatus::Waiting));
+        assert_eq!(
+            infer_status(&rules(), "✻ Thinking… (esc to interrupt)\n"),
+            Some(AgentStatus::Working)
+        );
+        assert_eq!(
+            infer_status(&rules(), "output\nTask complete\n\n\n"),
+            Some(AgentStatus::Done)
+        );
+        assert_eq!(infer_status(&rules(), "$ ls\nsrc\n"), None);
+    }
+
+    #[test]
+    fn rules_cover_custom_agents_with_status_patterns() {
+        let config: Config = serde_yaml::from_str(
+            "profiles:\n  my-agent:\n    status_patterns:\n      waiting: '^> $'\n",
+        )
+        .unwrap();
+        let mut watcher = StatusWatcher::default();
+        assert_eq!(watcher.rules_for("/usr/bin/my-agent", &config).len(), 1);
+        assert_eq!(watcher.rules_for("claude", &config).len(), 2);
+        assert!(watcher.rules_for("zsh", &config).is_empty());
+    }
+
+    #[test]
+    fn infer_status_ignores_scrolled_off_output() {
+        let mut content = String::from("Do you want to proceed?\n");
+        for i in 0..MATCH_LINES {
+            content.push_str(&format!("line {i}\n\n"));
+        }
+        assert_eq!(infer_status(&rules(), &content), None);
+    }
+}
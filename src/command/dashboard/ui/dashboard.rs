@@ -23,14 +23,14 @@ pub fn render_dashboard(f: &mut Frame, app: &mut App) {
 
     // Layout: table (top), preview (bottom, only if supported), footer
     let chunks = if !supports_preview {
-        // Zellij: no preview section
+        // No preview section (e.g. Zellij without zellij_preview)
         Layout::vertical([
             Constraint::Min(5),    // Table (takes all space except footer)
             Constraint::Length(1), // Footer
         ])
         .split(area)
     } else {
-        // Other multiplexers: include preview
+        // Include preview
         let table_size = 100u16.saturating_sub(app.preview_size as u16);
         Layout::vertical([
             Constraint::Percentage(table_size), // Table (top)
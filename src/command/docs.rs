@@ -2,7 +2,7 @@ use anyhow::Result;
 
 const README: &str = include_str!("../../README.md");
 
-pub fn run() -> Result<()> {
-    crate::markdown::display(README, README);
+pub fn run(options: &crate::markdown::DisplayOptions) -> Result<()> {
+    crate::markdown::display(README, README, options);
     Ok(())
 }
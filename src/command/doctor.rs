@@ -0,0 +1,285 @@
+use anyhow::Result;
+use console::style;
+use std::process::{Command, Stdio};
+
+use crate::agent_setup::{self, StatusCheck};
+use crate::config::{Config, SandboxBackend};
+use crate::git;
+use crate::multiplexer::{Multiplexer, create_backend, detect_backend};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+struct Check {
+    level: Level,
+    label: &'static str,
+    detail: String,
+}
+
+impl Check {
+    fn new(level: Level, label: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            level,
+            label,
+            detail: detail.into(),
+        }
+    }
+
+    fn print(&self) {
+        let icon = match self.level {
+            Level::Pass => style("✓").green(),
+            Level::Warn => style("!").yellow(),
+            Level::Fail => style("✗").red(),
+        };
+        println!("  {} {}: {}", icon, self.label, self.detail);
+    }
+}
+
+/// Check for a sandbox tool. A missing tool only fails when the configured
+/// sandbox backend needs it.
+fn tool_check(label: &'static str, found: bool, required: bool) -> Check {
+    match (found, required) {
+        (true, _) => Check::new(Level::Pass, label, "found"),
+        (false, true) => Check::new(Level::Fail, label, "not found (required by sandbox config)"),
+        (false, false) => Check::new(Level::Warn, label, "not found"),
+    }
+}
+
+/// Check for the container runtime sandboxes would use. Missing only fails
+/// when the container backend is in use.
+fn container_runtime_check(runtime: Option<&str>, required: bool) -> Check {
+    const LABEL: &str = "container runtime";
+    match (runtime, required) {
+        (Some(binary), _) => Check::new(Level::Pass, LABEL, format!("{} found", binary)),
+        (None, true) => Check::new(
+            Level::Fail,
+            LABEL,
+            "neither docker nor podman found (required by sandbox config)",
+        ),
+        (None, false) => Check::new(Level::Pass, LABEL, "none found (not needed)"),
+    }
+}
+
+/// Check that the window status format shows workmux status, for backends
+/// that use one.
+fn status_format_check(config: &Config, mux: &dyn Multiplexer) -> Check {
+    const LABEL: &str = "status format";
+    let Some(pane_id) = mux.active_pane_id() else {
+        return Check::new(
+            Level::Warn,
+            LABEL,
+            format!("not checked (not inside a {} pane)", mux.name()),
+        );
+    };
+    match mux.status_format_installed(&pane_id) {
+        Ok(None) => Check::new(Level::Pass, LABEL, format!("not used by {}", mux.name())),
+        Ok(Some(true)) => Check::new(Level::Pass, LABEL, "installed"),
+        Ok(Some(false)) if config.status_format.unwrap_or(true) => Check::new(
+            Level::Warn,
+            LABEL,
+            "not in this window (workmux adds it to the windows it creates)",
+        ),
+        Ok(Some(false)) => Check::new(
+            Level::Warn,
+            LABEL,
+            "not installed (status_format is disabled; add #{@workmux_status} to your window format)",
+        ),
+        Err(e) => Check::new(Level::Warn, LABEL, format!("could not check: {}", e)),
+    }
+}
+
+fn status_tracking_check(agent: &agent_setup::AgentCheck) -> Check {
+    let (level, detail) = match &agent.status {
+        StatusCheck::Installed => (Level::Pass, "installed".to_string()),
+        StatusCheck::NotInstalled => (
+            Level::Warn,
+            "not installed (run `workmux setup`)".to_string(),
+        ),
+        StatusCheck::Error(e) => (Level::Warn, format!("could not check: {}", e)),
+    };
+    Check::new(level, agent.agent.name(), detail)
+}
+
+fn git_check() -> Check {
+    match git::is_git_repo() {
+        Ok(true) => Check::new(Level::Pass, "git", "inside a git repository"),
+        Ok(false) => Check::new(Level::Fail, "git", "not inside a git repository"),
+        Err(e) => Check::new(Level::Fail, "git", format!("failed to run git: {}", e)),
+    }
+}
+
+fn multiplexer_check(mux: &dyn Multiplexer) -> Check {
+    match mux.is_running() {
+        Ok(true) => Check::new(
+            Level::Pass,
+            "multiplexer",
+            format!("{} is running", mux.name()),
+        ),
+        Ok(false) => Check::new(
+            Level::Fail,
+            "multiplexer",
+            format!("{} is not running", mux.name()),
+        ),
+        Err(e) => Check::new(
+            Level::Fail,
+            "multiplexer",
+            format!("failed to query {}: {}", mux.name(), e),
+        ),
+    }
+}
+
+fn sandbox_checks(config: &Config) -> Vec<Check> {
+    let sandbox = &config.sandbox;
+    let enabled = sandbox.is_enabled();
+    let uses_lima = enabled && sandbox.backend() == SandboxBackend::Lima;
+    let uses_container = enabled && sandbox.backend() == SandboxBackend::Container;
+    let found = |tool: &str| which::which(tool).is_ok();
+
+    let backend = match sandbox.backend() {
+        SandboxBackend::Container => "container",
+        SandboxBackend::Lima => "lima",
+    };
+    let mut checks = vec![if enabled {
+        Check::new(
+            Level::Pass,
+            "sandbox",
+            format!("enabled ({} backend)", backend),
+        )
+    } else {
+        Check::new(Level::Pass, "sandbox", "disabled")
+    }];
+
+    checks.push(tool_check("limactl", found("limactl"), uses_lima));
+
+    // Same runtime resolution as pane setup, so the image is checked with
+    // the runtime that will run it
+    let runtime = sandbox
+        .runtime()
+        .ok()
+        .map(|runtime| runtime.binary())
+        .filter(|binary| found(binary));
+    checks.push(container_runtime_check(runtime, uses_container));
+
+    if uses_container && let Some(runtime) = runtime {
+        let agent = crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name();
+        let image = sandbox.resolved_image(agent);
+        let exists = Command::new(runtime)
+            .args(["image", "inspect", &image])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        checks.push(if exists {
+            Check::new(Level::Pass, "sandbox image", image)
+        } else {
+            Check::new(
+                Level::Fail,
+                "sandbox image",
+                format!(
+                    "{} not found (run `workmux sandbox pull` or `workmux sandbox build`)",
+                    image
+                ),
+            )
+        });
+    }
+
+    checks
+}
+
+fn has_failures(checks: &[Check]) -> bool {
+    checks.iter().any(|check| check.level == Level::Fail)
+}
+
+pub fn run() -> Result<()> {
+    let mut checks = Vec::new();
+
+    let config = match Config::load(None) {
+        Ok(config) => {
+            checks.push(Check::new(Level::Pass, "config", "loaded"));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::new(Level::Fail, "config", format!("{:#}", e)));
+            Config::default()
+        }
+    };
+
+    checks.push(git_check());
+    let mux = create_backend(detect_backend(&config), &config);
+    let mux_check = multiplexer_check(mux.as_ref());
+    let mux_running = mux_check.level == Level::Pass;
+    checks.push(mux_check);
+    if mux_running {
+        checks.push(status_format_check(&config, mux.as_ref()));
+    }
+    checks.extend(sandbox_checks(&config));
+
+    let agents = agent_setup::check_all();
+    if agents.is_empty() {
+        checks.push(Check::new(
+            Level::Warn,
+            "status tracking",
+            "no supported agent CLI detected",
+        ));
+    }
+    checks.extend(agents.iter().map(status_tracking_check));
+
+    println!();
+    for check in &checks {
+        check.print();
+    }
+    println!();
+
+    if has_failures(&checks) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_check_fails_only_when_required() {
+        assert_eq!(tool_check("limactl", true, true).level, Level::Pass);
+        assert_eq!(tool_check("limactl", false, false).level, Level::Warn);
+        assert_eq!(tool_check("limactl", false, true).level, Level::Fail);
+    }
+
+    #[test]
+    fn container_runtime_missing_fails_only_when_required() {
+        let found = container_runtime_check(Some("podman"), true);
+        assert_eq!(found.level, Level::Pass);
+        assert!(found.detail.contains("podman"));
+        assert_eq!(container_runtime_check(None, false).level, Level::Pass);
+        assert_eq!(container_runtime_check(None, true).level, Level::Fail);
+    }
+
+    #[test]
+    fn status_tracking_missing_is_a_warning() {
+        let check = status_tracking_check(&agent_setup::AgentCheck {
+            agent: agent_setup::Agent::Claude,
+            reason: "found claude",
+            status: StatusCheck::NotInstalled,
+        });
+        assert_eq!(check.level, Level::Warn);
+        assert!(check.detail.contains("workmux setup"));
+    }
+
+    #[test]
+    fn has_failures_ignores_warnings() {
+        let warn = Check::new(Level::Warn, "limactl", "not found");
+        let fail = Check::new(Level::Fail, "git", "not inside a git repository");
+        assert!(!has_failures(&[warn]));
+        assert!(has_failures(&[
+            Check::new(Level::Pass, "config", "loaded"),
+            fail
+        ]));
+    }
+}
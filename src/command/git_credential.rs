@@ -0,0 +1,97 @@
+//! Git credential helper that asks the host for credentials via RPC.
+//!
+//! Registered as `credential.helper` inside sandbox guests so HTTPS pushes
+//! use the host's credentials without copying them into the guest.
+
+use anyhow::{Result, bail};
+use std::io::{BufRead, Write};
+use tracing::debug;
+
+use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
+
+/// Handle one credential helper invocation (`get`, `store` or `erase`).
+///
+/// Only `get` reaches the host; the guest never modifies the host's store.
+/// Failures print nothing so git falls back to its other helpers.
+pub fn run(operation: &str) -> Result<()> {
+    if !crate::sandbox::guest::is_sandbox_guest() {
+        bail!("git-credential only works inside a sandbox guest (WM_SANDBOX_GUEST=1)");
+    }
+
+    let stdin = std::io::stdin();
+    let Some(host) = read_https_host(&mut stdin.lock())? else {
+        return Ok(());
+    };
+    if operation != "get" {
+        return Ok(());
+    }
+
+    let mut client = RpcClient::from_env()?;
+    match client.call(&RpcRequest::GitCredential {
+        operation: operation.to_string(),
+        host,
+    })? {
+        RpcResponse::GitCredential { username, password } => {
+            let mut stdout = std::io::stdout().lock();
+            if !username.is_empty() {
+                writeln!(stdout, "username={}", username)?;
+            }
+            writeln!(stdout, "password={}", password)?;
+        }
+        RpcResponse::Error { message, .. } => debug!(message, "host git credential lookup failed"),
+        other => debug!(?other, "unexpected git credential response"),
+    }
+    Ok(())
+}
+
+/// Read the credential description git writes on stdin (`key=value` lines
+/// ending with a blank line) and return the host for HTTPS requests.
+fn read_https_host(input: &mut impl BufRead) -> Result<Option<String>> {
+    let mut protocol = None;
+    let mut host = None;
+    let mut line = String::new();
+    while input.read_line(&mut line)? > 0 {
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        match trimmed.split_once('=') {
+            Some(("protocol", value)) => protocol = Some(value.to_string()),
+            Some(("host", value)) => host = Some(value.to_string()),
+            _ => {}
+        }
+        line.clear();
+    }
+    Ok(host.filter(|_| protocol.as_deref() == Some("https")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn host(input: &str) -> Option<String> {
+        read_https_host(&mut Cursor::new(input.as_bytes().to_vec())).unwrap()
+    }
+
+    #[test]
+    fn reads_https_host() {
+        assert_eq!(
+            host("protocol=https\nhost=github.com\npath=org/repo.git\n\n"),
+            Some("github.com".to_string())
+        );
+        // EOF without a blank line also ends the description
+        assert_eq!(
+            host("host=gitlab.com\nprotocol=https"),
+            Some("gitlab.com".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_non_https_and_missing_host() {
+        assert_eq!(host("protocol=http\nhost=github.com\n\n"), None);
+        assert_eq!(host("protocol=https\n\n"), None);
+        // Fields after the blank line are not part of the request
+        assert_eq!(host("protocol=https\n\nhost=github.com\n"), None);
+    }
+}
@@ -0,0 +1,36 @@
+//! Periodic heartbeats from a sandboxed agent to the host supervisor.
+//!
+//! Started in the background next to the agent command so the host can tell
+//! the agent is still running (see `AgentState::last_heartbeat`).
+
+use anyhow::{Result, bail};
+use std::time::Duration;
+
+use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
+
+/// Send a heartbeat every `interval_secs` until the parent shell exits or
+/// the supervisor goes away.
+pub fn run(interval_secs: u64) -> Result<()> {
+    if !crate::sandbox::guest::is_sandbox_guest() {
+        bail!("heartbeat only works inside a sandbox guest (WM_SANDBOX_GUEST=1)");
+    }
+    if interval_secs == 0 {
+        return Ok(());
+    }
+
+    // The wrapper shell exits with the agent, which reparents this process
+    let parent = std::os::unix::process::parent_id();
+    let mut client = RpcClient::from_env()?;
+
+    loop {
+        match client.call(&RpcRequest::Heartbeat)? {
+            RpcResponse::Ok => {}
+            RpcResponse::Error { message, .. } => bail!("Heartbeat rejected: {}", message),
+            _ => {}
+        }
+        std::thread::sleep(Duration::from_secs(interval_secs));
+        if std::os::unix::process::parent_id() != parent {
+            return Ok(());
+        }
+    }
+}
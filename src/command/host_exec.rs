@@ -3,24 +3,51 @@
 //! Used by guest shims to transparently proxy toolchain commands
 //! (just, cargo, npm, etc.) to the host machine.
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 use std::io::Write;
 
 use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
 
+/// When set (and not `0`), log each forwarded command to stderr.
+const DEBUG_ENV: &str = "WM_HOSTEXEC_DEBUG";
+
+/// Seconds before the host kills the command, overriding
+/// `sandbox.host_exec_timeout`. 0 disables the timeout.
+const TIMEOUT_ENV: &str = "WM_HOSTEXEC_TIMEOUT";
+
 /// Run a command on the host and stream output to local stdout/stderr.
 /// Returns the remote process exit code.
-pub fn run(command: &str, args: &[String]) -> Result<i32> {
+///
+/// With `dry_run`, prints the command that would be forwarded and exits
+/// without contacting the host.
+pub fn run(command: &str, args: &[String], dry_run: bool) -> Result<i32> {
+    let line = command_line(command, args);
+    if dry_run {
+        println!("{}", line);
+        return Ok(0);
+    }
+
     if !crate::sandbox::guest::is_sandbox_guest() {
         bail!("host-exec only works inside a sandbox guest (WM_SANDBOX_GUEST=1)");
     }
 
+    // The host enforces the allowlist; reject malformed names (e.g. from an
+    // odd symlink) before making a round trip
+    if !crate::sandbox::shims::validate_command_name(command) {
+        bail!("host-exec: invalid command name '{}'", command);
+    }
+
+    if debug_enabled(std::env::var(DEBUG_ENV).ok().as_deref()) {
+        eprintln!("workmux host-exec: {}", line);
+    }
+
     let mut client = RpcClient::from_env()?;
 
     // Send exec request
     let request = RpcRequest::Exec {
         command: command.to_string(),
         args: args.to_vec(),
+        timeout_secs: timeout_override()?,
     };
     client.send(&request)?;
 
@@ -42,7 +69,7 @@ pub fn run(command: &str, args: &[String]) -> Result<i32> {
             RpcResponse::ExecExit { code } => {
                 return Ok(code);
             }
-            RpcResponse::Error { message } => {
+            RpcResponse::Error { message, .. } => {
                 bail!("Host exec failed: {}", message);
             }
             _ => {
@@ -51,3 +78,64 @@ pub fn run(command: &str, args: &[String]) -> Result<i32> {
         }
     }
 }
+
+/// Per-command timeout from `WM_HOSTEXEC_TIMEOUT`, in seconds.
+fn timeout_override() -> Result<Option<u64>> {
+    parse_timeout(std::env::var(TIMEOUT_ENV).ok().as_deref())
+}
+
+fn parse_timeout(value: Option<&str>) -> Result<Option<u64>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => v.parse().map(Some).map_err(|_| {
+            anyhow!(
+                "{} must be a number of seconds (0 disables the timeout), got '{}'",
+                TIMEOUT_ENV,
+                v
+            )
+        }),
+    }
+}
+
+/// The forwarded command as a shell-quoted line, for logging.
+fn command_line(command: &str, args: &[String]) -> String {
+    std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .map(crate::shell::shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn debug_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_quotes_args() {
+        let args = vec!["test".to_string(), "--".to_string(), "my test".to_string()];
+        assert_eq!(command_line("cargo", &args), "cargo test -- 'my test'");
+        assert_eq!(command_line("just", &[]), "just");
+    }
+
+    #[test]
+    fn parse_timeout_values() {
+        assert_eq!(parse_timeout(None).unwrap(), None);
+        assert_eq!(parse_timeout(Some("")).unwrap(), None);
+        assert_eq!(parse_timeout(Some("600")).unwrap(), Some(600));
+        assert_eq!(parse_timeout(Some("0")).unwrap(), Some(0));
+        assert!(parse_timeout(Some("10m")).is_err());
+    }
+
+    #[test]
+    fn debug_enabled_values() {
+        assert!(debug_enabled(Some("1")));
+        assert!(debug_enabled(Some("true")));
+        assert!(!debug_enabled(Some("0")));
+        assert!(!debug_enabled(Some("")));
+        assert!(!debug_enabled(None));
+    }
+}
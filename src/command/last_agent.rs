@@ -11,7 +11,8 @@ use crate::state::StateStore;
 /// Updates last_pane_id to the current pane after successful switch,
 /// but only if the current pane is also an agent pane.
 pub fn run() -> Result<()> {
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
     let store = StateStore::new()?;
 
     // Load agents to verify panes are actually agent panes
@@ -1,16 +1,19 @@
 use anyhow::Result;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::debug;
 
 use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
-use crate::state::StateStore;
+use crate::state::{DoneEntry, StateStore};
 
 /// Switch to the agent that most recently completed its task.
 ///
 /// Finds all agents with "done" status from the StateStore and switches to the
 /// one with the most recent timestamp. Cycles through completed agents on
-/// repeated invocations.
+/// repeated invocations. With `last_done_window` set, agents that completed
+/// longer ago than the window are skipped.
 pub fn run() -> Result<()> {
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
     let store = StateStore::new()?;
 
     // Read agent state directly from disk without validating against tmux.
@@ -39,6 +42,40 @@ pub fn run() -> Result<()> {
     // Sort by timestamp descending (most recent first)
     done_agents.sort_by(|a, b| b.status_ts.cmp(&a.status_ts));
 
+    // Prefer the done-stack order when available: it is updated under a lock,
+    // so agents finishing within the same second keep their completion order.
+    // Panes missing from the stack keep their timestamp order after it.
+    let stack = done_agents
+        .first()
+        .and_then(|agent| store.done_entries(&agent.pane_key).ok())
+        .unwrap_or_default();
+    if !stack.is_empty() {
+        done_agents.sort_by_key(|a| {
+            stack
+                .iter()
+                .rev()
+                .position(|entry| entry.pane_id == a.pane_key.pane_id)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    if let Some(window) = config.last_done_window()? {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        done_agents.retain(|a| {
+            let done_ts = completed_at(&stack, &a.pane_key.pane_id, a.status_ts);
+            is_within_window(done_ts, window, now)
+        });
+        debug!(count = done_agents.len(), ?window, "recent done agents");
+
+        if done_agents.is_empty() {
+            println!("No recent completed agents");
+            return Ok(());
+        }
+    }
+
     // Get current pane to determine where we are in the cycle
     // Use active_pane_id() instead of current_pane_id() - env var is stale in run-shell
     let current_pane = mux.active_pane_id();
@@ -70,3 +107,48 @@ pub fn run() -> Result<()> {
     println!("No active completed agents found");
     Ok(())
 }
+
+/// When a pane completed: its done-stack timestamp, or the agent's
+/// `status_ts` when it isn't on the stack.
+fn completed_at(stack: &[DoneEntry], pane_id: &str, status_ts: Option<u64>) -> Option<u64> {
+    stack
+        .iter()
+        .find(|entry| entry.pane_id == pane_id)
+        .map(|entry| entry.done_ts)
+        .or(status_ts)
+}
+
+/// Whether a completion at `done_ts` falls within `window` of `now`.
+/// Unknown completion times count as outside the window.
+fn is_within_window(done_ts: Option<u64>, window: Duration, now: u64) -> bool {
+    done_ts.is_some_and(|ts| now.saturating_sub(ts) <= window.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pane_id: &str, done_ts: u64) -> DoneEntry {
+        DoneEntry {
+            pane_id: pane_id.to_string(),
+            done_ts,
+        }
+    }
+
+    #[test]
+    fn completed_at_prefers_stack_timestamp() {
+        let stack = vec![entry("%1", 300), entry("%2", 500)];
+        assert_eq!(completed_at(&stack, "%2", Some(100)), Some(500));
+        // Off the stack falls back to status_ts
+        assert_eq!(completed_at(&stack, "%3", Some(100)), Some(100));
+        assert_eq!(completed_at(&stack, "%3", None), None);
+    }
+
+    #[test]
+    fn window_excludes_stale_and_unknown() {
+        let window = Duration::from_secs(3600);
+        assert!(is_within_window(Some(1000), window, 1000 + 3600));
+        assert!(!is_within_window(Some(1000), window, 1000 + 3601));
+        assert!(!is_within_window(None, window, 1000));
+    }
+}
@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::config;
+use crate::config::{self, SandboxBackend};
+use crate::git::{Forge, SandboxRecord};
+use crate::github::CheckState;
 use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
-use crate::workflow::types::AgentStatusSummary;
+use crate::workflow::types::{AgentStatusSummary, WorktreeInfo};
 use crate::{nerdfont, workflow};
 use anyhow::Result;
 use pathdiff::diff_paths;
+use serde::Serialize;
 use tabled::{
     Table, Tabled,
     settings::{Padding, Style, disable::Remove, object::Columns},
@@ -17,17 +23,62 @@ struct WorktreeRow {
     branch: String,
     #[tabled(rename = "PR")]
     pr_status: String,
+    #[tabled(rename = "CHECKS")]
+    checks_status: String,
     #[tabled(rename = "AGENT")]
     agent_status: String,
     #[tabled(rename = "MUX")]
     mux_status: String,
+    #[tabled(rename = "SANDBOX")]
+    sandbox_status: String,
     #[tabled(rename = "UNMERGED")]
     unmerged_status: String,
+    #[tabled(rename = "LAST COMMIT")]
+    last_commit: String,
     #[tabled(rename = "PATH")]
     path_str: String,
 }
 
-fn format_pr_status(pr_info: Option<crate::github::PrSummary>) -> String {
+/// One worktree in `list --json` output. Field names are a stable interface.
+#[derive(Serialize)]
+struct ListEntry {
+    branch: String,
+    path: PathBuf,
+    /// Whether the worktree has an open multiplexer window
+    has_tmux: bool,
+    has_unmerged: bool,
+    /// Only present with `--pr`; null when the branch has no PR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pr_info: Option<Option<ListPrInfo>>,
+}
+
+#[derive(Serialize)]
+struct ListPrInfo {
+    number: u32,
+    state: String,
+    is_draft: bool,
+}
+
+fn json_entries(worktrees: Vec<WorktreeInfo>, show_pr: bool) -> Vec<ListEntry> {
+    worktrees
+        .into_iter()
+        .map(|wt| ListEntry {
+            branch: wt.branch,
+            path: wt.path,
+            has_tmux: wt.has_mux_window,
+            has_unmerged: wt.has_unmerged,
+            pr_info: show_pr.then(|| {
+                wt.pr_info.map(|pr| ListPrInfo {
+                    number: pr.number,
+                    state: pr.state,
+                    is_draft: pr.is_draft,
+                })
+            }),
+        })
+        .collect()
+}
+
+fn format_pr_status(pr_info: Option<crate::github::PrSummary>, forge: Forge) -> String {
     pr_info
         .map(|pr| {
             let icons = nerdfont::pr_icons();
@@ -39,25 +90,52 @@ fn format_pr_status(pr_info: Option<crate::github::PrSummary>) -> String {
                 "CLOSED" => (icons.closed, "\x1b[31m"),             // red
                 _ => (icons.open, "\x1b[32m"),
             };
-            format!("#{} {}{}\x1b[0m", pr.number, color, icon)
+            format!(
+                "{}{} {}{}\x1b[0m",
+                forge.number_prefix(),
+                pr.number,
+                color,
+                icon
+            )
         })
         .unwrap_or_else(|| "-".to_string())
 }
 
+/// Format the PR's CI checks: a colored icon with passed/total counts,
+/// "none" when the PR has no checks configured, or "-" without a PR or when
+/// the provider doesn't report checks.
+fn format_checks_status(pr_info: Option<&crate::github::PrSummary>, forge: Forge) -> String {
+    let Some(pr) = pr_info.filter(|_| forge.reports_checks()) else {
+        return "-".to_string();
+    };
+    let icons = nerdfont::check_icons();
+    match &pr.checks {
+        None => "\x1b[90mnone\x1b[0m".to_string(),
+        Some(CheckState::Success) => format!("\x1b[32m{}\x1b[0m", icons.success),
+        Some(CheckState::Failure { passed, total }) => {
+            format!("\x1b[31m{} {}/{}\x1b[0m", icons.failure, passed, total)
+        }
+        Some(CheckState::Pending { passed, total }) => {
+            format!("\x1b[33m{} {}/{}\x1b[0m", icons.pending, passed, total)
+        }
+    }
+}
+
+/// Format the recorded sandbox backend, or "-" if the worktree ran unsandboxed.
+fn format_sandbox_status(record: Option<&SandboxRecord>) -> String {
+    match record {
+        Some(SandboxRecord::On(SandboxBackend::Container)) => "container".to_string(),
+        Some(SandboxRecord::On(SandboxBackend::Lima)) => "lima".to_string(),
+        Some(SandboxRecord::Off) | None => "-".to_string(),
+    }
+}
+
 /// Format a single agent status as either an icon (TTY) or text label (piped).
 fn format_status_label(status: AgentStatus, config: &config::Config, use_icons: bool) -> String {
     if use_icons {
-        match status {
-            AgentStatus::Working => config.status_icons.working().to_string(),
-            AgentStatus::Waiting => config.status_icons.waiting().to_string(),
-            AgentStatus::Done => config.status_icons.done().to_string(),
-        }
+        config.status_icons().icon(status).to_string()
     } else {
-        match status {
-            AgentStatus::Working => "working".to_string(),
-            AgentStatus::Waiting => "waiting".to_string(),
-            AgentStatus::Done => "done".to_string(),
-        }
+        super::status::status_label(Some(status))
     }
 }
 
@@ -109,18 +187,92 @@ fn format_agent_status(
     }
 }
 
-pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
+/// Age in seconds of a worktree's last commit if it counts as stale: no
+/// open multiplexer window, not the main worktree, and no commit within
+/// `window`. Worktrees whose last commit can't be read are never stale.
+fn stale_age(
+    has_mux_window: bool,
+    is_main: bool,
+    last_commit: Option<u64>,
+    window: Duration,
+    now: u64,
+) -> Option<u64> {
+    if has_mux_window || is_main {
+        return None;
+    }
+    let age = now.saturating_sub(last_commit?);
+    (age > window.as_secs()).then_some(age)
+}
+
+/// Format a commit age like "3d ago", "5h ago".
+fn format_commit_age(secs: u64) -> String {
+    const DAY: u64 = 24 * 60 * 60;
+    if secs >= DAY {
+        format!("{}d ago", secs / DAY)
+    } else {
+        format!("{} ago", crate::util::format_elapsed_secs(secs))
+    }
+}
+
+/// Keep only stale worktrees (see [`stale_age`]), returning their last-commit
+/// ages keyed by path.
+fn retain_stale(worktrees: &mut Vec<WorktreeInfo>, window: Duration) -> HashMap<PathBuf, u64> {
+    let main_root = crate::git::get_main_worktree_root().ok();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut ages = HashMap::new();
+    worktrees.retain(|wt| {
+        let is_main = main_root.as_deref() == Some(wt.path.as_path());
+        let last_commit = if wt.has_mux_window || is_main {
+            None
+        } else {
+            crate::git::get_last_commit_time_in(&wt.path).ok()
+        };
+        match stale_age(wt.has_mux_window, is_main, last_commit, window, now) {
+            Some(age) => {
+                ages.insert(wt.path.clone(), age);
+                true
+            }
+            None => false,
+        }
+    });
+    ages
+}
+
+pub fn run(show_pr: bool, json: bool, filter: &[String], stale: Option<Duration>) -> Result<()> {
     let config = config::Config::load(None)?;
-    let mux = create_backend(detect_backend());
-    let worktrees = workflow::list(&config, mux.as_ref(), show_pr, filter)?;
+    let mux = create_backend(detect_backend(&config), &config);
+    // Detected once: picks the CLI for the lookup and the #N/!N prefix
+    let forge = show_pr.then(|| crate::git::detect_forge(config.gitlab_hosts()));
+    let mut worktrees = workflow::list(&config, mux.as_ref(), forge, filter)?;
+
+    let ages = match stale {
+        Some(window) => retain_stale(&mut worktrees, window),
+        None => HashMap::new(),
+    };
+
+    if json {
+        let entries = json_entries(worktrees, show_pr);
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
 
     if worktrees.is_empty() {
-        println!("No worktrees found");
+        if stale.is_some() {
+            println!("No stale worktrees found");
+        } else {
+            println!("No worktrees found");
+        }
         return Ok(());
     }
 
     // Use icons when outputting to a terminal, text labels when piped (for agents)
     let use_icons = std::io::stdout().is_terminal();
+    // GitLab MRs are shown as !N instead of #N
+    let forge = forge.unwrap_or_default();
     let current_dir = std::env::current_dir()?;
 
     let display_data: Vec<WorktreeRow> = worktrees
@@ -139,18 +291,24 @@ pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
 
             WorktreeRow {
                 branch: wt.branch,
-                pr_status: format_pr_status(wt.pr_info),
+                checks_status: format_checks_status(wt.pr_info.as_ref(), forge),
+                pr_status: format_pr_status(wt.pr_info, forge),
                 agent_status: format_agent_status(wt.agent_status.as_ref(), &config, use_icons),
                 mux_status: if wt.has_mux_window {
                     "✓".to_string()
                 } else {
                     "-".to_string()
                 },
+                sandbox_status: format_sandbox_status(wt.sandbox.as_ref()),
                 unmerged_status: if wt.has_unmerged {
                     "●".to_string()
                 } else {
                     "-".to_string()
                 },
+                last_commit: ages
+                    .get(&wt.path)
+                    .map(|age| format_commit_age(*age))
+                    .unwrap_or_else(|| "-".to_string()),
                 path_str,
             }
         })
@@ -159,14 +317,167 @@ pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
     let mut table = Table::new(display_data);
     table
         .with(Style::blank())
-        .modify(Columns::new(0..6), Padding::new(0, 1, 0, 0));
+        .modify(Columns::new(0..9), Padding::new(0, 1, 0, 0));
+
+    // Hide LAST COMMIT column outside --stale mode (column 7). Removed
+    // before the PR columns so its index is still valid.
+    if stale.is_none() {
+        table.with(Remove::column(Columns::new(7..8)));
+    }
 
-    // Hide PR column if --pr flag not used (column 1)
+    // Hide PR and CHECKS columns if --pr flag not used (columns 1-2)
     if !show_pr {
-        table.with(Remove::column(Columns::new(1..2)));
+        table.with(Remove::column(Columns::new(1..3)));
     }
 
     println!("{table}");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_sandbox_status_shows_recorded_backend() {
+        assert_eq!(
+            format_sandbox_status(Some(&SandboxRecord::On(SandboxBackend::Container))),
+            "container"
+        );
+        assert_eq!(
+            format_sandbox_status(Some(&SandboxRecord::On(SandboxBackend::Lima))),
+            "lima"
+        );
+    }
+
+    #[test]
+    fn format_sandbox_status_dash_when_unsandboxed() {
+        assert_eq!(format_sandbox_status(Some(&SandboxRecord::Off)), "-");
+        assert_eq!(format_sandbox_status(None), "-");
+    }
+
+    fn worktree(branch: &str, pr_info: Option<crate::github::PrSummary>) -> WorktreeInfo {
+        WorktreeInfo {
+            branch: branch.to_string(),
+            path: PathBuf::from(format!("/repo__worktrees/{branch}")),
+            has_mux_window: true,
+            has_unmerged: false,
+            pr_info,
+            agent_status: None,
+            sandbox: None,
+        }
+    }
+
+    #[test]
+    fn format_pr_status_uses_forge_number_prefix() {
+        let pr = || crate::github::PrSummary {
+            number: 12,
+            title: "Add feature".to_string(),
+            state: "OPEN".to_string(),
+            is_draft: false,
+            checks: None,
+        };
+        assert!(format_pr_status(Some(pr()), Forge::GitHub).starts_with("#12 "));
+        assert!(format_pr_status(Some(pr()), Forge::GitLab).starts_with("!12 "));
+        assert_eq!(format_pr_status(None, Forge::GitLab), "-");
+    }
+
+    #[test]
+    fn stale_age_requires_no_window_and_old_commit() {
+        let window = Duration::from_secs(7 * 24 * 60 * 60);
+        let now = 100 * 24 * 60 * 60;
+        let old = Some(now - 8 * 24 * 60 * 60);
+        let recent = Some(now - 24 * 60 * 60);
+
+        assert_eq!(
+            stale_age(false, false, old, window, now),
+            Some(8 * 24 * 60 * 60)
+        );
+        assert_eq!(stale_age(false, false, recent, window, now), None);
+        // Open window or main worktree are never stale
+        assert_eq!(stale_age(true, false, old, window, now), None);
+        assert_eq!(stale_age(false, true, old, window, now), None);
+        // Unknown commit time is not stale
+        assert_eq!(stale_age(false, false, None, window, now), None);
+    }
+
+    #[test]
+    fn format_commit_age_uses_days() {
+        assert_eq!(format_commit_age(3 * 24 * 60 * 60 + 5), "3d ago");
+        assert_eq!(format_commit_age(2 * 60 * 60), "2h ago");
+    }
+
+    #[test]
+    fn format_checks_status_distinguishes_none_and_pending() {
+        let pr = |checks| crate::github::PrSummary {
+            number: 1,
+            title: String::new(),
+            state: "OPEN".to_string(),
+            is_draft: false,
+            checks,
+        };
+        let github = Forge::GitHub;
+        assert_eq!(format_checks_status(None, github), "-");
+        assert!(format_checks_status(Some(&pr(None)), github).contains("none"));
+        // GitLab lookups carry no check results, so there is nothing to show
+        assert_eq!(format_checks_status(Some(&pr(None)), Forge::GitLab), "-");
+
+        let pending = format_checks_status(
+            Some(&pr(Some(CheckState::Pending {
+                passed: 2,
+                total: 5,
+            }))),
+            github,
+        );
+        assert!(pending.starts_with("\x1b[33m") && pending.contains("2/5"));
+        let failed = format_checks_status(
+            Some(&pr(Some(CheckState::Failure {
+                passed: 4,
+                total: 5,
+            }))),
+            github,
+        );
+        assert!(failed.starts_with("\x1b[31m") && failed.contains("4/5"));
+        let passed = format_checks_status(Some(&pr(Some(CheckState::Success))), github);
+        assert!(passed.starts_with("\x1b[32m"));
+    }
+
+    #[test]
+    fn json_entries_omit_pr_info_without_pr_flag() {
+        let entries = json_entries(vec![worktree("feature", None)], false);
+        let value = serde_json::to_value(&entries).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "branch": "feature",
+                "path": "/repo__worktrees/feature",
+                "has_tmux": true,
+                "has_unmerged": false,
+            }])
+        );
+    }
+
+    #[test]
+    fn json_entries_include_pr_info_with_pr_flag() {
+        let pr = crate::github::PrSummary {
+            number: 42,
+            title: "Add feature".to_string(),
+            state: "OPEN".to_string(),
+            is_draft: true,
+            checks: None,
+        };
+        let entries = json_entries(
+            vec![worktree("feature", Some(pr)), worktree("bare", None)],
+            true,
+        );
+        let value = serde_json::to_value(&entries).unwrap();
+        assert_eq!(
+            value[0]["pr_info"],
+            serde_json::json!({"number": 42, "state": "OPEN", "is_draft": true})
+        );
+        // Key is present but null when the branch has no PR
+        assert!(value[1]["pr_info"].is_null());
+        assert!(value[1].as_object().unwrap().contains_key("pr_info"));
+    }
+}
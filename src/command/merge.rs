@@ -16,9 +16,11 @@ pub fn run(
     no_hooks: bool,
     notification: bool,
 ) -> Result<()> {
+    let config = config::Config::load(None)?;
+
     // Inside a sandbox guest, route through RPC to the host supervisor
     if crate::sandbox::guest::is_sandbox_guest() {
-        let name_to_merge = super::resolve_name(name)?;
+        let name_to_merge = super::resolve_name(name, &config)?;
         return run_via_rpc(
             &name_to_merge,
             into_branch,
@@ -32,8 +34,6 @@ pub fn run(
         );
     }
 
-    let config = config::Config::load(None)?;
-
     // Apply default strategy from config if no CLI flags are provided
     if !rebase
         && !squash
@@ -48,9 +48,9 @@ pub fn run(
 
     // Resolve name from argument or current directory
     // Note: Must be done BEFORE creating WorkflowContext (which may change CWD)
-    let name_to_merge = super::resolve_name(name)?;
+    let name_to_merge = super::resolve_name(name, &config)?;
 
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(&config), &config);
     let context = WorkflowContext::new(config, mux, None)?;
 
     let skip_hooks = no_verify || no_hooks;
@@ -139,7 +139,7 @@ fn run_via_rpc(
                 std::io::stdout().flush().ok();
             }
             RpcResponse::Ok => return Ok(()),
-            RpcResponse::Error { message } => {
+            RpcResponse::Error { message, .. } => {
                 anyhow::bail!("{}", message);
             }
             _ => {}
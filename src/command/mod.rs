@@ -1,28 +1,42 @@
 pub mod add;
+pub mod add_wizard;
 pub mod args;
+pub mod attach;
 pub mod capture;
 pub mod changelog;
+pub mod clipboard;
 pub mod close;
 pub mod config;
 pub mod dashboard;
 pub mod docs;
+pub mod doctor;
 pub mod exec;
+pub mod git_credential;
+pub mod heartbeat;
 pub mod host_exec;
 pub mod last_agent;
 pub mod last_done;
 pub mod list;
 pub mod merge;
+pub mod notify;
 pub mod open;
+pub mod open_url;
 pub mod path;
+pub mod pick;
+pub mod reconcile;
 pub mod remove;
+pub mod rename;
 pub mod run;
 pub mod sandbox;
+pub mod sandbox_exec;
 pub mod sandbox_run;
 pub mod send;
 pub mod set_base;
 pub mod set_window_status;
 pub mod setup;
+pub mod setup_done;
 pub mod status;
+pub mod switch;
 pub mod wait;
 
 use anyhow::{Context, Result, anyhow};
@@ -70,16 +84,29 @@ pub fn announce_hooks(config: &Config, options: Option<&SetupOptions>, phase: Ho
 
 /// Resolve name from argument or current worktree directory.
 ///
-/// When no argument is provided, extracts the worktree name from the current directory.
+/// When no argument is provided, extracts the worktree name from the current pane's
+/// directory (or the process cwd outside a multiplexer).
 /// If the user is in a subdirectory of a worktree, provides a helpful error message.
-pub fn resolve_name(arg: Option<&str>) -> Result<String> {
+pub fn resolve_name(arg: Option<&str>, config: &Config) -> Result<String> {
     match arg {
         Some(name) => Ok(name.to_string()),
-        None => {
-            let cwd = std::env::current_dir().context("Failed to get current directory")?;
-            resolve_name_from_path(&cwd)
-        }
+        None => resolve_name_from_path(&current_location(config)?),
+    }
+}
+
+/// Directory the user is working in.
+///
+/// Inside a multiplexer pane this is the pane's cwd, which stays correct when
+/// workmux is invoked from a script or keybinding with a different process cwd.
+fn current_location(config: &Config) -> Result<std::path::PathBuf> {
+    let mux =
+        crate::multiplexer::create_backend(crate::multiplexer::detect_backend(config), config);
+    if mux.current_pane_id().is_some()
+        && let Ok(path) = mux.current_pane_cwd()
+    {
+        return Ok(path);
     }
+    std::env::current_dir().context("Failed to get current directory")
 }
 
 /// Internal function to resolve worktree name from a path.
@@ -124,7 +151,10 @@ mod tests {
 
     #[test]
     fn test_resolve_name_with_explicit_arg() {
-        assert_eq!(resolve_name(Some("my-feature")).unwrap(), "my-feature");
+        assert_eq!(
+            resolve_name(Some("my-feature"), &Config::default()).unwrap(),
+            "my-feature"
+        );
     }
 
     #[test]
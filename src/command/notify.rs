@@ -0,0 +1,19 @@
+//! Send notifications, routing through the host inside a sandbox.
+
+use anyhow::Result;
+
+use crate::sandbox::rpc::{RpcClient, RpcRequest};
+
+/// Show a desktop notification with `title` and `body`.
+pub fn desktop(title: &str, body: &str) -> Result<()> {
+    // Inside a sandbox guest, the host supervisor owns the desktop
+    if crate::sandbox::guest::is_sandbox_guest() {
+        let request = RpcRequest::DesktopNotification {
+            title: title.to_string(),
+            body: body.to_string(),
+        };
+        return RpcClient::call_host(&request, "show desktop notifications");
+    }
+
+    crate::notification::send(title, body)
+}
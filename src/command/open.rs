@@ -1,6 +1,6 @@
 use crate::command::args::PromptArgs;
 use crate::config::MuxMode;
-use crate::multiplexer::{create_backend, detect_backend};
+use crate::multiplexer::{create_backend, detect_backend, ensure_forced_backend_running};
 use crate::workflow::prompt_loader::{PromptLoadArgs, load_prompt};
 use crate::workflow::{SetupOptions, WorkflowContext};
 use crate::{config, git, workflow};
@@ -11,19 +11,22 @@ pub fn run(
     run_hooks: bool,
     force_files: bool,
     new_window: bool,
+    rerun_setup: bool,
     prompt_args: PromptArgs,
 ) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+
     // Resolve the worktree name
     let resolved_name = match (name, new_window) {
         (Some(n), _) => n.to_string(),
-        (None, true) => super::resolve_name(None).context(
+        (None, true) => super::resolve_name(None, &config).context(
             "Could not infer current worktree. Run inside a worktree or provide a name.",
         )?,
         (None, false) => bail!("Worktree name is required unless --new is provided"),
     };
 
-    let (config, config_location) = config::Config::load_with_location(None)?;
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(&config), &config);
+    ensure_forced_backend_running(&config, mux.as_ref())?;
     let context = WorkflowContext::new(config, mux, config_location)?;
 
     // Determine the target mode from stored metadata
@@ -63,6 +66,7 @@ pub fn run(
     let mut options = SetupOptions::new(run_hooks, force_files, true);
     options.mode = stored_mode;
     options.prompt_file_path = prompt_file_path;
+    options.rerun_setup = rerun_setup;
 
     // Only announce hooks if we're forcing a new target (otherwise we might just switch)
     if new_window {
@@ -0,0 +1,19 @@
+//! Open a URL in the browser, routing through the host inside a sandbox.
+
+use anyhow::Result;
+
+use crate::sandbox::rpc::{RpcClient, RpcRequest};
+
+pub fn run(url: &str) -> Result<()> {
+    crate::browser::validate_url(url)?;
+
+    // Inside a sandbox guest there is no browser; ask the host supervisor
+    if crate::sandbox::guest::is_sandbox_guest() {
+        let request = RpcRequest::OpenUrl {
+            url: url.to_string(),
+        };
+        return RpcClient::call_host(&request, "open the URL");
+    }
+
+    crate::browser::open(url)
+}
@@ -0,0 +1,91 @@
+//! Fuzzy-pick a running agent and switch to it.
+
+use std::io::IsTerminal;
+
+use anyhow::{Result, bail};
+
+use crate::config::Config;
+use crate::git;
+use crate::multiplexer::{AgentPane, create_backend, detect_backend, ensure_not_dashboard_pane};
+use crate::state::StateStore;
+
+pub fn run() -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        bail!("workmux pick needs an interactive terminal");
+    }
+
+    let config = Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
+    let agents = StateStore::new()?.load_reconciled_agents(mux.as_ref())?;
+    if agents.is_empty() {
+        bail!("No running agents");
+    }
+
+    let labels: Vec<String> = agents
+        .iter()
+        .map(|agent| {
+            let branch = git::get_current_branch_in(&agent.path).unwrap_or_default();
+            agent_label(agent, &branch, &config)
+        })
+        .collect();
+
+    let Some(idx) = crate::ui::pick(&labels, "Type to filter, or enter a number:")? else {
+        return Ok(());
+    };
+    let agent = &agents[idx];
+    ensure_not_dashboard_pane(mux.as_ref(), &agent.pane_id)?;
+    mux.switch_to_pane(&agent.pane_id, Some(agent.window_name.as_str()))
+}
+
+/// `<icon> <window> (<branch>)`, leaving out whatever is unknown.
+fn agent_label(agent: &AgentPane, branch: &str, config: &Config) -> String {
+    let icon = super::status::status_icon(agent.status, config).unwrap_or_default();
+    let mut label = format!("{:<2} {}", icon, agent.window_name);
+    if !branch.is_empty() && !agent.window_name.ends_with(branch) {
+        label.push_str(&format!(" ({})", branch));
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multiplexer::AgentStatus;
+    use std::path::PathBuf;
+
+    fn agent(window: &str, status: Option<AgentStatus>) -> AgentPane {
+        AgentPane {
+            session: "main".to_string(),
+            window_name: window.to_string(),
+            pane_id: "%1".to_string(),
+            path: PathBuf::from("/repo__worktrees/auth"),
+            pane_title: None,
+            status,
+            status_ts: None,
+            command: "claude".to_string(),
+        }
+    }
+
+    #[test]
+    fn agent_label_shows_icon_and_branch() {
+        let mut config = Config::default();
+        config.status_icons.waiting = Some("W".to_string());
+        assert_eq!(
+            agent_label(
+                &agent("wm-auth", Some(AgentStatus::Waiting)),
+                "feat/login",
+                &config
+            ),
+            "W  wm-auth (feat/login)"
+        );
+        // Branch already visible in the window name, or unknown
+        assert_eq!(
+            agent_label(&agent("wm-auth", None), "auth", &config),
+            "   wm-auth"
+        );
+        assert_eq!(
+            agent_label(&agent("wm-auth", None), "", &config),
+            "   wm-auth"
+        );
+    }
+}
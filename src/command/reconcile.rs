@@ -0,0 +1,63 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::state::{AgentState, StateStore};
+
+/// Check every stored agent against the multiplexer and remove the dead ones.
+pub fn run() -> Result<()> {
+    let config = Config::load(None).unwrap_or_default();
+    let mux = create_backend(detect_backend(&config), &config);
+    let reconciled = StateStore::new()?.reconcile_agents(mux.as_ref())?;
+
+    for agent in &reconciled.pruned {
+        println!("✗ pruned {}", pruned_label(agent));
+    }
+    println!(
+        "Kept {} agent(s), pruned {}",
+        reconciled.kept.len(),
+        reconciled.pruned.len()
+    );
+    Ok(())
+}
+
+fn pruned_label(agent: &AgentState) -> String {
+    match agent.window_name.as_deref().filter(|name| !name.is_empty()) {
+        Some(window) => format!(
+            "{} ({}, pane {})",
+            window,
+            agent.workdir.display(),
+            agent.pane_key.pane_id
+        ),
+        None => format!(
+            "{} (pane {})",
+            agent.workdir.display(),
+            agent.pane_key.pane_id
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(window: Option<&str>) -> AgentState {
+        AgentState::for_test("%7", "/repo__worktrees/feature", window)
+    }
+
+    #[test]
+    fn pruned_label_falls_back_to_workdir() {
+        assert_eq!(
+            pruned_label(&agent(Some("wm-feature"))),
+            "wm-feature (/repo__worktrees/feature, pane %7)"
+        );
+        assert_eq!(
+            pruned_label(&agent(Some(""))),
+            "/repo__worktrees/feature (pane %7)"
+        );
+        assert_eq!(
+            pruned_label(&agent(None)),
+            "/repo__worktrees/feature (pane %7)"
+        );
+    }
+}
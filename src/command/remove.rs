@@ -25,13 +25,15 @@ pub fn run(
 
 /// Remove specific worktrees provided by user (or current if empty)
 fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<()> {
+    let config = config::Config::load(None)?;
+
     // Normalize all inputs (handles "." and other special cases)
     let resolved_names: Vec<String> = if names.is_empty() {
-        vec![super::resolve_name(None)?]
+        vec![super::resolve_name(None, &config)?]
     } else {
         names
             .iter()
-            .map(|n| super::resolve_name(Some(n)))
+            .map(|n| super::resolve_name(Some(n), &config))
             .collect::<Result<Vec<_>>>()?
     };
 
@@ -450,7 +452,7 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
 /// Execute the actual worktree removal
 fn remove_worktree(handle: &str, force: bool, keep_branch: bool) -> Result<()> {
     let config = config::Config::load(None)?;
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(&config), &config);
     let context = WorkflowContext::new(config, mux, None)?;
 
     super::announce_hooks(&context.config, None, super::HookPhase::PreRemove);
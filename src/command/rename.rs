@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::config::{Config, MuxMode};
+use crate::multiplexer::util::WindowNames;
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::state::{AgentState, StateStore};
+use crate::{git, naming};
+
+/// Rename a worktree's branch and its window. The worktree directory (and
+/// so the handle) stays put; the window gets the name `add` would give the
+/// new branch, stored in the worktree metadata so later lookups resolve it.
+pub fn run(name: &str, new_branch: &str) -> Result<()> {
+    let config = Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
+    let prefix = config.window_prefix();
+
+    let (worktree_path, branch) = git::find_worktree(name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    let handle = worktree_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid worktree path: no directory name"))?
+        .to_string_lossy()
+        .to_string();
+
+    if git::get_worktree_mode(&handle) == MuxMode::Session {
+        bail!("Renaming session-mode worktrees is not supported");
+    }
+    if branch == new_branch {
+        bail!("Branch is already named '{}'", new_branch);
+    }
+    if git::branch_exists(new_branch)? {
+        bail!("Branch '{}' already exists", new_branch);
+    }
+
+    let old_window = WindowNames::load().full_name(prefix, &handle);
+    let new_window = naming::render_window_name(
+        config.window_name_template.as_deref(),
+        prefix,
+        &naming::derive_handle(new_branch, None, &config)?,
+        new_branch,
+        git::get_worktree_pr(&handle),
+    );
+    let window_open = mux.window_exists_by_full_name(&old_window)?;
+    if new_window != old_window && mux.window_exists_by_full_name(&new_window)? {
+        bail!(
+            "A {} window named '{}' already exists",
+            mux.name(),
+            new_window
+        );
+    }
+
+    // Rename the window first: it is the step most likely to fail, and the
+    // branch rename and metadata are easy to undo. Renaming in place keeps
+    // the panes running, so this also works from inside the window.
+    let rename_open_window = window_open && new_window != old_window;
+    if rename_open_window {
+        mux.rename_window(&old_window, &new_window)?;
+    }
+    let undo_window = || {
+        if rename_open_window && let Err(e) = mux.rename_window(&new_window, &old_window) {
+            eprintln!("Warning: failed to restore window name: {:#}", e);
+        }
+    };
+
+    if let Err(e) = git::rename_branch(&branch, new_branch) {
+        undo_window();
+        return Err(e);
+    }
+    if new_window != old_window
+        && let Err(e) = git::set_worktree_meta(&handle, "window", &new_window)
+    {
+        if let Err(undo) = git::rename_branch(new_branch, &branch) {
+            eprintln!("Warning: failed to restore branch name: {:#}", undo);
+        }
+        undo_window();
+        return Err(e)
+            .with_context(|| format!("Failed to store window name for worktree '{}'", handle));
+    }
+
+    if rename_open_window {
+        let store = StateStore::new()?;
+        for agent in retarget_agents(
+            store.list_all_agents()?,
+            &worktree_path,
+            &old_window,
+            &new_window,
+        ) {
+            store.upsert_agent(&agent)?;
+        }
+    }
+
+    println!(
+        "✓ Renamed branch '{}' to '{}' (window '{}')",
+        branch, new_branch, new_window
+    );
+    Ok(())
+}
+
+/// Agents in the worktree's window, with their stored window name updated.
+fn retarget_agents(
+    agents: Vec<AgentState>,
+    worktree_path: &Path,
+    old_window: &str,
+    new_window: &str,
+) -> Vec<AgentState> {
+    agents
+        .into_iter()
+        .filter(|agent| {
+            agent.window_name.as_deref() == Some(old_window)
+                && agent.workdir.starts_with(worktree_path)
+        })
+        .map(|mut agent| {
+            agent.window_name = Some(new_window.to_string());
+            agent
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(pane_id: &str, workdir: &str, window: &str) -> AgentState {
+        AgentState::for_test(pane_id, workdir, Some(window))
+    }
+
+    #[test]
+    fn retarget_agents_only_touches_the_worktree_window() {
+        let agents = vec![
+            agent("%1", "/repo__worktrees/spike/src", "wm-spike"),
+            agent("%2", "/other__worktrees/spike", "wm-spike"),
+            agent("%3", "/repo__worktrees/spike", "wm-spike-2"),
+        ];
+        let updated = retarget_agents(
+            agents,
+            Path::new("/repo__worktrees/spike"),
+            "wm-spike",
+            "wm-feature",
+        );
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].pane_key.pane_id, "%1");
+        assert_eq!(updated[0].window_name.as_deref(), Some("wm-feature"));
+    }
+}
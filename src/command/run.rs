@@ -36,7 +36,9 @@ pub fn run(
         return Err(anyhow!("No command provided"));
     }
 
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+
+    let mux = create_backend(detect_backend(&config), &config);
 
     // Resolve worktree to agent pane (consistent with send/capture)
     let (worktree_path, agent) = workflow::resolve_worktree_agent(worktree_name, mux.as_ref())?;
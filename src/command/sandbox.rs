@@ -2,16 +2,17 @@
 
 use anyhow::{Context, Result, bail};
 use clap::{Args, Subcommand};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tracing::debug;
 
 use crate::config::Config;
 use crate::sandbox;
 use crate::sandbox::lima;
 use crate::sandbox::lima::{LimaInstance, parse_lima_instances};
+use crate::util::parse_age;
 
 #[derive(Debug, Args)]
 #[command(help_template = "\
@@ -24,6 +25,7 @@ Container commands:
   init-dockerfile  Export customizable Dockerfile templates
 
 Lima commands:
+  start            Boot the Lima VM for a worktree ahead of time
   stop             Stop Lima VMs to free resources
   prune            Delete unused Lima VMs to reclaim disk space
 
@@ -31,6 +33,7 @@ General commands:
   agent            Run an agent inside a sandbox with RPC support
   shell            Start an interactive shell in a sandbox
   install-dev      Cross-compile and install workmux into sandboxes
+  logs             Show the sandbox supervisor log for a worktree
   help             Print this message or the help of the given subcommand(s)
 
 {options}")]
@@ -43,9 +46,21 @@ pub struct SandboxArgs {
 pub enum SandboxCommand {
     /// Build the sandbox container image locally.
     /// Note: a pre-built image is available via `workmux sandbox pull`.
-    Build,
+    Build {
+        /// Image tag to build instead of the configured image
+        #[arg(long)]
+        image: Option<String>,
+        /// Target platform as os/arch (e.g. linux/amd64), for building on a
+        /// host of another architecture
+        #[arg(long, value_parser = parse_platform)]
+        platform: Option<String>,
+    },
     /// Pull the latest sandbox image from the container registry.
-    Pull,
+    Pull {
+        /// Image to pull instead of the configured image
+        #[arg(long)]
+        image: Option<String>,
+    },
     /// Export customizable Dockerfile templates for building your own sandbox image.
     InitDockerfile {
         /// Overwrite existing Dockerfiles
@@ -54,10 +69,21 @@ pub enum SandboxCommand {
     },
     /// Delete unused Lima VMs to reclaim disk space.
     Prune {
-        /// Skip confirmation and delete all workmux VMs
-        #[arg(short, long)]
+        /// Skip selection and confirmation; delete all matching workmux VMs
+        #[arg(short, long, alias = "yes")]
         force: bool,
+        /// Only delete VMs not accessed for at least this long (e.g. 30m, 12h, 7d, 2w)
+        #[arg(long, value_parser = parse_age)]
+        older_than: Option<Duration>,
+        /// Only delete VMs using more disk than this (e.g. 500M, 10G)
+        #[arg(long, value_parser = parse_size)]
+        larger_than: Option<u64>,
+        /// Keep the N most recently accessed VMs
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
     },
+    /// Delete temporary Lima config files left behind by deleted VMs.
+    Gc,
     /// Run a command inside a sandbox (internal, used by pane setup).
     #[command(hide = true)]
     Run {
@@ -66,6 +92,9 @@ pub enum SandboxCommand {
         /// Root of the worktree for mounting (defaults to worktree path)
         #[arg(long)]
         worktree_root: Option<PathBuf>,
+        /// Image to run instead of the worktree's recorded or configured image
+        #[arg(long)]
+        image: Option<String>,
         /// Command and arguments to run inside the sandbox
         #[arg(last = true, required = true)]
         command: Vec<String>,
@@ -79,6 +108,11 @@ pub enum SandboxCommand {
         #[arg(long)]
         release: bool,
     },
+    /// Boot the Lima VM for a worktree ahead of time, so agents start instantly.
+    Start {
+        /// Worktree whose VM to start (defaults to the current directory)
+        worktree: Option<PathBuf>,
+    },
     /// Stop Lima VMs to free resources.
     Stop {
         /// VM name to stop (if not provided, show interactive list)
@@ -95,17 +129,33 @@ pub enum SandboxCommand {
     /// Unlike `shell`, this starts an RPC server so the agent can call
     /// workmux commands (e.g., `workmux add` to spawn sub-agents).
     Agent {
+        /// Image to run instead of the worktree's recorded or configured image
+        #[arg(long)]
+        image: Option<String>,
         /// Command to run instead of the configured agent
         #[arg(last = true)]
         command: Vec<String>,
     },
+    /// Show the sandbox supervisor log for a worktree.
+    Logs {
+        /// Worktree whose log to show (defaults to the current directory)
+        worktree: Option<PathBuf>,
+        /// Keep printing new lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+    },
     /// Start an interactive shell in a sandbox.
     /// Uses the same mounts and environment as a normal worktree sandbox.
     Shell {
+        /// Worktree to open the shell in (defaults to the current directory)
+        worktree: Option<PathBuf>,
         /// Exec into an existing container for this worktree instead of starting a new one
         /// (container backend only)
         #[arg(long, short)]
         exec: bool,
+        /// Image to run instead of the worktree's recorded or configured image
+        #[arg(long)]
+        image: Option<String>,
         /// Command to run instead of bash
         #[arg(last = true)]
         command: Vec<String>,
@@ -117,7 +167,16 @@ fn resolve_agent(config: &Config) -> &'static str {
     crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name()
 }
 
-fn run_agent(command: Vec<String>) -> Result<()> {
+/// Load config with an `--image` override applied.
+fn load_config(image: Option<String>) -> Result<Config> {
+    let mut config = Config::load(None)?;
+    if let Some(image) = image {
+        config.sandbox.image = Some(image);
+    }
+    Ok(config)
+}
+
+fn run_agent(image: Option<String>, command: Vec<String>) -> Result<()> {
     let config = Config::load(None)?;
 
     let cwd = std::env::current_dir().context("Failed to get current directory")?;
@@ -138,45 +197,93 @@ fn run_agent(command: Vec<String>) -> Result<()> {
         command
     };
 
-    let exit_code = super::sandbox_run::run(cwd, Some(worktree_root), agent_command)?;
+    let exit_code = super::sandbox_run::run(cwd, Some(worktree_root), agent_command, image)?;
     std::process::exit(exit_code);
 }
 
+/// Log file for a sandbox supervisor command, rotated if it has grown too
+/// large. None for other sandbox commands or when the path can't be resolved.
+pub fn supervisor_log_file(args: &SandboxArgs) -> Option<PathBuf> {
+    let root = match &args.command {
+        SandboxCommand::Run {
+            worktree,
+            worktree_root,
+            ..
+        } => worktree_root.clone().unwrap_or_else(|| worktree.clone()),
+        SandboxCommand::Agent { .. } => crate::git::get_repo_root().ok()?,
+        _ => return None,
+    };
+    let path = super::sandbox_run::log_path(&root).ok()?;
+    if let Err(e) = crate::logger::rotate_if_larger(&path, super::sandbox_run::LOG_MAX_BYTES) {
+        eprintln!("Warning: {:#}", e);
+    }
+    Some(path)
+}
+
 pub fn run(args: SandboxArgs) -> Result<()> {
     match args.command {
-        SandboxCommand::Build => run_build(),
-        SandboxCommand::Pull => run_pull(),
+        SandboxCommand::Build { image, platform } => run_build(image, platform),
+        SandboxCommand::Pull { image } => run_pull(image),
         SandboxCommand::InitDockerfile { force } => run_init_dockerfile(force),
         SandboxCommand::Run {
             worktree,
             worktree_root,
+            image,
             command,
         } => {
-            debug!(worktree = %worktree.display(), ?worktree_root, ?command, "sandbox run");
-            let exit_code = super::sandbox_run::run(worktree, worktree_root, command)?;
+            debug!(worktree = %worktree.display(), ?worktree_root, ?image, ?command, "sandbox run");
+            let exit_code = super::sandbox_run::run(worktree, worktree_root, command, image)?;
             std::process::exit(exit_code);
         }
         SandboxCommand::InstallDev {
             skip_build,
             release,
         } => run_install_dev(skip_build, release),
-        SandboxCommand::Agent { command } => run_agent(command),
-        SandboxCommand::Prune { force } => run_prune(force),
+        SandboxCommand::Agent { image, command } => run_agent(image, command),
+        SandboxCommand::Prune {
+            force,
+            older_than,
+            larger_than,
+            keep,
+        } => run_prune(
+            force,
+            PruneFilter {
+                older_than,
+                larger_than,
+                keep,
+            },
+        ),
+        SandboxCommand::Gc => run_gc(),
+        SandboxCommand::Start { worktree } => run_start(worktree),
         SandboxCommand::Stop { name, all, yes } => run_stop(name, all, yes),
-        SandboxCommand::Shell { exec, command } => run_shell(exec, command),
+        SandboxCommand::Logs { worktree, follow } => run_logs(worktree, follow),
+        SandboxCommand::Shell {
+            worktree,
+            exec,
+            image,
+            command,
+        } => run_shell(worktree, exec, image, command),
     }
 }
 
-fn run_build() -> Result<()> {
-    let config = Config::load(None)?;
+fn run_build(image: Option<String>, platform: Option<String>) -> Result<()> {
+    let config = load_config(image)?;
     let agent = resolve_agent(&config);
 
-    println!(
-        "Building sandbox image '{}' for agent '{}'...",
-        config.sandbox.resolved_image(agent),
-        agent,
-    );
-    sandbox::build_image(&config.sandbox, agent)?;
+    match &platform {
+        Some(platform) => println!(
+            "Building sandbox image '{}' for agent '{}' ({})...",
+            config.sandbox.resolved_image(agent),
+            agent,
+            platform,
+        ),
+        None => println!(
+            "Building sandbox image '{}' for agent '{}'...",
+            config.sandbox.resolved_image(agent),
+            agent,
+        ),
+    }
+    sandbox::build_image(&config.sandbox, agent, platform.as_deref())?;
     println!("\nSandbox image built successfully!");
     println!();
     println!(
@@ -189,13 +296,13 @@ fn run_build() -> Result<()> {
     Ok(())
 }
 
-fn run_pull() -> Result<()> {
-    let config = Config::load(None)?;
+fn run_pull(image: Option<String>) -> Result<()> {
+    let config = load_config(image)?;
     let agent = resolve_agent(&config);
     let image = config.sandbox.resolved_image(agent);
 
     sandbox::pull_image(&config.sandbox, &image)?;
-    sandbox::freshness::mark_fresh(&image, config.sandbox.runtime());
+    sandbox::freshness::mark_fresh(&image, config.sandbox.runtime()?);
 
     println!("Image '{}' is up to date.", image);
     Ok(())
@@ -544,12 +651,7 @@ fn run_install_dev(skip_build: bool, release: bool) -> Result<()> {
 /// Returns Ok(true) if the image was patched, Ok(false) if the base image
 /// doesn't exist.
 fn install_dev_container(binary_path: &Path, image_name: &str, config: &Config) -> Result<bool> {
-    use crate::config::SandboxRuntime;
-
-    let runtime = match config.sandbox.runtime() {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
-    };
+    let runtime = config.sandbox.runtime()?.binary();
 
     // Check if the base image exists
     let inspect = Command::new(runtime)
@@ -602,9 +704,125 @@ struct VmInfo {
     status: String,
     created: Option<SystemTime>,
     last_accessed: Option<SystemTime>,
+    /// Disk space used by the VM directory, in bytes
+    size: Option<u64>,
 }
 
-fn run_prune(force: bool) -> Result<()> {
+impl VmInfo {
+    /// Most recent use, falling back to creation time.
+    fn last_used(&self) -> Option<SystemTime> {
+        self.last_accessed.or(self.created)
+    }
+}
+
+/// Which VMs `sandbox prune` deletes. The default matches every VM.
+#[derive(Debug, Default)]
+struct PruneFilter {
+    older_than: Option<Duration>,
+    larger_than: Option<u64>,
+    keep: Option<usize>,
+}
+
+impl PruneFilter {
+    fn is_active(&self) -> bool {
+        self.older_than.is_some() || self.larger_than.is_some() || self.keep.is_some()
+    }
+
+    /// Select the VMs to delete. VMs with unknown age or size never match a
+    /// filter on that property, so they are only deleted without filters.
+    fn apply(&self, mut vms: Vec<VmInfo>, now: SystemTime) -> Vec<VmInfo> {
+        if let Some(keep) = self.keep {
+            // Most recently used first; unknown usage sorts last (oldest)
+            vms.sort_by_key(|vm| std::cmp::Reverse(vm.last_used()));
+            vms.drain(..keep.min(vms.len()));
+        }
+
+        vms.retain(|vm| {
+            let old_enough = self.older_than.is_none_or(|min_age| {
+                vm.last_used()
+                    .and_then(|t| now.duration_since(t).ok())
+                    .is_some_and(|age| age >= min_age)
+            });
+            let large_enough = self
+                .larger_than
+                .is_none_or(|min_size| vm.size.is_some_and(|size| size > min_size));
+            old_enough && large_enough
+        });
+        vms
+    }
+}
+
+/// Parse a size like "500M", "10G", or "1.5GiB" (binary units). A bare number is bytes.
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("invalid size '{}' (expected e.g. 500M, 10G)", s))?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let unit = unit.trim_end_matches("IB").trim_end_matches('B');
+    let multiplier: u64 = match unit {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return Err(format!("invalid size unit in '{}' (use K, M, G, or T)", s)),
+    };
+    Ok((value * multiplier as f64) as u64)
+}
+
+/// Parse a container platform like "linux/amd64" or "linux/arm/v7".
+fn parse_platform(s: &str) -> Result<String, String> {
+    let parts: Vec<&str> = s.trim().split('/').collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|part| part.is_empty()) {
+        return Err(format!(
+            "invalid platform '{}' (expected os/arch, e.g. linux/amd64)",
+            s
+        ));
+    }
+    Ok(parts.join("/"))
+}
+
+/// Format a byte count as a human-readable string (binary units).
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Disk space actually allocated under `path`. Counts blocks rather than
+/// file lengths because VM disk images are sparse.
+fn dir_disk_usage(path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let mut total = metadata.blocks() * 512;
+    if metadata.is_dir()
+        && let Ok(entries) = std::fs::read_dir(path)
+    {
+        for entry in entries.flatten() {
+            total += dir_disk_usage(&entry.path());
+        }
+    }
+    total
+}
+
+fn run_prune(force: bool, filter: PruneFilter) -> Result<()> {
     if !LimaInstance::is_lima_available() {
         bail!("limactl is not installed or not in PATH");
     }
@@ -642,11 +860,15 @@ fn run_prune(force: bool) -> Result<()> {
             .map(PathBuf::from)
             .unwrap_or_else(|| default_lima_dir.join(&instance.name));
 
-        let (created, last_accessed) = if vm_dir.exists() {
+        let (created, last_accessed, size) = if vm_dir.exists() {
             let metadata = std::fs::metadata(&vm_dir)?;
-            (metadata.created().ok(), metadata.accessed().ok())
+            (
+                metadata.created().ok(),
+                metadata.accessed().ok(),
+                Some(dir_disk_usage(&vm_dir)),
+            )
         } else {
-            (None, None)
+            (None, None, None)
         };
 
         vm_infos.push(VmInfo {
@@ -654,6 +876,7 @@ fn run_prune(force: bool) -> Result<()> {
             status: instance.status,
             created,
             last_accessed,
+            size,
         });
     }
 
@@ -662,34 +885,67 @@ fn run_prune(force: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Display VM information
-    println!("Found {} workmux Lima VM(s):\n", vm_infos.len());
+    let total_vms = vm_infos.len();
+    let vm_infos = filter.apply(vm_infos, SystemTime::now());
 
-    for (i, vm) in vm_infos.iter().enumerate() {
-        println!("{}. {} ({})", i + 1, vm.name, vm.status);
-        if let Some(created) = vm.created {
-            println!("   Age: {}", format_duration_since(created));
-        }
-        if let Some(accessed) = vm.last_accessed {
-            println!("   Last accessed: {}", format_duration_since(accessed));
-        }
-        println!();
+    if filter.is_active() && vm_infos.is_empty() {
+        println!(
+            "None of the {} workmux Lima VM(s) match the filters.",
+            total_vms
+        );
+        return Ok(());
     }
 
-    // Confirm deletion unless --force
-    if !force {
-        print!("Delete all these VMs? [y/N] ");
-        io::stdout().flush().context("Failed to flush stdout")?;
+    if !force && !std::io::stdin().is_terminal() {
+        bail!("Non-interactive stdin detected. Use --force to delete all matching VMs.");
+    }
 
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .context("Failed to read input")?;
+    if filter.is_active() {
+        println!(
+            "{} of {} workmux Lima VM(s) match the filters:\n",
+            vm_infos.len(),
+            total_vms
+        );
+    } else {
+        println!("Found {} workmux Lima VM(s):\n", vm_infos.len());
+    }
 
-        if input.trim().to_lowercase() != "y" {
-            println!("Aborted.");
-            return Ok(());
+    let labels: Vec<String> = vm_infos.iter().map(prune_label).collect();
+    let vm_infos: Vec<VmInfo> = if force {
+        for (i, label) in labels.iter().enumerate() {
+            println!("  {}. {}", i + 1, label);
         }
+        println!();
+        vm_infos
+    } else {
+        let selected = crate::ui::select_many(
+            &labels,
+            "Enter VM numbers to delete (e.g. 1, 2-4, or 'all'):",
+        )?;
+        vm_infos
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| selected.contains(idx))
+            .map(|(_, vm)| vm)
+            .collect()
+    };
+
+    if vm_infos.is_empty() {
+        println!("No VMs selected.");
+        return Ok(());
+    }
+
+    let reclaimable: u64 = vm_infos.iter().filter_map(|vm| vm.size).sum();
+    let prompt = format!(
+        "\nDelete {} VM(s), reclaiming {}?",
+        vm_infos.len(),
+        format_bytes(reclaimable)
+    );
+
+    // Confirm deletion unless --force
+    if !crate::ui::confirm(&prompt, true, force)? {
+        println!("Aborted.");
+        return Ok(());
     }
 
     // Delete VMs
@@ -701,29 +957,10 @@ fn run_prune(force: bool) -> Result<()> {
         print!("  Deleting {}... ", vm.name);
         io::stdout().flush().ok();
 
-        let result = Command::new("limactl")
-            .arg("delete")
-            .arg(&vm.name)
-            .arg("--force")
-            .output();
-
-        match result {
-            Ok(output) if output.status.success() => {
+        match LimaInstance::delete_by_name(&vm.name) {
+            Ok(()) => {
                 println!("done");
                 deleted_count += 1;
-
-                // Clean up per-VM state directory
-                if let Ok(state_dir) = lima::mounts::lima_state_dir_path(&vm.name)
-                    && state_dir.exists()
-                    && let Err(e) = std::fs::remove_dir_all(&state_dir)
-                {
-                    tracing::warn!(vm = %vm.name, error = %e, "failed to clean up state dir");
-                }
-            }
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                println!("failed");
-                failed.push((vm.name, stderr.trim().to_string()));
             }
             Err(e) => {
                 println!("failed");
@@ -749,6 +986,60 @@ fn run_prune(force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Delete temporary Lima configs whose VM no longer exists.
+fn run_gc() -> Result<()> {
+    if !LimaInstance::is_lima_available() {
+        bail!("limactl is not installed or not in PATH");
+    }
+
+    let instances = LimaInstance::list()?;
+    let live: std::collections::HashSet<&str> =
+        instances.iter().map(|vm| vm.name.as_str()).collect();
+    let orphans = lima::orphaned_config_files(&std::env::temp_dir(), &live)?;
+
+    let mut removed_count = 0;
+    let mut removed_bytes = 0;
+    for (path, size) in orphans {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                removed_count += 1;
+                removed_bytes += size;
+            }
+            Err(e) => eprintln!("Warning: failed to remove {}: {}", path.display(), e),
+        }
+    }
+
+    if removed_count == 0 {
+        println!("No orphaned Lima config files found.");
+    } else {
+        println!(
+            "Removed {} file(s) ({}).",
+            removed_count,
+            format_bytes(removed_bytes)
+        );
+    }
+    Ok(())
+}
+
+/// Entry for a VM in the prune list, with age, last access, and disk usage
+/// on indented lines below the name.
+fn prune_label(vm: &VmInfo) -> String {
+    let mut label = format!("{} ({})", vm.name, vm.status);
+    if let Some(created) = vm.created {
+        label.push_str(&format!("\n     Age: {}", format_duration_since(created)));
+    }
+    if let Some(accessed) = vm.last_accessed {
+        label.push_str(&format!(
+            "\n     Last accessed: {}",
+            format_duration_since(accessed)
+        ));
+    }
+    if let Some(size) = vm.size {
+        label.push_str(&format!("\n     Disk usage: {}", format_bytes(size)));
+    }
+    label
+}
+
 /// Format duration since a timestamp as human-readable string.
 fn format_duration_since(time: SystemTime) -> String {
     let now = SystemTime::now();
@@ -792,6 +1083,74 @@ fn format_duration_since(time: SystemTime) -> String {
     format!("{} year{} ago", years, if years == 1 { "" } else { "s" })
 }
 
+/// Resolve an optional worktree argument, defaulting to the current directory.
+fn resolve_worktree_arg(worktree: Option<PathBuf>) -> Result<PathBuf> {
+    match worktree {
+        Some(path) => path
+            .canonicalize()
+            .with_context(|| format!("Worktree path not found: {}", path.display())),
+        None => std::env::current_dir().context("Failed to get current directory"),
+    }
+}
+
+fn run_logs(worktree: Option<PathBuf>, follow: bool) -> Result<()> {
+    let worktree = match worktree {
+        Some(path) => resolve_worktree_arg(Some(path))?,
+        // Prefer the worktree root so this works from a subdirectory
+        None => crate::git::get_repo_root().or_else(|_| resolve_worktree_arg(None))?,
+    };
+    let path = super::sandbox_run::log_path(&worktree)?;
+    if !path.exists() {
+        bail!(
+            "No sandbox log for {} (expected {})",
+            worktree.display(),
+            path.display()
+        );
+    }
+
+    if follow {
+        let status = Command::new("tail")
+            .args(["-n", "+1", "-f"])
+            .arg(&path)
+            .status()
+            .context("Failed to run tail")?;
+        if !status.success() {
+            bail!("tail exited with {}", status);
+        }
+        return Ok(());
+    }
+
+    let mut file =
+        std::fs::File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    io::copy(&mut file, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+fn run_start(worktree: Option<PathBuf>) -> Result<()> {
+    use crate::config::SandboxBackend;
+
+    let worktree = resolve_worktree_arg(worktree)?;
+    let config = Config::load_in(&worktree, None)?;
+    if config.sandbox.backend() != SandboxBackend::Lima {
+        bail!(
+            "'workmux sandbox start' only applies to the Lima backend.\n\
+             Set 'sandbox.backend: lima' in config to use it."
+        );
+    }
+
+    let isolation = lima::resolve_isolation(&worktree, &config);
+    let vm_name = lima::instance_name(&worktree, isolation, &config)?;
+    if LimaInstance::is_lima_available() && LimaInstance::is_running(&vm_name)? {
+        println!("VM '{}' is already running.", vm_name);
+        return Ok(());
+    }
+
+    // Creates or boots the VM with streaming progress
+    let vm_name = lima::ensure_vm_running(&config, &worktree)?;
+    println!("✓ VM '{}' is running", vm_name);
+    Ok(())
+}
+
 fn run_stop(name: Option<String>, all: bool, skip_confirm: bool) -> Result<()> {
     use crate::sandbox::lima::{LimaInstance, LimaInstanceInfo, VM_PREFIX};
     use std::io::{self, IsTerminal, Write};
@@ -864,21 +1223,13 @@ fn run_stop(name: Option<String>, all: bool, skip_confirm: bool) -> Result<()> {
     }
 
     // Confirm unless --yes flag is provided
-    if !skip_confirm {
-        print!(
-            "\nAre you sure you want to stop {} VM(s)? [y/N] ",
-            vms_to_stop.len()
-        );
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        let answer = input.trim().to_ascii_lowercase();
-        if !matches!(answer.as_str(), "y" | "yes") {
-            println!("Aborted.");
-            return Ok(());
-        }
+    let prompt = format!(
+        "\nAre you sure you want to stop {} VM(s)?",
+        vms_to_stop.len()
+    );
+    if !crate::ui::confirm(&prompt, true, skip_confirm)? {
+        println!("Aborted.");
+        return Ok(());
     }
 
     // Stop VMs
@@ -893,6 +1244,8 @@ fn run_stop(name: Option<String>, all: bool, skip_confirm: bool) -> Result<()> {
             Ok(()) => {
                 println!("✓");
                 success_count += 1;
+                // The VM keeps its own copy of the config; the temp file is only needed at creation
+                crate::sandbox::lima::remove_config_temp_file(&vm.name);
             }
             Err(e) => {
                 println!("✗");
@@ -917,24 +1270,42 @@ fn run_stop(name: Option<String>, all: bool, skip_confirm: bool) -> Result<()> {
     Ok(())
 }
 
-fn run_shell(exec: bool, command: Vec<String>) -> Result<()> {
+fn run_shell(
+    worktree: Option<PathBuf>,
+    exec: bool,
+    image: Option<String>,
+    command: Vec<String>,
+) -> Result<()> {
     use crate::config::SandboxBackend;
 
-    let config = Config::load(None)?;
+    let worktree = resolve_worktree_arg(worktree)?;
+    let mut config = Config::load_in(&worktree, None)?;
+    if !config.sandbox.is_enabled() && config.sandbox.backend.is_none() {
+        bail!(
+            "No sandbox is configured.\n\
+             Set 'sandbox.enabled: true' (and optionally 'sandbox.backend') in config."
+        );
+    }
+    if let Some(image) = sandbox::image_override(image, &worktree) {
+        config.sandbox.image = Some(image);
+    }
 
     match config.sandbox.backend() {
-        SandboxBackend::Container => run_shell_container(exec, command, &config),
-        SandboxBackend::Lima => run_shell_lima(exec, command, &config),
+        SandboxBackend::Container => run_shell_container(&worktree, exec, command, &config),
+        SandboxBackend::Lima => run_shell_lima(&worktree, exec, command, &config),
     }
 }
 
-fn run_shell_container(exec: bool, command: Vec<String>, config: &Config) -> Result<()> {
-    use crate::config::SandboxRuntime;
+fn run_shell_container(
+    worktree: &Path,
+    exec: bool,
+    command: Vec<String>,
+    config: &Config,
+) -> Result<()> {
     use crate::sandbox::network_proxy::NetworkProxy;
     use crate::state::StateStore;
 
-    // Get current directory as worktree
-    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let cwd = worktree.to_path_buf();
     let worktree_root = cwd.clone();
 
     // Get handle from directory name
@@ -943,10 +1314,7 @@ fn run_shell_container(exec: bool, command: Vec<String>, config: &Config) -> Res
         .and_then(|n| n.to_str())
         .context("Could not determine worktree handle from directory name")?;
 
-    let runtime = match config.sandbox.runtime() {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
-    };
+    let runtime = config.sandbox.runtime()?.binary();
 
     // Build shell command
     let shell_cmd = if command.is_empty() {
@@ -1012,7 +1380,7 @@ fn run_shell_container(exec: bool, command: Vec<String>, config: &Config) -> Res
         };
 
         // Build env vars (owned, then borrowed -- same pattern as sandbox_run)
-        let rpc_host = config.sandbox.resolved_rpc_host();
+        let rpc_host = config.sandbox.resolved_rpc_host()?;
         let mut owned_envs: Vec<(String, String)> = Vec::new();
 
         if let Some((proxy_port, ref proxy_token, _)) = proxy {
@@ -1064,7 +1432,12 @@ fn run_shell_container(exec: bool, command: Vec<String>, config: &Config) -> Res
     }
 }
 
-fn run_shell_lima(exec: bool, command: Vec<String>, config: &Config) -> Result<()> {
+fn run_shell_lima(
+    worktree: &Path,
+    exec: bool,
+    command: Vec<String>,
+    config: &Config,
+) -> Result<()> {
     if exec {
         bail!(
             "The --exec flag is only supported with the container backend.\n\
@@ -1072,10 +1445,10 @@ fn run_shell_lima(exec: bool, command: Vec<String>, config: &Config) -> Result<(
         );
     }
 
-    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let cwd = worktree;
 
     // Ensure VM is running (creates it if needed)
-    let vm_name = lima::ensure_vm_running(config, &cwd)?;
+    let vm_name = lima::ensure_vm_running(config, cwd)?;
 
     // Build shell command
     let shell_cmd = if command.is_empty() {
@@ -1086,6 +1459,11 @@ fn run_shell_lima(exec: bool, command: Vec<String>, config: &Config) -> Result<(
 
     debug!(vm = %vm_name, cmd = %shell_cmd, "starting Lima shell");
 
+    // Keep a scheduled stop from an exiting agent from taking the VM away
+    let lease = lima::lease_vm(&vm_name)
+        .inspect_err(|e| tracing::warn!(vm = %vm_name, error = %e, "failed to lease VM"))
+        .ok();
+
     let status = Command::new("limactl")
         .arg("shell")
         .args(["--workdir", &cwd.to_string_lossy()])
@@ -1095,43 +1473,127 @@ fn run_shell_lima(exec: bool, command: Vec<String>, config: &Config) -> Result<(
         .status()
         .context("Failed to execute limactl shell")?;
 
+    // exit() skips destructors
+    drop(lease);
     std::process::exit(status.code().unwrap_or(1));
 }
 
 fn select_vms_interactive<'a>(
     vms: &'a [&'a crate::sandbox::lima::LimaInstanceInfo],
 ) -> Result<Vec<&'a crate::sandbox::lima::LimaInstanceInfo>> {
-    use std::io::{self, Write};
-
     println!("Running workmux VMs:");
     println!();
-    for (idx, vm) in vms.iter().enumerate() {
-        println!("  {}. {} ({})", idx + 1, vm.name, vm.status);
+    let labels: Vec<String> = vms
+        .iter()
+        .map(|vm| format!("{} ({})", vm.name, vm.status))
+        .collect();
+    let selected =
+        crate::ui::select_many(&labels, "Enter VM numbers to stop (e.g. 1, 2-4, or 'all'):")?;
+    Ok(selected.into_iter().map(|idx| vms[idx]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn vm(name: &str, days_ago: Option<u64>, size: Option<u64>, now: SystemTime) -> VmInfo {
+        VmInfo {
+            name: name.to_string(),
+            status: "Stopped".to_string(),
+            created: None,
+            last_accessed: days_ago.map(|d| now - DAY * d as u32),
+            size,
+        }
     }
-    println!();
-    println!("Enter VM number to stop (or 'all' for all VMs):");
-    print!("> ");
-    io::stdout().flush()?;
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let input = input.trim();
+    fn names(vms: &[VmInfo]) -> Vec<&str> {
+        vms.iter().map(|vm| vm.name.as_str()).collect()
+    }
 
-    if input.eq_ignore_ascii_case("all") {
-        return Ok(vms.to_vec());
+    #[test]
+    fn parse_platform_requires_os_and_arch() {
+        assert_eq!(parse_platform("linux/amd64"), Ok("linux/amd64".to_string()));
+        assert_eq!(
+            parse_platform("linux/arm/v7"),
+            Ok("linux/arm/v7".to_string())
+        );
+        assert!(parse_platform("amd64").is_err());
+        assert!(parse_platform("linux/").is_err());
+        assert!(parse_platform("linux/arm/v7/x").is_err());
     }
 
-    // Parse as number
-    let idx: usize = input
-        .parse()
-        .context("Invalid input. Please enter a number or 'all'.")?;
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_size("10G"), Ok(10 << 30));
+        assert_eq!(parse_size("10GB"), Ok(10 << 30));
+        assert_eq!(parse_size("1.5GiB"), Ok(3 << 29));
+        assert_eq!(parse_size("2k"), Ok(2048));
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("G").is_err());
+    }
+
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(10 << 30), "10.0 GiB");
+    }
 
-    if idx < 1 || idx > vms.len() {
-        anyhow::bail!(
-            "Invalid selection. Please choose a number between 1 and {}",
-            vms.len()
+    #[test]
+    fn prune_filter_default_matches_all() {
+        let now = SystemTime::now();
+        let vms = vec![vm("wm-a", Some(1), None, now), vm("wm-b", None, None, now)];
+        assert_eq!(
+            names(&PruneFilter::default().apply(vms, now)),
+            ["wm-a", "wm-b"]
         );
     }
 
-    Ok(vec![vms[idx - 1]])
+    #[test]
+    fn prune_filter_older_than_and_larger_than() {
+        let now = SystemTime::now();
+        let vms = vec![
+            vm("wm-old-big", Some(30), Some(20 << 30), now),
+            vm("wm-old-small", Some(30), Some(1 << 30), now),
+            vm("wm-new-big", Some(1), Some(20 << 30), now),
+            vm("wm-unknown", None, None, now),
+        ];
+        let filter = PruneFilter {
+            older_than: Some(DAY * 7),
+            larger_than: Some(10 << 30),
+            keep: None,
+        };
+        assert_eq!(names(&filter.apply(vms, now)), ["wm-old-big"]);
+    }
+
+    #[test]
+    fn prune_filter_keep_most_recent() {
+        let now = SystemTime::now();
+        let vms = vec![
+            vm("wm-10d", Some(10), None, now),
+            vm("wm-unknown", None, None, now),
+            vm("wm-1d", Some(1), None, now),
+            vm("wm-5d", Some(5), None, now),
+        ];
+        let filter = PruneFilter {
+            keep: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(names(&filter.apply(vms, now)), ["wm-10d", "wm-unknown"]);
+
+        let filter = PruneFilter {
+            keep: Some(10),
+            ..Default::default()
+        };
+        assert!(
+            filter
+                .apply(vec![vm("wm-a", Some(1), None, now)], now)
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! `workmux exec`: run a one-off command in a worktree's sandbox.
+//!
+//! The command is wrapped the same way as a sandboxed pane command and runs
+//! in the foreground under a one-shot `workmux sandbox run` supervisor, which
+//! exits with the command and leaves the worktree's agent and VM alone.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow, bail};
+use tracing::debug;
+
+use super::sandbox_run::ONESHOT_ENV;
+use crate::config::{Config, SandboxBackend, SandboxConfig};
+use crate::git::{self, SandboxRecord};
+use crate::sandbox::{self, lima};
+use crate::shell::shell_quote;
+
+pub fn run(name: &str, command_parts: &[String]) -> Result<()> {
+    if command_parts.is_empty() {
+        bail!("No command provided");
+    }
+    let (worktree_path, _branch) = git::find_worktree(name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    // The worktree's own config, not the caller's, picks its sandbox
+    let config = Config::load_in(&worktree_path, None)?;
+    let handle = worktree_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not derive handle from worktree path: {:?}",
+                worktree_path
+            )
+        })?;
+
+    // Preserve argument boundaries
+    let command = command_parts
+        .iter()
+        .map(|part| shell_quote(part))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let backend = sandbox_backend(handle, git::get_worktree_sandbox(handle), &config.sandbox)?;
+    let shell_command = wrap(&config, backend, &worktree_path, &command)?;
+
+    debug!(handle, command = %shell_command, "exec:running");
+    let status = Command::new("sh")
+        .args(["-c", &shell_command])
+        .current_dir(&worktree_path)
+        .env(ONESHOT_ENV, "1")
+        .status()
+        .context("Failed to run command")?;
+
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+/// Sandbox backend to run in: the one the worktree's panes were launched
+/// with, or the configured one for worktrees that were never launched.
+fn sandbox_backend(
+    handle: &str,
+    recorded: Option<SandboxRecord>,
+    config: &SandboxConfig,
+) -> Result<SandboxBackend> {
+    match recorded {
+        Some(SandboxRecord::Off) => bail!(
+            "Worktree '{}' was opened without a sandbox, so there is no sandbox to run in.",
+            handle
+        ),
+        // The supervisor starts whatever backend is configured, so a
+        // different one would not be the worktree's sandbox
+        Some(SandboxRecord::On(backend)) if backend != config.backend() => bail!(
+            "Worktree '{}' runs in a {} sandbox, but 'sandbox.backend' is now {}.\n\
+             Set it back to run commands in the worktree's sandbox.",
+            handle,
+            backend_name(&backend),
+            backend_name(&config.backend())
+        ),
+        Some(SandboxRecord::On(backend)) => Ok(backend),
+        None if config.is_enabled() => Ok(config.backend()),
+        None => bail!(
+            "Worktree '{}' does not run in a sandbox. Set 'sandbox.enabled' in the config first.",
+            handle
+        ),
+    }
+}
+
+fn backend_name(backend: &SandboxBackend) -> &'static str {
+    match backend {
+        SandboxBackend::Container => "container",
+        SandboxBackend::Lima => "lima",
+    }
+}
+
+/// Wrap `command` like a sandboxed pane command, so it runs under a sandbox
+/// supervisor rooted at `worktree`.
+fn wrap(
+    config: &Config,
+    backend: SandboxBackend,
+    worktree: &Path,
+    command: &str,
+) -> Result<String> {
+    match backend {
+        SandboxBackend::Container => {
+            sandbox::wrap_for_container(command, &config.sandbox, worktree, worktree)
+        }
+        SandboxBackend::Lima => {
+            // Boot the VM here so its progress shows before the command runs
+            let vm_name = lima::ensure_vm_running(config, worktree)?;
+            sandbox::wrap_for_lima(command, config, &vm_name, worktree)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox_config(enabled: bool, backend: SandboxBackend) -> SandboxConfig {
+        SandboxConfig {
+            enabled: Some(enabled),
+            backend: Some(backend),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sandbox_backend_follows_recorded_launch() {
+        let config = sandbox_config(false, SandboxBackend::Lima);
+        assert_eq!(
+            sandbox_backend(
+                "feat",
+                Some(SandboxRecord::On(SandboxBackend::Lima)),
+                &config
+            )
+            .unwrap(),
+            SandboxBackend::Lima
+        );
+    }
+
+    #[test]
+    fn sandbox_backend_rejects_unsandboxed_worktree() {
+        let config = sandbox_config(true, SandboxBackend::Lima);
+        let err = sandbox_backend("feat", Some(SandboxRecord::Off), &config).unwrap_err();
+        assert!(err.to_string().contains("without a sandbox"));
+
+        let disabled = sandbox_config(false, SandboxBackend::Container);
+        assert!(sandbox_backend("feat", None, &disabled).is_err());
+    }
+
+    #[test]
+    fn sandbox_backend_falls_back_to_config() {
+        let enabled = sandbox_config(true, SandboxBackend::Container);
+        assert_eq!(
+            sandbox_backend("feat", None, &enabled).unwrap(),
+            SandboxBackend::Container
+        );
+    }
+
+    #[test]
+    fn sandbox_backend_rejects_changed_backend() {
+        let config = sandbox_config(true, SandboxBackend::Container);
+        let err = sandbox_backend(
+            "feat",
+            Some(SandboxRecord::On(SandboxBackend::Lima)),
+            &config,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("runs in a lima sandbox"));
+    }
+}
@@ -7,21 +7,56 @@ use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
 use std::collections::HashSet;
 
-use crate::config::{Config, SandboxBackend, SandboxRuntime};
+use crate::config::{Config, SandboxBackend};
 use crate::multiplexer;
 use crate::sandbox::build_docker_run_args;
 use crate::sandbox::ensure_sandbox_config_dirs;
 use crate::sandbox::lima;
 use crate::sandbox::network_proxy::NetworkProxy;
-use crate::sandbox::rpc::{RpcContext, RpcServer, generate_token};
+use crate::sandbox::rpc::{
+    ENDPOINT_DIR, RpcContext, RpcEndpoint, RpcServer, generate_token, write_endpoint_file,
+};
 use crate::sandbox::shims;
 use crate::sandbox::toolchain;
 use crate::state::StateStore;
 
+/// Exit code used when the sandbox VM dies while the agent is running, so
+/// callers and restart policies can tell infra failure from agent failure.
+pub const VM_TERMINATED_EXIT_CODE: i32 = 125;
+
+/// Set by `workmux exec` for supervisors that run a single command next to
+/// the worktree's agent. Such a supervisor leaves the agent's Lima endpoint
+/// file alone and never schedules a VM stop.
+pub const ONESHOT_ENV: &str = "WM_SANDBOX_ONESHOT";
+
+/// Directory under the XDG state dir holding supervisor logs.
+const LOG_DIR: &str = "workmux/sandbox-logs";
+
+/// Supervisor logs past this size are rotated when the next supervisor starts.
+pub const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Supervisor log file for a worktree, named after its canonical path so it
+/// can be found without a running supervisor.
+pub fn log_path(worktree_root: &Path) -> Result<PathBuf> {
+    let root = worktree_root
+        .canonicalize()
+        .unwrap_or_else(|_| worktree_root.to_path_buf());
+    Ok(crate::state::store::get_state_dir()?
+        .join(LOG_DIR)
+        .join(log_file_name(&root)))
+}
+
+/// The worktree's directory name for readability, then a hash of the full
+/// path so worktrees with the same name don't share a log.
+fn log_file_name(worktree_root: &Path) -> String {
+    format!("{}.log", crate::util::path_file_stem(worktree_root))
+}
+
 /// Guard that stops a container when dropped.
 /// Ensures cleanup even if the supervisor is killed or panics.
 struct ContainerGuard {
@@ -63,21 +98,31 @@ impl Drop for ContainerGuard {
 ///
 /// Detects the sandbox backend from config and dispatches to the
 /// appropriate handler (Lima VM or Docker/Podman container).
-pub fn run(worktree: PathBuf, worktree_root: Option<PathBuf>, command: Vec<String>) -> Result<i32> {
+pub fn run(
+    worktree: PathBuf,
+    worktree_root: Option<PathBuf>,
+    command: Vec<String>,
+    image: Option<String>,
+) -> Result<i32> {
     if command.is_empty() {
         bail!("No command specified. Usage: workmux sandbox run <worktree> -- <command...>");
     }
 
-    let config = Config::load(None)?;
+    let mut config = Config::load(None)?;
     let worktree = worktree.canonicalize().unwrap_or_else(|_| worktree.clone());
 
+    let wt_root = worktree_root
+        .map(|p| p.canonicalize().unwrap_or(p))
+        .unwrap_or_else(|| worktree.clone());
+    if let Some(image) = crate::sandbox::image_override(image, &wt_root) {
+        config.sandbox.image = Some(image);
+    }
+    let env_file_vars = crate::env_file::load(&wt_root, config.env_file());
+
     match config.sandbox.backend() {
-        SandboxBackend::Lima => run_lima(&config, &worktree, &command),
+        SandboxBackend::Lima => run_lima(&config, &worktree, &command, &env_file_vars),
         SandboxBackend::Container => {
-            let wt_root = worktree_root
-                .map(|p| p.canonicalize().unwrap_or(p))
-                .unwrap_or_else(|| worktree.clone());
-            run_container(&config, &worktree, &wt_root, &command)
+            run_container(&config, &worktree, &wt_root, &command, &env_file_vars)
         }
     }
 }
@@ -85,17 +130,17 @@ pub fn run(worktree: PathBuf, worktree_root: Option<PathBuf>, command: Vec<Strin
 /// Start RPC server and return (server, port, token, context).
 /// Shared setup between Lima and Container backends.
 fn start_rpc(
+    config: &Config,
     worktree: &Path,
     allowed_commands: HashSet<String>,
     detected_toolchain: toolchain::DetectedToolchain,
-    allow_unsandboxed_host_exec: bool,
 ) -> Result<(RpcServer, u16, String, Arc<RpcContext>)> {
     let rpc_server = RpcServer::bind()?;
     let rpc_port = rpc_server.port();
     let rpc_token = generate_token();
     info!(port = rpc_port, "RPC server listening");
 
-    let mux = multiplexer::create_backend(multiplexer::detect_backend());
+    let mux = multiplexer::create_backend(multiplexer::detect_backend(config), config);
     let pane_id = mux.current_pane_id().unwrap_or_default();
 
     let ctx = Arc::new(RpcContext {
@@ -105,19 +150,33 @@ fn start_rpc(
         token: rpc_token.clone(),
         allowed_commands,
         detected_toolchain,
-        allow_unsandboxed_host_exec,
+        allow_unsandboxed_host_exec: config.sandbox.allow_unsandboxed_host_exec(),
+        git_credentials: config.sandbox.git_credentials(),
+        exec_timeout: config.sandbox.host_exec_timeout(),
     });
 
     Ok((rpc_server, rpc_port, rpc_token, ctx))
 }
 
+/// Start the guest heartbeat loop in the background before the agent command.
+/// The loop exits on its own once the agent (and its wrapper shell) is gone.
+fn with_heartbeat(command: &str, interval: u64) -> String {
+    if interval == 0 {
+        return command.to_string();
+    }
+    format!("workmux _heartbeat --interval {interval} >/dev/null 2>&1 & {command}")
+}
+
 /// Extract git `user.name` and `user.email` from the host's git config and
 /// return `GIT_CONFIG_*` environment variable pairs to inject into the sandbox.
 ///
 /// Runs `git config user.name` and `git config user.email` from `worktree_dir`
 /// to respect all config scopes (system, global, conditional includes).
 /// Returns an empty vec if neither value is configured (graceful no-op).
-fn git_user_config_envs(worktree_dir: &Path) -> Vec<(String, String)> {
+///
+/// With `credential_helper`, also registers `workmux git-credential` as a
+/// credential helper so HTTPS pushes get credentials from the host over RPC.
+fn git_user_config_envs(worktree_dir: &Path, credential_helper: bool) -> Vec<(String, String)> {
     let mut entries = Vec::new();
 
     for key in &["user.name", "user.email"] {
@@ -134,6 +193,13 @@ fn git_user_config_envs(worktree_dir: &Path) -> Vec<(String, String)> {
         }
     }
 
+    if credential_helper {
+        entries.push((
+            "credential.helper".to_string(),
+            "!workmux git-credential".to_string(),
+        ));
+    }
+
     if entries.is_empty() {
         return Vec::new();
     }
@@ -147,11 +213,27 @@ fn git_user_config_envs(worktree_dir: &Path) -> Vec<(String, String)> {
     envs
 }
 
-fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32> {
+fn run_lima(
+    config: &Config,
+    worktree: &Path,
+    command: &[String],
+    env_file_vars: &[(String, String)],
+) -> Result<i32> {
     info!(worktree = %worktree.display(), "sandbox supervisor starting (lima)");
 
     // Ensure Lima VM is running
-    let vm_name = lima::ensure_vm_running(config, worktree)?;
+    let vm_name = match lima::ensure_vm_running(config, worktree) {
+        Ok(vm_name) => vm_name,
+        // Without limactl the error already says what to do
+        Err(e) if !lima::LimaInstance::is_lima_available() => return Err(e),
+        Err(e) => {
+            let isolation = lima::resolve_isolation(worktree, config);
+            let vm_name = lima::instance_name(worktree, isolation, config)?;
+            bail!(boot_diagnostic(&vm_name, &format!("{:#}", e)));
+        }
+    };
+    validate_vm(&vm_name)?;
+    wait_for_lima_ready(config, &vm_name)?;
     info!(vm_name = %vm_name, "Lima VM ready");
 
     let agent = crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name();
@@ -175,14 +257,37 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
     let state_dir = lima::mounts::lima_state_dir_path(&vm_name)?;
     shims::create_shim_directory(&state_dir, &host_commands)?;
     info!(commands = ?host_commands, "created host-exec shims");
+    let shadow_cache = state_dir.join("shadowed-commands.json");
+    shims::warn_shadowed_commands(config.sandbox.host_commands(), &shadow_cache, |script| {
+        let mut cmd = Command::new("limactl");
+        cmd.args(["shell", &vm_name, "--", "sh", "-c", script]);
+        cmd
+    });
 
-    let (rpc_server, rpc_port, rpc_token, ctx) = start_rpc(
-        worktree,
-        allowed_commands,
-        detected.clone(),
-        config.sandbox.allow_unsandboxed_host_exec(),
-    )?;
-    let _rpc_handle = rpc_server.spawn(ctx);
+    let (rpc_server, rpc_port, rpc_token, ctx) =
+        start_rpc(config, worktree, allowed_commands, detected.clone())?;
+    let rpc_handle = rpc_server.spawn(ctx);
+
+    // Persist the endpoint so guest processes without the env vars can
+    // connect. A one-shot command inherits the env vars, and the file is
+    // named after the worktree, so writing it would replace the agent's.
+    let oneshot = std::env::var_os(ONESHOT_ENV).is_some();
+    let rpc_dir = state_dir.join("rpc");
+    let endpoint_file = if oneshot {
+        None
+    } else {
+        write_endpoint_file(
+            &rpc_dir,
+            worktree,
+            &RpcEndpoint {
+                host: "host.lima.internal".to_string(),
+                port: rpc_port,
+                token: rpc_token.clone(),
+            },
+        )
+        .inspect_err(|e| warn!(error = %e, "failed to write RPC endpoint file"))
+        .ok()
+    };
 
     // Build limactl shell command
     let mut lima_cmd = Command::new("limactl");
@@ -192,38 +297,56 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
         .arg(&vm_name);
 
     let mut env_exports = vec![
-        r#"PATH="$HOME/.workmux-state/shims/bin:$HOME/.local/bin:/nix/var/nix/profiles/default/bin:$PATH""#.to_string(),
+        LIMA_GUEST_PATH.to_string(),
         "WM_SANDBOX_GUEST=1".to_string(),
         "WM_RPC_HOST=host.lima.internal".to_string(),
         format!("WM_RPC_PORT={}", rpc_port),
         format!("WM_RPC_TOKEN={}", rpc_token),
     ];
 
+    // Host values are quoted: they end up in the `sh -c` script
     for term_var in ["TERM", "COLORTERM"] {
         if let Ok(val) = std::env::var(term_var) {
-            env_exports.push(format!("{}={}", term_var, val));
+            env_exports.push(format!("{}={}", term_var, crate::shell::shell_quote(&val)));
         }
     }
 
-    for env_var in config.sandbox.env_passthrough() {
-        if let Ok(val) = std::env::var(env_var) {
-            env_exports.push(format!("{}={}", env_var, val));
+    for env_var in config.sandbox.passthrough_env_vars() {
+        if let Ok(val) = std::env::var(&env_var) {
+            env_exports.push(format!("{}={}", env_var, crate::shell::shell_quote(&val)));
         }
     }
 
     // Inject host git user config (user.name, user.email) for commits
-    for (key, val) in git_user_config_envs(worktree) {
+    for (key, val) in git_user_config_envs(worktree, config.sandbox.git_credentials()) {
         env_exports.push(format!("{}='{}'", key, crate::shell::shell_escape(&val)));
     }
 
-    let exports: String = env_exports
-        .iter()
-        .map(|e| format!("export {e}"))
+    // Variables from the worktree's env file are sourced from a private file
+    // in the mounted state dir, so their values stay out of argv. They go
+    // first so workmux's own win.
+    let env_file = match crate::env_file::export_statement(env_file_vars) {
+        Some(statement) => Some(crate::env_file::write_private(
+            &state_dir.join("env"),
+            &format!("{statement}\n"),
+        )?),
+        None => None,
+    };
+    let source_env_file = env_file.as_ref().and_then(|file| {
+        let name = file.path().file_name()?.to_string_lossy().to_string();
+        Some(format!(". \"$HOME/.workmux-state/env/{name}\""))
+    });
+    let exports: String = source_env_file
+        .into_iter()
+        .chain(env_exports.into_iter().map(|e| format!("export {e}")))
         .collect::<Vec<_>>()
         .join("; ");
     let user_command = command.join(" ");
 
-    let final_command = toolchain::wrap_command(&user_command, &detected);
+    let final_command = with_heartbeat(
+        &toolchain::wrap_command(&user_command, &detected),
+        config.sandbox.heartbeat_interval(),
+    );
     let full_command = format!("{exports}; {final_command}");
 
     lima_cmd.arg("--");
@@ -238,14 +361,255 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
 
     let exit_code = status.code().unwrap_or(1);
     info!(exit_code, "agent command exited");
+
+    let exit_code = classify_lima_exit(exit_code, || {
+        lima::LimaInstance::is_running(&vm_name)
+            .inspect_err(|e| warn!(vm = %vm_name, error = %e, "failed to probe VM state"))
+            .ok()
+    });
+    if exit_code == VM_TERMINATED_EXIT_CODE {
+        eprintln!("workmux: sandbox VM '{}' terminated unexpectedly", vm_name);
+    }
+
+    rpc_handle.shutdown();
+    drop(endpoint_file);
+
+    let isolation = lima::resolve_isolation(worktree, config);
+    if !oneshot
+        && exit_code != VM_TERMINATED_EXIT_CODE
+        && config.sandbox.lima.stop_vm_on_exit(&isolation)
+    {
+        lima::schedule_vm_stop(&vm_name, &rpc_dir);
+    }
     Ok(exit_code)
 }
 
+/// `PATH` for commands run in a Lima guest: host-exec shims first, then the
+/// user-local and Nix locations agents are installed to.
+const LIMA_GUEST_PATH: &str = r#"PATH="$HOME/.workmux-state/shims/bin:$HOME/.local/bin:/nix/var/nix/profiles/default/bin:$PATH""#;
+
+/// First pause between `sandbox.ready_check` attempts.
+const READY_CHECK_FIRST_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest pause between `sandbox.ready_check` attempts.
+const READY_CHECK_MAX_DELAY: Duration = Duration::from_secs(4);
+
+/// Call `probe` until it passes, sleeping with exponential backoff between
+/// attempts. Each attempt gets the rest of the budget (at least a second)
+/// as its time limit, so a hanging check can't block forever. Returns false
+/// once `timeout` has been spent waiting.
+fn poll_ready(
+    timeout: Duration,
+    mut probe: impl FnMut(Duration) -> bool,
+    mut sleep: impl FnMut(Duration),
+) -> bool {
+    let mut delay = READY_CHECK_FIRST_DELAY;
+    let mut waited = Duration::ZERO;
+    loop {
+        if probe((timeout - waited).max(Duration::from_secs(1))) {
+            return true;
+        }
+        if waited >= timeout {
+            return false;
+        }
+        let pause = delay.min(timeout - waited);
+        sleep(pause);
+        waited += pause;
+        delay = (delay * 2).min(READY_CHECK_MAX_DELAY);
+    }
+}
+
+fn ready_check_error(check: &str, timeout: Duration) -> String {
+    format!(
+        "Sandbox ready check '{}' did not pass within {}s, so the agent was not started.\n\
+         Make sure the command succeeds in the guest, or raise 'sandbox.ready_timeout'.",
+        check,
+        timeout.as_secs()
+    )
+}
+
+/// Wait for `sandbox.ready_check` to pass in the VM, so the agent command
+/// doesn't land in a guest that is still provisioning.
+fn wait_for_lima_ready(config: &Config, vm_name: &str) -> Result<()> {
+    let Some(check) = config.sandbox.ready_check() else {
+        return Ok(());
+    };
+    let timeout = config.sandbox.ready_timeout();
+    info!(vm = %vm_name, check, "waiting for sandbox ready check");
+    let script = format!("export {LIMA_GUEST_PATH}; {check}");
+    let probe = |limit| {
+        succeeds_within(
+            Command::new("limactl").args(["shell", vm_name, "--", "sh", "-c", &script]),
+            limit,
+        )
+    };
+    if !poll_ready(timeout, probe, std::thread::sleep) {
+        bail!(ready_check_error(check, timeout));
+    }
+    Ok(())
+}
+
+/// Run `cmd` with no stdio and report whether it exits successfully within
+/// `limit`. A command still running at the limit is killed.
+fn succeeds_within(cmd: &mut Command, limit: Duration) -> bool {
+    let child = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return false;
+    };
+    let start = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if start.elapsed() < limit => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+/// Prefix a container command with a loop that waits for
+/// `sandbox.ready_check` to pass, backing off and limiting each attempt
+/// like `poll_ready` (when the image has `timeout`). The loop runs in the
+/// container itself, so there is no separate `exec`.
+fn with_ready_check(command: &str, check: Option<&str>, timeout: Duration) -> String {
+    let Some(check) = check else {
+        return command.to_string();
+    };
+    let message = format!(
+        "workmux: {}",
+        ready_check_error(check, timeout).replace('\n', " ")
+    );
+    format!(
+        "waited=0; delay={first}; ready() {{ left=$(({timeout} - waited)); \
+         if [ \"$left\" -lt 1 ]; then left=1; fi; \
+         if command -v timeout >/dev/null 2>&1; then timeout \"$left\" sh -c {check}; \
+         else sh -c {check}; fi; }}; \
+         until ready >/dev/null 2>&1; do \
+         if [ \"$waited\" -ge {timeout} ]; then echo {message} >&2; exit 1; fi; \
+         sleep \"$delay\"; waited=$((waited + delay)); \
+         if [ \"$delay\" -lt {max} ]; then delay=$((delay * 2)); fi; \
+         done; {command}",
+        check = crate::shell::shell_quote(check),
+        timeout = timeout.as_secs(),
+        message = crate::shell::shell_quote(&message),
+        first = READY_CHECK_FIRST_DELAY.as_secs(),
+        max = READY_CHECK_MAX_DELAY.as_secs(),
+    )
+}
+
+/// Confirm the VM is running and accepts shell sessions before starting the
+/// agent, so a broken boot surfaces as a diagnostic instead of a dead pane.
+fn validate_vm(vm_name: &str) -> Result<()> {
+    let problem = match lima::LimaInstance::is_running(vm_name) {
+        Ok(true) => match lima::LimaInstance::probe_shell(vm_name) {
+            Ok(()) => return Ok(()),
+            Err(e) => format!(
+                "the VM is running but does not accept shell sessions ({:#})",
+                e
+            ),
+        },
+        Ok(false) => "the VM is not running after boot".to_string(),
+        Err(e) => format!("could not query the VM state ({:#})", e),
+    };
+    warn!(vm = %vm_name, problem = %problem, "Lima VM failed validation");
+    bail!(boot_diagnostic(vm_name, &problem))
+}
+
+/// Directory holding Lima's VM disks: `$LIMA_HOME` or `~/.lima`.
+fn lima_home() -> Option<PathBuf> {
+    match std::env::var_os("LIMA_HOME") {
+        Some(home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => home::home_dir().map(|home| home.join(".lima")),
+    }
+}
+
+/// Available bytes from `df -Pk` output.
+fn parse_df_available(output: &str) -> Option<u64> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    let kib: u64 = fields.get(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Free space on the filesystem holding `path`, via `df`.
+fn disk_available(path: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Explain a failed VM boot with the usual causes and how to recover.
+fn boot_diagnostic(vm_name: &str, problem: &str) -> String {
+    let disk = lima_home()
+        .filter(|dir| dir.exists())
+        .or_else(home::home_dir)
+        .and_then(|dir| disk_available(&dir).map(|bytes| (dir, bytes)));
+    let version = lima::LimaInstance::version()
+        .map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch));
+    format_boot_diagnostic(vm_name, problem, disk, version.as_deref())
+}
+
+fn format_boot_diagnostic(
+    vm_name: &str,
+    problem: &str,
+    disk: Option<(PathBuf, u64)>,
+    limactl_version: Option<&str>,
+) -> String {
+    let disk = match disk {
+        Some((dir, bytes)) => format!(
+            "{} free in {}",
+            super::sandbox::format_bytes(bytes),
+            dir.display()
+        ),
+        None => "unknown".to_string(),
+    };
+    format!(
+        "Sandbox VM '{vm}' failed to boot: {problem}\n\
+         \n\
+         \x20 Disk space:      {disk}\n\
+         \x20 limactl version: {version}\n\
+         \n\
+         Common causes are a full disk, a bad `sandbox.image`, or a failed provision script.\n\
+         To recreate the VM from scratch:\n\
+         \x20 limactl delete -f {vm}\n\
+         \x20 workmux sandbox start\n\
+         See `workmux sandbox logs` for the supervisor log.",
+        vm = vm_name,
+        version = limactl_version.unwrap_or("unknown"),
+    )
+}
+
+/// Map the `limactl shell` exit code to the supervisor's exit code.
+///
+/// A non-zero exit is ambiguous: the agent may have failed, or the VM may
+/// have died under it. `vm_running` probes the VM (None if the probe failed);
+/// only a VM confirmed down yields `VM_TERMINATED_EXIT_CODE`.
+fn classify_lima_exit(exit_code: i32, vm_running: impl FnOnce() -> Option<bool>) -> i32 {
+    if exit_code == 0 {
+        return 0;
+    }
+    match vm_running() {
+        Some(false) => VM_TERMINATED_EXIT_CODE,
+        Some(true) | None => exit_code,
+    }
+}
+
 fn run_container(
     config: &Config,
     pane_cwd: &Path,
     worktree_root: &Path,
     command: &[String],
+    env_file_vars: &[(String, String)],
 ) -> Result<i32> {
     info!(
         pane_cwd = %pane_cwd.display(),
@@ -292,13 +656,9 @@ fn run_container(
         Some(dir)
     };
 
-    let (rpc_server, rpc_port, rpc_token, ctx) = start_rpc(
-        pane_cwd,
-        allowed_commands,
-        detected.clone(),
-        config.sandbox.allow_unsandboxed_host_exec(),
-    )?;
-    let _rpc_handle = rpc_server.spawn(ctx);
+    let (rpc_server, rpc_port, rpc_token, ctx) =
+        start_rpc(config, pane_cwd, allowed_commands, detected.clone())?;
+    let rpc_handle = rpc_server.spawn(ctx);
 
     // Start network proxy when policy is deny
     let network_deny = config.sandbox.network_policy_is_deny();
@@ -315,12 +675,27 @@ fn run_container(
     };
 
     // Compute RPC host BEFORE matching on runtime (SandboxRuntime is not Copy)
-    let rpc_host = config.sandbox.resolved_rpc_host();
-    let runtime = config.sandbox.runtime();
-    let runtime_bin: &'static str = match runtime {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
-    };
+    let rpc_host = config.sandbox.resolved_rpc_host()?;
+    let runtime = config.sandbox.runtime()?;
+    let runtime_bin: &'static str = runtime.binary();
+
+    // Probe each image once: the result is cached by image ID. An image that
+    // isn't pulled yet is checked on the next run.
+    let agent = crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name();
+    let image = config.sandbox.resolved_image(agent);
+    if let Ok(image_id) = crate::sandbox::freshness::get_local_image_id(runtime_bin, &image)
+        && let Ok(state_dir) = crate::state::store::get_state_dir()
+    {
+        let digest = image_id.trim_start_matches("sha256:");
+        let shadow_cache = state_dir
+            .join("workmux/shadowed-commands")
+            .join(format!("{}.json", digest));
+        shims::warn_shadowed_commands(config.sandbox.host_commands(), &shadow_cache, |script| {
+            let mut cmd = Command::new(runtime_bin);
+            cmd.args(["run", "--rm", "--entrypoint", "sh", &image_id, "-c", script]);
+            cmd
+        });
+    }
 
     // Generate container name from worktree directory name so cleanup can find it.
     // Include PID to allow multiple agents in the same worktree (e.g., open -n).
@@ -365,7 +740,10 @@ fn run_container(
     }
 
     // Inject host git user config (user.name, user.email) for commits
-    owned_envs.extend(git_user_config_envs(worktree_root));
+    owned_envs.extend(git_user_config_envs(
+        worktree_root,
+        config.sandbox.git_credentials(),
+    ));
 
     // Borrow owned envs for call site
     let env_refs: Vec<(&str, &str)> = owned_envs
@@ -373,11 +751,13 @@ fn run_container(
         .map(|(k, v)| (k.as_str(), v.as_str()))
         .collect();
 
-    let agent = crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name();
-
-    let user_command = command.join(" ");
+    let user_command = with_ready_check(
+        &with_heartbeat(&command.join(" "), config.sandbox.heartbeat_interval()),
+        config.sandbox.ready_check(),
+        config.sandbox.ready_timeout(),
+    );
     let shim_host_dir = _shim_dir.as_ref().map(|d| d.path().join("shims/bin"));
-    let mut docker_args = build_docker_run_args(
+    let configured_args = build_docker_run_args(
         &user_command,
         &config.sandbox,
         agent,
@@ -388,16 +768,56 @@ fn run_container(
         network_deny,
     )?;
 
-    // Insert --name after "run" (index 0 is "run")
-    docker_args.insert(1, "--name".to_string());
-    docker_args.insert(2, container_name.clone());
+    // `run`, then the options workmux adds here, then the configured ones
+    let mut docker_args = vec![
+        "run".to_string(),
+        "--name".to_string(),
+        container_name.clone(),
+    ];
+
+    // Persist the endpoint so guest processes without the env vars can
+    // connect. Lives in the shim temp dir, so it is removed with it.
+    if let Some(dir) = &_shim_dir {
+        let endpoint_dir = dir.path().join("rpc");
+        let endpoint = RpcEndpoint {
+            host: rpc_host.clone(),
+            port: rpc_port,
+            token: rpc_token.clone(),
+        };
+        if let Err(e) = write_endpoint_file(&endpoint_dir, worktree_root, &endpoint) {
+            warn!(error = %e, "failed to write RPC endpoint file");
+        } else {
+            docker_args.push("--mount".to_string());
+            docker_args.push(format!(
+                "type=bind,source={},target=/tmp/{},readonly",
+                endpoint_dir.display(),
+                ENDPOINT_DIR
+            ));
+        }
+    }
+
+    // Env file values go through a private `--env-file`, never argv or the
+    // runtime CLI's own environment (where PATH or DOCKER_HOST would change
+    // which runtime and daemon run). Explicit `--env` values take precedence.
+    let env_file = match (&_shim_dir, env_file_vars.is_empty()) {
+        (Some(dir), false) => Some(crate::env_file::write_private(
+            dir.path(),
+            &crate::env_file::runtime_env_file_content(env_file_vars),
+        )?),
+        _ => None,
+    };
+    if let Some(file) = &env_file {
+        docker_args.push("--env-file".to_string());
+        docker_args.push(file.path().to_string_lossy().to_string());
+    }
+    docker_args.extend(configured_args.into_iter().skip(1));
 
     let redacted_args: Vec<_> = docker_args.iter().map(|a| redact_env_arg(a)).collect();
     debug!(runtime = runtime_bin, container = %container_name, args = ?redacted_args, "spawning container");
 
     // Background freshness check (non-blocking)
     let freshness_image = config.sandbox.resolved_image(agent);
-    crate::sandbox::freshness::check_in_background(freshness_image, config.sandbox.runtime());
+    crate::sandbox::freshness::check_in_background(freshness_image, runtime.clone());
 
     // Create guard to stop container on exit (panic, SIGTERM, etc.)
     let _guard = ContainerGuard {
@@ -413,6 +833,7 @@ fn run_container(
 
     let exit_code = status.code().unwrap_or(1);
     info!(exit_code, "container command exited");
+    rpc_handle.shutdown();
     Ok(exit_code)
 }
 
@@ -431,6 +852,83 @@ pub(super) fn redact_env_arg(arg: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_df_available_reads_posix_output() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/disk3s5 971350180 803010588 168339592 83% /System/Volumes/Data\n";
+        assert_eq!(parse_df_available(output), Some(168339592 * 1024));
+        assert_eq!(parse_df_available("Filesystem\n"), None);
+    }
+
+    #[test]
+    fn boot_diagnostic_lists_checks_and_recovery() {
+        let message = format_boot_diagnostic(
+            "wm-proj-1a2b",
+            "the VM is not running after boot",
+            Some((PathBuf::from("/Users/me/.lima"), 2 * 1024 * 1024 * 1024)),
+            Some("1.0.3"),
+        );
+        assert!(message.starts_with(
+            "Sandbox VM 'wm-proj-1a2b' failed to boot: the VM is not running after boot\n"
+        ));
+        assert!(message.contains("  Disk space:      2.0 GiB free in /Users/me/.lima\n"));
+        assert!(message.contains("  limactl version: 1.0.3\n"));
+        assert!(message.contains("  limactl delete -f wm-proj-1a2b\n"));
+
+        let unknown = format_boot_diagnostic("vm", "boom", None, None);
+        assert!(unknown.contains("Disk space:      unknown"));
+        assert!(unknown.contains("limactl version: unknown"));
+    }
+
+    #[test]
+    fn log_file_name_is_readable_and_unique_per_path() {
+        let name = log_file_name(Path::new("/home/u/proj__worktrees/feat-x"));
+        assert!(name.starts_with("feat-x-"), "{name}");
+        assert!(name.ends_with(".log"));
+        assert_ne!(
+            name,
+            log_file_name(Path::new("/home/u/other__worktrees/feat-x"))
+        );
+    }
+
+    #[test]
+    fn log_file_name_stays_short_for_deep_paths() {
+        let deep = format!("/{}/{}", "dir/".repeat(100), "é".repeat(200));
+        let name = log_file_name(Path::new(&deep));
+        assert!(name.len() < 100, "{} bytes", name.len());
+    }
+
+    #[test]
+    fn classify_lima_exit_success_skips_probe() {
+        assert_eq!(
+            classify_lima_exit(0, || panic!("VM should not be probed")),
+            0
+        );
+    }
+
+    #[test]
+    fn classify_lima_exit_agent_failure_with_vm_alive() {
+        assert_eq!(classify_lima_exit(1, || Some(true)), 1);
+        assert_eq!(classify_lima_exit(130, || Some(true)), 130);
+    }
+
+    #[test]
+    fn classify_lima_exit_vm_dead() {
+        assert_eq!(
+            classify_lima_exit(255, || Some(false)),
+            VM_TERMINATED_EXIT_CODE
+        );
+        assert_eq!(
+            classify_lima_exit(1, || Some(false)),
+            VM_TERMINATED_EXIT_CODE
+        );
+    }
+
+    #[test]
+    fn classify_lima_exit_probe_failed_keeps_code() {
+        assert_eq!(classify_lima_exit(255, || None), 255);
+    }
+
     #[test]
     fn redact_rpc_token() {
         assert_eq!(
@@ -473,6 +971,83 @@ mod tests {
         assert_eq!(redact_env_arg("WM_SANDBOX_GUEST=1"), "WM_SANDBOX_GUEST=1");
     }
 
+    #[test]
+    fn test_with_heartbeat() {
+        assert_eq!(
+            with_heartbeat("claude --resume", 30),
+            "workmux _heartbeat --interval 30 >/dev/null 2>&1 & claude --resume"
+        );
+        assert_eq!(with_heartbeat("claude", 0), "claude");
+    }
+
+    #[test]
+    fn poll_ready_backs_off_until_timeout() {
+        let mut sleeps = Vec::new();
+        let mut limits = Vec::new();
+        let ready = poll_ready(
+            Duration::from_secs(10),
+            |limit| {
+                limits.push(limit.as_secs());
+                false
+            },
+            |d| sleeps.push(d),
+        );
+        assert!(!ready);
+        assert_eq!(sleeps, [1, 2, 4, 3].map(Duration::from_secs));
+        assert_eq!(limits, [10, 9, 7, 3, 1]);
+
+        let mut attempts = 0;
+        let ready = poll_ready(
+            Duration::from_secs(10),
+            |_| {
+                attempts += 1;
+                attempts == 3
+            },
+            |_| {},
+        );
+        assert!(ready);
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn succeeds_within_kills_a_hanging_command() {
+        let start = std::time::Instant::now();
+        assert!(!succeeds_within(
+            Command::new("sh").args(["-c", "sleep 5"]),
+            Duration::from_millis(100)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(4));
+        assert!(succeeds_within(
+            Command::new("true").arg("x"),
+            Duration::from_secs(5)
+        ));
+    }
+
+    #[test]
+    fn with_ready_check_gates_the_command() {
+        assert_eq!(with_ready_check("claude", None, Duration::ZERO), "claude");
+
+        let run = |check: &str| {
+            let script = with_ready_check("echo started", Some(check), Duration::ZERO);
+            Command::new("sh").args(["-c", &script]).output().unwrap()
+        };
+        let passed = run("true");
+        assert!(passed.status.success());
+        assert_eq!(String::from_utf8_lossy(&passed.stdout), "started\n");
+
+        let failed = run("which no-such-agent");
+        assert_eq!(failed.status.code(), Some(1));
+        assert!(failed.stdout.is_empty());
+        assert!(
+            String::from_utf8_lossy(&failed.stderr).contains("'which no-such-agent' did not pass")
+        );
+
+        let start = std::time::Instant::now();
+        let hung = run("sleep 5");
+        assert_eq!(hung.status.code(), Some(1));
+        assert!(start.elapsed() < Duration::from_secs(4));
+    }
+
     // ── git_user_config_envs tests ──────────────────────────────────────
 
     /// Create a temp directory with a git repo and local user config.
@@ -499,7 +1074,7 @@ mod tests {
     #[test]
     fn test_git_user_config_envs_with_both_values() {
         let tmp = git_repo_with_user("Test User", "test@example.com");
-        let envs = git_user_config_envs(tmp.path());
+        let envs = git_user_config_envs(tmp.path(), false);
 
         assert_eq!(envs.len(), 5); // COUNT + 2*(KEY + VALUE)
         assert_eq!(envs[0], ("GIT_CONFIG_COUNT".into(), "2".into()));
@@ -521,7 +1096,7 @@ mod tests {
             .current_dir(tmp.path())
             .output()
             .unwrap();
-        let envs = git_user_config_envs(tmp.path());
+        let envs = git_user_config_envs(tmp.path(), false);
         // May return values from global/system config or empty vec
         // We can't assert empty because the test runner's global git config may have user.*
         // Instead, verify the structure is correct if any values are returned
@@ -534,7 +1109,7 @@ mod tests {
     fn test_git_user_config_envs_not_a_git_repo() {
         let tmp = tempfile::tempdir().unwrap();
         // No git init -- not a git repo
-        let envs = git_user_config_envs(tmp.path());
+        let envs = git_user_config_envs(tmp.path(), false);
         // Should not crash, returns empty or global config values
         for (key, _) in &envs {
             assert!(key.starts_with("GIT_CONFIG_"), "unexpected key: {}", key);
@@ -544,7 +1119,7 @@ mod tests {
     #[test]
     fn test_git_user_config_envs_special_characters() {
         let tmp = git_repo_with_user("John O'Brien", "john@example.com");
-        let envs = git_user_config_envs(tmp.path());
+        let envs = git_user_config_envs(tmp.path(), false);
 
         let name_val = envs
             .iter()
@@ -552,4 +1127,23 @@ mod tests {
             .map(|(_, v)| v.as_str());
         assert_eq!(name_val, Some("John O'Brien"));
     }
+
+    #[test]
+    fn test_git_user_config_envs_credential_helper() {
+        let tmp = git_repo_with_user("Test User", "test@example.com");
+        let envs = git_user_config_envs(tmp.path(), true);
+
+        assert_eq!(envs[0], ("GIT_CONFIG_COUNT".into(), "3".into()));
+        assert_eq!(
+            envs[5],
+            ("GIT_CONFIG_KEY_2".into(), "credential.helper".into())
+        );
+        assert_eq!(
+            envs[6],
+            (
+                "GIT_CONFIG_VALUE_2".into(),
+                "!workmux git-credential".into()
+            )
+        );
+    }
 }
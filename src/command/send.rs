@@ -1,17 +1,66 @@
 use std::io::{IsTerminal, Read};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 
 use crate::config;
-use crate::multiplexer::{create_backend, detect_backend};
+use crate::multiplexer::agent::{resolve_input_profile, resolve_profile};
+use crate::multiplexer::{
+    AgentPane, Multiplexer, create_backend, detect_backend, ensure_not_dashboard_pane,
+};
+use crate::state::StateStore;
 use crate::workflow;
 
-pub fn run(name: &str, text: Option<&str>, file: Option<&str>) -> Result<()> {
+pub fn run(name: &str, text: Option<&str>, file: Option<&str>, paste: bool) -> Result<()> {
     let cfg = config::Config::load(None).unwrap_or_default();
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(&cfg), &cfg);
     let (_path, agent) = workflow::resolve_worktree_agent(name, mux.as_ref())?;
 
-    // Determine content: positional arg > --file > stdin
+    let content = read_content(text, file)?;
+    send_to_agent(mux.as_ref(), &agent, &content, paste, &cfg)
+}
+
+/// Send the same content to every running agent.
+pub fn run_all(text: Option<&str>, file: Option<&str>, paste: bool, dry_run: bool) -> Result<()> {
+    let cfg = config::Config::load(None).unwrap_or_default();
+    let mux = create_backend(detect_backend(&cfg), &cfg);
+    let agents = StateStore::new()?.load_reconciled_agents(mux.as_ref())?;
+
+    if agents.is_empty() {
+        bail!("No running agents");
+    }
+
+    if dry_run {
+        for agent in &agents {
+            println!("{}", target_label(agent));
+        }
+        return Ok(());
+    }
+
+    let content = read_content(text, file)?;
+    let mut failed = 0;
+    for agent in &agents {
+        match send_to_agent(mux.as_ref(), agent, &content, paste, &cfg) {
+            Ok(()) => println!("✓ {}", target_label(agent)),
+            Err(e) => {
+                println!("✗ {}: {:#}", target_label(agent), e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "\nSent to {} of {} agent(s)",
+        agents.len() - failed,
+        agents.len()
+    );
+    if failed > 0 {
+        bail!("Failed to send to {} agent(s)", failed);
+    }
+    Ok(())
+}
+
+/// Read the content to send: positional arg > --file > stdin.
+fn read_content(text: Option<&str>, file: Option<&str>) -> Result<String> {
     let content = if let Some(t) = text {
         t.to_string()
     } else if let Some(f) = file {
@@ -34,14 +83,83 @@ pub fn run(name: &str, text: Option<&str>, file: Option<&str>) -> Result<()> {
     if content.is_empty() {
         return Err(anyhow!("No content to send"));
     }
+    Ok(content.to_string())
+}
 
-    // Single-line: use send_keys_to_agent (handles Claude's ! prefix delay)
-    // Multi-line: use paste_multiline (already sends Enter in both backends)
-    if content.contains('\n') {
-        mux.paste_multiline(&agent.pane_id, content)?;
+/// Profile to send with: the agent's own command when it names a known
+/// agent, otherwise the configured agent.
+fn agent_for<'a>(agent: &'a AgentPane, configured: Option<&'a str>) -> Option<&'a str> {
+    if resolve_profile(Some(&agent.command)).name() != resolve_profile(None).name() {
+        Some(&agent.command)
     } else {
-        mux.send_keys_to_agent(&agent.pane_id, content, cfg.agent.as_deref())?;
+        configured
     }
+}
 
-    Ok(())
+fn send_to_agent(
+    mux: &dyn Multiplexer,
+    agent: &AgentPane,
+    content: &str,
+    paste: bool,
+    cfg: &config::Config,
+) -> Result<()> {
+    ensure_not_dashboard_pane(mux, &agent.pane_id)?;
+
+    // Single-line: use send_keys_to_agent (applies the agent's input profile)
+    // Multi-line or --paste: use paste_multiline (sends Enter in every backend)
+    let input = resolve_input_profile(agent_for(agent, cfg.agent.as_deref()), cfg);
+    if paste || content.contains('\n') {
+        mux.paste_multiline(&agent.pane_id, content, input.bracketed_paste)
+    } else {
+        mux.send_keys_to_agent(&agent.pane_id, content, &input)
+    }
+}
+
+fn target_label(agent: &AgentPane) -> String {
+    format!(
+        "{} ({}, pane {})",
+        agent.window_name,
+        agent.path.display(),
+        agent.pane_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn agent_with_command(command: &str) -> AgentPane {
+        AgentPane {
+            session: "main".to_string(),
+            window_name: "wm-feature".to_string(),
+            pane_id: "%1".to_string(),
+            path: PathBuf::from("/repo__worktrees/feature"),
+            pane_title: None,
+            status: None,
+            status_ts: None,
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn agent_for_prefers_known_pane_command() {
+        let agent = agent_with_command("claude");
+        assert_eq!(agent_for(&agent, Some("codex")), Some("claude"));
+    }
+
+    #[test]
+    fn agent_for_falls_back_to_config() {
+        let agent = agent_with_command("node");
+        assert_eq!(agent_for(&agent, Some("claude")), Some("claude"));
+        assert_eq!(agent_for(&agent, None), None);
+    }
+
+    #[test]
+    fn target_label_includes_pane() {
+        assert_eq!(
+            target_label(&agent_with_command("claude")),
+            "wm-feature (/repo__worktrees/feature, pane %1)"
+        );
+    }
 }
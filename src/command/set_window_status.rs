@@ -1,9 +1,11 @@
 use anyhow::Result;
 use clap::ValueEnum;
-use tracing::warn;
+use std::path::Path;
+use tracing::{debug, warn};
 
 use crate::config::Config;
-use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
+use crate::multiplexer::{AgentStatus, Multiplexer, create_backend, detect_backend};
+use crate::shell::shell_quote;
 
 #[derive(ValueEnum, Debug, Clone)]
 pub enum SetWindowStatusCommand {
@@ -24,50 +26,113 @@ pub fn run(cmd: SetWindowStatusCommand) -> Result<()> {
     }
 
     let config = Config::load(None)?;
-    let mux = create_backend(detect_backend());
+    let mux = create_backend(detect_backend(&config), &config);
 
     // Fail silently if not in a multiplexer session
     let Some(pane_id) = mux.current_pane_id() else {
         return Ok(());
     };
 
-    match cmd {
+    let status = match cmd {
         SetWindowStatusCommand::Clear => {
             // Clear icon only - state file cleanup is handled by reconciliation
-            mux.clear_status(&pane_id)?;
-        }
-        SetWindowStatusCommand::Working
-        | SetWindowStatusCommand::Waiting
-        | SetWindowStatusCommand::Done => {
-            let (status, icon, auto_clear) = match cmd {
-                SetWindowStatusCommand::Working => {
-                    (AgentStatus::Working, config.status_icons.working(), false)
-                }
-                SetWindowStatusCommand::Waiting => {
-                    (AgentStatus::Waiting, config.status_icons.waiting(), true)
-                }
-                SetWindowStatusCommand::Done => {
-                    (AgentStatus::Done, config.status_icons.done(), true)
-                }
-                SetWindowStatusCommand::Clear => unreachable!(),
-            };
-
-            // Ensure the status format is applied so the icon actually shows up
-            if config.status_format.unwrap_or(true) {
-                let _ = mux.ensure_status_format(&pane_id);
-            }
-
-            // Update backend UI (status bar icon)
-            mux.set_status(&pane_id, icon, auto_clear)?;
-
-            // Persist to state store so the dashboard sees this agent
-            crate::state::persist_agent_update(&*mux, &pane_id, Some(status), None);
+            return mux.clear_status(&pane_id);
         }
+        SetWindowStatusCommand::Working => AgentStatus::Working,
+        SetWindowStatusCommand::Waiting => AgentStatus::Waiting,
+        SetWindowStatusCommand::Done => AgentStatus::Done,
+    };
+
+    let cwd = std::env::current_dir().ok();
+    apply_status(&config, &*mux, &pane_id, status, cwd.as_deref())
+}
+
+/// Show `status` on a pane and record it for the dashboard, then, when the
+/// status changed, send the notification and run the status hook. `cwd` is
+/// the agent's directory. Shared by the host command and sandbox RPC.
+pub(crate) fn apply_status(
+    config: &Config,
+    mux: &dyn Multiplexer,
+    pane_id: &str,
+    status: AgentStatus,
+    cwd: Option<&Path>,
+) -> Result<()> {
+    // Theme first, then per-icon overrides
+    let icons = config.status_icons();
+    let icon = icons.icon(status);
+    let auto_clear = status != AgentStatus::Working;
+
+    // Ensure the status format is applied so the icon actually shows up
+    if config.status_format.unwrap_or(true) {
+        let _ = mux.ensure_status_format(pane_id);
+    }
+
+    // Update backend UI (status bar icon)
+    mux.set_status(pane_id, icon, auto_clear)?;
+
+    // Persist to state store so the dashboard sees this agent
+    let previous = crate::state::persist_agent_update(mux, pane_id, Some(status), None);
+    let changed = previous != Some(status);
+
+    if let Some(cwd) = cwd {
+        crate::notification::notify_status(config, previous, status, cwd);
+    }
+
+    if changed && let Some(hook) = config.hooks.for_status(status) {
+        run_status_hook(mux, hook, status, pane_id, cwd);
     }
 
     Ok(())
 }
 
+/// Spawn the user's status hook detached through the multiplexer, so it
+/// outlives this short-lived process. Failures are logged, not returned.
+fn run_status_hook(
+    mux: &dyn Multiplexer,
+    hook: &str,
+    status: AgentStatus,
+    pane_id: &str,
+    cwd: Option<&Path>,
+) {
+    let window_name = mux
+        .get_live_pane_info(pane_id)
+        .ok()
+        .flatten()
+        .and_then(|info| info.window)
+        .unwrap_or_default();
+    let script = hook_script(hook, status, pane_id, &window_name, cwd);
+    debug!(script, "set_window_status:running status hook");
+    if let Err(e) = mux.run_deferred_script(&script) {
+        warn!(error = %e, "failed to run status hook");
+    }
+}
+
+/// Build the shell script for a status hook. The pane id and window name
+/// are passed as `$1`/`$2` and as `WM_PANE_ID`/`WM_WINDOW_NAME`, along with
+/// `WM_STATUS`.
+fn hook_script(
+    hook: &str,
+    status: AgentStatus,
+    pane_id: &str,
+    window_name: &str,
+    cwd: Option<&Path>,
+) -> String {
+    let status = match status {
+        AgentStatus::Working => "working",
+        AgentStatus::Waiting => "waiting",
+        AgentStatus::Done => "done",
+    };
+    let cd = cwd
+        .map(|dir| format!("cd {} && ", shell_quote(&dir.to_string_lossy())))
+        .unwrap_or_default();
+    format!(
+        "{cd}WM_STATUS={status} WM_PANE_ID={pane} WM_WINDOW_NAME={window} sh -c {hook} workmux-hook {pane} {window} >/dev/null 2>&1",
+        pane = shell_quote(pane_id),
+        window = shell_quote(window_name),
+        hook = shell_quote(hook),
+    )
+}
+
 /// Send a status update via RPC when running inside a sandbox guest.
 fn run_via_rpc(cmd: SetWindowStatusCommand) -> Result<()> {
     use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
@@ -86,10 +151,40 @@ fn run_via_rpc(cmd: SetWindowStatusCommand) -> Result<()> {
 
     match response {
         RpcResponse::Ok => Ok(()),
-        RpcResponse::Error { message } => {
+        RpcResponse::Error { message, .. } => {
             warn!(error = %message, "RPC SetStatus failed");
             Ok(()) // Fail silently like the host path does
         }
         _ => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_script_passes_pane_and_window() {
+        let script = hook_script(
+            "echo \"$1 $WM_WINDOW_NAME\"",
+            AgentStatus::Done,
+            "%5",
+            "wm-fix bug",
+            Some(Path::new("/tmp/wt")),
+        );
+        assert_eq!(
+            script,
+            "cd /tmp/wt && WM_STATUS=done WM_PANE_ID='%5' WM_WINDOW_NAME='wm-fix bug' \
+             sh -c 'echo \"$1 $WM_WINDOW_NAME\"' workmux-hook '%5' 'wm-fix bug' >/dev/null 2>&1"
+        );
+    }
+
+    #[test]
+    fn hook_script_without_cwd_or_window() {
+        let script = hook_script("./done.sh", AgentStatus::Waiting, "3", "", None);
+        assert_eq!(
+            script,
+            "WM_STATUS=waiting WM_PANE_ID=3 WM_WINDOW_NAME='' sh -c ./done.sh workmux-hook 3 '' >/dev/null 2>&1"
+        );
+    }
+}
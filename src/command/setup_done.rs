@@ -0,0 +1,19 @@
+//! Record that a worktree's setup script succeeded.
+//!
+//! Chained after the setup script in the pane command, so an interrupted or
+//! failing script is run again on the next `open`.
+
+use anyhow::Result;
+
+use crate::git;
+use crate::sandbox::rpc::{RpcClient, RpcRequest};
+
+pub fn run(handle: &str) -> Result<()> {
+    // Inside a sandbox guest the host owns the git metadata, and marks the
+    // worktree its supervisor runs in
+    if crate::sandbox::guest::is_sandbox_guest() {
+        return RpcClient::call_host(&RpcRequest::SetupDone, "record setup");
+    }
+
+    git::set_worktree_setup_done(handle)
+}
@@ -7,9 +7,11 @@ use tabled::{
     settings::{Padding, Style, object::Columns},
 };
 
-use crate::git;
+use crate::config::{Config, SandboxBackend};
+use crate::git::{self, SandboxRecord};
+use crate::multiplexer::util::WindowNames;
 use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
-use crate::state::StateStore;
+use crate::state::{PaneKey, StateStore};
 use crate::util;
 use crate::workflow;
 
@@ -67,7 +69,7 @@ fn git_label(git: &Option<GitInfo>) -> String {
     }
 }
 
-fn status_label(status: Option<AgentStatus>) -> String {
+pub(crate) fn status_label(status: Option<AgentStatus>) -> String {
     match status {
         Some(AgentStatus::Working) => "working".to_string(),
         Some(AgentStatus::Waiting) => "waiting".to_string(),
@@ -76,8 +78,117 @@ fn status_label(status: Option<AgentStatus>) -> String {
     }
 }
 
+/// Exit code for `status --current` outside a workmux-managed pane.
+pub const NOT_MANAGED_EXIT_CODE: i32 = 2;
+
+#[derive(Serialize)]
+struct CurrentPaneEntry {
+    handle: String,
+    branch: String,
+    sandbox: String,
+    status: String,
+    icon: Option<String>,
+    pane_id: String,
+}
+
+fn sandbox_label(record: Option<&SandboxRecord>) -> &'static str {
+    match record {
+        Some(SandboxRecord::On(SandboxBackend::Container)) => "container",
+        Some(SandboxRecord::On(SandboxBackend::Lima)) => "lima",
+        Some(SandboxRecord::Off) | None => "off",
+    }
+}
+
+pub(crate) fn status_icon(status: Option<AgentStatus>, config: &Config) -> Option<String> {
+    status.map(|s| config.status_icons().icon(s).to_string())
+}
+
+/// Print why the pane can't be identified and exit with [`NOT_MANAGED_EXIT_CODE`].
+fn exit_not_managed(reason: &str) -> ! {
+    eprintln!("{}", reason);
+    std::process::exit(NOT_MANAGED_EXIT_CODE);
+}
+
+/// Handles to look up for a window's handle: the handle itself, then the
+/// base handle of a numbered duplicate window (`feat-2` from `open --new`).
+fn handle_candidates(handle: String) -> Vec<String> {
+    let base = handle
+        .rsplit_once('-')
+        .filter(|(base, n)| {
+            !base.is_empty() && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|(base, _)| base.to_string());
+    std::iter::once(handle).chain(base).collect()
+}
+
+/// Describe the pane this command runs in. Exits with
+/// [`NOT_MANAGED_EXIT_CODE`] when the pane can't be identified as a
+/// workmux-managed pane, including when the config fails to load.
+pub fn run_current(json: bool) -> Result<()> {
+    let config = Config::load(None)
+        .unwrap_or_else(|e| exit_not_managed(&format!("Failed to load config: {:#}", e)));
+    let mux = create_backend(detect_backend(&config), &config);
+
+    let Some(pane_id) = mux.current_pane_id() else {
+        exit_not_managed("Not running inside a multiplexer pane");
+    };
+
+    // Window mode encodes the handle in the window name, session mode in the session name
+    let names = [
+        mux.current_window_name().ok().flatten(),
+        mux.current_session(),
+    ];
+    let window_names = WindowNames::load();
+    let found = names
+        .iter()
+        .flatten()
+        .filter_map(|name| window_names.handle_for(name, config.window_prefix()))
+        .flat_map(handle_candidates)
+        .find_map(|handle| {
+            git::find_worktree(&handle)
+                .ok()
+                .map(|(_, branch)| (handle.to_string(), branch))
+        });
+    let Some((handle, branch)) = found else {
+        exit_not_managed("Not inside a workmux-managed pane");
+    };
+
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.clone(),
+    };
+    let status = StateStore::new()
+        .and_then(|store| store.get_agent(&pane_key))?
+        .and_then(|agent| agent.status);
+
+    let entry = CurrentPaneEntry {
+        sandbox: sandbox_label(git::get_worktree_sandbox(&handle).as_ref()).to_string(),
+        status: status_label(status),
+        icon: status_icon(status, &config),
+        handle,
+        branch,
+        pane_id,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entry)?);
+    } else {
+        println!("handle:  {}", entry.handle);
+        println!("branch:  {}", entry.branch);
+        println!("sandbox: {}", entry.sandbox);
+        match &entry.icon {
+            Some(icon) => println!("status:  {} {}", icon, entry.status),
+            None => println!("status:  {}", entry.status),
+        }
+    }
+
+    Ok(())
+}
+
 pub fn run(worktrees: &[String], json: bool, show_git: bool) -> Result<()> {
-    let mux = create_backend(detect_backend());
+    let config = Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
 
     let agent_panes =
         StateStore::new().and_then(|store| store.load_reconciled_agents(mux.as_ref()))?;
@@ -204,3 +315,41 @@ pub fn run(worktrees: &[String], json: bool, show_git: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_label_defaults_to_off() {
+        assert_eq!(sandbox_label(None), "off");
+        assert_eq!(sandbox_label(Some(&SandboxRecord::Off)), "off");
+        assert_eq!(
+            sandbox_label(Some(&SandboxRecord::On(SandboxBackend::Lima))),
+            "lima"
+        );
+    }
+
+    #[test]
+    fn handle_candidates_fall_back_to_duplicate_base() {
+        assert_eq!(handle_candidates("feat-2".to_string()), ["feat-2", "feat"]);
+        assert_eq!(handle_candidates("feat".to_string()), ["feat"]);
+        assert_eq!(handle_candidates("fix-v2".to_string()), ["fix-v2"]);
+        assert_eq!(handle_candidates("feat-".to_string()), ["feat-"]);
+    }
+
+    #[test]
+    fn status_icon_uses_config() {
+        let mut config = Config::default();
+        config.status_icons.done = Some("D".to_string());
+        assert_eq!(
+            status_icon(Some(AgentStatus::Done), &config).as_deref(),
+            Some("D")
+        );
+        assert_eq!(
+            status_icon(Some(AgentStatus::Working), &config).as_deref(),
+            Some("🤖")
+        );
+        assert_eq!(status_icon(None, &config), None);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::multiplexer::handle::mode_label;
+use crate::multiplexer::util::WindowNames;
+use crate::multiplexer::{MuxHandle, create_backend, detect_backend};
+use crate::{config, git};
+use anyhow::{Context, Result, anyhow};
+
+/// Focus the open window/session of a worktree.
+pub fn run(name: &str) -> Result<()> {
+    let config = config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
+    let prefix = config.window_prefix();
+
+    // Smart resolution: try handle first, then branch name
+    let (worktree_path, _branch) = git::find_worktree(name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    let handle = worktree_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not derive handle from worktree path: {:?}",
+                worktree_path
+            )
+        })?;
+
+    let mode = git::get_worktree_mode(handle);
+    let full_name = WindowNames::load().full_name(prefix, handle);
+    let target = MuxHandle::new(mux.as_ref(), mode, &full_name);
+    let kind = mode_label(mode);
+
+    if !target.exists()? {
+        let open = if target.is_session() {
+            mux.get_all_session_names()?
+        } else {
+            mux.get_all_window_names()?
+        };
+        return Err(anyhow!(not_open_message(
+            kind,
+            handle,
+            prefix,
+            open.into_iter().collect()
+        )));
+    }
+
+    target.select()?;
+    println!("✓ Switched to {} '{}'", kind, full_name);
+    Ok(())
+}
+
+/// Error for a worktree without an open target, listing the open ones.
+fn not_open_message(kind: &str, handle: &str, prefix: &str, open: Vec<String>) -> String {
+    let mut open: Vec<String> = open
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    open.sort();
+
+    let mut message = format!(
+        "No open {} for '{}'. Use 'workmux open {}' to open it.",
+        kind, handle, handle
+    );
+    if open.is_empty() {
+        message.push_str(&format!("\nNo workmux {}s are open.", kind));
+    } else {
+        message.push_str(&format!("\nOpen {}s:", kind));
+        for name in open {
+            message.push_str(&format!("\n  {}", name));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_open_message_lists_prefixed_targets() {
+        let message = not_open_message(
+            "window",
+            "feat",
+            "wm-",
+            vec!["zsh".into(), "wm-other".into(), "wm-api".into()],
+        );
+        assert!(message.starts_with("No open window for 'feat'"));
+        assert!(message.contains("workmux open feat"));
+        assert!(message.ends_with("Open windows:\n  wm-api\n  wm-other"));
+        assert!(!message.contains("zsh"));
+    }
+
+    #[test]
+    fn not_open_message_without_open_targets() {
+        let message = not_open_message("session", "feat", "wm-", vec!["main".into()]);
+        assert!(message.ends_with("No workmux sessions are open."));
+    }
+}
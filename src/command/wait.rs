@@ -29,7 +29,8 @@ pub fn run(
     any: bool,
 ) -> Result<()> {
     let target = parse_status(target_status)?;
-    let mux = create_backend(detect_backend());
+    let config = crate::config::Config::load(None)?;
+    let mux = create_backend(detect_backend(&config), &config);
     let start = Instant::now();
 
     // Resolve worktree paths upfront
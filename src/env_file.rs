@@ -0,0 +1,232 @@
+//! Per-worktree environment files exported into new panes.
+
+use std::io::Write;
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+use tempfile::NamedTempFile;
+
+use crate::shell::shell_quote;
+
+/// Whether `key` is a valid shell variable name.
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Strip one pair of matching surrounding quotes from a value.
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Parse `KEY=VALUE` lines. Blank lines and `#` comments are ignored and an
+/// optional leading `export ` is accepted. Returns the variables in file order
+/// and a warning for each malformed line.
+fn parse(content: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut vars = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        match line.split_once('=') {
+            Some((key, value)) if is_valid_key(key.trim_end()) => {
+                vars.push((
+                    key.trim_end().to_string(),
+                    unquote(value.trim()).to_string(),
+                ));
+            }
+            _ => warnings.push(format!("line {}: expected KEY=VALUE, skipping", i + 1)),
+        }
+    }
+
+    (vars, warnings)
+}
+
+/// Load the env file named `file_name` from `worktree_root`.
+///
+/// A missing file yields no variables. Malformed lines and read errors are
+/// reported as warnings rather than failing pane creation.
+///
+/// `file_name` comes from project config, so it must be a relative path that
+/// stays inside the worktree after following symlinks. Otherwise a repository
+/// could have the host read files like `~/.aws/credentials` into a sandbox.
+pub fn load(worktree_root: &Path, file_name: &str) -> Vec<(String, String)> {
+    if !Path::new(file_name)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        eprintln!(
+            "Warning: env_file '{}' must be a relative path inside the worktree, skipping",
+            file_name
+        );
+        return Vec::new();
+    }
+
+    let path = worktree_root.join(file_name);
+    let contained = match (path.canonicalize(), worktree_root.canonicalize()) {
+        (Ok(resolved), Ok(root)) => resolved.starts_with(root),
+        (Err(e), _) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        _ => false,
+    };
+    if !contained {
+        eprintln!(
+            "Warning: env_file '{}' resolves outside the worktree, skipping",
+            file_name
+        );
+        return Vec::new();
+    }
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            eprintln!("Warning: failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let (vars, warnings) = parse(&content);
+    for warning in warnings {
+        eprintln!("Warning: {}: {}", path.display(), warning);
+    }
+    vars
+}
+
+/// Shell `export` statement for `vars`, or None when there are none.
+pub fn export_statement(vars: &[(String, String)]) -> Option<String> {
+    if vars.is_empty() {
+        return None;
+    }
+    let assignments: Vec<String> = vars
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, shell_quote(value)))
+        .collect();
+    Some(format!("export {}", assignments.join(" ")))
+}
+
+/// `vars` in docker/podman `--env-file` format: one raw `KEY=VALUE` per line.
+/// Values never contain newlines since the env file is parsed by line.
+pub fn runtime_env_file_content(vars: &[(String, String)]) -> String {
+    vars.iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+/// Write `content` to a new file in `dir` that only the current user can
+/// read (0600). The file is removed when the returned handle is dropped.
+pub fn write_private(dir: &Path, content: &str) -> Result<NamedTempFile> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut file = tempfile::Builder::new()
+        .prefix("env-")
+        .tempfile_in(dir)
+        .context("Failed to create env file")?;
+    file.write_all(content.as_bytes())
+        .context("Failed to write env file")?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_comments_and_blank_lines() {
+        let (vars, warnings) = parse("# comment\n\nFOO=bar\nexport BAZ = \"a b\"\nEMPTY=\n");
+        assert_eq!(
+            vars,
+            vec![
+                ("FOO".to_string(), "bar".to_string()),
+                ("BAZ".to_string(), "a b".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_warns_on_malformed_lines() {
+        let (vars, warnings) = parse("GOOD=1\nno equals sign\n1BAD=x\n=value\n");
+        assert_eq!(vars, vec![("GOOD".to_string(), "1".to_string())]);
+        assert_eq!(
+            warnings,
+            vec![
+                "line 2: expected KEY=VALUE, skipping",
+                "line 3: expected KEY=VALUE, skipping",
+                "line 4: expected KEY=VALUE, skipping",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_keeps_equals_in_value() {
+        let (vars, _) = parse("URL='postgres://u:p@host/db?a=b'");
+        assert_eq!(vars[0].1, "postgres://u:p@host/db?a=b");
+    }
+
+    #[test]
+    fn export_statement_quotes_values() {
+        assert_eq!(export_statement(&[]), None);
+        let vars = vec![
+            ("A".to_string(), "plain".to_string()),
+            ("B".to_string(), "it's $HOME".to_string()),
+        ];
+        assert_eq!(
+            export_statement(&vars).unwrap(),
+            "export A=plain B='it'\\''s $HOME'"
+        );
+    }
+
+    #[test]
+    fn write_private_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let vars = vec![("A".to_string(), "x y $(z)".to_string())];
+        let file =
+            write_private(&dir.path().join("env"), &runtime_env_file_content(&vars)).unwrap();
+        let meta = std::fs::metadata(file.path()).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "A=x y $(z)\n"
+        );
+    }
+
+    #[test]
+    fn load_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(dir.path(), ".workmux.env").is_empty());
+        std::fs::write(dir.path().join(".workmux.env"), "KEY=value\n").unwrap();
+        assert_eq!(
+            load(dir.path(), ".workmux.env"),
+            vec![("KEY".to_string(), "value".to_string())]
+        );
+    }
+
+    #[test]
+    fn load_rejects_paths_outside_worktree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("wt");
+        std::fs::create_dir_all(&root).unwrap();
+        let secret = tmp.path().join("credentials");
+        std::fs::write(&secret, "aws_secret_access_key = hunter2\n").unwrap();
+
+        assert!(load(&root, "../credentials").is_empty());
+        assert!(load(&root, secret.to_str().unwrap()).is_empty());
+
+        std::os::unix::fs::symlink(&secret, root.join(".workmux.env")).unwrap();
+        assert!(load(&root, ".workmux.env").is_empty());
+    }
+}
@@ -117,6 +117,29 @@ pub fn get_current_branch() -> Result<String> {
         .run_and_capture_stdout()
 }
 
+/// Get the branch checked out in `workdir`
+pub fn get_current_branch_in(workdir: &Path) -> Result<String> {
+    Cmd::new("git")
+        .workdir(workdir)
+        .args(&["branch", "--show-current"])
+        .run_and_capture_stdout()
+}
+
+/// List local branch names.
+pub fn list_local_branches() -> Result<Vec<String>> {
+    let output = Cmd::new("git")
+        .args(&["for-each-ref", "--format=%(refname:short)", "refs/heads/"])
+        .run_and_capture_stdout()
+        .context("Failed to list local branches")?;
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect())
+}
+
 /// List all checkout-able branches (local and remote) for shell completion.
 /// Excludes branches that are already checked out in existing worktrees.
 pub fn list_checkout_branches() -> Result<Vec<String>> {
@@ -162,6 +185,16 @@ pub fn delete_branch_in(branch_name: &str, force: bool, git_common_dir: &Path) -
     Ok(())
 }
 
+/// Rename a branch. Git moves its `branch.<name>.*` config (including the
+/// stored workmux base) along with it.
+pub fn rename_branch(old: &str, new: &str) -> Result<()> {
+    Cmd::new("git")
+        .args(&["branch", "-m", old, new])
+        .run()
+        .with_context(|| format!("Failed to rename branch '{}' to '{}'", old, new))?;
+    Ok(())
+}
+
 /// Get the base branch for merge checks, preferring local branch over remote
 pub fn get_merge_base(main_branch: &str) -> Result<String> {
     // Check if the local branch exists first.
@@ -180,6 +213,19 @@ pub fn get_merge_base(main_branch: &str) -> Result<String> {
     }
 }
 
+/// Get the Unix timestamp of the last commit checked out in a worktree
+pub fn get_last_commit_time_in(workdir: &Path) -> Result<u64> {
+    let output = Cmd::new("git")
+        .workdir(workdir)
+        .args(&["log", "-1", "--format=%ct", "HEAD"])
+        .run_and_capture_stdout()
+        .context("Failed to get last commit time")?;
+    output
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid commit timestamp: {}", output.trim()))
+}
+
 /// Get a set of all branches not merged into the base branch
 pub fn get_unmerged_branches(base_branch: &str) -> Result<HashSet<String>> {
     // Special handling for potential errors since base branch might not exist
@@ -264,15 +310,18 @@ pub(super) fn branch_has_upstream(branch_name: &str) -> Result<bool> {
 
 /// Store the base branch/commit that a branch was created from
 pub fn set_branch_base(branch: &str, base: &str) -> Result<()> {
-    Cmd::new("git")
-        .args(&[
-            "config",
-            "--local",
-            &format!("branch.{}.workmux-base", branch),
-            base,
-        ])
-        .run()
-        .context("Failed to set workmux-base config")?;
+    set_branch_base_in(branch, base, None)
+}
+
+/// Store the base branch for a given branch in a specific workdir
+pub fn set_branch_base_in(branch: &str, base: &str, workdir: Option<&Path>) -> Result<()> {
+    let config_key = format!("branch.{}.workmux-base", branch);
+    let cmd = Cmd::new("git").args(&["config", "--local", &config_key, base]);
+    let cmd = match workdir {
+        Some(path) => cmd.workdir(path),
+        None => cmd,
+    };
+    cmd.run().context("Failed to set workmux-base config")?;
     Ok(())
 }
 
@@ -347,4 +396,22 @@ mod tests {
     fn test_parse_fork_branch_spec_remote_branch_format() {
         assert!(parse_fork_branch_spec("origin/feature").is_none());
     }
+
+    #[test]
+    fn test_branch_base_round_trip_with_remote_ref() {
+        // --base-pr records the PR's remote head ref (e.g. "origin/feature-a")
+        let tmp = tempfile::tempdir().unwrap();
+        Cmd::new("git")
+            .args(&["init", "-q"])
+            .workdir(tmp.path())
+            .run()
+            .unwrap();
+
+        set_branch_base_in("stacked", "origin/feature-a", Some(tmp.path())).unwrap();
+        assert_eq!(
+            get_branch_base_in("stacked", Some(tmp.path())).unwrap(),
+            "origin/feature-a"
+        );
+        assert!(get_branch_base_in("other", Some(tmp.path())).is_err());
+    }
 }
@@ -146,6 +146,68 @@ fn parse_owner_from_git_url(url: &str) -> Option<&str> {
     }
 }
 
+/// Most recent PRs (or GitLab MRs) fetched for `workmux list --pr`
+pub const PR_LIST_LIMIT: usize = 200;
+
+/// Code hosting provider of a repository, for PR/MR lookups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Forge {
+    #[default]
+    GitHub,
+    GitLab,
+}
+
+impl Forge {
+    /// Detect the provider from a remote URL. Hosts containing "gitlab"
+    /// (gitlab.com or self-hosted gitlab.example.com) and hosts listed in
+    /// `gitlab_hosts` are GitLab; anything else is treated as GitHub.
+    pub fn from_remote_url(url: &str, gitlab_hosts: &[String]) -> Self {
+        let host = parse_host_from_git_url(url)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if host.contains("gitlab") || gitlab_hosts.iter().any(|h| h.eq_ignore_ascii_case(&host)) {
+            Forge::GitLab
+        } else {
+            Forge::GitHub
+        }
+    }
+
+    /// Prefix GitHub or GitLab puts before PR/MR numbers (`#12` vs `!12`).
+    pub fn number_prefix(self) -> &'static str {
+        match self {
+            Forge::GitHub => "#",
+            Forge::GitLab => "!",
+        }
+    }
+
+    /// Whether PR lookups for this provider include CI check results.
+    pub fn reports_checks(self) -> bool {
+        match self {
+            Forge::GitHub => true,
+            Forge::GitLab => false,
+        }
+    }
+}
+
+/// Detect the provider from the origin remote, defaulting to GitHub.
+pub fn detect_forge(gitlab_hosts: &[String]) -> Forge {
+    get_remote_url("origin")
+        .map(|url| Forge::from_remote_url(&url, gitlab_hosts))
+        .unwrap_or_default()
+}
+
+/// Parse the host from an HTTPS, `ssh://` or scp-style (`git@host:path`) URL
+fn parse_host_from_git_url(url: &str) -> Option<&str> {
+    if let Some((_, rest)) = url.split_once("://") {
+        let authority = rest.split('/').next()?;
+        let host = authority.rsplit('@').next()?;
+        Some(host.split(':').next().unwrap_or(host))
+    } else {
+        let (user_host, _) = url.split_once(':')?;
+        Some(user_host.rsplit('@').next().unwrap_or(user_host))
+    }
+}
+
 /// Get the repository owner from the origin remote URL
 pub fn get_repo_owner() -> Result<String> {
     let url = get_remote_url("origin")?;
@@ -157,7 +219,42 @@ pub fn get_repo_owner() -> Result<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_owner_from_git_url;
+    use super::{Forge, parse_owner_from_git_url};
+
+    #[test]
+    fn test_forge_from_remote_url() {
+        for url in [
+            "git@gitlab.com:group/repo.git",
+            "https://gitlab.com/group/sub/repo.git",
+            "ssh://git@gitlab.example.com:2222/group/repo.git",
+        ] {
+            assert_eq!(Forge::from_remote_url(url, &[]), Forge::GitLab, "{url}");
+        }
+        for url in [
+            "git@github.com:owner/repo.git",
+            "https://github.enterprise.com/owner/repo",
+            // Only the host decides, not the path
+            "https://github.com/gitlab/repo.git",
+            "not a url",
+        ] {
+            assert_eq!(Forge::from_remote_url(url, &[]), Forge::GitHub, "{url}");
+        }
+    }
+
+    #[test]
+    fn test_forge_from_configured_gitlab_host() {
+        let hosts = vec!["git.example.com".to_string()];
+        for url in [
+            "git@git.example.com:group/repo.git",
+            "https://GIT.example.com/group/repo.git",
+        ] {
+            assert_eq!(Forge::from_remote_url(url, &hosts), Forge::GitLab, "{url}");
+        }
+        assert_eq!(
+            Forge::from_remote_url("git@git.example.org:group/repo.git", &hosts),
+            Forge::GitHub
+        );
+    }
 
     #[test]
     fn test_parse_repo_owner_https_github_com() {
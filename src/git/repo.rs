@@ -53,6 +53,14 @@ pub fn get_repo_root_for(dir: &Path) -> Result<PathBuf> {
     Ok(PathBuf::from(path))
 }
 
+/// Handle of the worktree containing `dir`, i.e. the name of its root.
+pub fn worktree_handle_for(dir: &Path) -> Option<String> {
+    get_repo_root_for(dir)
+        .ok()?
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
+
 /// Get the common git directory (shared across all worktrees).
 ///
 /// This returns the absolute path where git stores shared data like refs, objects, and config.
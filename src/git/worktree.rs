@@ -1,8 +1,9 @@
 use anyhow::{Context, Result, anyhow};
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 
 use crate::cmd::Cmd;
-use crate::config::MuxMode;
+use crate::config::{IsolationLevel, MuxMode, SandboxBackend};
 
 use super::WorktreeNotFound;
 use super::branch::unset_branch_upstream;
@@ -178,49 +179,225 @@ pub fn get_worktree_meta(handle: &str, key: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+/// A typed value stored under a fixed worktree metadata key.
+pub trait MetaValue: Sized {
+    /// Key under `workmux.worktree.<handle>`.
+    const KEY: &'static str;
+
+    fn as_meta_value(&self) -> Cow<'static, str>;
+
+    fn from_meta_value(value: &str) -> Option<Self>;
+}
+
+/// Store a typed metadata value for a worktree.
+pub fn set_worktree_meta_value<T: MetaValue>(handle: &str, value: &T) -> Result<()> {
+    set_worktree_meta(handle, T::KEY, &value.as_meta_value())
+}
+
+/// Read a typed metadata value for a worktree.
+/// Returns None if the key is missing or holds an unrecognized value.
+pub fn get_worktree_meta_value<T: MetaValue>(handle: &str) -> Option<T> {
+    get_worktree_meta(handle, T::KEY).and_then(|v| T::from_meta_value(&v))
+}
+
+/// Batch-load a typed metadata value for all worktrees.
+fn get_all_worktree_meta_values<T: MetaValue>() -> std::collections::HashMap<String, T> {
+    get_all_worktree_meta(T::KEY)
+        .into_iter()
+        .filter_map(|(handle, value)| T::from_meta_value(&value).map(|v| (handle, v)))
+        .collect()
+}
+
+impl MetaValue for MuxMode {
+    const KEY: &'static str = "mode";
+
+    fn as_meta_value(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            MuxMode::Window => "window",
+            MuxMode::Session => "session",
+        })
+    }
+
+    fn from_meta_value(value: &str) -> Option<Self> {
+        match value {
+            "window" => Some(MuxMode::Window),
+            "session" => Some(MuxMode::Session),
+            _ => None,
+        }
+    }
+}
+
 /// Determine the tmux mode for a worktree from git metadata.
 /// Falls back to Window mode if no metadata is found (backward compatibility).
 pub fn get_worktree_mode(handle: &str) -> MuxMode {
-    match get_worktree_meta(handle, "mode") {
-        Some(mode) if mode == "session" => MuxMode::Session,
-        _ => MuxMode::Window,
-    }
+    get_worktree_meta_value(handle).unwrap_or(MuxMode::Window)
 }
 
-/// Batch-load all worktree modes from git config in a single subprocess call.
-/// Returns a map from handle to MuxMode. Handles not in the map default to Window.
-pub fn get_all_worktree_modes() -> std::collections::HashMap<String, MuxMode> {
+/// Batch-load a metadata key for all worktrees in a single subprocess call.
+/// Returns a map from handle to the raw stored value.
+fn get_all_worktree_meta(key: &str) -> std::collections::HashMap<String, String> {
     let output = Cmd::new("git")
         .args(&[
             "config",
             "--local",
             "--get-regexp",
-            r"^workmux\.worktree\..*\.mode$",
+            &format!(r"^workmux\.worktree\..*\.{}$", key),
         ])
         .run_and_capture_stdout()
         .unwrap_or_default();
 
-    let mut modes = std::collections::HashMap::new();
+    parse_worktree_meta_lines(&output, key)
+}
+
+/// Parse `git config --get-regexp` output for a worktree metadata key.
+///
+/// Each line has the format "workmux.worktree.<handle>.<key> <value>".
+fn parse_worktree_meta_lines(output: &str, key: &str) -> std::collections::HashMap<String, String> {
+    let suffix = format!(".{}", key);
+    let mut values = std::collections::HashMap::new();
     for line in output.lines() {
-        // Format: "workmux.worktree.<handle>.mode <value>"
         let parts: Vec<&str> = line.splitn(2, ' ').collect();
-        if parts.len() == 2 {
-            let key = parts[0];
-            let value = parts[1].trim();
-            // Extract handle from "workmux.worktree.<handle>.mode"
-            if let Some(rest) = key.strip_prefix("workmux.worktree.")
-                && let Some(handle) = rest.strip_suffix(".mode")
-            {
-                let mode = if value == "session" {
-                    MuxMode::Session
-                } else {
-                    MuxMode::Window
-                };
-                modes.insert(handle.to_string(), mode);
-            }
+        if parts.len() == 2
+            && let Some(rest) = parts[0].strip_prefix("workmux.worktree.")
+            && let Some(handle) = rest.strip_suffix(suffix.as_str())
+        {
+            values.insert(handle.to_string(), parts[1].trim().to_string());
         }
     }
-    modes
+    values
+}
+
+/// Batch-load all worktree modes from git config in a single subprocess call.
+/// Returns a map from handle to MuxMode. Handles not in the map default to Window.
+pub fn get_all_worktree_modes() -> std::collections::HashMap<String, MuxMode> {
+    get_all_worktree_meta_values()
+}
+
+/// Batch-load the window names rendered from `window_name_template`.
+/// Returns a map from handle to full window/session name.
+pub fn get_all_worktree_window_names() -> std::collections::HashMap<String, String> {
+    get_all_worktree_meta("window")
+}
+
+/// Sandbox usage recorded for a worktree's most recent launch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SandboxRecord {
+    /// Panes were launched without a sandbox
+    Off,
+    /// Panes were launched inside the given sandbox backend
+    On(SandboxBackend),
+}
+
+impl MetaValue for SandboxRecord {
+    const KEY: &'static str = "sandbox";
+
+    fn as_meta_value(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            SandboxRecord::Off => "off",
+            SandboxRecord::On(SandboxBackend::Container) => "container",
+            SandboxRecord::On(SandboxBackend::Lima) => "lima",
+        })
+    }
+
+    fn from_meta_value(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(SandboxRecord::Off),
+            "container" => Some(SandboxRecord::On(SandboxBackend::Container)),
+            "lima" => Some(SandboxRecord::On(SandboxBackend::Lima)),
+            _ => None,
+        }
+    }
+}
+
+/// Record whether a worktree's panes were launched in a sandbox.
+pub fn set_worktree_sandbox(handle: &str, record: &SandboxRecord) -> Result<()> {
+    set_worktree_meta_value(handle, record)
+}
+
+/// Read the recorded sandbox usage for a worktree.
+/// Returns None for worktrees launched before sandbox usage was recorded.
+pub fn get_worktree_sandbox(handle: &str) -> Option<SandboxRecord> {
+    get_worktree_meta_value(handle)
+}
+
+/// Batch-load recorded sandbox usage for all worktrees in a single subprocess call.
+pub fn get_all_worktree_sandboxes() -> std::collections::HashMap<String, SandboxRecord> {
+    get_all_worktree_meta_values()
+}
+
+impl MetaValue for IsolationLevel {
+    const KEY: &'static str = "isolation";
+
+    /// Stored under the same names `sandbox.lima.isolation` accepts.
+    fn as_meta_value(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            IsolationLevel::Shared => "shared",
+            IsolationLevel::Project => "project",
+            IsolationLevel::Worktree => "worktree",
+        })
+    }
+
+    fn from_meta_value(value: &str) -> Option<Self> {
+        match value {
+            "shared" => Some(IsolationLevel::Shared),
+            "project" => Some(IsolationLevel::Project),
+            "worktree" => Some(IsolationLevel::Worktree),
+            _ => None,
+        }
+    }
+}
+
+/// Record the Lima isolation level a worktree was created with.
+pub fn set_worktree_isolation(handle: &str, isolation: &IsolationLevel) -> Result<()> {
+    set_worktree_meta_value(handle, isolation)
+}
+
+/// Read the Lima isolation level recorded for a worktree, if any.
+/// Worktrees created before isolation was recorded follow
+/// `sandbox.lima.isolation` in config.
+pub fn get_worktree_isolation(handle: &str) -> Option<IsolationLevel> {
+    get_worktree_meta_value(handle)
+}
+
+/// Record a sandbox image chosen with `add --image`.
+pub fn set_worktree_sandbox_image(handle: &str, image: &str) -> Result<()> {
+    set_worktree_meta(handle, "image", image)
+}
+
+/// Read the sandbox image recorded for a worktree, if any.
+/// Worktrees without an override follow `sandbox.image` in config.
+pub fn get_worktree_sandbox_image(handle: &str) -> Option<String> {
+    get_worktree_meta(handle, "image")
+}
+
+/// Record the agent a worktree was created with (`add --agent`).
+pub fn set_worktree_agent(handle: &str, agent: &str) -> Result<()> {
+    set_worktree_meta(handle, "agent", agent)
+}
+
+/// Read the agent recorded for a worktree, if any.
+pub fn get_worktree_agent(handle: &str) -> Option<String> {
+    get_worktree_meta(handle, "agent")
+}
+
+/// Record the PR a worktree was created from with `add --pr`.
+pub fn set_worktree_pr(handle: &str, pr: u32) -> Result<()> {
+    set_worktree_meta(handle, "pr", &pr.to_string())
+}
+
+/// Read the PR number recorded for a worktree, if any.
+pub fn get_worktree_pr(handle: &str) -> Option<u32> {
+    get_worktree_meta(handle, "pr").and_then(|pr| pr.parse().ok())
+}
+
+/// Record that the worktree's setup script succeeded.
+pub fn set_worktree_setup_done(handle: &str) -> Result<()> {
+    set_worktree_meta(handle, "setup", "done")
+}
+
+/// Whether the worktree's setup script already ran.
+pub fn is_worktree_setup_done(handle: &str) -> bool {
+    get_worktree_meta(handle, "setup").is_some()
 }
 
 /// Remove all metadata for a worktree handle.
@@ -288,3 +465,69 @@ pub fn get_main_worktree_root() -> Result<PathBuf> {
         Err(anyhow!("No main worktree found"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_worktree_meta_lines_extracts_handles() {
+        let output = "workmux.worktree.feature-a.sandbox container\n\
+                      workmux.worktree.feature-b.sandbox off\n\
+                      workmux.worktree.feature-c.mode session";
+        let values = parse_worktree_meta_lines(output, "sandbox");
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["feature-a"], "container");
+        assert_eq!(values["feature-b"], "off");
+    }
+
+    #[test]
+    fn parse_worktree_meta_lines_handles_dotted_handles() {
+        let output = "workmux.worktree.v1.2-fix.mode session";
+        let values = parse_worktree_meta_lines(output, "mode");
+        assert_eq!(values["v1.2-fix"], "session");
+    }
+
+    #[test]
+    fn sandbox_record_meta_roundtrip() {
+        for record in [
+            SandboxRecord::Off,
+            SandboxRecord::On(SandboxBackend::Container),
+            SandboxRecord::On(SandboxBackend::Lima),
+        ] {
+            assert_eq!(
+                SandboxRecord::from_meta_value(&record.as_meta_value()),
+                Some(record)
+            );
+        }
+    }
+
+    #[test]
+    fn isolation_level_meta_value_round_trip() {
+        for level in [
+            IsolationLevel::Shared,
+            IsolationLevel::Project,
+            IsolationLevel::Worktree,
+        ] {
+            assert_eq!(
+                IsolationLevel::from_meta_value(&level.as_meta_value()),
+                Some(level)
+            );
+        }
+        assert_eq!(IsolationLevel::from_meta_value("vm"), None);
+    }
+
+    #[test]
+    fn mux_mode_meta_value_round_trip() {
+        for mode in [MuxMode::Window, MuxMode::Session] {
+            assert_eq!(MuxMode::from_meta_value(&mode.as_meta_value()), Some(mode));
+        }
+        assert_eq!(MuxMode::from_meta_value("tab"), None);
+    }
+
+    #[test]
+    fn sandbox_record_unknown_value() {
+        assert_eq!(SandboxRecord::from_meta_value("docker"), None);
+        assert_eq!(SandboxRecord::from_meta_value(""), None);
+    }
+}
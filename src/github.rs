@@ -5,6 +5,8 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::debug;
 
+use crate::git::PR_LIST_LIMIT;
+
 #[derive(Debug, Deserialize)]
 pub struct PrDetails {
     #[serde(rename = "headRefName")]
@@ -246,7 +248,7 @@ struct PrBatchItem {
     status_check_rollup: Vec<CheckRollupItem>,
 }
 
-/// Fetch all PRs for the current repository.
+/// Fetch up to [`PR_LIST_LIMIT`] PRs for the current repository.
 pub fn list_prs() -> Result<HashMap<String, PrSummary>> {
     let output = Command::new("gh")
         .args([
@@ -257,8 +259,8 @@ pub fn list_prs() -> Result<HashMap<String, PrSummary>> {
             "--json",
             "number,title,state,isDraft,headRefName,statusCheckRollup",
             "--limit",
-            "200",
         ])
+        .arg(PR_LIST_LIMIT.to_string())
         .output();
 
     let output = match output {
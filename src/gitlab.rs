@@ -0,0 +1,165 @@
+//! GitLab merge request lookups via the `glab` CLI.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Command;
+use tracing::debug;
+
+use crate::git::PR_LIST_LIMIT;
+use crate::github::PrSummary;
+
+/// GitLab caps `--per-page` at 100, so larger limits take several pages.
+const GLAB_PAGE_SIZE: usize = 100;
+
+/// Summary of a merge request, keyed by its source branch in [`list_mrs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MrSummary {
+    /// Project-scoped MR number (the `!123` in GitLab's UI)
+    pub number: u32,
+    pub title: String,
+    /// GitLab state: "opened", "merged", "closed" or "locked"
+    pub state: String,
+    pub is_draft: bool,
+}
+
+impl From<MrSummary> for PrSummary {
+    /// Map onto the GitHub PR shape so list and dashboard code handle both
+    /// providers. States are translated to GitHub's vocabulary.
+    fn from(mr: MrSummary) -> Self {
+        let state = match mr.state.as_str() {
+            "opened" => "OPEN",
+            "merged" => "MERGED",
+            _ => "CLOSED",
+        };
+        PrSummary {
+            number: mr.number,
+            title: mr.title,
+            state: state.to_string(),
+            is_draft: mr.is_draft,
+            checks: None,
+        }
+    }
+}
+
+/// Subset of the GitLab merge request JSON printed by `glab mr list --output json`
+#[derive(Debug, Deserialize)]
+struct MrListItem {
+    iid: u32,
+    title: String,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    source_branch: String,
+}
+
+/// Parse one page of `glab mr list` JSON.
+fn parse_mr_page(json: &[u8]) -> Result<Vec<MrListItem>> {
+    serde_json::from_slice(json).context("Failed to parse glab JSON output")
+}
+
+/// Key merge requests by source branch.
+fn mrs_by_branch(mrs: Vec<MrListItem>) -> HashMap<String, MrSummary> {
+    mrs.into_iter()
+        .map(|mr| {
+            (
+                mr.source_branch,
+                MrSummary {
+                    number: mr.iid,
+                    title: mr.title,
+                    state: mr.state,
+                    is_draft: mr.draft,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Fetch up to [`PR_LIST_LIMIT`] merge requests for the current repository.
+pub fn list_mrs() -> Result<HashMap<String, MrSummary>> {
+    let mut mrs = Vec::new();
+    for page in 1..=PR_LIST_LIMIT.div_ceil(GLAB_PAGE_SIZE) {
+        let Some(stdout) = fetch_mr_page(page)? else {
+            break;
+        };
+        let items = parse_mr_page(&stdout)?;
+        let last_page = items.len() < GLAB_PAGE_SIZE;
+        mrs.extend(items);
+        if last_page {
+            break;
+        }
+    }
+    Ok(mrs_by_branch(mrs))
+}
+
+/// Run `glab mr list` for one page. `None` when glab is missing or fails.
+fn fetch_mr_page(page: usize) -> Result<Option<Vec<u8>>> {
+    let output = Command::new("glab")
+        .args(["mr", "list", "--all", "--output", "json", "--per-page"])
+        .arg(GLAB_PAGE_SIZE.to_string())
+        .arg("--page")
+        .arg(page.to_string())
+        .output();
+
+    let output = match output {
+        Ok(out) => out,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("gitlab:glab CLI not found, skipping MR lookup");
+            return Ok(None);
+        }
+        Err(e) => {
+            return Err(e).context("Failed to execute glab command");
+        }
+    };
+
+    if !output.status.success() {
+        debug!(page, "gitlab:mr list failed, treating as no more MRs");
+        return Ok(None);
+    }
+
+    Ok(Some(output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mr_list_keys_by_source_branch() {
+        let json = br#"[
+            {"iid": 12, "title": "Add feature", "state": "opened", "draft": true,
+             "source_branch": "feature", "target_branch": "main"},
+            {"iid": 7, "title": "Fix bug", "state": "merged", "source_branch": "fix"}
+        ]"#;
+        let mrs = mrs_by_branch(parse_mr_page(json).unwrap());
+        assert_eq!(mrs.len(), 2);
+        assert_eq!(
+            mrs["feature"],
+            MrSummary {
+                number: 12,
+                title: "Add feature".to_string(),
+                state: "opened".to_string(),
+                is_draft: true,
+            }
+        );
+        assert!(!mrs["fix"].is_draft);
+    }
+
+    #[test]
+    fn mr_states_map_to_pr_states() {
+        let mr = |state: &str| MrSummary {
+            number: 1,
+            title: String::new(),
+            state: state.to_string(),
+            is_draft: false,
+        };
+        for (state, expected) in [
+            ("opened", "OPEN"),
+            ("merged", "MERGED"),
+            ("closed", "CLOSED"),
+            ("locked", "CLOSED"),
+        ] {
+            assert_eq!(PrSummary::from(mr(state)).state, expected, "{state}");
+        }
+    }
+}
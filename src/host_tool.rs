@@ -0,0 +1,79 @@
+//! Run whichever of several host tools is installed, for the clipboard,
+//! browser, notification and sound support.
+
+use anyhow::{Context, Result, bail};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The first of `candidates` found in PATH.
+pub fn first_available<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .find(|program| which::which(program).is_ok())
+}
+
+/// Run the first installed program of `candidates` with the arguments `args`
+/// gives for it, writing `stdin` to its input, and wait for it. Fails with
+/// `missing` when none is installed, or when the program exits unsuccessfully.
+pub fn spawn_first_available(
+    candidates: &[&str],
+    args: impl Fn(&str) -> Vec<String>,
+    stdin: Option<&[u8]>,
+    missing: &str,
+) -> Result<()> {
+    let Some(program) = first_available(candidates) else {
+        bail!("{}", missing);
+    };
+    let mut child = Command::new(program)
+        .args(args(program))
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input)?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("{} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSING: &str = "workmux-no-such-tool";
+
+    #[test]
+    fn first_available_skips_missing_tools() {
+        assert_eq!(first_available(&[MISSING, "sh"]), Some("sh"));
+        assert_eq!(first_available(&[MISSING]), None);
+    }
+
+    #[test]
+    fn spawn_first_available_runs_and_reports_failures() {
+        let sh = |script: &'static str| move |_: &str| vec!["-c".to_string(), script.to_string()];
+        spawn_first_available(
+            &[MISSING, "sh"],
+            sh("read x; [ \"$x\" = hi ]"),
+            Some(b"hi\n"),
+            "",
+        )
+        .unwrap();
+
+        let err = spawn_first_available(&["sh"], sh("exit 3"), None, "").unwrap_err();
+        assert!(err.to_string().starts_with("sh exited with"), "{err}");
+
+        let err =
+            spawn_first_available(&[MISSING], sh("true"), None, "Install a tool").unwrap_err();
+        assert_eq!(err.to_string(), "Install a tool");
+    }
+}
@@ -6,59 +6,151 @@ use anyhow::{Context, Result, anyhow};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{EnvFilter, fmt};
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt};
 
 use crate::sandbox::guest::is_sandbox_guest;
 
 static INIT: OnceLock<()> = OnceLock::new();
-static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static GUARDS: OnceLock<Vec<WorkerGuard>> = OnceLock::new();
+
+/// Logging options from the global CLI flags.
+#[derive(Debug, Default)]
+pub struct LogOptions {
+    /// Number of `-v` flags: 1 logs at debug, 2 or more at trace.
+    pub verbose: u8,
+    /// `-q`: only log warnings and errors.
+    pub quiet: bool,
+    /// Extra files that receive a copy of the log.
+    pub log_files: Vec<PathBuf>,
+}
 
-pub fn init() -> Result<()> {
+pub fn init(options: &LogOptions) -> Result<()> {
     if INIT.get().is_some() {
         return Ok(());
     }
 
-    // Skip file logging in sandbox guests - they're thin RPC clients and the
-    // host supervisor handles all real logging. Also avoids needing to create
-    // ~/.local/state/ in containers.
-    if is_sandbox_guest() {
-        let _ = INIT.set(());
-        return Ok(());
-    }
-
-    init_inner()?;
+    init_inner(options)?;
     let _ = INIT.set(());
     Ok(())
 }
 
-fn init_inner() -> Result<()> {
-    let log_path = determine_log_path()?;
-    if let Some(parent) = log_path.parent() {
+/// Filter directive for the verbosity flags, or None to fall back to
+/// `RUST_LOG` (default: info).
+fn level_directive(verbose: u8, quiet: bool) -> Option<&'static str> {
+    match (quiet, verbose) {
+        (true, _) => Some("warn"),
+        (false, 0) => None,
+        (false, 1) => Some("debug"),
+        (false, _) => Some("trace"),
+    }
+}
+
+/// Non-blocking appender for `path`, creating its directory if needed.
+fn file_writer(
+    path: &Path,
+    guards: &mut Vec<WorkerGuard>,
+) -> Result<tracing_appender::non_blocking::NonBlocking> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create log directory at {}", parent.display()))?;
     }
 
-    let (directory, file_name) = split_path(&log_path)?;
-    let file_appender = rolling::never(directory, file_name);
-    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
-    let _ = GUARD.set(guard);
+    // `rolling::never` panics on files it can't open, so surface that as an error
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))?;
+
+    let (directory, file_name) = split_path(path)?;
+    let (non_blocking, guard) =
+        tracing_appender::non_blocking(rolling::never(directory, file_name));
+    guards.push(guard);
+    Ok(non_blocking)
+}
+
+fn init_inner(options: &LogOptions) -> Result<()> {
+    let mut guards = Vec::new();
+
+    // Skip the default log file in sandbox guests - they're thin RPC clients
+    // and the host supervisor handles all real logging. Also avoids needing to
+    // create ~/.local/state/ in containers.
+    let mut writers = Vec::new();
+    if !is_sandbox_guest() {
+        // The default log is best effort; a missing one shouldn't stop the command
+        match determine_log_path().and_then(|path| file_writer(&path, &mut guards)) {
+            Ok(writer) => writers.push(writer),
+            Err(e) => eprintln!("Warning: {:#}", e),
+        }
+    }
+    // Log requested in a file goes to stderr if the file can't be opened
+    let mut stderr_fallback = false;
+    for path in &options.log_files {
+        match file_writer(path, &mut guards) {
+            Ok(writer) => writers.push(writer),
+            Err(e) => {
+                eprintln!("Warning: {:#}; logging to stderr instead", e);
+                stderr_fallback = true;
+            }
+        }
+    }
+    let _ = GUARDS.set(guards);
+
+    if writers.is_empty() && options.verbose == 0 && !stderr_fallback {
+        return Ok(());
+    }
 
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let env_filter = match level_directive(options.verbose, options.quiet) {
+        Some(level) => EnvFilter::new(level),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
 
-    tracing_subscriber::registry()
-        .with(env_filter)
-        .with(
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+    for writer in writers {
+        layers.push(
             fmt::layer()
-                .with_writer(non_blocking)
+                .with_writer(writer)
                 .with_ansi(false)
-                .with_target(false),
-        )
+                .with_target(false)
+                .boxed(),
+        );
+    }
+    // -v also shows the log on the terminal
+    if options.verbose > 0 || stderr_fallback {
+        layers.push(
+            fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_target(false)
+                .boxed(),
+        );
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(env_filter)
         .try_init()
         .context("Failed to initialize tracing subscriber")?;
 
     Ok(())
 }
 
+/// Move `path` aside to `<path>.1` once it grows past `max_bytes`, replacing
+/// any previous rotation. Missing files are left alone.
+pub fn rotate_if_larger(path: &Path, max_bytes: u64) -> Result<()> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    if metadata.len() <= max_bytes {
+        return Ok(());
+    }
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    fs::rename(path, &rotated)
+        .with_context(|| format!("Failed to rotate log file {}", path.display()))
+}
+
 fn determine_log_path() -> Result<PathBuf> {
     // Check XDG_STATE_HOME environment variable first
     if let Ok(state_home) = std::env::var("XDG_STATE_HOME")
@@ -95,3 +187,46 @@ fn split_path(path: &Path) -> Result<(PathBuf, &str)> {
 
     Ok((dir, file_name))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_directive_from_flags() {
+        assert_eq!(level_directive(0, false), None);
+        assert_eq!(level_directive(1, false), Some("debug"));
+        assert_eq!(level_directive(3, false), Some("trace"));
+        assert_eq!(level_directive(0, true), Some("warn"));
+    }
+
+    #[test]
+    fn file_writer_errors_on_unopenable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("{}.log", "x".repeat(300)));
+        let mut guards = Vec::new();
+        assert!(file_writer(&path, &mut guards).is_err());
+        assert!(guards.is_empty());
+    }
+
+    #[test]
+    fn rotate_if_larger_moves_big_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.log");
+
+        rotate_if_larger(&path, 10).unwrap();
+        assert!(!path.exists());
+
+        fs::write(&path, "small").unwrap();
+        rotate_if_larger(&path, 10).unwrap();
+        assert!(path.exists());
+
+        fs::write(&path, "more than ten bytes").unwrap();
+        rotate_if_larger(&path, 10).unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("run.log.1")).unwrap(),
+            "more than ten bytes"
+        );
+    }
+}
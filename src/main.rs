@@ -1,23 +1,31 @@
 mod agent_setup;
+mod browser;
 mod claude;
 mod cli;
+mod clipboard;
 mod cmd;
 mod command;
 mod config;
+mod env_file;
 mod git;
 mod github;
+mod gitlab;
+mod host_tool;
 mod llm;
 mod logger;
 mod markdown;
 mod multiplexer;
 mod naming;
 mod nerdfont;
+mod notification;
 mod prompt;
 mod sandbox;
 mod shell;
+mod sound;
 mod spinner;
 mod state;
 mod template;
+mod ui;
 mod util;
 mod workflow;
 
@@ -25,9 +33,7 @@ use anyhow::Result;
 use tracing::{error, info};
 
 fn main() -> Result<()> {
-    logger::init()?;
-    info!(args = ?std::env::args().collect::<Vec<_>>(), "workmux start");
-
+    // The logger is initialized by cli::run once the global log flags are parsed
     match cli::run() {
         Ok(result) => {
             info!("workmux finished successfully");
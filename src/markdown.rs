@@ -139,6 +139,23 @@ struct Styles {
     link: Style,
 }
 
+impl Styles {
+    /// Styles that add no escape codes, for `--no-color`.
+    fn plain() -> Self {
+        Self {
+            h1: Style::new(),
+            h2: Style::new(),
+            h3: Style::new(),
+            h4: Style::new(),
+            bold: Style::new(),
+            italic: Style::new(),
+            bold_italic: Style::new(),
+            code: Style::new(),
+            link: Style::new(),
+        }
+    }
+}
+
 impl Default for Styles {
     fn default() -> Self {
         Self {
@@ -164,14 +181,29 @@ fn wrap_styled_text(text: &str, width: usize, subsequent_indent: &str) -> Vec<St
         .collect()
 }
 
+/// How `docs` and `changelog` render markdown.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayOptions {
+    /// Wrap width in columns. Defaults to the terminal width, clamped to 40-100.
+    pub width: Option<usize>,
+    /// Render without colors or other styling.
+    pub no_color: bool,
+}
+
 /// Render markdown to terminal-formatted text
-pub fn render(input: &str) -> String {
+pub fn render(input: &str, options: &DisplayOptions) -> String {
     let mut output = String::new();
-    let term_width = Term::stdout().size().1 as usize;
-    let wrap_width = term_width.clamp(40, 100);
+    let wrap_width = options
+        .width
+        .map(|width| width.max(20))
+        .unwrap_or_else(|| (Term::stdout().size().1 as usize).clamp(40, 100));
 
     let parser = Parser::new_ext(input, Options::all());
-    let styles = Styles::default();
+    let styles = if options.no_color {
+        Styles::plain()
+    } else {
+        Styles::default()
+    };
 
     // State
     let mut text_buf = TextBuffer::new();
@@ -527,14 +559,26 @@ fn render_table(rows: &[Vec<String>], output: &mut String, max_width: usize, sty
     output.push('\n');
 }
 
-/// Display markdown content with pager when in a terminal, or raw when piped
-pub fn display(content: &str, raw: &str) {
+/// Whether `rendered` is too tall to fit on a terminal of `height` rows.
+fn needs_pager(rendered: &str, height: usize) -> bool {
+    rendered.lines().count() >= height
+}
+
+/// Display markdown content when in a terminal, or raw when piped.
+///
+/// Output taller than the terminal goes through `$PAGER` (default `less -R`).
+pub fn display(content: &str, raw: &str, options: &DisplayOptions) {
     if !std::io::stdout().is_terminal() {
         print!("{raw}");
         return;
     }
 
-    let rendered = render(content);
+    let rendered = render(content, options);
+    if !needs_pager(&rendered, Term::stdout().size().0 as usize) {
+        print!("{rendered}");
+        return;
+    }
+
     let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
     let mut parts = pager.split_whitespace();
     let cmd = parts.next().unwrap_or("less");
@@ -549,3 +593,39 @@ pub fn display(content: &str, raw: &str) {
         print!("{rendered}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str =
+        "# Title\n\nSome **bold** text that is long enough to need wrapping at a narrow width.\n";
+
+    #[test]
+    fn render_no_color_has_no_escape_codes() {
+        let options = DisplayOptions {
+            width: Some(80),
+            no_color: true,
+        };
+        let rendered = render(SAMPLE, &options);
+        assert!(!rendered.contains('\x1b'));
+        assert!(rendered.contains("Title"));
+    }
+
+    #[test]
+    fn render_wraps_at_requested_width() {
+        let options = DisplayOptions {
+            width: Some(30),
+            no_color: true,
+        };
+        let rendered = render(SAMPLE, &options);
+        assert!(rendered.lines().all(|line| measure_text_width(line) <= 30));
+        assert!(rendered.lines().count() > 3);
+    }
+
+    #[test]
+    fn needs_pager_only_when_taller_than_terminal() {
+        assert!(!needs_pager("a\nb\n", 24));
+        assert!(needs_pager(&"line\n".repeat(24), 24));
+    }
+}
@@ -5,18 +5,33 @@
 //! implementing this trait.
 
 use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::multiplexer::AgentStatus;
 
 /// Describes agent-specific behaviors for command rewriting and status handling.
 pub trait AgentProfile: Send + Sync {
     /// Canonical name used for matching (e.g., "claude", "gemini").
     fn name(&self) -> &'static str;
 
-    /// Whether this agent needs special handling for ! prefix (delay after !).
+    /// Milliseconds to wait after sending a leading `!` (0 = no delay).
     ///
     /// Claude Code requires a small delay after sending `!` for it to register
     /// as a bash command.
-    fn needs_bang_delay(&self) -> bool {
-        false
+    fn bang_delay_ms(&self) -> u64 {
+        0
+    }
+
+    /// Key that submits typed input, as a tmux key name (e.g. `C-Enter`).
+    fn submit_key(&self) -> &'static str {
+        "Enter"
+    }
+
+    /// Whether the agent accepts multi-line input as a bracketed paste.
+    /// Without it, multi-line text is typed line by line instead.
+    fn bracketed_paste(&self) -> bool {
+        true
     }
 
     /// Whether this agent needs auto-status when launched with a prompt file.
@@ -37,6 +52,12 @@ pub trait AgentProfile: Send + Sync {
         None
     }
 
+    /// Built-in regexes that the status watcher matches against the agent's
+    /// latest output, in order of precedence.
+    fn status_patterns(&self) -> &'static [(AgentStatus, &'static str)] {
+        &[]
+    }
+
     /// Format the prompt injection argument for this agent.
     ///
     /// Returns the CLI fragment to append (e.g., `-- "$(cat PROMPT.md)"`).
@@ -54,8 +75,8 @@ impl AgentProfile for ClaudeProfile {
         "claude"
     }
 
-    fn needs_bang_delay(&self) -> bool {
-        true
+    fn bang_delay_ms(&self) -> u64 {
+        50
     }
 
     fn needs_auto_status(&self) -> bool {
@@ -65,6 +86,13 @@ impl AgentProfile for ClaudeProfile {
     fn skip_permissions_flag(&self) -> Option<&'static str> {
         Some("--dangerously-skip-permissions")
     }
+
+    fn status_patterns(&self) -> &'static [(AgentStatus, &'static str)] {
+        &[
+            (AgentStatus::Waiting, r"Do you want to proceed\?"),
+            (AgentStatus::Working, r"esc to interrupt"),
+        ]
+    }
 }
 
 pub struct GeminiProfile;
@@ -78,6 +106,10 @@ impl AgentProfile for GeminiProfile {
         Some("--yolo")
     }
 
+    fn status_patterns(&self) -> &'static [(AgentStatus, &'static str)] {
+        &[(AgentStatus::Working, r"esc to cancel")]
+    }
+
     fn prompt_argument(&self, prompt_path: &str) -> String {
         format!("-i \"$(cat {})\"", prompt_path)
     }
@@ -109,6 +141,10 @@ impl AgentProfile for CodexProfile {
     fn skip_permissions_flag(&self) -> Option<&'static str> {
         Some("--yolo")
     }
+
+    fn status_patterns(&self) -> &'static [(AgentStatus, &'static str)] {
+        &[(AgentStatus::Working, r"(?i)esc to interrupt")]
+    }
 }
 
 pub struct DefaultProfile;
@@ -155,6 +191,104 @@ pub fn resolve_profile(agent_command: Option<&str>) -> &'static dyn AgentProfile
         .unwrap_or(&DefaultProfile)
 }
 
+/// How text is typed into an agent pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputProfile {
+    /// Pause after a leading `!` before the rest of the text.
+    pub bang_delay: Duration,
+    /// Pause between the text and the submit key.
+    pub submit_delay: Duration,
+    /// Key that submits the input, as a tmux key name.
+    pub submit_key: String,
+    /// Whether multi-line input is sent as a bracketed paste.
+    pub bracketed_paste: bool,
+}
+
+impl Default for InputProfile {
+    fn default() -> Self {
+        Self {
+            bang_delay: Duration::ZERO,
+            submit_delay: Duration::ZERO,
+            submit_key: "Enter".to_string(),
+            bracketed_paste: true,
+        }
+    }
+}
+
+/// Resolve the input profile for an agent command.
+///
+/// Starts from the built-in profile and applies the matching entry of the
+/// `profiles` config, looked up by the command's executable name.
+pub fn resolve_input_profile(agent_command: Option<&str>, config: &Config) -> InputProfile {
+    let profile = resolve_profile(agent_command);
+    let mut input = InputProfile {
+        bang_delay: Duration::from_millis(profile.bang_delay_ms()),
+        submit_delay: Duration::ZERO,
+        submit_key: profile.submit_key().to_string(),
+        bracketed_paste: profile.bracketed_paste(),
+    };
+
+    let overrides = agent_command.and_then(|cmd| {
+        let stem = extract_executable_stem(cmd);
+        config.profiles.as_ref()?.get(&stem)
+    });
+    if let Some(overrides) = overrides {
+        if let Some(ms) = overrides.bang_delay_ms {
+            input.bang_delay = Duration::from_millis(ms);
+        }
+        if let Some(ms) = overrides.submit_delay_ms {
+            input.submit_delay = Duration::from_millis(ms);
+        }
+        if let Some(key) = &overrides.submit_key {
+            input.submit_key = key.clone();
+        }
+        if let Some(bracketed) = overrides.bracketed_paste {
+            input.bracketed_paste = bracketed;
+        }
+    }
+    input
+}
+
+/// Resolve the status watcher's patterns for an agent command.
+///
+/// Starts from the built-in profile's patterns and applies the matching
+/// `profiles.<agent>.status_patterns` config entry per status. The result
+/// is ordered by precedence: waiting, done, then working.
+pub fn resolve_status_patterns(
+    agent_command: Option<&str>,
+    config: &Config,
+) -> Vec<(AgentStatus, String)> {
+    let profile = resolve_profile(agent_command);
+    let overrides = agent_command.and_then(|cmd| {
+        let stem = extract_executable_stem(cmd);
+        config
+            .profiles
+            .as_ref()?
+            .get(&stem)?
+            .status_patterns
+            .clone()
+    });
+    let overrides = overrides.unwrap_or_default();
+
+    [
+        (AgentStatus::Waiting, overrides.waiting),
+        (AgentStatus::Done, overrides.done),
+        (AgentStatus::Working, overrides.working),
+    ]
+    .into_iter()
+    .filter_map(|(status, configured)| {
+        let pattern = configured.or_else(|| {
+            profile
+                .status_patterns()
+                .iter()
+                .find(|(s, _)| *s == status)
+                .map(|(_, p)| p.to_string())
+        })?;
+        (!pattern.is_empty()).then_some((status, pattern))
+    })
+    .collect()
+}
+
 /// Extract the executable stem from a command string.
 ///
 /// Examples:
@@ -185,7 +319,7 @@ mod tests {
     fn test_claude_profile() {
         let profile = ClaudeProfile;
         assert_eq!(profile.name(), "claude");
-        assert!(profile.needs_bang_delay());
+        assert_eq!(profile.bang_delay_ms(), 50);
         assert!(profile.needs_auto_status());
         assert_eq!(
             profile.prompt_argument("PROMPT.md"),
@@ -201,7 +335,7 @@ mod tests {
     fn test_gemini_profile() {
         let profile = GeminiProfile;
         assert_eq!(profile.name(), "gemini");
-        assert!(!profile.needs_bang_delay());
+        assert_eq!(profile.bang_delay_ms(), 0);
         assert!(!profile.needs_auto_status());
         assert_eq!(
             profile.prompt_argument("PROMPT.md"),
@@ -214,7 +348,7 @@ mod tests {
     fn test_opencode_profile() {
         let profile = OpenCodeProfile;
         assert_eq!(profile.name(), "opencode");
-        assert!(!profile.needs_bang_delay());
+        assert_eq!(profile.bang_delay_ms(), 0);
         assert!(profile.needs_auto_status());
         assert_eq!(
             profile.prompt_argument("PROMPT.md"),
@@ -226,7 +360,7 @@ mod tests {
     fn test_codex_profile() {
         let profile = CodexProfile;
         assert_eq!(profile.name(), "codex");
-        assert!(!profile.needs_bang_delay());
+        assert_eq!(profile.bang_delay_ms(), 0);
         assert!(!profile.needs_auto_status());
         assert_eq!(
             profile.prompt_argument("PROMPT.md"),
@@ -239,7 +373,8 @@ mod tests {
     fn test_default_profile() {
         let profile = DefaultProfile;
         assert_eq!(profile.name(), "default");
-        assert!(!profile.needs_bang_delay());
+        assert_eq!(profile.bang_delay_ms(), 0);
+        assert_eq!(profile.submit_key(), "Enter");
         assert!(!profile.needs_auto_status());
         assert_eq!(
             profile.prompt_argument("PROMPT.md"),
@@ -291,6 +426,70 @@ mod tests {
         assert_eq!(profile.name(), "default");
     }
 
+    // === resolve_input_profile tests ===
+
+    fn profiles_config(yaml: &str) -> Config {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_input_profile_builtin_defaults() {
+        let config = Config::default();
+        let claude = resolve_input_profile(Some("claude"), &config);
+        assert_eq!(claude.bang_delay, Duration::from_millis(50));
+        assert_eq!(claude.submit_delay, Duration::ZERO);
+        assert_eq!(claude.submit_key, "Enter");
+        assert!(claude.bracketed_paste);
+        assert_eq!(
+            resolve_input_profile(None, &config),
+            InputProfile::default()
+        );
+    }
+
+    #[test]
+    fn test_input_profile_config_overrides() {
+        let config = profiles_config(
+            "profiles:\n  claude:\n    bang_delay_ms: 120\n  my-agent:\n    submit_delay_ms: 80\n    submit_key: C-m\n    bracketed_paste: false\n",
+        );
+        let claude = resolve_input_profile(Some("claude --verbose"), &config);
+        assert_eq!(claude.bang_delay, Duration::from_millis(120));
+
+        let custom = resolve_input_profile(Some("my-agent --fast"), &config);
+        assert_eq!(custom.bang_delay, Duration::ZERO);
+        assert_eq!(custom.submit_delay, Duration::from_millis(80));
+        assert_eq!(custom.submit_key, "C-m");
+        assert!(!custom.bracketed_paste);
+    }
+
+    #[test]
+    fn test_status_patterns_builtin_defaults() {
+        let patterns = resolve_status_patterns(Some("claude"), &Config::default());
+        let statuses: Vec<_> = patterns.iter().map(|(s, _)| *s).collect();
+        assert_eq!(statuses, [AgentStatus::Waiting, AgentStatus::Working]);
+        assert!(resolve_status_patterns(None, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_status_patterns_config_overrides() {
+        let config = profiles_config(
+            "profiles:\n  claude:\n    status_patterns:\n      working: ''\n      done: 'Task complete'\n  my-agent:\n    status_patterns:\n      waiting: '^> $'\n",
+        );
+        let claude = resolve_status_patterns(Some("claude"), &config);
+        assert_eq!(
+            claude,
+            [
+                (
+                    AgentStatus::Waiting,
+                    r"Do you want to proceed\?".to_string()
+                ),
+                (AgentStatus::Done, "Task complete".to_string()),
+            ]
+        );
+
+        let custom = resolve_status_patterns(Some("my-agent"), &config);
+        assert_eq!(custom, [(AgentStatus::Waiting, "^> $".to_string())]);
+    }
+
     // === is_known_agent tests ===
 
     #[test]
@@ -9,7 +9,6 @@ use std::time::Duration;
 use crate::config::MuxMode;
 
 use super::Multiplexer;
-use super::util;
 
 /// Returns "window" or "session" for a given mode.
 pub fn mode_label(mode: MuxMode) -> &'static str {
@@ -21,22 +20,22 @@ pub fn mode_label(mode: MuxMode) -> &'static str {
 
 /// A unified handle for a multiplexer target (window or session).
 ///
-/// Wraps a reference to the backend, the mode, prefix, and handle name,
+/// Wraps a reference to the backend, the mode, and the full target name,
 /// then dispatches to the correct window or session methods.
 pub struct MuxHandle<'a> {
     mux: &'a dyn Multiplexer,
     mode: MuxMode,
-    prefix: &'a str,
-    name: &'a str,
+    full_name: String,
 }
 
 impl<'a> MuxHandle<'a> {
-    pub fn new(mux: &'a dyn Multiplexer, mode: MuxMode, prefix: &'a str, name: &'a str) -> Self {
+    /// `full_name` is the resolved target name, e.g. from
+    /// [`WindowNames::full_name`](super::util::WindowNames::full_name).
+    pub fn new(mux: &'a dyn Multiplexer, mode: MuxMode, full_name: impl Into<String>) -> Self {
         Self {
             mux,
             mode,
-            prefix,
-            name,
+            full_name: full_name.into(),
         }
     }
 
@@ -49,17 +48,16 @@ impl<'a> MuxHandle<'a> {
         self.mode == MuxMode::Session
     }
 
-    /// The prefixed name (e.g., "wm-feature-auth").
-    pub fn full_name(&self) -> String {
-        util::prefixed(self.prefix, self.name)
+    /// The full name (e.g., "wm-feature-auth").
+    pub fn full_name(&self) -> &str {
+        &self.full_name
     }
 
     /// Check if the target exists.
     pub fn exists(&self) -> Result<bool> {
-        let full = self.full_name();
         match self.mode {
-            MuxMode::Session => self.mux.session_exists(&full),
-            MuxMode::Window => self.mux.window_exists(self.prefix, self.name),
+            MuxMode::Session => self.mux.session_exists(&self.full_name),
+            MuxMode::Window => self.mux.window_exists("", &self.full_name),
         }
     }
 
@@ -74,9 +72,10 @@ impl<'a> MuxHandle<'a> {
 
     /// Activate (focus/switch to) the target.
     pub fn select(&self) -> Result<()> {
+        // The name is already resolved, so no prefix
         match self.mode {
-            MuxMode::Session => self.mux.switch_to_session(self.prefix, self.name),
-            MuxMode::Window => self.mux.select_window(self.prefix, self.name),
+            MuxMode::Session => self.mux.switch_to_session("", &self.full_name),
+            MuxMode::Window => self.mux.select_window("", &self.full_name),
         }
     }
 
@@ -111,10 +110,11 @@ impl<'a> MuxHandle<'a> {
 
     /// Wait until the target is closed.
     pub fn wait_until_closed(&self) -> Result<()> {
-        let full = self.full_name();
         match self.mode {
-            MuxMode::Session => self.mux.wait_until_session_closed(&full),
-            MuxMode::Window => self.mux.wait_until_windows_closed(&[full]),
+            MuxMode::Session => self.mux.wait_until_session_closed(&self.full_name),
+            MuxMode::Window => self
+                .mux
+                .wait_until_windows_closed(std::slice::from_ref(&self.full_name)),
         }
     }
 
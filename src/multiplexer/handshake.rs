@@ -32,12 +32,40 @@ pub trait PaneHandshake: Send {
         self.wrapper_command(shell)
     }
 
-    /// Waits for the handshake signal, consuming the handshake object.
-    fn wait(self: Box<Self>) -> Result<()>;
+    /// Waits up to `timeout` for the handshake signal, consuming the handshake
+    /// object. Fails with [`HandshakeTimeout`] when the shell never signals.
+    fn wait(self: Box<Self>, timeout: Duration) -> Result<()>;
 }
 
-/// Timeout for waiting for pane readiness (seconds)
-const HANDSHAKE_TIMEOUT_SECS: u64 = 5;
+/// The pane's shell did not signal readiness in time, usually because it
+/// failed to start (e.g. an error in the shell's rc file).
+#[derive(Debug, thiserror::Error)]
+#[error("Pane handshake timed out after {}s - shell may have failed to start", .0.as_secs())]
+pub struct HandshakeTimeout(pub Duration);
+
+/// Lines of pane output included in a handshake timeout error.
+pub const TIMEOUT_CAPTURE_LINES: u16 = 20;
+
+/// Attach the pane's captured output to a [`HandshakeTimeout`] so the user can
+/// see why the shell didn't start. Other errors pass through unchanged.
+pub fn with_pane_output(err: anyhow::Error, pane_output: Option<&str>) -> anyhow::Error {
+    if !err.is::<HandshakeTimeout>() {
+        return err;
+    }
+    let output = pane_output.map(str::trim).unwrap_or_default();
+    let output = if output.is_empty() {
+        "(no output captured)"
+    } else {
+        output
+    };
+    anyhow!(
+        "{}.\nThe pane's shell never started; check your shell's rc files. \
+         If it is just slow, raise `handshake_timeout` in your config.\n\
+         Pane output:\n{}",
+        err,
+        output
+    )
+}
 
 /// Manages the tmux wait-for handshake protocol for pane synchronization.
 ///
@@ -108,7 +136,7 @@ impl PaneHandshake for TmuxHandshake {
     /// This method consumes the handshake to ensure cleanup happens exactly once.
     /// Uses a polling loop with timeout to prevent indefinite hangs if the pane
     /// fails to start.
-    fn wait(self: Box<Self>) -> Result<()> {
+    fn wait(self: Box<Self>, timeout: Duration) -> Result<()> {
         debug!(channel = %self.channel, "tmux:handshake start");
 
         let mut child = std::process::Command::new("tmux")
@@ -119,7 +147,6 @@ impl PaneHandshake for TmuxHandshake {
             .context("Failed to spawn tmux wait-for command")?;
 
         let start = Instant::now();
-        let timeout = Duration::from_secs(HANDSHAKE_TIMEOUT_SECS);
 
         loop {
             match child.try_wait() {
@@ -155,13 +182,10 @@ impl PaneHandshake for TmuxHandshake {
 
                         warn!(
                             channel = %self.channel,
-                            timeout_secs = HANDSHAKE_TIMEOUT_SECS,
+                            timeout_secs = timeout.as_secs(),
                             "tmux:handshake timeout"
                         );
-                        return Err(anyhow!(
-                            "Pane handshake timed out after {}s - shell may have failed to start",
-                            HANDSHAKE_TIMEOUT_SECS
-                        ));
+                        return Err(HandshakeTimeout(timeout).into());
                     }
                     trace!(
                         channel = %self.channel,
@@ -234,7 +258,7 @@ impl PaneHandshake for UnixPipeHandshake {
         )
     }
 
-    fn wait(self: Box<Self>) -> Result<()> {
+    fn wait(self: Box<Self>, timeout: Duration) -> Result<()> {
         use std::os::unix::fs::OpenOptionsExt;
         use std::os::unix::io::AsRawFd;
 
@@ -249,7 +273,6 @@ impl PaneHandshake for UnixPipeHandshake {
 
         let fd = file.as_raw_fd();
         let start = Instant::now();
-        let timeout = Duration::from_secs(HANDSHAKE_TIMEOUT_SECS);
 
         loop {
             // Check if data available via poll()
@@ -279,10 +302,12 @@ impl PaneHandshake for UnixPipeHandshake {
 
             if start.elapsed() >= timeout {
                 let _ = std::fs::remove_file(&self.pipe_path);
-                return Err(anyhow!(
-                    "Pane handshake timed out after {}s - shell may have failed to start",
-                    HANDSHAKE_TIMEOUT_SECS
-                ));
+                warn!(
+                    pipe = %self.pipe_path.display(),
+                    timeout_secs = timeout.as_secs(),
+                    "pipe:handshake timeout"
+                );
+                return Err(HandshakeTimeout(timeout).into());
             }
 
             // Continue polling
@@ -297,3 +322,42 @@ impl Drop for UnixPipeHandshake {
         let _ = std::fs::remove_file(&self.pipe_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_pane_output_appends_capture_on_timeout() {
+        let err = with_pane_output(
+            HandshakeTimeout(Duration::from_secs(10)).into(),
+            Some("zsh: command not found: nvm\n\n"),
+        );
+        let msg = err.to_string();
+        assert!(
+            msg.starts_with("Pane handshake timed out after 10s"),
+            "{msg}"
+        );
+        assert!(
+            msg.ends_with("Pane output:\nzsh: command not found: nvm"),
+            "{msg}"
+        );
+
+        let err = with_pane_output(HandshakeTimeout(Duration::from_secs(1)).into(), None);
+        assert!(err.to_string().contains("(no output captured)"));
+    }
+
+    #[test]
+    fn with_pane_output_passes_other_errors_through() {
+        let err = with_pane_output(anyhow!("Failed to open pipe"), Some("output"));
+        assert_eq!(err.to_string(), "Failed to open pipe");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pipe_handshake_times_out_without_signal() {
+        let handshake = Box::new(UnixPipeHandshake::new().unwrap());
+        let err = handshake.wait(Duration::from_millis(100)).unwrap_err();
+        assert!(err.is::<HandshakeTimeout>());
+    }
+}
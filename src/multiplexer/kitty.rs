@@ -17,7 +17,6 @@ use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::Duration;
 
-use super::agent;
 use super::handshake::UnixPipeHandshake;
 use super::types::*;
 use super::util;
@@ -184,7 +183,6 @@ impl KittyBackend {
     }
 
     /// Set the tab title for a window.
-    #[allow(dead_code)]
     fn set_tab_title(&self, window_id: &str, title: &str) -> Result<()> {
         self.kitten_cmd()
             .args(&[
@@ -397,6 +395,17 @@ impl Multiplexer for KittyBackend {
         Ok(())
     }
 
+    fn rename_window(&self, old_full_name: &str, new_full_name: &str) -> Result<()> {
+        let panes = self.list_panes()?;
+        let window_id = self
+            .panes_in_current_scope(&panes)
+            .iter()
+            .find(|p| p.tab_title == old_full_name)
+            .map(|p| p.window_id)
+            .ok_or_else(|| anyhow!("No tab named '{}'", old_full_name))?;
+        self.set_tab_title(&window_id.to_string(), new_full_name)
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let panes = self.list_panes()?;
         let scoped_panes = self.panes_in_current_scope(&panes);
@@ -649,54 +658,17 @@ impl Multiplexer for KittyBackend {
         Ok(())
     }
 
-    fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
-        if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
-            // Send ! first
-            self.kitten_cmd()
-                .args(&["send-text", "--match", &format!("id:{}", pane_id), "!"])
-                .run()
-                .context("Failed to send ! to pane")?;
-
-            // Small delay to let Claude register the !
-            thread::sleep(Duration::from_millis(50));
-
-            // Send the rest of the command
-            self.kitten_cmd()
-                .args(&[
-                    "send-text",
-                    "--match",
-                    &format!("id:{}", pane_id),
-                    &command[1..],
-                ])
-                .run()
-                .context("Failed to send keys to pane")?;
-
-            // Send Enter
-            self.kitten_cmd()
-                .args(&["send-text", "--match", &format!("id:{}", pane_id), "\r"])
-                .run()
-                .context("Failed to send Enter key to pane")?;
-
-            Ok(())
-        } else {
-            self.send_keys(pane_id, command)
-        }
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        self.kitten_cmd()
+            .args(&["send-text", "--match", &format!("id:{}", pane_id), text])
+            .run()
+            .context("Failed to send text to pane")?;
+        Ok(())
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
-        // Translate tmux key names to ANSI escape sequences for kitty.
         // The dashboard sends tmux-style names like "BSpace", "Enter", etc.
-        let translated = match key {
-            "BSpace" => "\x7f",
-            "Enter" => "\r",
-            "Tab" => "\t",
-            "Up" => "\x1b[A",
-            "Down" => "\x1b[B",
-            "Right" => "\x1b[C",
-            "Left" => "\x1b[D",
-            "Escape" => "\x1b",
-            _ => key,
-        };
+        let translated = util::key_sequence(key);
         self.kitten_cmd()
             .args(&[
                 "send-text",
@@ -709,7 +681,11 @@ impl Multiplexer for KittyBackend {
         Ok(())
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
+    fn paste_multiline(&self, pane_id: &str, content: &str, bracketed: bool) -> Result<()> {
+        if !bracketed {
+            return self.type_multiline(pane_id, content);
+        }
+
         // Use bracketed paste mode
         self.kitten_cmd()
             .args(&[
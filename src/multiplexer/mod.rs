@@ -13,7 +13,7 @@ pub mod util;
 pub mod wezterm;
 pub mod zellij;
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -24,7 +24,7 @@ pub use handshake::PaneHandshake;
 pub use tmux::TmuxBackend;
 pub use types::*;
 
-use crate::config::{Config, PaneConfig, SplitDirection};
+use crate::config::{Config, MultiplexerChoice, PaneConfig, SplitDirection};
 
 /// Main trait for terminal multiplexer backends.
 ///
@@ -49,6 +49,12 @@ pub trait Multiplexer: Send + Sync {
     /// Get the working directory of the active pane in the current client's session
     fn get_client_active_pane_path(&self) -> Result<PathBuf>;
 
+    /// Get the working directory of the pane workmux is running in.
+    /// More reliable than the process cwd for identifying the current worktree.
+    fn current_pane_cwd(&self) -> Result<PathBuf> {
+        self.get_client_active_pane_path()
+    }
+
     // === Window/Tab Management ===
 
     /// Create a new window/tab with the given parameters.
@@ -85,6 +91,10 @@ pub trait Multiplexer: Send + Sync {
     /// Kill a window by its full name (including prefix)
     fn kill_window(&self, full_name: &str) -> Result<()>;
 
+    /// Rename a window by its full name (including prefix). Panes keep
+    /// running; fails if no window has `old_full_name`.
+    fn rename_window(&self, old_full_name: &str, new_full_name: &str) -> Result<()>;
+
     /// Schedule a window to close after a delay
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()>;
 
@@ -182,6 +192,19 @@ pub trait Multiplexer: Send + Sync {
     /// Capture the content of a pane
     fn capture_pane(&self, pane_id: &str, lines: u16) -> Option<String>;
 
+    /// Check whether a pane is running the workmux dashboard.
+    ///
+    /// See [`util::is_dashboard`]. Backends whose `capture_pane` filters out
+    /// the dashboard must override this.
+    fn is_dashboard_pane(&self, pane_id: &str) -> bool {
+        let command = self
+            .get_live_pane_info(pane_id)
+            .ok()
+            .flatten()
+            .and_then(|info| info.current_command);
+        util::is_dashboard(command.as_deref(), || self.capture_pane(pane_id, 40))
+    }
+
     /// Whether this backend supports preview capture efficiently.
     /// Defaults to true. Override to return false for backends where preview capture
     /// requires expensive operations (process spawning, temp files).
@@ -201,14 +224,57 @@ pub trait Multiplexer: Send + Sync {
         false
     }
 
-    /// Send keys to an agent pane, with special handling for Claude's ! prefix
-    fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()>;
+    /// Type literal text into a pane without pressing Enter
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()>;
+
+    /// Send keys to an agent pane, applying the agent's input delays and
+    /// submit key (e.g. the pause Claude needs after a leading `!`)
+    fn send_keys_to_agent(
+        &self,
+        pane_id: &str,
+        command: &str,
+        input: &agent::InputProfile,
+    ) -> Result<()> {
+        let text = match command.strip_prefix('!') {
+            Some(rest) if !input.bang_delay.is_zero() => {
+                self.send_text(pane_id, "!")?;
+                std::thread::sleep(input.bang_delay);
+                rest
+            }
+            _ => command,
+        };
+        if !text.is_empty() {
+            self.send_text(pane_id, text)?;
+        }
+        if !input.submit_delay.is_zero() {
+            std::thread::sleep(input.submit_delay);
+        }
+        self.send_key(pane_id, &input.submit_key)
+    }
 
     /// Send a single key to a pane
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()>;
 
-    /// Paste multiline content to a pane (using bracketed paste)
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()>;
+    /// Paste multiline content to a pane and submit it.
+    ///
+    /// With `bracketed`, the content is sent as one bracketed paste. Agents
+    /// without bracketed-paste support get it typed via `type_multiline`.
+    fn paste_multiline(&self, pane_id: &str, content: &str, bracketed: bool) -> Result<()>;
+
+    /// Type multiline content line by line and submit it. Lines are joined
+    /// by LF, which most agents treat as a line break rather than a submit.
+    fn type_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
+        let text = util::normalize_paste(content);
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                self.send_text(pane_id, "\n")?;
+            }
+            if !line.is_empty() {
+                self.send_text(pane_id, line)?;
+            }
+        }
+        self.send_key(pane_id, "Enter")
+    }
 
     /// Clear the pane screen. Default is no-op; backends override if needed.
     fn clear_pane(&self, _pane_id: &str) -> Result<()> {
@@ -237,6 +303,12 @@ pub trait Multiplexer: Send + Sync {
     /// Ensure the status format is configured (for backends that need it)
     fn ensure_status_format(&self, pane_id: &str) -> Result<()>;
 
+    /// Whether the status format shows workmux status in the pane's window,
+    /// or None for backends that don't use one.
+    fn status_format_installed(&self, _pane_id: &str) -> Result<Option<bool>> {
+        Ok(None)
+    }
+
     // === Pane Setup ===
 
     /// Split a pane, returning the new pane ID.
@@ -251,6 +323,13 @@ pub trait Multiplexer: Send + Sync {
         command: Option<&str>,
     ) -> Result<String>;
 
+    /// Pane IDs `create_window` already laid out from
+    /// `CreateWindowParams::panes`, indexed like the panes `setup_panes`
+    /// creates. None (the default) means panes are split one by one.
+    fn take_layout_panes(&self) -> Option<Vec<String>> {
+        None
+    }
+
     /// Setup panes in a window according to configuration.
     ///
     /// Default implementation handles the full orchestration: command resolution,
@@ -269,16 +348,75 @@ pub trait Multiplexer: Send + Sync {
         if panes.is_empty() {
             return Ok(PaneSetupResult {
                 focus_pane_id: initial_pane_id.to_string(),
+                setup_started: false,
             });
         }
 
+        // Resolve per-pane cwds up front so an invalid one fails before any pane exists
+        let pane_cwds = util::resolve_pane_cwds(
+            panes,
+            options.worktree_root.unwrap_or(working_dir),
+            working_dir,
+        )?;
+
         let mut focus_pane_id: Option<String> = None;
         let mut pane_ids: Vec<String> = vec![initial_pane_id.to_string()];
         let effective_agent = task_agent.or(config.agent.as_deref());
+        let is_agent_command = |cmd: &str| {
+            cmd == "<agent>"
+                || agent::is_known_agent(cmd)
+                || effective_agent.is_some_and(|a| crate::config::is_agent_command(cmd, a))
+        };
+        // The setup script goes to the first agent pane, or to the first pane
+        // with a command when no pane runs an agent
+        let setup_pane = options.setup_script.and_then(|_| {
+            panes
+                .iter()
+                .position(|p| p.command.as_deref().is_some_and(is_agent_command))
+                .or_else(|| panes.iter().position(|p| p.command.is_some()))
+        });
+        let mut setup_started = false;
         let shell = self.get_default_shell()?;
+        // Sandboxed panes get these from the sandbox supervisor instead
+        let env_exports = crate::env_file::export_statement(&crate::env_file::load(
+            options.worktree_root.unwrap_or(working_dir),
+            config.env_file(),
+        ));
+        let host_command = |command: &str| match &env_exports {
+            Some(exports) => util::with_env_exports(command, exports, &shell),
+            None => command.to_string(),
+        };
+        // Panes the backend laid out with the window are reused, not split
+        let layout_panes = self.take_layout_panes();
+        let split = |pane_config: &PaneConfig,
+                     pane_ids: &[String],
+                     cwd: &Path,
+                     command: Option<&str>|
+         -> Result<String> {
+            if let Some(id) = layout_panes
+                .as_ref()
+                .and_then(|ids| ids.get(pane_ids.len()))
+            {
+                return self.respawn_pane(id, cwd, command);
+            }
+            let direction = pane_config.split.as_ref().unwrap();
+            let target_idx = pane_config.target.unwrap_or(pane_ids.len() - 1);
+            let target = pane_ids
+                .get(target_idx)
+                .ok_or_else(|| anyhow!("Invalid target pane index: {}", target_idx))?;
+            self.split_pane(
+                target,
+                direction,
+                cwd,
+                pane_config.size,
+                pane_config.percentage,
+                command,
+            )
+        };
 
         for (i, pane_config) in panes.iter().enumerate() {
             let is_first = i == 0;
+            let pane_cwd = pane_cwds[i].as_path();
 
             // Skip non-first panes that have no split direction
             if !is_first && pane_config.split.is_none() {
@@ -290,7 +428,7 @@ pub trait Multiplexer: Send + Sync {
                 pane_config.command.as_deref(),
                 options.run_commands,
                 options.prompt_file_path,
-                working_dir,
+                pane_cwd,
                 effective_agent,
                 &shell,
             );
@@ -304,40 +442,40 @@ pub trait Multiplexer: Send + Sync {
                 let script = handshake.script_content(&shell);
 
                 let spawned_id = if is_first {
-                    self.respawn_pane(&pane_ids[0], working_dir, Some(&script))?
+                    self.respawn_pane(&pane_ids[0], pane_cwd, Some(&script))?
                 } else {
-                    let direction = pane_config.split.as_ref().unwrap();
-                    let target_idx = pane_config.target.unwrap_or(pane_ids.len() - 1);
-                    let target = pane_ids
-                        .get(target_idx)
-                        .ok_or_else(|| anyhow!("Invalid target pane index: {}", target_idx))?;
-                    self.split_pane(
-                        target,
-                        direction,
-                        working_dir,
-                        pane_config.size,
-                        pane_config.percentage,
-                        Some(&script),
-                    )?
+                    split(pane_config, &pane_ids, pane_cwd, Some(&script))?
                 };
 
-                handshake.wait()?;
+                handshake.wait(config.handshake_timeout()).map_err(|e| {
+                    handshake::with_pane_output(
+                        e,
+                        self.capture_pane(&spawned_id, handshake::TIMEOUT_CAPTURE_LINES)
+                            .as_deref(),
+                    )
+                })?;
 
                 // Detect if this is an agent pane for sandbox targeting
-                let is_agent_pane = pane_config.command.as_deref().is_some_and(|cmd| {
-                    cmd == "<agent>"
-                        || agent::is_known_agent(cmd)
-                        || effective_agent.is_some_and(|a| crate::config::is_agent_command(cmd, a))
-                });
+                let is_agent_pane = pane_config.command.as_deref().is_some_and(is_agent_command);
+                let setup_script = options.setup_script.filter(|_| setup_pane == Some(i));
+                let with_setup = |command: &str| match setup_script {
+                    Some(script) => {
+                        util::with_setup_script(command, script, options.handle, &shell)
+                    }
+                    None => command.to_string(),
+                };
 
-                // Apply sandbox wrapping if enabled for this pane type
+                // Apply sandbox wrapping if enabled for this pane type. The
+                // setup script always runs sandboxed, even in a non-agent pane.
                 let final_command = if config.sandbox.is_enabled() {
                     let should_wrap = match config.sandbox.target() {
                         crate::config::SandboxTarget::All => true,
-                        crate::config::SandboxTarget::Agent => is_agent_pane,
+                        crate::config::SandboxTarget::Agent => {
+                            is_agent_pane || setup_script.is_some()
+                        }
                     };
                     if should_wrap {
-                        // Use worktree_root for mounting, working_dir for cwd
+                        // Use worktree_root for mounting, the pane cwd for cwd
                         let wt_root = options.worktree_root.unwrap_or(working_dir);
 
                         // Inject skip-permissions flag for agent panes only
@@ -353,16 +491,37 @@ pub trait Multiplexer: Send + Sync {
                         } else {
                             resolved.command.clone()
                         };
+                        // Sandboxed commands run in the guest's POSIX shell
+                        let command_to_wrap = match setup_script {
+                            Some(script) => util::with_setup_script(
+                                &command_to_wrap,
+                                script,
+                                options.handle,
+                                "sh",
+                            ),
+                            None => command_to_wrap,
+                        };
 
                         // Choose backend based on config
                         let wrap_result = match config.sandbox.backend() {
                             crate::config::SandboxBackend::Container => {
-                                crate::sandbox::wrap_for_container(
-                                    &command_to_wrap,
-                                    &config.sandbox,
-                                    wt_root,
-                                    working_dir,
-                                )
+                                // The supervisor picks the image for the configured agent,
+                                // or the one recorded with `add --image`
+                                let image_agent =
+                                    agent::resolve_profile(config.agent.as_deref()).name();
+                                let mut image_config = config.sandbox.clone();
+                                if let Some(image) = crate::sandbox::image_override(None, wt_root) {
+                                    image_config.image = Some(image);
+                                }
+                                crate::sandbox::check_image_build_hash(&image_config, image_agent)
+                                    .and_then(|()| {
+                                        crate::sandbox::wrap_for_container(
+                                            &command_to_wrap,
+                                            &config.sandbox,
+                                            wt_root,
+                                            pane_cwd,
+                                        )
+                                    })
                             }
                             crate::config::SandboxBackend::Lima => {
                                 let vm_name = options.lima_vm_name.ok_or_else(|| {
@@ -375,7 +534,7 @@ pub trait Multiplexer: Send + Sync {
                                     &command_to_wrap,
                                     config,
                                     vm_name,
-                                    working_dir,
+                                    pane_cwd,
                                 )
                             }
                         };
@@ -392,20 +551,22 @@ pub trait Multiplexer: Send + Sync {
                             }
                         }
                     } else {
-                        resolved.command.clone()
+                        host_command(&with_setup(&resolved.command))
                     }
                 } else {
-                    resolved.command.clone()
+                    host_command(&with_setup(&resolved.command))
                 };
 
                 let _ = self.clear_pane(&spawned_id);
                 self.send_keys(&spawned_id, &final_command)?;
+                setup_started |= setup_script.is_some();
 
                 // Set working status for agent panes with injected prompts
                 if resolved.prompt_injected
                     && agent::resolve_profile(pane_agent).needs_auto_status()
                 {
-                    let icon = config.status_icons.working();
+                    let icons = config.status_icons();
+                    let icon = icons.working();
                     if config.status_format.unwrap_or(true) {
                         let _ = self.ensure_status_format(&spawned_id);
                     }
@@ -414,23 +575,15 @@ pub trait Multiplexer: Send + Sync {
 
                 spawned_id
             } else if is_first {
-                // No command for first pane - keep as-is
-                pane_ids[0].clone()
+                // No command for first pane - keep as-is unless it needs its own cwd
+                if pane_config.cwd.is_some() {
+                    self.respawn_pane(&pane_ids[0], pane_cwd, None)?
+                } else {
+                    pane_ids[0].clone()
+                }
             } else {
                 // No command - just split
-                let direction = pane_config.split.as_ref().unwrap();
-                let target_idx = pane_config.target.unwrap_or(pane_ids.len() - 1);
-                let target = pane_ids
-                    .get(target_idx)
-                    .ok_or_else(|| anyhow!("Invalid target pane index: {}", target_idx))?;
-                self.split_pane(
-                    target,
-                    direction,
-                    working_dir,
-                    pane_config.size,
-                    pane_config.percentage,
-                    None,
-                )?
+                split(pane_config, &pane_ids, pane_cwd, None)?
             };
 
             if is_first {
@@ -446,6 +599,7 @@ pub trait Multiplexer: Send + Sync {
 
         Ok(PaneSetupResult {
             focus_pane_id: focus_pane_id.unwrap_or_else(|| pane_ids[0].clone()),
+            setup_started,
         })
     }
 
@@ -497,7 +651,16 @@ pub trait Multiplexer: Send + Sync {
     /// Called when a pane is not found in the batched `get_all_live_pane_info()` result.
     /// Backends can implement custom validation logic (e.g., Zellij checks pane existence
     /// and command matching). Default implementation queries the pane individually.
-    fn validate_agent_alive(&self, state: &crate::state::AgentState) -> Result<bool> {
+    ///
+    /// `live_panes` is that batched result, when the caller has one. Backends whose
+    /// individual lookup reads the same listing (Zellij) use it instead of querying
+    /// again; others ignore it.
+    fn validate_agent_alive(
+        &self,
+        state: &crate::state::AgentState,
+        live_panes: Option<&std::collections::HashMap<String, LivePaneInfo>>,
+    ) -> Result<bool> {
+        let _ = live_panes;
         let live_pane = self.get_live_pane_info(&state.pane_key.pane_id)?;
 
         match live_pane {
@@ -516,6 +679,18 @@ pub trait Multiplexer: Send + Sync {
     }
 }
 
+/// Refuse to act on the dashboard's own pane. Sending keys to it, or wiring
+/// an agent to it, feeds the dashboard back into itself.
+pub fn ensure_not_dashboard_pane(mux: &dyn Multiplexer, pane_id: &str) -> Result<()> {
+    if mux.is_dashboard_pane(pane_id) {
+        bail!(
+            "Pane {} is running the workmux dashboard, not an agent. Refusing to act on it.",
+            pane_id
+        );
+    }
+    Ok(())
+}
+
 /// Detect which backend to use based on environment.
 ///
 /// Checks `$WORKMUX_BACKEND` first for an explicit override, then auto-detects
@@ -524,15 +699,16 @@ pub trait Multiplexer: Send + Sync {
 /// from the parent terminal):
 ///
 /// 1. `$WORKMUX_BACKEND` set → use that backend
-/// 2. `$TMUX` set → tmux
-/// 3. `$WEZTERM_PANE` set → WezTerm
-/// 4. `$ZELLIJ` set → Zellij
-/// 5. `$KITTY_WINDOW_ID` set → Kitty
-/// 6. None → defaults to tmux (for backward compatibility)
+/// 2. `multiplexer` set in config (not `auto`) → that backend
+/// 3. `$TMUX` set → tmux
+/// 4. `$WEZTERM_PANE` set → WezTerm
+/// 5. `$ZELLIJ` set → Zellij
+/// 6. `$KITTY_WINDOW_ID` set → Kitty
+/// 7. None → defaults to tmux (for backward compatibility)
 ///
 /// This ordering ensures that running tmux inside kitty (or wezterm) correctly
 /// selects the innermost multiplexer.
-pub fn detect_backend() -> BackendType {
+pub fn detect_backend(config: &Config) -> BackendType {
     if let Ok(val) = std::env::var("WORKMUX_BACKEND") {
         match val.parse() {
             Ok(bt) => return bt,
@@ -544,6 +720,10 @@ pub fn detect_backend() -> BackendType {
         }
     }
 
+    if let Some(forced) = forced_backend(config.multiplexer()) {
+        return forced;
+    }
+
     resolve_backend(
         std::env::var("TMUX").is_ok(),
         std::env::var("WEZTERM_PANE").is_ok(),
@@ -552,6 +732,34 @@ pub fn detect_backend() -> BackendType {
     )
 }
 
+/// Fail if config forces a backend that is not running.
+///
+/// Called once by the commands that create windows. `detect_backend` itself
+/// never probes, so hooks outside a multiplexer still exit quietly.
+pub fn ensure_forced_backend_running(config: &Config, mux: &dyn Multiplexer) -> Result<()> {
+    if let Some(forced) = forced_backend(config.multiplexer())
+        && detect_backend(config) == forced
+        && !mux.is_running().unwrap_or(false)
+    {
+        bail!(
+            "Config sets `multiplexer: {forced}`, but {forced} is not running. \
+             Start {forced} or set `multiplexer: auto`."
+        );
+    }
+    Ok(())
+}
+
+/// Backend forced by config, or None to auto-detect.
+fn forced_backend(choice: MultiplexerChoice) -> Option<BackendType> {
+    match choice {
+        MultiplexerChoice::Auto => None,
+        MultiplexerChoice::Tmux => Some(BackendType::Tmux),
+        MultiplexerChoice::Wezterm => Some(BackendType::WezTerm),
+        MultiplexerChoice::Kitty => Some(BackendType::Kitty),
+        MultiplexerChoice::Zellij => Some(BackendType::Zellij),
+    }
+}
+
 /// Pure auto-detection logic, separated for testability.
 fn resolve_backend(tmux: bool, wezterm: bool, zellij: bool, kitty: bool) -> BackendType {
     if tmux {
@@ -574,12 +782,12 @@ fn resolve_backend(tmux: bool, wezterm: bool, zellij: bool, kitty: bool) -> Back
 }
 
 /// Create a backend instance based on the backend type.
-pub fn create_backend(backend_type: BackendType) -> Arc<dyn Multiplexer> {
+pub fn create_backend(backend_type: BackendType, config: &Config) -> Arc<dyn Multiplexer> {
     match backend_type {
         BackendType::Tmux => Arc::new(TmuxBackend::new()),
         BackendType::WezTerm => Arc::new(wezterm::WezTermBackend::new()),
         BackendType::Kitty => Arc::new(kitty::KittyBackend::new()),
-        BackendType::Zellij => Arc::new(zellij::ZellijBackend::new()),
+        BackendType::Zellij => Arc::new(zellij::ZellijBackend::new(config)),
     }
 }
 
@@ -587,6 +795,19 @@ pub fn create_backend(backend_type: BackendType) -> Arc<dyn Multiplexer> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn forced_backend_from_config() {
+        assert_eq!(forced_backend(MultiplexerChoice::Auto), None);
+        assert_eq!(
+            forced_backend(MultiplexerChoice::Zellij),
+            Some(BackendType::Zellij)
+        );
+        assert_eq!(
+            forced_backend(MultiplexerChoice::Wezterm),
+            Some(BackendType::WezTerm)
+        );
+    }
+
     #[test]
     fn no_env_defaults_to_tmux() {
         assert_eq!(
@@ -14,7 +14,7 @@ use crate::config::SplitDirection as ConfigSplitDirection;
 
 use super::handshake::TmuxHandshake;
 use super::types::*;
-use super::{Multiplexer, PaneHandshake, agent, util};
+use super::{Multiplexer, PaneHandshake, util};
 
 /// tmux backend implementation.
 ///
@@ -67,10 +67,8 @@ impl TmuxBackend {
         let _ = self.tmux_cmd(&["set-option", "-uw", "-t", pane_id, "@workmux_status"]);
     }
 
-    /// Updates a single tmux format option for the target window to include workmux status.
-    fn update_format_option(&self, pane: &str, option: &str) -> Result<()> {
-        // Read current format. Try window-level first, fall back to global.
-        //
+    /// Read a tmux format option for the target window, falling back to global.
+    fn current_format_option(&self, pane: &str, option: &str) -> String {
         // Uses run() instead of tmux_query()/run_and_capture_stdout() because the latter
         // calls .trim() which strips meaningful whitespace from format strings (e.g.,
         // padding spaces in tmux themes). We only strip trailing newlines from command output.
@@ -82,7 +80,7 @@ impl TmuxBackend {
             .map(|s| s.trim_end_matches('\n').to_string())
             .filter(|s| !s.is_empty());
 
-        let current = match window_format {
+        match window_format {
             Some(fmt) => fmt,
             None => Cmd::new("tmux")
                 .args(&["show-option", "-gv", option])
@@ -92,8 +90,12 @@ impl TmuxBackend {
                 .map(|s| s.trim_end_matches('\n').to_string())
                 .filter(|s| !s.is_empty())
                 .unwrap_or_else(|| "#I:#W#{?window_flags,#{window_flags}, }".to_string()),
-        };
+        }
+    }
 
+    /// Updates a single tmux format option for the target window to include workmux status.
+    fn update_format_option(&self, pane: &str, option: &str) -> Result<()> {
+        let current = self.current_format_option(pane, option);
         if !current.contains("@workmux_status") {
             let new_format = inject_status_format(&current);
             // Set per-window to avoid affecting other windows/sessions
@@ -197,6 +199,23 @@ impl Multiplexer for TmuxBackend {
         Ok(PathBuf::from(path))
     }
 
+    fn current_pane_cwd(&self) -> Result<PathBuf> {
+        let mut args = vec!["display-message", "-p"];
+        let pane = self.current_pane_id();
+        if let Some(pane) = pane.as_deref() {
+            args.extend(["-t", pane]);
+        }
+        args.push("#{pane_current_path}");
+
+        let output = self.tmux_query(&args)?;
+        let path = output.trim();
+        if path.is_empty() {
+            return Err(anyhow!("Empty path returned from tmux"));
+        }
+
+        Ok(PathBuf::from(path))
+    }
+
     // === Window/Tab Management ===
 
     fn create_window(&self, params: CreateWindowParams) -> Result<String> {
@@ -338,6 +357,11 @@ impl Multiplexer for TmuxBackend {
         self.tmux_cmd(&["kill-window", "-t", &target])
     }
 
+    fn rename_window(&self, old_full_name: &str, new_full_name: &str) -> Result<()> {
+        let target = format!("={}", old_full_name);
+        self.tmux_cmd(&["rename-window", "-t", &target, new_full_name])
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let delay_secs = format!("{:.3}", delay.as_secs_f64());
         let target = format!("={}", full_name);
@@ -489,19 +513,13 @@ impl Multiplexer for TmuxBackend {
             .unwrap_or_default();
 
         let full_base = util::prefixed(prefix, base_handle);
-        let full_base_dash = format!("{}-", full_base);
         let mut last_match: Option<String> = None;
 
         for line in output.lines() {
-            if let Some((id, name)) = line.split_once(' ') {
-                let is_exact = name == full_base;
-                let is_numeric_suffix = name.strip_prefix(&full_base_dash).is_some_and(|suffix| {
-                    !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit())
-                });
-
-                if is_exact || is_numeric_suffix {
-                    last_match = Some(id.to_string());
-                }
+            if let Some((id, name)) = line.split_once(' ')
+                && util::is_base_handle_window(name, &full_base)
+            {
+                last_match = Some(id.to_string());
             }
         }
 
@@ -601,31 +619,21 @@ impl Multiplexer for TmuxBackend {
         self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
     }
 
-    fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
-        if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
-            // Send ! first
-            self.tmux_cmd(&["send-keys", "-t", pane_id, "-l", "!"])?;
-
-            // Small delay to let Claude register the !
-            thread::sleep(Duration::from_millis(50));
-
-            // Send the rest of the command
-            self.tmux_cmd(&["send-keys", "-t", pane_id, "-l", &command[1..]])?;
-
-            // Send Enter
-            self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
-        } else {
-            self.send_keys(pane_id, command)
-        }
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        self.tmux_cmd(&["send-keys", "-t", pane_id, "-l", text])
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
         self.tmux_cmd(&["send-keys", "-t", pane_id, key])
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
+    fn paste_multiline(&self, pane_id: &str, content: &str, bracketed: bool) -> Result<()> {
         use std::io::Write;
 
+        if !bracketed {
+            return self.type_multiline(pane_id, content);
+        }
+
         let mut child = std::process::Command::new("tmux")
             .args(["load-buffer", "-"])
             .stdin(std::process::Stdio::piped())
@@ -695,6 +703,17 @@ impl Multiplexer for TmuxBackend {
         Ok(())
     }
 
+    fn status_format_installed(&self, pane_id: &str) -> Result<Option<bool>> {
+        Ok(Some(
+            ["window-status-format", "window-status-current-format"]
+                .iter()
+                .all(|option| {
+                    self.current_format_option(pane_id, option)
+                        .contains("@workmux_status")
+                }),
+        ))
+    }
+
     fn split_pane(
         &self,
         target_pane_id: &str,
@@ -37,6 +37,8 @@ pub struct AgentPane {
     pub status: Option<AgentStatus>,
     /// Unix timestamp when status was last set
     pub status_ts: Option<u64>,
+    /// Foreground command recorded when the agent registered (e.g., "claude")
+    pub command: String,
 }
 
 /// Parameters for creating a new window/tab
@@ -50,6 +52,9 @@ pub struct CreateWindowParams<'a> {
     pub cwd: &'a std::path::Path,
     /// Optional window ID to insert after (for ordering)
     pub after_window: Option<&'a str>,
+    /// Panes `setup_panes` will lay out, for backends that create the
+    /// splits together with the window
+    pub panes: &'a [crate::config::PaneConfig],
 }
 
 /// Parameters for creating a new session
@@ -81,6 +86,9 @@ pub struct CreateWindowInSessionParams<'a> {
 pub struct PaneSetupResult {
     /// The ID of the pane that should receive focus
     pub focus_pane_id: String,
+    /// Whether the setup script was sent to a pane (it marks the worktree
+    /// as set up itself once it succeeds)
+    pub setup_started: bool,
 }
 
 /// Options for pane setup
@@ -94,6 +102,11 @@ pub struct PaneSetupOptions<'a> {
     pub worktree_root: Option<&'a std::path::Path>,
     /// Pre-booted Lima VM name (if sandbox backend is Lima and VM was booted before window creation)
     pub lima_vm_name: Option<&'a str>,
+    /// Setup script to run ahead of the command of the first agent pane, or
+    /// of the first pane with a command when there is no agent pane
+    pub setup_script: Option<&'a str>,
+    /// Worktree handle, marked as set up once the setup script succeeds
+    pub handle: &'a str,
 }
 
 /// Backend type for multiplexer selection
@@ -2,8 +2,89 @@
 //!
 //! These helpers are shared between tmux, WezTerm, and any future backends.
 
+use anyhow::{Result, anyhow};
 use std::borrow::Cow;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::PaneConfig;
+
+/// Full window/session names that differ from `{prefix}{handle}`, keyed by
+/// handle: names rendered from `window_name_template`, or suffixed at
+/// creation because another worktree's window had the name.
+///
+/// Loaded from the current repository's worktree metadata.
+#[derive(Debug, Clone, Default)]
+pub struct WindowNames(HashMap<String, String>);
+
+impl WindowNames {
+    pub fn load() -> Self {
+        Self(crate::git::get_all_worktree_window_names())
+    }
+
+    /// Stored full name for `handle`, if any.
+    pub fn get(&self, handle: &str) -> Option<&str> {
+        self.0.get(handle).map(String::as_str)
+    }
+
+    /// Full name for `handle`: the stored one, else `{prefix}{handle}`.
+    /// A numbered handle (`handle-2`) of a stored name gets the numbered name
+    /// (`name-2`), matching [`Self::handle_for`].
+    pub fn full_name(&self, prefix: &str, handle: &str) -> String {
+        if let Some(name) = self.0.get(handle) {
+            return name.clone();
+        }
+        if let Some((base, suffix)) = handle.rsplit_once('-')
+            && !suffix.is_empty()
+            && suffix.chars().all(|c| c.is_ascii_digit())
+            && let Some(name) = self.0.get(base)
+        {
+            return format!("{}-{}", name, suffix);
+        }
+        prefixed(prefix, handle)
+    }
+
+    /// Worktree handle for a full name: the inverse of [`Self::full_name`].
+    /// A numbered duplicate of a stored name (`name-2`) maps to the numbered
+    /// handle (`handle-2`), as `open --new` names them.
+    pub fn handle_for(&self, full_name: &str, prefix: &str) -> Option<String> {
+        if let Some((handle, _)) = self.0.iter().find(|(_, name)| name.as_str() == full_name) {
+            return Some(handle.clone());
+        }
+        let duplicate = self.0.iter().find_map(|(handle, name)| {
+            let suffix = full_name.strip_prefix(name.as_str())?.strip_prefix('-')?;
+            is_base_handle_window(full_name, name).then(|| format!("{}-{}", handle, suffix))
+        });
+        duplicate.or_else(|| handle_for_name(full_name, prefix))
+    }
+}
+
+impl FromIterator<(String, String)> for WindowNames {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// `name`, or `name-2`, `name-3`, ... when it's already in `existing`.
+pub fn unique_name(existing: &HashSet<String>, name: &str) -> String {
+    if !existing.contains(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !existing.contains(candidate))
+        .expect("unbounded suffix range")
+}
+
+/// Whether `name` is `full_base` or one of its numbered duplicates
+/// (`full_base-2`, `full_base-3`, ...).
+pub fn is_base_handle_window(name: &str, full_base: &str) -> bool {
+    name == full_base
+        || name
+            .strip_prefix(full_base)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|suffix| !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()))
+}
 
 /// Helper function to add prefix to window name.
 ///
@@ -12,6 +93,82 @@ pub fn prefixed(prefix: &str, window_name: &str) -> String {
     format!("{}{}", prefix, window_name)
 }
 
+/// Worktree handle for a full window/session name: the inverse of [`prefixed`].
+/// Use [`WindowNames::handle_for`] where templated names are possible.
+pub fn handle_for_name(full_name: &str, prefix: &str) -> Option<String> {
+    full_name
+        .strip_prefix(prefix)
+        .filter(|handle| !handle.is_empty())
+        .map(str::to_string)
+}
+
+/// Check whether captured content is the workmux dashboard itself.
+///
+/// `dump-screen` falls back to the focused pane on older zellij versions, so
+/// a capture taken while the dashboard is focused would show the dashboard
+/// inside its own preview. Anything reading agent output from captures
+/// should skip such content.
+pub fn contains_dashboard_ui(content: &str) -> bool {
+    (content.contains("[Enter] go") && content.contains("[q] quit"))
+        || content.contains("INPUT MODE - Type to send keys to agent")
+}
+
+/// Check whether a pane runs the workmux dashboard, from its foreground
+/// command (with arguments, where the backend reports them) and, failing
+/// that, a capture of its screen.
+///
+/// A known command other than workmux rules the pane out without a capture,
+/// and `workmux dashboard` rules it in.
+pub fn is_dashboard(command: Option<&str>, capture: impl FnOnce() -> Option<String>) -> bool {
+    if let Some(command) = command {
+        let mut words = command.split_whitespace();
+        let program = words.next().and_then(|p| p.rsplit('/').next());
+        match program {
+            Some("workmux") if words.next() == Some("dashboard") => return true,
+            Some("workmux") | None => {}
+            Some(_) => return false,
+        }
+    }
+    capture().is_some_and(|content| contains_dashboard_ui(&content))
+}
+
+/// Ctrl-Enter in the CSI u encoding understood by modern agent TUIs.
+pub const CTRL_ENTER: &str = "\x1b[13;5u";
+
+/// Shift-Enter in the CSI u encoding.
+pub const SHIFT_ENTER: &str = "\x1b[13;2u";
+
+/// Alt-Enter: ESC followed by carriage return.
+pub const ALT_ENTER: &str = "\x1b\r";
+
+/// Translate a tmux key name to the bytes a terminal sends for it.
+///
+/// Used by backends that can only send raw text. Names without a mapping
+/// are sent as-is.
+pub fn key_sequence(key: &str) -> &str {
+    match key {
+        "BSpace" => "\x7f",
+        "Enter" => "\r",
+        "C-Enter" => CTRL_ENTER,
+        "S-Enter" => SHIFT_ENTER,
+        "M-Enter" => ALT_ENTER,
+        "Tab" => "\t",
+        "Up" => "\x1b[A",
+        "Down" => "\x1b[B",
+        "Right" => "\x1b[C",
+        "Left" => "\x1b[D",
+        "Escape" => "\x1b",
+        _ => key,
+    }
+}
+
+/// Normalize text before pasting: CRLF and CR line endings become LF and
+/// trailing newlines are dropped, so the only Enter is the final submit.
+pub fn normalize_paste(content: &str) -> String {
+    let text = content.replace("\r\n", "\n").replace('\r', "\n");
+    text.trim_end_matches('\n').to_string()
+}
+
 /// Check if a shell is POSIX-compatible (supports `$(...)` syntax).
 ///
 /// Used to determine whether agent commands need to be wrapped in `sh -c '...'`
@@ -208,6 +365,40 @@ pub fn escape_for_sh_c_inner_single_quote(s: &str) -> String {
     escape_for_double_quotes(&single_escaped)
 }
 
+/// Prefix a pane command with an `export` statement, keeping its leading
+/// history-prevention space. Non-POSIX shells run both inside `sh -c`.
+pub fn with_env_exports(command: &str, exports: &str, shell: &str) -> String {
+    let trimmed = command.trim_start();
+    let leading_spaces = &command[..command.len() - trimmed.len()];
+    let combined = format!("{}; {}", exports, trimmed);
+    if is_posix_shell(shell) {
+        format!("{}{}", leading_spaces, combined)
+    } else {
+        format!("{}{}", leading_spaces, wrap_for_non_posix_shell(&combined))
+    }
+}
+
+/// Run a setup script ahead of a pane command, keeping its leading
+/// history-prevention space. The command only starts once the script
+/// succeeds, and only then is the worktree `handle` marked as set up; a
+/// failure to record that doesn't keep the command from starting.
+/// Non-POSIX shells run everything inside `sh -c`.
+pub fn with_setup_script(command: &str, script: &str, handle: &str, shell: &str) -> String {
+    let trimmed = command.trim_start();
+    let leading_spaces = &command[..command.len() - trimmed.len()];
+    let combined = format!(
+        "{{ {}; }} && {{ workmux _setup-done {}; {}; }}",
+        script.trim(),
+        crate::shell::shell_quote(handle),
+        trimmed
+    );
+    if is_posix_shell(shell) {
+        format!("{}{}", leading_spaces, combined)
+    } else {
+        format!("{}{}", leading_spaces, wrap_for_non_posix_shell(&combined))
+    }
+}
+
 /// Wrap a command in `sh -c '...'` for execution in non-POSIX shells.
 ///
 /// Used when the default shell (nushell, fish, etc.) doesn't support
@@ -256,10 +447,112 @@ fn inject_flag_after_executable(command: &str, flag: &str) -> String {
     }
 }
 
+/// Resolve the working directory of each pane.
+///
+/// A pane's `cwd` is relative to the worktree root, not to `--cwd`. It must
+/// not be absolute or contain `..`, and must name an existing directory that
+/// stays inside the worktree after following symlinks. Panes without `cwd`
+/// use the window's working directory.
+pub fn resolve_pane_cwds(
+    panes: &[PaneConfig],
+    worktree_root: &Path,
+    working_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    panes
+        .iter()
+        .map(|pane| match pane.cwd.as_deref() {
+            None => Ok(working_dir.to_path_buf()),
+            Some(subdir) => {
+                let relative = Path::new(subdir);
+                if !relative
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+                {
+                    return Err(anyhow!(
+                        "Pane cwd '{}' must be a relative path inside the worktree",
+                        subdir
+                    ));
+                }
+                let dir = worktree_root.join(relative);
+                if !dir.is_dir() {
+                    return Err(anyhow!(
+                        "Pane cwd '{}' does not exist in the worktree",
+                        subdir
+                    ));
+                }
+                if !dir
+                    .canonicalize()?
+                    .starts_with(worktree_root.canonicalize()?)
+                {
+                    return Err(anyhow!(
+                        "Pane cwd '{}' must be a relative path inside the worktree",
+                        subdir
+                    ));
+                }
+                Ok(dir)
+            }
+        })
+        .collect()
+}
+
+/// Check that a `--cwd` start directory is a relative path that stays inside
+/// the worktree. Purely lexical, so `add` can reject it before creating
+/// anything.
+pub fn check_start_dir(subdir: &Path) -> Result<()> {
+    if !subdir
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(anyhow!(
+            "--cwd '{}' must be a relative path inside the worktree",
+            subdir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve the `--cwd` start directory for a new worktree's panes.
+///
+/// `subdir` has passed [`check_start_dir`]. It must name an existing
+/// directory that stays inside the worktree after following symlinks.
+pub fn resolve_start_dir(worktree_root: &Path, subdir: &Path) -> Result<PathBuf> {
+    let dir = worktree_root.join(subdir);
+    if !dir.is_dir() {
+        return Err(anyhow!(
+            "--cwd '{}' does not exist in the worktree",
+            subdir.display()
+        ));
+    }
+    let canonical_root = worktree_root.canonicalize()?;
+    if !dir.canonicalize()?.starts_with(&canonical_root) {
+        return Err(anyhow!(
+            "--cwd '{}' must be a relative path inside the worktree",
+            subdir.display()
+        ));
+    }
+    Ok(dir)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+
+    // --- key_sequence tests ---
+
+    #[test]
+    fn key_sequence_translates_named_keys() {
+        assert_eq!(key_sequence("Enter"), "\r");
+        assert_eq!(key_sequence("C-Enter"), "\x1b[13;5u");
+        assert_eq!(key_sequence("S-Enter"), "\x1b[13;2u");
+        assert_eq!(key_sequence("M-Enter"), "\x1b\r");
+        assert_eq!(key_sequence("y"), "y");
+    }
+
+    #[test]
+    fn normalize_paste_unifies_line_endings() {
+        assert_eq!(normalize_paste("a\r\nb\rc\n\n"), "a\nb\nc");
+        assert_eq!(normalize_paste("\n"), "");
+    }
 
     // --- prefixed tests ---
 
@@ -270,6 +563,56 @@ mod tests {
         assert_eq!(prefixed("prefix-", ""), "prefix-");
     }
 
+    #[test]
+    fn handle_for_name_strips_prefix() {
+        assert_eq!(
+            handle_for_name("wm-fix-bug", "wm-").as_deref(),
+            Some("fix-bug")
+        );
+        assert_eq!(handle_for_name("wm-", "wm-"), None);
+        assert_eq!(handle_for_name("scratch", "wm-"), None);
+    }
+
+    #[test]
+    fn window_names_round_trip() {
+        let names: WindowNames = [("fix".to_string(), "wm-42-fix".to_string())]
+            .into_iter()
+            .collect();
+        assert_eq!(names.full_name("wm-", "fix"), "wm-42-fix");
+        assert_eq!(names.full_name("wm-", "fix-2"), "wm-42-fix-2");
+        assert_eq!(names.full_name("wm-", "other"), "wm-other");
+        assert_eq!(names.handle_for("wm-42-fix", "wm-").as_deref(), Some("fix"));
+        assert_eq!(
+            names.handle_for("wm-42-fix-2", "wm-").as_deref(),
+            Some("fix-2")
+        );
+        assert_eq!(
+            names.handle_for("wm-other", "wm-").as_deref(),
+            Some("other")
+        );
+        assert_eq!(names.handle_for("wm-", "wm-"), None);
+        assert_eq!(names.handle_for("scratch", "wm-"), None);
+    }
+
+    #[test]
+    fn is_base_handle_window_matches_numbered_duplicates() {
+        assert!(is_base_handle_window("wm-feat", "wm-feat"));
+        assert!(is_base_handle_window("wm-feat-2", "wm-feat"));
+        assert!(!is_base_handle_window("wm-feat-x", "wm-feat"));
+        assert!(!is_base_handle_window("wm-feat-", "wm-feat"));
+        assert!(!is_base_handle_window("wm-feature", "wm-feat"));
+    }
+
+    #[test]
+    fn unique_name_appends_first_free_suffix() {
+        let existing: HashSet<String> = ["wm-a", "wm-a-2", "wm-a-4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(unique_name(&existing, "wm-b"), "wm-b");
+        assert_eq!(unique_name(&existing, "wm-a"), "wm-a-3");
+    }
+
     // --- is_posix_shell tests ---
 
     #[test]
@@ -515,6 +858,133 @@ mod tests {
         );
     }
 
+    // --- with_env_exports tests ---
+
+    #[test]
+    fn test_with_env_exports_posix_keeps_leading_space() {
+        assert_eq!(
+            with_env_exports(" claude", "export A=1", "/bin/zsh"),
+            " export A=1; claude"
+        );
+    }
+
+    #[test]
+    fn test_with_env_exports_non_posix_wraps() {
+        assert_eq!(
+            with_env_exports(" claude", "export A='x y'", "/usr/bin/fish"),
+            " sh -c 'export A='\\''x y'\\''; claude'"
+        );
+    }
+
+    // --- with_setup_script tests ---
+
+    #[test]
+    fn test_with_setup_script_gates_command() {
+        assert_eq!(
+            with_setup_script(" claude", "npm install || true\n", "fix", "/bin/bash"),
+            " { npm install || true; } && { workmux _setup-done fix; claude; }"
+        );
+        assert_eq!(
+            with_setup_script(" claude", "direnv allow", "fix", "/usr/bin/fish"),
+            " sh -c '{ direnv allow; } && { workmux _setup-done fix; claude; }'"
+        );
+    }
+
+    // --- resolve_pane_cwds tests ---
+
+    fn pane(cwd: Option<&str>) -> PaneConfig {
+        PaneConfig {
+            command: Some("<agent>".to_string()),
+            focus: false,
+            split: None,
+            size: None,
+            percentage: None,
+            target: None,
+            cwd: cwd.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn resolve_pane_cwds_per_agent_subdirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for dir in ["apps/web", "apps/api", "apps/docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let working_dir = root.join("apps/docs");
+        let panes = vec![pane(Some("apps/web")), pane(Some("./apps/api")), pane(None)];
+        let cwds = resolve_pane_cwds(&panes, root, &working_dir).unwrap();
+        assert_eq!(
+            cwds,
+            vec![
+                root.join("apps/web"),
+                root.join("./apps/api"),
+                working_dir.clone(),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_pane_cwds_rejects_escaping_subdirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("wt");
+        std::fs::create_dir_all(&root).unwrap();
+        for subdir in ["../other", "apps/../../other", "/etc"] {
+            let err = resolve_pane_cwds(&[pane(Some(subdir))], &root, &root).unwrap_err();
+            assert!(
+                err.to_string().contains("inside the worktree"),
+                "{subdir}: {err}"
+            );
+        }
+
+        let err = resolve_pane_cwds(&[pane(Some("missing"))], &root, &root).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{err}");
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path(), root.join("escape")).unwrap();
+            let err = resolve_pane_cwds(&[pane(Some("escape"))], &root, &root).unwrap_err();
+            assert!(err.to_string().contains("inside the worktree"), "{err}");
+        }
+    }
+
+    #[test]
+    fn resolve_start_dir_accepts_existing_subdir() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("apps/web")).unwrap();
+        let dir = resolve_start_dir(tmp.path(), Path::new("apps/web")).unwrap();
+        assert_eq!(dir, tmp.path().join("apps/web"));
+    }
+
+    #[test]
+    fn resolve_start_dir_rejects_missing_or_escaping_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("wt");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("file.txt"), "").unwrap();
+
+        for subdir in ["../other", "/etc", "apps/../../other"] {
+            assert!(
+                check_start_dir(Path::new(subdir)).is_err(),
+                "{subdir} should be rejected"
+            );
+        }
+        assert!(check_start_dir(Path::new("./apps/web")).is_ok());
+        for subdir in ["missing", "file.txt"] {
+            assert!(
+                resolve_start_dir(&root, Path::new(subdir)).is_err(),
+                "{subdir} should be rejected"
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path(), root.join("escape")).unwrap();
+            let err = resolve_start_dir(&root, Path::new("escape")).unwrap_err();
+            assert!(err.to_string().contains("inside the worktree"), "{err}");
+        }
+    }
+
     // --- inject_skip_permissions_flag tests ---
 
     #[test]
@@ -739,4 +1209,53 @@ mod tests {
         assert!(resolved.command.contains("-i"));
         assert_eq!(resolved.effective_agent.as_deref(), Some("gemini"));
     }
+
+    #[test]
+    fn test_contains_dashboard_ui_footer() {
+        let content = "agent output\n[Enter] go  [q] quit";
+        assert!(contains_dashboard_ui(content));
+    }
+
+    #[test]
+    fn test_contains_dashboard_ui_input_mode() {
+        assert!(contains_dashboard_ui(
+            "INPUT MODE - Type to send keys to agent"
+        ));
+    }
+
+    #[test]
+    fn test_is_dashboard_rules_out_other_commands_without_capture() {
+        assert!(!is_dashboard(Some("claude"), || panic!("captured")));
+        assert!(!is_dashboard(Some("/usr/bin/zsh -l"), || panic!(
+            "captured"
+        )));
+    }
+
+    #[test]
+    fn test_is_dashboard_matches_dashboard_command() {
+        assert!(is_dashboard(Some("workmux dashboard"), || panic!(
+            "captured"
+        )));
+        assert!(is_dashboard(
+            Some("/home/u/.cargo/bin/workmux dashboard --diff"),
+            || { panic!("captured") }
+        ));
+    }
+
+    #[test]
+    fn test_is_dashboard_falls_back_to_capture() {
+        let dashboard = || Some("[Enter] go  [q] quit".to_string());
+        assert!(is_dashboard(Some("workmux"), dashboard));
+        assert!(is_dashboard(None, dashboard));
+        assert!(!is_dashboard(Some("workmux"), || Some(
+            "$ cargo test".to_string()
+        )));
+        assert!(!is_dashboard(None, || None));
+    }
+
+    #[test]
+    fn test_contains_dashboard_ui_agent_output() {
+        assert!(!contains_dashboard_ui("$ cargo test\nok"));
+        assert!(!contains_dashboard_ui("press [q] quit to exit"));
+    }
 }
@@ -13,7 +13,6 @@ use std::time::Duration;
 use crate::cmd::Cmd;
 use crate::config::SplitDirection;
 
-use super::agent;
 use super::handshake::UnixPipeHandshake;
 use super::types::*;
 use super::util;
@@ -155,13 +154,8 @@ impl WezTermBackend {
             direction_arg,
         ];
 
-        let percent_arg;
-        if let Some(p) = percentage {
-            percent_arg = format!("{}", p);
-            args.push("--percent");
-            args.push(&percent_arg);
-        }
-        let _ = size; // WezTerm doesn't support absolute sizes via CLI
+        let size_args = split_size_args(size, percentage);
+        args.extend(size_args.iter().map(String::as_str));
 
         // Handle optional command: always wrap in sh -c to correctly handle
         // both simple commands and complex shell scripts with quoting
@@ -295,6 +289,19 @@ impl Multiplexer for WezTermBackend {
         Ok(())
     }
 
+    fn rename_window(&self, old_full_name: &str, new_full_name: &str) -> Result<()> {
+        let panes = self.list_panes()?;
+        let current_ws = self.current_workspace();
+        let pane = panes
+            .iter()
+            .find(|p| {
+                p.tab_title == old_full_name
+                    && current_ws.as_ref().is_none_or(|ws| &p.workspace == ws)
+            })
+            .ok_or_else(|| anyhow!("No tab named '{}'", old_full_name))?;
+        self.set_tab_title(&pane.pane_id.to_string(), new_full_name)
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let panes = self.list_panes()?;
         let current_ws = self.current_workspace();
@@ -662,43 +669,16 @@ impl Multiplexer for WezTermBackend {
         Ok(())
     }
 
-    fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
-        if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
-            // Send ! first
-            self.wezterm_cmd()
-                .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", "!"])
-                .run()
-                .context("Failed to send ! to pane")?;
-
-            // Small delay to let Claude register the !
-            thread::sleep(Duration::from_millis(50));
-
-            // Send the rest of the command (without the !)
-            self.wezterm_cmd()
-                .args(&[
-                    "cli",
-                    "send-text",
-                    "--pane-id",
-                    pane_id,
-                    "--no-paste",
-                    &command[1..],
-                ])
-                .run()
-                .context("Failed to send keys to pane")?;
-
-            // Send Enter
-            self.wezterm_cmd()
-                .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", "\r"])
-                .run()
-                .context("Failed to send Enter key to pane")?;
-
-            Ok(())
-        } else {
-            self.send_keys(pane_id, command)
-        }
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        self.wezterm_cmd()
+            .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", text])
+            .run()
+            .context("Failed to send text to pane")?;
+        Ok(())
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
+        let key = util::key_sequence(key);
         self.wezterm_cmd()
             .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", key])
             .run()
@@ -706,7 +686,11 @@ impl Multiplexer for WezTermBackend {
         Ok(())
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
+    fn paste_multiline(&self, pane_id: &str, content: &str, bracketed: bool) -> Result<()> {
+        if !bracketed {
+            return self.type_multiline(pane_id, content);
+        }
+
         // Without --no-paste, WezTerm uses bracketed paste
         self.wezterm_cmd()
             .args(&["cli", "send-text", "--pane-id", pane_id, content])
@@ -962,10 +946,28 @@ fn send_pane_switch_signal(workspace: &str, tab_title: &str) {
     let _ = std::io::stdout().flush();
 }
 
+/// Size flags for `wezterm cli split-pane`. A percentage wins over an
+/// absolute size, which maps to `--cells`.
+fn split_size_args(size: Option<u16>, percentage: Option<u8>) -> Vec<String> {
+    match (percentage, size) {
+        (Some(p), _) => vec!["--percent".to_string(), p.to_string()],
+        (None, Some(cells)) => vec!["--cells".to_string(), cells.to_string()],
+        (None, None) => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_split_size_args() {
+        assert_eq!(split_size_args(None, Some(30)), vec!["--percent", "30"]);
+        assert_eq!(split_size_args(Some(20), None), vec!["--cells", "20"]);
+        assert_eq!(split_size_args(Some(20), Some(30)), vec!["--percent", "30"]);
+        assert!(split_size_args(None, None).is_empty());
+    }
+
     #[test]
     fn test_cwd_path_parsing() {
         let pane = WezTermPane {
@@ -3,24 +3,288 @@
 //! Limitations:
 //! - No percentage-based pane size control (can resize with +/- but not set exact %)
 //! - No window insertion order (tabs always append)
-//! - No visual status indicator (set_status is a no-op)
+//! - Status icons go in pane titles, and only with `rename-pane --pane-id`
+//! - Session mode creates detached sessions; the client is not switched to them
+//! - Dashboard preview is opt-in (`dashboard.zellij_preview`) and cached briefly
 
 use anyhow::{Context, Result, anyhow};
-use std::collections::HashSet;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
 use crate::cmd::Cmd;
-use crate::config::SplitDirection;
+use crate::config::{Config, PaneConfig, SplitDirection};
 
 use super::handshake::UnixPipeHandshake;
 use super::types::{CreateWindowParams, LivePaneInfo};
-use super::{Multiplexer, PaneHandshake};
+use super::{Multiplexer, PaneHandshake, util};
+
+/// Most bytes read from a `dump-screen` file. Long scrollback can run to
+/// megabytes, but previews only need the last few screens.
+const CAPTURE_MAX_BYTES: u64 = 256 * 1024;
 
 /// Zellij multiplexer backend.
 pub struct ZellijBackend {
-    _private: (),
+    /// Whether preview captures are enabled in config.
+    preview_enabled: bool,
+    /// How long a capture is reused before `dump-screen` is spawned again.
+    preview_cache_ttl: Duration,
+    /// Recent captures keyed by pane ID and line count, to throttle
+    /// `dump-screen` spawns.
+    preview_cache: Mutex<HashMap<(String, u16), CachedCapture>>,
+    /// Session created by `create_session`. Pane actions target it via
+    /// `--session` so setup works from outside (or another) session.
+    target_session: Mutex<Option<String>>,
+    /// Whether `zellij action focus-pane-with-id` exists (probed once).
+    focus_by_id_supported: OnceLock<bool>,
+    /// Whether `zellij action rename-pane` accepts `--pane-id` (probed once).
+    rename_by_id_supported: OnceLock<bool>,
+    /// Whether `zellij action undo-rename-pane` accepts `--pane-id` (probed once).
+    undo_rename_by_id_supported: OnceLock<bool>,
+    /// Configured status icons, to recognise them in pane titles.
+    status_icons: Vec<String>,
+    /// Sandbox heartbeat interval from config.
+    heartbeat_interval: u64,
+    /// Panes of the last tab created from a KDL layout, for `setup_panes`.
+    layout_panes: Mutex<Option<Vec<String>>>,
+}
+
+/// A pane capture and the time it was taken.
+struct CachedCapture {
+    captured_at: Instant,
+    content: Option<String>,
+}
+
+impl CachedCapture {
+    fn is_fresh(&self, now: Instant, ttl: Duration) -> bool {
+        now.saturating_duration_since(self.captured_at) < ttl
+    }
+}
+
+/// Build a `zellij` command, targeting a specific session when given.
+fn zellij_cmd(session: Option<&str>) -> Cmd<'_> {
+    match session {
+        Some(name) => Cmd::new("zellij").args(&["--session", name]),
+        None => Cmd::new("zellij"),
+    }
+}
+
+/// Build the `zellij action` argument lists that paste `content` into a
+/// pane and submit it.
+///
+/// The text is normalized with `util::normalize_paste`, so the only Enter
+/// (13) is the final submit. With `bracketed`, the whole text
+/// is wrapped in bracketed-paste markers and written at once. Otherwise each
+/// line is typed separately, joined by LF (10) so blank lines and line breaks
+/// don't submit early.
+fn paste_actions(content: &str, bracketed: bool) -> Vec<Vec<String>> {
+    let text = util::normalize_paste(content);
+    let text = text.as_str();
+    let write = |bytes: &[u8]| {
+        std::iter::once("write".to_string())
+            .chain(bytes.iter().map(|b| b.to_string()))
+            .collect::<Vec<_>>()
+    };
+    let write_chars = |chars: &str| vec!["write-chars".to_string(), chars.to_string()];
+
+    let mut actions = Vec::new();
+    if bracketed {
+        actions.push(write(b"\x1b[200~"));
+        if !text.is_empty() {
+            actions.push(write_chars(text));
+        }
+        actions.push(write(b"\x1b[201~"));
+    } else {
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                actions.push(write(b"\n"));
+            }
+            if !line.is_empty() {
+                actions.push(write_chars(line));
+            }
+        }
+    }
+    actions.push(write(b"\r"));
+    actions
+}
+
+/// Decimal byte codes for zellij's `write` action for a named key, or None
+/// for keys that are typed as text with `write-chars`.
+///
+/// Shares the byte sequences of `util::key_sequence` so every backend sends
+/// the same bytes for a submit key like `C-Enter`.
+fn key_codes(key: &str) -> Option<Vec<String>> {
+    let sequence = match key {
+        "Enter" | "Escape" | "Tab" | "C-Enter" | "S-Enter" | "M-Enter" => util::key_sequence(key),
+        _ => return None,
+    };
+    Some(sequence.bytes().map(|b| b.to_string()).collect())
+}
+
+/// Check whether `zellij action --help` output lists a subcommand.
+fn help_lists_action(help: &str, action: &str) -> bool {
+    help.lines()
+        .any(|line| line.split_whitespace().next() == Some(action))
+}
+
+/// Check whether a `--help` output documents a long flag like `--pane-id`.
+fn help_lists_flag(help: &str, flag: &str) -> bool {
+    help.split(|c: char| c.is_whitespace() || c == ',' || c == '=')
+        .any(|word| word == flag)
+}
+
+/// Strip a status icon that workmux prefixed to a pane title.
+fn strip_status_icon<'a>(title: &'a str, icons: &[String]) -> &'a str {
+    icons
+        .iter()
+        .filter(|icon| !icon.is_empty())
+        .find_map(|icon| title.strip_prefix(icon.as_str()))
+        .map(|rest| rest.strip_prefix(' ').unwrap_or(rest))
+        .unwrap_or(title)
+}
+
+/// Pane title showing `icon` before the pane's own title.
+fn status_title(title: &str, icon: &str, icons: &[String]) -> String {
+    match strip_status_icon(title, icons) {
+        "" => icon.to_string(),
+        base => format!("{} {}", icon, base),
+    }
+}
+
+/// Parse session names from `zellij list-sessions --no-formatting`.
+///
+/// Lines look like "name [Created 2h ago] (current)". Exited sessions kept
+/// for resurrection ("(EXITED - attach to resurrect)") are not running, so
+/// they are skipped.
+fn parse_session_names(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter(|line| !line.contains("(EXITED"))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+/// A host process and the zellij pane it runs in (from its environment).
+#[derive(Debug, Clone, PartialEq)]
+struct PaneProcess {
+    pid: u32,
+    ppid: u32,
+    pane: u32,
+}
+
+/// Extract the zellij pane ID from a process environment (NUL-separated),
+/// if the process belongs to the given session.
+fn parse_pane_from_environ(environ: &[u8], session: &str) -> Option<u32> {
+    let mut pane = None;
+    let mut in_session = false;
+    for var in environ.split(|b| *b == 0) {
+        if let Some(id) = var.strip_prefix(b"ZELLIJ_PANE_ID=") {
+            pane = std::str::from_utf8(id).ok().and_then(|id| id.parse().ok());
+        } else if let Some(name) = var.strip_prefix(b"ZELLIJ_SESSION_NAME=") {
+            in_session = name == session.as_bytes();
+        }
+    }
+    pane.filter(|_| in_session)
+}
+
+/// Parse the parent PID from `/proc/<pid>/stat`.
+///
+/// The command name field may contain spaces and parentheses, so fields are
+/// read after the last ')'.
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Map each pane to the PID of its root process.
+///
+/// All processes in a pane inherit `ZELLIJ_PANE_ID`; the root is the one whose
+/// parent (the zellij server) is not in the same pane. If several qualify
+/// (e.g. a daemonized child), the oldest (lowest) PID wins.
+fn pane_root_pids(procs: &[PaneProcess]) -> HashMap<u32, u32> {
+    let mut roots: HashMap<u32, u32> = HashMap::new();
+    for proc in procs {
+        let parent_in_pane = procs
+            .iter()
+            .any(|p| p.pid == proc.ppid && p.pane == proc.pane);
+        if parent_in_pane {
+            continue;
+        }
+        roots
+            .entry(proc.pane)
+            .and_modify(|pid| *pid = (*pid).min(proc.pid))
+            .or_insert(proc.pid);
+    }
+    roots
+}
+
+/// Resolve pane root PIDs for a session by scanning `/proc`.
+///
+/// Zellij doesn't report PIDs, so this relies on the pane environment. Returns
+/// an empty map where `/proc` is unavailable (e.g. macOS).
+fn resolve_pane_pids(session: &str) -> HashMap<u32, u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+
+    let procs: Vec<PaneProcess> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let environ = std::fs::read(format!("/proc/{}/environ", pid)).ok()?;
+            let pane = parse_pane_from_environ(&environ, session)?;
+            let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+            let ppid = parse_stat_ppid(&stat)?;
+            Some(PaneProcess { pid, ppid, pane })
+        })
+        .collect();
+
+    pane_root_pids(&procs)
+}
+
+/// Check whether a process is alive using `kill(pid, 0)`.
+///
+/// EPERM means the process exists but belongs to another user.
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 performs error checking only; no signal is sent.
+    let ret = unsafe { libc::kill(pid, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Read at most the last `max_bytes` of a file. When the file is larger,
+/// the first (partial) line of the window is dropped.
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut bytes = Vec::with_capacity((len - start) as usize);
+    file.read_to_end(&mut bytes)?;
+    let content = String::from_utf8_lossy(&bytes);
+    if start == 0 {
+        return Ok(content.into_owned());
+    }
+    Ok(content
+        .split_once('\n')
+        .map(|(_, rest)| rest.to_string())
+        .unwrap_or_default())
+}
+
+/// Keep only the last `lines` lines of captured content.
+fn tail_lines(content: &str, lines: u16) -> String {
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines as usize);
+    all[start..].join("\n")
 }
 
 /// Info about a pane from `zellij action list-panes --json --tab --command`
@@ -45,11 +309,107 @@ struct PaneInfo {
     title: String,
 }
 
+/// Find the focused terminal pane, restricted to the focused tab if known.
+fn find_focused_pane<'a>(panes: &'a [PaneInfo], focused_tab: Option<&str>) -> Result<&'a PaneInfo> {
+    // Filter by focused tab if we know which tab is focused
+    if let Some(tab_name) = focused_tab {
+        panes
+            .iter()
+            .find(|p| p.is_focused && !p.is_plugin && p.tab_name == tab_name)
+            .ok_or_else(|| anyhow!("No focused terminal pane found in tab '{}'", tab_name))
+    } else {
+        // Fallback: just find any focused terminal pane
+        panes
+            .iter()
+            .find(|p| p.is_focused && !p.is_plugin)
+            .ok_or_else(|| anyhow!("No focused terminal pane found"))
+    }
+}
+
+/// Focused pane to restore after targeting `target_id`, or None if the
+/// target is already focused.
+fn focus_to_restore(
+    panes: &[PaneInfo],
+    focused_tab: Option<&str>,
+    target_id: u32,
+) -> Result<Option<u32>> {
+    let focused = find_focused_pane(panes, focused_tab)?.id;
+    Ok((focused != target_id).then_some(focused))
+}
+
+/// Working directory of the terminal pane with `pane_id`.
+fn pane_cwd(panes: &[PaneInfo], pane_id: u32) -> Result<PathBuf> {
+    let pane = panes
+        .iter()
+        .find(|p| p.id == pane_id && !p.is_plugin)
+        .ok_or_else(|| anyhow!("Terminal pane {} not found", pane_id))?;
+    pane.pane_cwd
+        .as_deref()
+        .filter(|cwd| !cwd.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("Pane {} has no working directory", pane.id))
+}
+
+/// Zellij state fetched at most once per top-level operation.
+///
+/// Each query (`list-panes`, `list-tabs`, `current-tab-info`) runs lazily on
+/// first use, then every helper the snapshot is passed to reuses the result.
+/// Actions that change focus or layout make it stale, so a snapshot never
+/// outlives the trait method call that created it.
+struct ZellijSnapshot<'a> {
+    backend: &'a ZellijBackend,
+    panes: OnceCell<Vec<PaneInfo>>,
+    tabs: OnceCell<Vec<TabInfo>>,
+    focused_tab: OnceCell<Option<String>>,
+}
+
+impl<'a> ZellijSnapshot<'a> {
+    fn new(backend: &'a ZellijBackend) -> Self {
+        Self {
+            backend,
+            panes: OnceCell::new(),
+            tabs: OnceCell::new(),
+            focused_tab: OnceCell::new(),
+        }
+    }
+
+    fn panes(&self) -> Result<&[PaneInfo]> {
+        if let Some(panes) = self.panes.get() {
+            return Ok(panes);
+        }
+        let panes = self.backend.list_panes()?;
+        Ok(self.panes.get_or_init(|| panes))
+    }
+
+    fn tabs(&self) -> Result<&[TabInfo]> {
+        if let Some(tabs) = self.tabs.get() {
+            return Ok(tabs);
+        }
+        let tabs = ZellijBackend::list_tabs()?;
+        Ok(self.tabs.get_or_init(|| tabs))
+    }
+
+    fn focused_tab(&self) -> Option<&str> {
+        self.focused_tab
+            .get_or_init(|| self.backend.focused_tab_name())
+            .as_deref()
+    }
+
+    /// Tab with the given name, if any.
+    fn tab_named(&self, name: &str) -> Result<Option<&TabInfo>> {
+        Ok(self.tabs()?.iter().find(|t| t.name == name))
+    }
+
+    /// Focused terminal pane, restricted to the focused tab if known.
+    fn focused_pane(&self) -> Result<&PaneInfo> {
+        find_focused_pane(self.panes()?, self.focused_tab())
+    }
+}
+
 /// Info about a tab from `zellij action list-tabs --json`
 #[derive(Debug, serde::Deserialize)]
 struct TabInfo {
-    tab_id: u32, // Stable tab ID (available in zellij 0.44.0+)
-    #[allow(dead_code)]
+    tab_id: u32,   // Stable tab ID (available in zellij 0.44.0+)
     position: u32, // Tab position (can change when tabs are reordered)
     name: String,
     #[allow(dead_code)]
@@ -63,6 +423,141 @@ impl TabInfo {
     }
 }
 
+/// ID of the right-most tab whose name satisfies `matches`.
+fn last_tab_id(tabs: &[TabInfo], matches: impl Fn(&str) -> bool) -> Option<String> {
+    tabs.iter()
+        .filter(|tab| matches(&tab.name))
+        .max_by_key(|tab| tab.position)
+        .map(|tab| tab.tab_id.to_string())
+}
+
+/// Number of `move-tab left` steps that place tab `moving` directly after
+/// tab `after`. Zero when it is already there or either tab is gone.
+fn moves_to_follow(tabs: &[TabInfo], moving: u32, after: u32) -> usize {
+    let position = |id: u32| tabs.iter().find(|t| t.tab_id == id).map(|t| t.position);
+    match (position(moving), position(after)) {
+        (Some(moving), Some(after)) if moving > after + 1 => (moving - after - 1) as usize,
+        _ => 0,
+    }
+}
+
+/// Name given to the `index`th pane of a KDL layout, to find its ID after.
+fn layout_pane_name(index: usize) -> String {
+    format!("workmux-pane-{}", index)
+}
+
+/// A pane of a KDL layout: a created pane, or a split of one into two.
+enum LayoutNode {
+    Pane(usize),
+    Split {
+        direction: SplitDirection,
+        first: Box<LayoutNode>,
+        second: Box<LayoutNode>,
+        /// KDL `size` of the new (second) pane
+        size: Option<String>,
+    },
+}
+
+impl LayoutNode {
+    /// Split the pane `target` in place, the new pane `index` taking `size`.
+    fn split(
+        &mut self,
+        target: usize,
+        index: usize,
+        direction: &SplitDirection,
+        size: Option<String>,
+    ) -> bool {
+        match self {
+            LayoutNode::Pane(id) if *id == target => {
+                *self = LayoutNode::Split {
+                    direction: direction.clone(),
+                    first: Box::new(LayoutNode::Pane(target)),
+                    second: Box::new(LayoutNode::Pane(index)),
+                    size,
+                };
+                true
+            }
+            LayoutNode::Pane(_) => false,
+            LayoutNode::Split { first, second, .. } => {
+                first.split(target, index, direction, size.clone())
+                    || second.split(target, index, direction, size)
+            }
+        }
+    }
+
+    fn write_kdl(&self, out: &mut String, indent: usize, size: Option<&str>) {
+        let pad = "    ".repeat(indent);
+        let size = size.map(|s| format!(" size={}", s)).unwrap_or_default();
+        match self {
+            LayoutNode::Pane(index) => {
+                out.push_str(&format!(
+                    "{}pane name=\"{}\"{}\n",
+                    pad,
+                    layout_pane_name(*index),
+                    size
+                ));
+            }
+            LayoutNode::Split {
+                direction,
+                first,
+                second,
+                size: second_size,
+            } => {
+                // Zellij names splits by the divider: "vertical" is side by side
+                let split_direction = match direction {
+                    SplitDirection::Horizontal => "vertical",
+                    SplitDirection::Vertical => "horizontal",
+                };
+                out.push_str(&format!(
+                    "{}pane split_direction=\"{}\"{} {{\n",
+                    pad, split_direction, size
+                ));
+                first.write_kdl(out, indent + 1, None);
+                second.write_kdl(out, indent + 1, second_size.as_deref());
+                out.push_str(&format!("{}}}\n", pad));
+            }
+        }
+    }
+}
+
+/// A KDL layout for a new tab holding `panes`, or None when no split sets a
+/// size. `new-pane` can't size panes, so sized splits are created together
+/// with the tab instead. Panes are named with [`layout_pane_name`] by their
+/// index among the panes `setup_panes` creates.
+fn split_layout_kdl(panes: &[PaneConfig]) -> Option<String> {
+    let splits: Vec<&PaneConfig> = panes.iter().skip(1).filter(|p| p.split.is_some()).collect();
+    if !splits
+        .iter()
+        .any(|p| p.size.is_some() || p.percentage.is_some())
+    {
+        return None;
+    }
+
+    let mut root = LayoutNode::Pane(0);
+    for (i, pane) in splits.iter().enumerate() {
+        let index = i + 1;
+        let target = pane.target.unwrap_or(index - 1);
+        let size = match (pane.percentage, pane.size) {
+            (Some(percentage), _) => Some(format!("\"{}%\"", percentage)),
+            (None, Some(size)) => Some(size.to_string()),
+            (None, None) => None,
+        };
+        if !root.split(target, index, pane.split.as_ref()?, size) {
+            return None;
+        }
+    }
+
+    let mut kdl = String::from(
+        "layout {\n    default_tab_template {\n        pane size=1 borderless=true {\n            \
+         plugin location=\"zellij:tab-bar\"\n        }\n        children\n        \
+         pane size=2 borderless=true {\n            plugin location=\"zellij:status-bar\"\n        \
+         }\n    }\n",
+    );
+    root.write_kdl(&mut kdl, 1, None);
+    kdl.push_str("}\n");
+    Some(kdl)
+}
+
 /// Parse a numeric pane ID from a "terminal_X" string.
 fn parse_pane_id(pane_id: &str) -> Option<u32> {
     pane_id
@@ -97,13 +592,276 @@ fn parse_tab_name_from_output(output: &str) -> Option<String> {
 
 impl Default for ZellijBackend {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
 
 impl ZellijBackend {
-    pub fn new() -> Self {
-        Self { _private: () }
+    pub fn new(config: &Config) -> Self {
+        let icons = config.status_icons();
+        Self {
+            preview_enabled: config.dashboard.zellij_preview(),
+            preview_cache_ttl: config.dashboard.zellij_preview_cache_ttl(),
+            preview_cache: Mutex::new(HashMap::new()),
+            target_session: Mutex::new(None),
+            focus_by_id_supported: OnceLock::new(),
+            rename_by_id_supported: OnceLock::new(),
+            undo_rename_by_id_supported: OnceLock::new(),
+            status_icons: vec![
+                icons.working().to_string(),
+                icons.waiting().to_string(),
+                icons.done().to_string(),
+            ],
+            heartbeat_interval: config.sandbox.heartbeat_interval(),
+            layout_panes: Mutex::new(None),
+        }
+    }
+
+    /// Focus the pane a split should target.
+    ///
+    /// Returns the previously focused pane ID when focus had to move, or None
+    /// when the target was already focused.
+    fn focus_split_target(
+        &self,
+        snapshot: &ZellijSnapshot,
+        target_pane_id: &str,
+    ) -> Result<Option<u32>> {
+        let target_id: u32 = parse_pane_id(target_pane_id)
+            .ok_or_else(|| anyhow!("Invalid pane_id: {}", target_pane_id))?;
+
+        let Some(focused) = focus_to_restore(snapshot.panes()?, snapshot.focused_tab(), target_id)?
+        else {
+            return Ok(None);
+        };
+
+        debug!(target_id, focused, "split_pane: focusing target pane");
+        self.select_pane_in(snapshot, target_pane_id)?;
+        Ok(Some(focused))
+    }
+
+    /// Focus a pane, reading pane layout from `snapshot`.
+    fn select_pane_in(&self, snapshot: &ZellijSnapshot, pane_id: &str) -> Result<()> {
+        let session = self.target_session();
+
+        // Extract numeric ID from pane_id
+        let target_id: u32 =
+            parse_pane_id(pane_id).ok_or_else(|| anyhow!("Invalid pane_id: {}", pane_id))?;
+
+        // Jump directly when supported
+        if self.supports_focus_by_id() {
+            let id = target_id.to_string();
+            zellij_cmd(session.as_deref())
+                .args(&["action", "focus-pane-with-id", &id])
+                .run()
+                .context("Failed to focus pane")?;
+            return Ok(());
+        }
+
+        // Older zellij has no focus-pane-by-id action, so navigate
+        // using focus-next-pane or focus-previous-pane
+
+        // Get focused tab name to filter panes
+        let focused_tab = snapshot
+            .focused_tab()
+            .ok_or_else(|| anyhow!("Could not determine focused tab"))?;
+
+        // Get all panes in the current tab
+        let tab_panes: Vec<_> = snapshot
+            .panes()?
+            .iter()
+            .filter(|p| !p.is_plugin && p.tab_name == focused_tab)
+            .collect();
+
+        // Find current and target indices
+        let current_idx = tab_panes
+            .iter()
+            .position(|p| p.is_focused)
+            .ok_or_else(|| anyhow!("No focused pane found in current tab"))?;
+
+        let target_idx = tab_panes
+            .iter()
+            .position(|p| p.id == target_id)
+            .ok_or_else(|| anyhow!("Target pane {} not found in current tab", pane_id))?;
+
+        if current_idx == target_idx {
+            // Already focused
+            return Ok(());
+        }
+
+        // Navigate to target pane
+        if target_idx < current_idx {
+            // Navigate backwards
+            let steps = current_idx - target_idx;
+            debug!(
+                current_idx,
+                target_idx, steps, "Navigating backwards to focused pane"
+            );
+            for _ in 0..steps {
+                zellij_cmd(session.as_deref())
+                    .args(&["action", "focus-previous-pane"])
+                    .run()
+                    .context("Failed to navigate to previous pane")?;
+            }
+        } else {
+            // Navigate forwards
+            let steps = target_idx - current_idx;
+            debug!(
+                current_idx,
+                target_idx, steps, "Navigating forwards to focused pane"
+            );
+            for _ in 0..steps {
+                zellij_cmd(session.as_deref())
+                    .args(&["action", "focus-next-pane"])
+                    .run()
+                    .context("Failed to navigate to next pane")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether this zellij can focus a pane directly by ID.
+    fn supports_focus_by_id(&self) -> bool {
+        *self.focus_by_id_supported.get_or_init(|| {
+            Cmd::new("zellij")
+                .args(&["action", "--help"])
+                .run_and_capture_stdout()
+                .map(|help| help_lists_action(&help, "focus-pane-with-id"))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Whether this zellij can rename a pane without focusing it.
+    fn supports_rename_by_id(&self) -> bool {
+        *self.rename_by_id_supported.get_or_init(|| {
+            Cmd::new("zellij")
+                .args(&["action", "rename-pane", "--help"])
+                .run_and_capture_stdout()
+                .map(|help| help_lists_flag(&help, "--pane-id"))
+                .unwrap_or(false)
+        })
+    }
+
+    fn supports_undo_rename_by_id(&self) -> bool {
+        *self.undo_rename_by_id_supported.get_or_init(|| {
+            Cmd::new("zellij")
+                .args(&["action", "undo-rename-pane", "--help"])
+                .run_and_capture_stdout()
+                .map(|help| help_lists_flag(&help, "--pane-id"))
+                .unwrap_or(false)
+        })
+    }
+
+    /// IDs of the panes of a tab created from [`split_layout_kdl`], found by
+    /// their layout names, which are dropped again afterwards.
+    fn find_layout_panes(&self, tab_id: u32, panes: &[PaneConfig]) -> Result<Vec<String>> {
+        let count = 1 + panes.iter().skip(1).filter(|p| p.split.is_some()).count();
+        let session = self.target_session();
+        let ids = zellij_cmd(session.as_deref())
+            .args(&["action", "list-panes", "--json", "--tab"])
+            .run_and_capture_stdout_with_retry(5, crate::cmd::DEFAULT_RETRY_DELAY, |output| {
+                let listed = serde_json::from_str::<Vec<PaneInfo>>(output).ok()?;
+                (0..count)
+                    .map(|i| {
+                        let name = layout_pane_name(i);
+                        listed
+                            .iter()
+                            .find(|p| !p.is_plugin && p.tab_id == Some(tab_id) && p.title == name)
+                            .map(|p| p.id)
+                    })
+                    .collect::<Option<Vec<u32>>>()
+            })
+            .with_context(|| format!("Layout panes not found in new tab {}", tab_id))?;
+
+        if self.supports_undo_rename_by_id() {
+            for &id in &ids {
+                if let Err(e) = self.undo_rename_pane(id) {
+                    debug!(id, error = %e, "create_window: failed to drop layout pane name");
+                }
+            }
+        }
+        Ok(ids.iter().map(|id| format!("terminal_{}", id)).collect())
+    }
+
+    /// Rename a pane by ID, leaving focus where it is.
+    fn rename_pane(&self, pane_id: u32, title: &str) -> Result<()> {
+        let session = self.target_session();
+        let id = pane_id.to_string();
+        zellij_cmd(session.as_deref())
+            .args(&["action", "rename-pane", "--pane-id", &id, title])
+            .run()
+            .context("Failed to rename pane")?;
+        Ok(())
+    }
+
+    /// Drop a pane's custom name so it shows the title its program sets again.
+    fn undo_rename_pane(&self, pane_id: u32) -> Result<()> {
+        let session = self.target_session();
+        let id = pane_id.to_string();
+        zellij_cmd(session.as_deref())
+            .args(&["action", "undo-rename-pane", "--pane-id", &id])
+            .run()
+            .context("Failed to undo pane rename")?;
+        Ok(())
+    }
+
+    /// Current title of a pane, or None if it no longer exists.
+    fn pane_title(&self, pane_id: u32) -> Result<Option<String>> {
+        Ok(self
+            .list_panes()?
+            .into_iter()
+            .find(|p| p.id == pane_id && !p.is_plugin)
+            .map(|p| p.title))
+    }
+
+    /// Session that pane actions should target, if one was created.
+    fn target_session(&self) -> Option<String> {
+        self.target_session.lock().ok().and_then(|s| s.clone())
+    }
+
+    /// Resolve root PIDs for panes of the targeted (or current) session.
+    fn pane_pids(&self) -> HashMap<u32, u32> {
+        self.target_session()
+            .or_else(Self::session_name)
+            .map(|session| resolve_pane_pids(&session))
+            .unwrap_or_default()
+    }
+
+    /// Run `dump-screen` for a pane and return the last `lines` lines.
+    fn dump_screen(&self, pane_id: &str, lines: u16) -> Option<String> {
+        // Use PID + thread ID + timestamp for thread-safe temp file naming
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let thread_id = std::thread::current().id();
+        let temp_path = std::env::temp_dir().join(format!(
+            "zellij_capture_{}_{:?}_{}",
+            std::process::id(),
+            thread_id,
+            timestamp
+        ));
+        let temp_str = temp_path.to_string_lossy();
+
+        // Try with --pane-id first, fall back to focused pane if not supported
+        let session = self.target_session();
+        let dumped = zellij_cmd(session.as_deref())
+            .args(&["action", "dump-screen", "--pane-id", pane_id, &temp_str])
+            .run()
+            .is_ok()
+            || zellij_cmd(session.as_deref())
+                .args(&["action", "dump-screen", &temp_str])
+                .run()
+                .is_ok();
+
+        let content = if dumped {
+            read_tail(&temp_path, CAPTURE_MAX_BYTES).ok()
+        } else {
+            None
+        };
+        let _ = std::fs::remove_file(&temp_path);
+
+        content.map(|c| tail_lines(&c, lines))
     }
 
     /// Check if inside a zellij session
@@ -124,8 +882,9 @@ impl ZellijBackend {
     }
 
     /// Get the name of the currently focused tab using `current-tab-info`.
-    fn focused_tab_name() -> Option<String> {
-        let output = Cmd::new("zellij")
+    fn focused_tab_name(&self) -> Option<String> {
+        let session = self.target_session();
+        let output = zellij_cmd(session.as_deref())
             .args(&["action", "current-tab-info"])
             .run_and_capture_stdout()
             .ok()?;
@@ -137,8 +896,9 @@ impl ZellijBackend {
     ///
     /// The `--tab` flag includes `tab_id`, `tab_name`, `tab_position`.
     /// The `--command` flag includes `pane_command`, `pane_cwd`.
-    fn list_panes() -> Result<Vec<PaneInfo>> {
-        let output = Cmd::new("zellij")
+    fn list_panes(&self) -> Result<Vec<PaneInfo>> {
+        let session = self.target_session();
+        let output = zellij_cmd(session.as_deref())
             .args(&["action", "list-panes", "--json", "--tab", "--command"])
             .run_and_capture_stdout()
             .context("Failed to list panes")?;
@@ -159,25 +919,8 @@ impl ZellijBackend {
     /// Get focused pane ID from list-panes output
     ///
     /// Returns the focused pane in the currently active tab.
-    fn focused_pane_id() -> Result<u32> {
-        let panes = Self::list_panes()?;
-        let focused_tab = Self::focused_tab_name();
-
-        // Filter by focused tab if we know which tab is focused
-        if let Some(tab_name) = focused_tab {
-            panes
-                .iter()
-                .find(|p| p.is_focused && !p.is_plugin && p.tab_name == tab_name)
-                .map(|p| p.id)
-                .ok_or_else(|| anyhow!("No focused terminal pane found in tab '{}'", tab_name))
-        } else {
-            // Fallback: just find any focused terminal pane
-            panes
-                .iter()
-                .find(|p| p.is_focused && !p.is_plugin)
-                .map(|p| p.id)
-                .ok_or_else(|| anyhow!("No focused terminal pane found"))
-        }
+    fn focused_pane_id(&self) -> Result<u32> {
+        ZellijSnapshot::new(self).focused_pane().map(|p| p.id)
     }
 
     /// Get tab ID by tab name (for future use)
@@ -197,7 +940,18 @@ impl Multiplexer for ZellijBackend {
     }
 
     fn supports_preview(&self) -> bool {
-        false // Preview requires expensive process spawning
+        // Preview requires spawning dump-screen, so it is opt-in via config
+        self.preview_enabled
+    }
+
+    fn is_dashboard_pane(&self, pane_id: &str) -> bool {
+        // `capture_pane` drops captures that look like the dashboard, so check
+        // the pane's full command and then its raw screen instead
+        let panes = self.list_panes().unwrap_or_default();
+        let command = parse_pane_id(pane_id)
+            .and_then(|id| panes.iter().find(|p| p.id == id && !p.is_plugin))
+            .and_then(|p| p.pane_command.as_deref().or(p.terminal_command.as_deref()));
+        util::is_dashboard(command, || self.dump_screen(pane_id, 40))
     }
 
     fn requires_focus_for_input(&self) -> bool {
@@ -227,7 +981,7 @@ impl Multiplexer for ZellijBackend {
 
     fn active_pane_id(&self) -> Option<String> {
         // Reliable path: Query focused pane ID
-        Self::focused_pane_id()
+        self.focused_pane_id()
             .ok()
             .map(|id| format!("terminal_{}", id))
     }
@@ -238,46 +992,129 @@ impl Multiplexer for ZellijBackend {
         std::env::current_dir().context("Failed to get current directory")
     }
 
+    fn current_pane_cwd(&self) -> Result<PathBuf> {
+        // The pane workmux runs in, which need not be the focused one
+        let Some(pane_id) = std::env::var("ZELLIJ_PANE_ID")
+            .ok()
+            .and_then(|id| id.parse::<u32>().ok())
+        else {
+            return std::env::current_dir().context("Failed to get current directory");
+        };
+        pane_cwd(&self.list_panes()?, pane_id)
+    }
+
     fn instance_id(&self) -> String {
         Self::session_name().unwrap_or_else(|| "default".to_string())
     }
 
-    // === Session Management (not supported in Zellij) ===
+    // === Session Management ===
 
-    fn create_session(&self, _params: super::types::CreateSessionParams) -> Result<String> {
-        Err(anyhow!(
-            "Session mode (--session) is not supported in Zellij. Use window mode instead."
-        ))
+    /// Create a detached zellij session for the worktree.
+    ///
+    /// `zellij attach --create-background` is the detached form of
+    /// `zellij --session <name>`: it starts the session without attaching a
+    /// client. Subsequent pane actions target it via `--session`.
+    fn create_session(&self, params: super::types::CreateSessionParams) -> Result<String> {
+        let full_name = util::prefixed(params.prefix, params.name);
+
+        if self.session_exists(&full_name)? {
+            return Err(anyhow!("Zellij session '{}' already exists", full_name));
+        }
+
+        // The initial pane inherits the working directory of the spawning process
+        Cmd::new("zellij")
+            .args(&["attach", "--create-background", &full_name])
+            .workdir(params.cwd)
+            .run()
+            .with_context(|| format!("Failed to create zellij session '{}'", full_name))?;
+
+        if let Ok(mut target) = self.target_session.lock() {
+            *target = Some(full_name.clone());
+        }
+
+        // The background session may take a moment before it accepts actions
+        let mut panes = Vec::new();
+        for _ in 0..50 {
+            if let Ok(found) = self.list_panes()
+                && found.iter().any(|p| !p.is_plugin)
+            {
+                panes = found;
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let pane = panes
+            .iter()
+            .find(|p| !p.is_plugin)
+            .ok_or_else(|| anyhow!("No terminal pane found in new session '{}'", full_name))?;
+
+        if let Some(window_name) = params.initial_window_name {
+            zellij_cmd(Some(&full_name))
+                .args(&["action", "rename-tab", window_name])
+                .run()
+                .context("Failed to name initial tab")?;
+        }
+
+        debug!(session = %full_name, pane_id = pane.id, "create_session: created");
+        Ok(format!("terminal_{}", pane.id))
     }
 
-    fn switch_to_session(&self, _prefix: &str, _name: &str) -> Result<()> {
-        Err(anyhow!(
-            "Session mode is not supported in Zellij. Use window mode instead."
-        ))
+    fn switch_to_session(&self, prefix: &str, name: &str) -> Result<()> {
+        // Zellij's CLI cannot move an attached client to another session, so
+        // tell the user how to attach instead.
+        let full_name = util::prefixed(prefix, name);
+        println!(
+            "Zellij session '{}' is ready. Attach with: zellij attach {}",
+            full_name, full_name
+        );
+        Ok(())
     }
 
-    fn session_exists(&self, _full_name: &str) -> Result<bool> {
-        Ok(false)
+    fn session_exists(&self, full_name: &str) -> Result<bool> {
+        Ok(self.get_all_session_names()?.contains(full_name))
     }
 
-    fn kill_session(&self, _full_name: &str) -> Result<()> {
+    fn kill_session(&self, full_name: &str) -> Result<()> {
+        Cmd::new("zellij")
+            .args(&["delete-session", "--force", full_name])
+            .run()
+            .with_context(|| format!("Failed to delete zellij session '{}'", full_name))?;
         Ok(())
     }
 
-    fn schedule_session_close(&self, _full_name: &str, _delay: Duration) -> Result<()> {
-        Err(anyhow!(
-            "Session mode is not supported in Zellij. Use window mode instead."
-        ))
+    fn schedule_session_close(&self, full_name: &str, delay: Duration) -> Result<()> {
+        let script = format!(
+            "sleep {} && {}",
+            delay.as_secs(),
+            self.shell_kill_session_cmd(full_name)?
+        );
+
+        std::process::Command::new("sh")
+            .args(["-c", &script])
+            .spawn()
+            .context("Failed to spawn delayed session close")?;
+
+        Ok(())
     }
 
     fn get_all_session_names(&self) -> Result<HashSet<String>> {
-        Ok(HashSet::new())
+        // list-sessions exits non-zero when there are no sessions
+        let output = Cmd::new("zellij")
+            .args(&["list-sessions", "--no-formatting"])
+            .run_and_capture_stdout()
+            .unwrap_or_default();
+        Ok(parse_session_names(&output))
     }
 
-    fn wait_until_session_closed(&self, _full_session_name: &str) -> Result<()> {
-        Err(anyhow!(
-            "Session mode is not supported in Zellij. Use window mode instead."
-        ))
+    fn wait_until_session_closed(&self, full_session_name: &str) -> Result<()> {
+        println!("Waiting for session '{}' to close...", full_session_name);
+
+        loop {
+            if !self.session_exists(full_session_name)? {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
     }
 
     fn run_deferred_script(&self, script: &str) -> Result<()> {
@@ -312,13 +1149,15 @@ impl Multiplexer for ZellijBackend {
 
     fn shell_switch_session_cmd(&self, _full_name: &str) -> Result<String> {
         Err(anyhow!(
-            "Session mode is not supported in Zellij. Use window mode instead."
+            "Zellij cannot switch an attached client to another session"
         ))
     }
 
-    fn shell_kill_session_cmd(&self, _full_name: &str) -> Result<String> {
-        Err(anyhow!(
-            "Session mode is not supported in Zellij. Use window mode instead."
+    fn shell_kill_session_cmd(&self, full_name: &str) -> Result<String> {
+        let escaped = format!("'{}'", full_name.replace('\'', r#"'\''"#));
+        Ok(format!(
+            "zellij delete-session --force {} >/dev/null 2>&1",
+            escaped
         ))
     }
 
@@ -327,19 +1166,40 @@ impl Multiplexer for ZellijBackend {
     /// Create a new tab in Zellij.
     /// Returns: Pane ID of the initial pane (e.g., "terminal_5")
     fn create_window(&self, params: CreateWindowParams) -> Result<String> {
-        let full_name = format!("{}{}", params.prefix, params.name);
+        let full_name = util::prefixed(params.prefix, params.name);
         let cwd_str = params
             .cwd
             .to_str()
             .ok_or_else(|| anyhow!("Path contains non-UTF8 characters"))?;
 
-        if params.after_window.is_some() {
-            debug!("Zellij does not support window insertion order - ignoring after_window");
+        // Sized splits come from a KDL layout given to new-tab
+        if let Ok(mut slot) = self.layout_panes.lock() {
+            *slot = None;
         }
+        let layout = split_layout_kdl(params.panes)
+            .map(|kdl| -> Result<tempfile::NamedTempFile> {
+                let mut file = tempfile::Builder::new()
+                    .prefix("workmux-layout-")
+                    .suffix(".kdl")
+                    .tempfile()
+                    .context("Failed to create zellij layout file")?;
+                std::io::Write::write_all(&mut file, kdl.as_bytes())
+                    .context("Failed to write zellij layout file")?;
+                Ok(file)
+            })
+            .transpose()?;
+        let layout_path = layout
+            .as_ref()
+            .map(|f| f.path().to_string_lossy().into_owned());
 
         // new-tab returns tab_id on stdout and auto-focuses the new tab
-        let tab_id_str = Cmd::new("zellij")
-            .args(&["action", "new-tab", "--name", &full_name, "--cwd", cwd_str])
+        let session = self.target_session();
+        let mut new_tab = zellij_cmd(session.as_deref())
+            .args(&["action", "new-tab", "--name", &full_name, "--cwd", cwd_str]);
+        if let Some(path) = &layout_path {
+            new_tab = new_tab.args(&["--layout", path]);
+        }
+        let tab_id_str = new_tab
             .run_and_capture_stdout()
             .with_context(|| format!("Failed to create zellij tab '{}'", full_name))?;
 
@@ -348,14 +1208,45 @@ impl Multiplexer for ZellijBackend {
             .parse()
             .with_context(|| format!("Invalid tab ID from new-tab: '{}'", tab_id_str.trim()))?;
 
-        // Find the initial pane in the new tab by tab_id
-        let panes = Self::list_panes()?;
-        let pane = panes
-            .iter()
-            .find(|p| !p.is_plugin && p.tab_id == Some(tab_id))
-            .ok_or_else(|| anyhow!("No terminal pane found in new tab {}", tab_id))?;
+        // new-tab appends at the end; move the (focused) new tab left until it
+        // sits right after its sibling
+        if let Some(after) = params.after_window.and_then(|id| id.parse::<u32>().ok()) {
+            let moves = moves_to_follow(&Self::list_tabs()?, tab_id, after);
+            for _ in 0..moves {
+                zellij_cmd(session.as_deref())
+                    .args(&["action", "move-tab", "left"])
+                    .run()
+                    .context("Failed to move zellij tab")?;
+            }
+            debug!(
+                tab_id,
+                after, moves, "create_window: moved tab after sibling"
+            );
+        }
 
-        Ok(format!("terminal_{}", pane.id))
+        if layout.is_some() {
+            let pane_ids = self.find_layout_panes(tab_id, params.panes)?;
+            let initial = pane_ids[0].clone();
+            if let Ok(mut slot) = self.layout_panes.lock() {
+                *slot = Some(pane_ids);
+            }
+            return Ok(initial);
+        }
+
+        // Find the initial pane in the new tab by tab_id. Right after new-tab
+        // the pane may not be listed yet, so retry until it shows up.
+        let pane_id = zellij_cmd(session.as_deref())
+            .args(&["action", "list-panes", "--json", "--tab", "--command"])
+            .run_and_capture_stdout_with_retry(5, crate::cmd::DEFAULT_RETRY_DELAY, |output| {
+                serde_json::from_str::<Vec<PaneInfo>>(output)
+                    .ok()?
+                    .into_iter()
+                    .find(|p| !p.is_plugin && p.tab_id == Some(tab_id))
+                    .map(|p| p.id)
+            })
+            .with_context(|| format!("No terminal pane found in new tab {}", tab_id))?;
+
+        Ok(format!("terminal_{}", pane_id))
     }
 
     fn kill_window(&self, full_name: &str) -> Result<()> {
@@ -383,6 +1274,24 @@ impl Multiplexer for ZellijBackend {
         Ok(())
     }
 
+    fn rename_window(&self, old_full_name: &str, new_full_name: &str) -> Result<()> {
+        let tabs = Self::list_tabs()?;
+        let tab = tabs
+            .iter()
+            .find(|t| t.name == old_full_name)
+            .ok_or_else(|| anyhow!("No tab named '{}'", old_full_name))?;
+        Cmd::new("zellij")
+            .args(&[
+                "action",
+                "rename-tab-by-id",
+                &tab.tab_id().to_string(),
+                new_full_name,
+            ])
+            .run()
+            .context("Failed to rename zellij tab")?;
+        Ok(())
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         // Try to find the tab ID for more reliable closing (zellij PR #4695)
         let tabs = Self::list_tabs()?;
@@ -417,11 +1326,11 @@ impl Multiplexer for ZellijBackend {
     }
 
     fn select_window(&self, prefix: &str, name: &str) -> Result<()> {
-        let full_name = format!("{}{}", prefix, name);
+        let full_name = util::prefixed(prefix, name);
 
         // Try to find the tab by name and switch by ID (zellij PR #4695)
-        let tabs = Self::list_tabs()?;
-        if let Some(tab) = tabs.iter().find(|t| t.name == full_name) {
+        let snapshot = ZellijSnapshot::new(self);
+        if let Some(tab) = snapshot.tab_named(&full_name)? {
             let tab_id = tab.tab_id().to_string();
             Cmd::new("zellij")
                 .args(&["action", "go-to-tab-by-id", &tab_id])
@@ -442,7 +1351,7 @@ impl Multiplexer for ZellijBackend {
     }
 
     fn window_exists(&self, prefix: &str, name: &str) -> Result<bool> {
-        let full_name = format!("{}{}", prefix, name);
+        let full_name = util::prefixed(prefix, name);
         self.window_exists_by_full_name(&full_name)
     }
 
@@ -456,7 +1365,11 @@ impl Multiplexer for ZellijBackend {
     }
 
     fn current_window_name(&self) -> Result<Option<String>> {
-        Ok(Self::focused_tab_name())
+        Ok(self.focused_tab_name())
+    }
+
+    fn current_session(&self) -> Option<String> {
+        Self::session_name()
     }
 
     fn get_all_window_names(&self) -> Result<HashSet<String>> {
@@ -475,101 +1388,42 @@ impl Multiplexer for ZellijBackend {
             .iter()
             .filter(|w| active.contains(*w))
             .cloned()
-            .collect())
-    }
-
-    fn find_last_window_with_prefix(&self, _prefix: &str) -> Result<Option<String>> {
-        // Zellij doesn't support window ordering
-        Ok(None)
-    }
-
-    fn find_last_window_with_base_handle(
-        &self,
-        _prefix: &str,
-        _base_handle: &str,
-    ) -> Result<Option<String>> {
-        Ok(None)
-    }
-
-    fn wait_until_windows_closed(&self, full_window_names: &[String]) -> Result<()> {
-        use std::thread;
-
-        loop {
-            let active = self.get_all_window_names()?;
-            if full_window_names.iter().all(|w| !active.contains(w)) {
-                return Ok(());
-            }
-            thread::sleep(Duration::from_millis(100));
-        }
-    }
-
-    // === Pane Management ===
-
-    fn select_pane(&self, pane_id: &str) -> Result<()> {
-        // Zellij doesn't have a focus-pane-by-id action, so we need to navigate
-        // using focus-next-pane or focus-previous-pane
-
-        // Extract numeric ID from pane_id
-        let target_id: u32 =
-            parse_pane_id(pane_id).ok_or_else(|| anyhow!("Invalid pane_id: {}", pane_id))?;
-
-        // Get focused tab name to filter panes
-        let focused_tab =
-            Self::focused_tab_name().ok_or_else(|| anyhow!("Could not determine focused tab"))?;
-
-        // Get all panes in the current tab
-        let all_panes = Self::list_panes()?;
-        let tab_panes: Vec<_> = all_panes
-            .iter()
-            .filter(|p| !p.is_plugin && p.tab_name == focused_tab)
-            .collect();
-
-        // Find current and target indices
-        let current_idx = tab_panes
-            .iter()
-            .position(|p| p.is_focused)
-            .ok_or_else(|| anyhow!("No focused pane found in current tab"))?;
-
-        let target_idx = tab_panes
-            .iter()
-            .position(|p| p.id == target_id)
-            .ok_or_else(|| anyhow!("Target pane {} not found in current tab", pane_id))?;
-
-        if current_idx == target_idx {
-            // Already focused
-            return Ok(());
-        }
-
-        // Navigate to target pane
-        if target_idx < current_idx {
-            // Navigate backwards
-            let steps = current_idx - target_idx;
-            debug!(
-                current_idx,
-                target_idx, steps, "Navigating backwards to focused pane"
-            );
-            for _ in 0..steps {
-                Cmd::new("zellij")
-                    .args(&["action", "focus-previous-pane"])
-                    .run()
-                    .context("Failed to navigate to previous pane")?;
-            }
-        } else {
-            // Navigate forwards
-            let steps = target_idx - current_idx;
-            debug!(
-                current_idx,
-                target_idx, steps, "Navigating forwards to focused pane"
-            );
-            for _ in 0..steps {
-                Cmd::new("zellij")
-                    .args(&["action", "focus-next-pane"])
-                    .run()
-                    .context("Failed to navigate to next pane")?;
+            .collect())
+    }
+
+    fn find_last_window_with_prefix(&self, prefix: &str) -> Result<Option<String>> {
+        let tabs = Self::list_tabs().unwrap_or_default();
+        Ok(last_tab_id(&tabs, |name| name.starts_with(prefix)))
+    }
+
+    fn find_last_window_with_base_handle(
+        &self,
+        prefix: &str,
+        base_handle: &str,
+    ) -> Result<Option<String>> {
+        let full_base = util::prefixed(prefix, base_handle);
+        let tabs = Self::list_tabs().unwrap_or_default();
+        Ok(last_tab_id(&tabs, |name| {
+            util::is_base_handle_window(name, &full_base)
+        }))
+    }
+
+    fn wait_until_windows_closed(&self, full_window_names: &[String]) -> Result<()> {
+        use std::thread;
+
+        loop {
+            let active = self.get_all_window_names()?;
+            if full_window_names.iter().all(|w| !active.contains(w)) {
+                return Ok(());
             }
+            thread::sleep(Duration::from_millis(100));
         }
+    }
 
-        Ok(())
+    // === Pane Management ===
+
+    fn select_pane(&self, pane_id: &str) -> Result<()> {
+        self.select_pane_in(&ZellijSnapshot::new(self), pane_id)
     }
 
     fn switch_to_pane(&self, pane_id: &str, window_hint: Option<&str>) -> Result<()> {
@@ -584,8 +1438,8 @@ impl Multiplexer for ZellijBackend {
         debug!(pane_id, tab_name, "switch_to_pane: switching to tab");
 
         // Try to switch by tab ID for more reliability
-        let tabs = Self::list_tabs()?;
-        if let Some(tab) = tabs.iter().find(|t| t.name == tab_name) {
+        let snapshot = ZellijSnapshot::new(self);
+        if let Some(tab) = snapshot.tab_named(tab_name)? {
             let tab_id = tab.tab_id().to_string();
             Cmd::new("zellij")
                 .args(&["action", "go-to-tab-by-id", &tab_id])
@@ -603,10 +1457,13 @@ impl Multiplexer for ZellijBackend {
     }
 
     fn respawn_pane(&self, pane_id: &str, cwd: &Path, cmd: Option<&str>) -> Result<String> {
+        let session = self.target_session();
         debug!(pane_id, "respawn_pane: starting");
 
         // Verify the pane exists - if list-panes returns it, it's ready for --pane-id targeting
-        let panes = Self::list_panes().context("Failed to list panes in respawn_pane")?;
+        let panes = self
+            .list_panes()
+            .context("Failed to list panes in respawn_pane")?;
         let numeric_id: u32 =
             parse_pane_id(pane_id).ok_or_else(|| anyhow!("Invalid pane_id format: {}", pane_id))?;
 
@@ -639,10 +1496,10 @@ impl Multiplexer for ZellijBackend {
             format!("cd '{}'", cwd_str.replace('\'', "'\\''"))
         };
 
-        Cmd::new("zellij")
+        zellij_cmd(session.as_deref())
             .args(&["action", "write-chars", "--pane-id", pane_id, &combined])
             .run()?;
-        Cmd::new("zellij")
+        zellij_cmd(session.as_deref())
             .args(&["action", "write", "--pane-id", pane_id, "13"])
             .run()?;
 
@@ -650,131 +1507,108 @@ impl Multiplexer for ZellijBackend {
         Ok(pane_id.to_string())
     }
 
-    fn capture_pane(&self, _pane_id: &str, _lines: u16) -> Option<String> {
-        // Zellij limitation: dump-screen always captures the focused pane,
-        // not the pane specified by pane_id. When the dashboard is focused,
-        // it captures itself, creating a recursive loop. We detect this and
-        // return None to prevent the recursion.
-
-        // Use PID + thread ID + timestamp for thread-safe temp file naming
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        let thread_id = std::thread::current().id();
-        let temp_path = std::env::temp_dir().join(format!(
-            "zellij_capture_{}_{:?}_{}",
-            std::process::id(),
-            thread_id,
-            timestamp
-        ));
-        let temp_str = temp_path.to_string_lossy();
-
-        if Cmd::new("zellij")
-            .args(&["action", "dump-screen", &temp_str])
-            .run()
-            .is_ok()
+    fn capture_pane(&self, pane_id: &str, lines: u16) -> Option<String> {
+        let now = Instant::now();
+        let key = (pane_id.to_string(), lines);
+        if let Ok(cache) = self.preview_cache.lock()
+            && let Some(cached) = cache.get(&key)
+            && cached.is_fresh(now, self.preview_cache_ttl)
         {
-            if let Ok(content) = std::fs::read_to_string(&temp_path) {
-                let _ = std::fs::remove_file(&temp_path);
-                return Some(content);
-            }
-            let _ = std::fs::remove_file(&temp_path);
+            return cached.content.clone();
+        }
+
+        // Older zellij versions ignore --pane-id and capture the focused pane.
+        // When the dashboard is focused it would capture itself, creating a
+        // recursive preview, so drop captures that look like the dashboard.
+        let content = self
+            .dump_screen(pane_id, lines)
+            .filter(|c| !util::contains_dashboard_ui(c));
+
+        if let Ok(mut cache) = self.preview_cache.lock() {
+            cache.insert(
+                key,
+                CachedCapture {
+                    captured_at: now,
+                    content: content.clone(),
+                },
+            );
         }
 
-        None
+        content
     }
 
     // === Text I/O ===
 
     fn send_keys(&self, pane_id: &str, command: &str) -> Result<()> {
+        let session = self.target_session();
         // Use --pane-id for reliable pane targeting (zellij PR #4691)
-        Cmd::new("zellij")
+        zellij_cmd(session.as_deref())
             .args(&["action", "write-chars", "--pane-id", pane_id, command])
             .run()
             .context("Failed to send keys")?;
 
         // Send Enter (ASCII 13)
-        Cmd::new("zellij")
+        zellij_cmd(session.as_deref())
             .args(&["action", "write", "--pane-id", pane_id, "13"])
             .run()
             .context("Failed to send Enter")?;
         Ok(())
     }
 
-    fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
-        use super::agent;
-
-        let profile = agent::resolve_profile(agent);
-
-        if profile.needs_bang_delay() && command.starts_with('!') {
-            // Send ! first, wait, then rest of command
-            Cmd::new("zellij")
-                .args(&["action", "write-chars", "--pane-id", pane_id, "!"])
-                .run()?;
-
-            std::thread::sleep(std::time::Duration::from_millis(50));
-
-            Cmd::new("zellij")
-                .args(&["action", "write-chars", "--pane-id", pane_id, &command[1..]])
-                .run()?;
-
-            Cmd::new("zellij")
-                .args(&["action", "write", "--pane-id", pane_id, "13"])
-                .run()?;
-
-            Ok(())
-        } else {
-            self.send_keys(pane_id, command)
-        }
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        let session = self.target_session();
+        zellij_cmd(session.as_deref())
+            .args(&["action", "write-chars", "--pane-id", pane_id, text])
+            .run()
+            .context("Failed to send text")?;
+        Ok(())
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
-        // Map common key names to ASCII codes
-        let code = match key {
-            "Enter" => "13",
-            "Escape" => "27",
-            "Tab" => "9",
-            _ => {
-                // For single chars, use write-chars with pane targeting
-                Cmd::new("zellij")
-                    .args(&["action", "write-chars", "--pane-id", pane_id, key])
-                    .run()
-                    .context("Failed to send key")?;
-                return Ok(());
-            }
+        let session = self.target_session();
+        let Some(codes) = key_codes(key) else {
+            // For single chars, use write-chars with pane targeting
+            zellij_cmd(session.as_deref())
+                .args(&["action", "write-chars", "--pane-id", pane_id, key])
+                .run()
+                .context("Failed to send key")?;
+            return Ok(());
         };
 
-        Cmd::new("zellij")
-            .args(&["action", "write", "--pane-id", pane_id, code])
+        let mut args = vec!["action", "write", "--pane-id", pane_id];
+        args.extend(codes.iter().map(String::as_str));
+        zellij_cmd(session.as_deref())
+            .args(&args)
             .run()
             .context("Failed to send key")?;
         Ok(())
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
-        // Send line by line with pane targeting
-        for line in content.lines() {
-            Cmd::new("zellij")
-                .args(&["action", "write-chars", "--pane-id", pane_id, line])
-                .run()?;
-            Cmd::new("zellij")
-                .args(&["action", "write", "--pane-id", pane_id, "13"])
+    fn paste_multiline(&self, pane_id: &str, content: &str, bracketed: bool) -> Result<()> {
+        let session = self.target_session();
+        for action in paste_actions(content, bracketed) {
+            let Some((name, values)) = action.split_first() else {
+                continue;
+            };
+            zellij_cmd(session.as_deref())
+                .args(&["action", name, "--pane-id", pane_id])
+                .args(&values.iter().map(String::as_str).collect::<Vec<_>>())
                 .run()?;
         }
         Ok(())
     }
 
     fn clear_pane(&self, pane_id: &str) -> Result<()> {
+        let session = self.target_session();
         // Clear the pane to hide handshake setup commands
         // Try with --pane-id first, fall back to focused pane if not supported
-        let result = Cmd::new("zellij")
+        let result = zellij_cmd(session.as_deref())
             .args(&["action", "clear", "--pane-id", pane_id])
             .run();
 
         if result.is_err() {
             // Fallback for older zellij versions without --pane-id support for clear
-            Cmd::new("zellij")
+            zellij_cmd(session.as_deref())
                 .args(&["action", "clear"])
                 .run()
                 .context("Failed to clear pane")?;
@@ -795,15 +1629,42 @@ impl Multiplexer for ZellijBackend {
 
     // === Status ===
 
-    fn set_status(&self, _pane_id: &str, _icon: &str, _auto_clear_on_focus: bool) -> Result<()> {
-        // No-op: can't target specific panes, and rename-pane would hijack
-        // the user's focused pane. Status is tracked in StateStore by tab name.
+    fn set_status(&self, pane_id: &str, icon: &str, _auto_clear_on_focus: bool) -> Result<()> {
+        // Without `rename-pane --pane-id`, renaming would hit the user's
+        // focused pane instead; status is still tracked in StateStore.
+        if !self.supports_rename_by_id() {
+            return Ok(());
+        }
+        let id = parse_pane_id(pane_id).ok_or_else(|| anyhow!("Invalid pane_id: {}", pane_id))?;
+        let Some(title) = self.pane_title(id)? else {
+            return Ok(());
+        };
+        let new_title = status_title(&title, icon, &self.status_icons);
+        if new_title != title {
+            self.rename_pane(id, &new_title)?;
+        }
         Ok(())
     }
 
-    fn clear_status(&self, _pane_id: &str) -> Result<()> {
-        // No-op: status is managed by StateStore
-        Ok(())
+    fn clear_status(&self, pane_id: &str) -> Result<()> {
+        if !self.supports_rename_by_id() {
+            return Ok(());
+        }
+        let id = parse_pane_id(pane_id).ok_or_else(|| anyhow!("Invalid pane_id: {}", pane_id))?;
+        let Some(title) = self.pane_title(id)? else {
+            return Ok(());
+        };
+        let base = strip_status_icon(&title, &self.status_icons);
+        if base == title {
+            return Ok(());
+        }
+        // Undoing the rename hands the title back to the pane's program;
+        // renaming to the stripped title would freeze it.
+        if self.supports_undo_rename_by_id() {
+            self.undo_rename_pane(id)
+        } else {
+            self.rename_pane(id, base)
+        }
     }
 
     fn ensure_status_format(&self, _pane_id: &str) -> Result<()> {
@@ -816,12 +1677,19 @@ impl Multiplexer for ZellijBackend {
     // Use default implementation from trait - no need for Zellij-specific workarounds
     // now that pane targeting is reliable with --pane-id (zellij PR #4691)
 
+    /// Pane IDs of the layout the last `create_window` built, taken once.
+    fn take_layout_panes(&self) -> Option<Vec<String>> {
+        self.layout_panes.lock().ok()?.take()
+    }
+
     /// Split a pane in Zellij.
     ///
+    /// Zellij's `new-pane` always splits the focused pane, so the target pane is
+    /// focused first and the prior focus restored afterwards.
+    ///
     /// **Zellij CLI Limitations:**
-    /// - `target_pane_id` is ignored - Zellij's `new-pane` command doesn't support
-    ///   targeting specific panes for splitting (always splits the focused pane).
-    /// - `size`/`percentage` are ignored - all splits are 50/50.
+    /// - `size`/`percentage` are ignored - all splits are 50/50. Sized splits
+    ///   set up with a new tab come from [`split_layout_kdl`] instead.
     ///
     /// **Returns:** The pane ID from `new-pane` stdout (e.g., "terminal_5").
     fn split_pane(
@@ -833,10 +1701,21 @@ impl Multiplexer for ZellijBackend {
         _percentage: Option<u8>,
         command: Option<&str>,
     ) -> Result<String> {
-        debug!(
-            "split_pane: target_pane_id '{}' (note: new-pane splits focused pane only)",
-            target_pane_id
-        );
+        let session = self.target_session();
+
+        // new-pane splits the focused pane, so move focus to the target first
+        let restore_focus =
+            match self.focus_split_target(&ZellijSnapshot::new(self), target_pane_id) {
+                Ok(previous) => previous,
+                Err(e) => {
+                    warn!(
+                        target_pane_id,
+                        error = %e,
+                        "split_pane: could not focus target, splitting focused pane"
+                    );
+                    None
+                }
+            };
 
         let dir_arg = match direction {
             SplitDirection::Horizontal => "right", // panes side-by-side (left/right)
@@ -847,7 +1726,7 @@ impl Multiplexer for ZellijBackend {
             .to_str()
             .ok_or_else(|| anyhow!("Path contains non-UTF8 characters"))?;
 
-        let mut cmd = Cmd::new("zellij").args(&[
+        let mut cmd = zellij_cmd(session.as_deref()).args(&[
             "action",
             "new-pane",
             "--direction",
@@ -866,13 +1745,19 @@ impl Multiplexer for ZellijBackend {
             .run_and_capture_stdout()
             .context("Failed to split pane")?;
 
+        if let Some(previous) = restore_focus
+            && let Err(e) = self.select_pane(&format!("terminal_{}", previous))
+        {
+            debug!(previous, error = %e, "split_pane: failed to restore focus");
+        }
+
         Ok(pane_id.trim().to_string())
     }
 
     // === State Reconciliation ===
 
     fn get_live_pane_info(&self, pane_id: &str) -> Result<Option<LivePaneInfo>> {
-        let panes = Self::list_panes()?;
+        let panes = self.list_panes()?;
 
         // Extract numeric ID from "terminal_X"
         let numeric_id: u32 =
@@ -902,23 +1787,61 @@ impl Multiplexer for ZellijBackend {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
         Ok(Some(LivePaneInfo {
-            pid: None, // Zellij doesn't expose PID
+            pid: self.pane_pids().get(&numeric_id).copied(),
             current_command,
             working_dir,
-            title: Some(pane.title.clone()).filter(|t| !t.is_empty()),
+            title: Some(strip_status_icon(&pane.title, &self.status_icons).to_string())
+                .filter(|t| !t.is_empty()),
             session: Self::session_name(),
             window: Some(pane.tab_name.clone()).filter(|t| !t.is_empty()),
         }))
     }
 
-    fn validate_agent_alive(&self, state: &crate::state::AgentState) -> Result<bool> {
-        // Check if pane exists
-        let pane_info = self.get_live_pane_info(&state.pane_key.pane_id)?;
+    fn validate_agent_alive(
+        &self,
+        state: &crate::state::AgentState,
+        live_panes: Option<&std::collections::HashMap<String, LivePaneInfo>>,
+    ) -> Result<bool> {
+        // Check if pane exists. A batched listing comes from the same
+        // `list-panes` call, so reuse it rather than spawning zellij per agent.
+        let pane_info = match live_panes {
+            Some(panes) => panes.get(&state.pane_key.pane_id).cloned(),
+            None => self.get_live_pane_info(&state.pane_key.pane_id)?,
+        };
         let pane_info = match pane_info {
             Some(info) => info,
             None => return Ok(false), // Pane doesn't exist
         };
 
+        // Fast path: sandboxed agents send heartbeats over RPC while running
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if state.has_fresh_heartbeat(now, self.heartbeat_interval) {
+            return Ok(true);
+        }
+
+        // When a PID was recorded, it must still match the pane and be alive.
+        // A stored PID of 0 means it was unknown when the agent was registered.
+        if state.pane_pid != 0 {
+            if pane_info.pid.is_some_and(|pid| pid != state.pane_pid) {
+                debug!(
+                    stored_pid = state.pane_pid,
+                    live_pid = ?pane_info.pid,
+                    "Agent validation: PID mismatch (pane ID recycled)"
+                );
+                return Ok(false);
+            }
+            if !process_alive(state.pane_pid) {
+                debug!(
+                    pid = state.pane_pid,
+                    "Agent validation: process no longer alive"
+                );
+                return Ok(false);
+            }
+        }
+
         // Secondary validation: Check if command matches stored command
         // This detects if the agent process was killed and replaced with something else
         if let Some(ref live_command) = pane_info.current_command
@@ -951,7 +1874,8 @@ impl Multiplexer for ZellijBackend {
         let mut result = HashMap::new();
 
         // Use list-panes to get all panes (not just focused ones)
-        let panes = Self::list_panes()?;
+        let panes = self.list_panes()?;
+        let pane_pids = self.pane_pids();
 
         for pane in panes {
             // Skip plugin panes, only include terminal panes
@@ -981,10 +1905,11 @@ impl Multiplexer for ZellijBackend {
             result.insert(
                 pane_id,
                 LivePaneInfo {
-                    pid: None, // Zellij doesn't expose PID
+                    pid: pane_pids.get(&pane.id).copied(),
                     current_command,
                     working_dir,
-                    title: Some(pane.title.clone()).filter(|t| !t.is_empty()),
+                    title: Some(strip_status_icon(&pane.title, &self.status_icons).to_string())
+                        .filter(|t| !t.is_empty()),
                     session: Self::session_name(),
                     window: Some(pane.tab_name.clone()).filter(|t| !t.is_empty()),
                 },
@@ -999,6 +1924,74 @@ impl Multiplexer for ZellijBackend {
 mod tests {
     use super::*;
 
+    fn split(
+        direction: SplitDirection,
+        percentage: Option<u8>,
+        target: Option<usize>,
+    ) -> PaneConfig {
+        PaneConfig {
+            split: Some(direction),
+            percentage,
+            target,
+            ..PaneConfig::default()
+        }
+    }
+
+    #[test]
+    fn split_layout_kdl_only_for_sized_splits() {
+        let even = [
+            PaneConfig::default(),
+            split(SplitDirection::Horizontal, None, None),
+        ];
+        assert_eq!(split_layout_kdl(&even), None);
+    }
+
+    #[test]
+    fn split_layout_kdl_nests_splits_of_earlier_panes() {
+        // Agent left 60%, then the right pane split into top and bottom
+        let panes = [
+            PaneConfig::default(),
+            split(SplitDirection::Horizontal, Some(40), None),
+            split(SplitDirection::Vertical, None, Some(1)),
+        ];
+        let kdl = split_layout_kdl(&panes).unwrap();
+        let body = &kdl[kdl.find("\n    pane split_direction").unwrap() + 1..];
+        assert_eq!(
+            body,
+            "    pane split_direction=\"vertical\" {\n\
+             \x20       pane name=\"workmux-pane-0\"\n\
+             \x20       pane split_direction=\"horizontal\" size=\"40%\" {\n\
+             \x20           pane name=\"workmux-pane-1\"\n\
+             \x20           pane name=\"workmux-pane-2\"\n\
+             \x20       }\n\
+             \x20   }\n\
+             }\n"
+        );
+        assert!(kdl.contains("zellij:tab-bar"));
+    }
+
+    #[test]
+    fn split_layout_kdl_rejects_unknown_target() {
+        let panes = [
+            PaneConfig::default(),
+            split(SplitDirection::Vertical, Some(30), Some(5)),
+        ];
+        assert_eq!(split_layout_kdl(&panes), None);
+    }
+
+    #[test]
+    fn key_codes_for_submit_keys() {
+        assert_eq!(key_codes("Enter").unwrap(), vec!["13"]);
+        assert_eq!(key_codes("Escape").unwrap(), vec!["27"]);
+        assert_eq!(key_codes("Tab").unwrap(), vec!["9"]);
+        assert_eq!(key_codes("M-Enter").unwrap(), vec!["27", "13"]);
+        assert_eq!(
+            key_codes("C-Enter").unwrap(),
+            vec!["27", "91", "49", "51", "59", "53", "117"]
+        );
+        assert_eq!(key_codes("y"), None);
+    }
+
     // === parse_pane_id ===
 
     #[test]
@@ -1179,6 +2172,47 @@ mod tests {
         assert!(panes[1].is_plugin);
     }
 
+    // === Tab ordering ===
+
+    fn tabs(entries: &[(u32, u32, &str)]) -> Vec<TabInfo> {
+        entries
+            .iter()
+            .map(|&(tab_id, position, name)| TabInfo {
+                tab_id,
+                position,
+                name: name.to_string(),
+                active: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn last_tab_id_uses_position_not_list_order() {
+        let tabs = tabs(&[(4, 3, "wm-b"), (9, 1, "wm-a"), (2, 0, "shell")]);
+        assert_eq!(
+            last_tab_id(&tabs, |n| n.starts_with("wm-")),
+            Some("4".to_string())
+        );
+        assert_eq!(
+            last_tab_id(&tabs, |n| util::is_base_handle_window(n, "wm-a")),
+            Some("9".to_string())
+        );
+        assert_eq!(last_tab_id(&tabs, |n| n.starts_with("x")), None);
+    }
+
+    #[test]
+    fn moves_to_follow_counts_left_moves() {
+        let tabs = tabs(&[
+            (0, 0, "shell"),
+            (1, 1, "wm-a"),
+            (2, 2, "other"),
+            (5, 3, "wm-a-2"),
+        ]);
+        assert_eq!(moves_to_follow(&tabs, 5, 1), 1);
+        assert_eq!(moves_to_follow(&tabs, 2, 1), 0);
+        assert_eq!(moves_to_follow(&tabs, 5, 42), 0);
+    }
+
     // === TabInfo deserialization ===
 
     #[test]
@@ -1213,4 +2247,317 @@ mod tests {
         assert_eq!(tabs[1].name, "my-feature");
         assert!(!tabs[1].active);
     }
+
+    // === tail_lines ===
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a\nb", 10), "a\nb");
+        assert_eq!(tail_lines("", 5), "");
+    }
+
+    #[test]
+    fn test_read_tail_within_budget_reads_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump");
+        std::fs::write(&path, "line 1\nline 2\nline 3\n").unwrap();
+        assert_eq!(read_tail(&path, 1024).unwrap(), "line 1\nline 2\nline 3\n");
+    }
+
+    #[test]
+    fn test_read_tail_drops_partial_first_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump");
+        let fixture: String = (1..=100)
+            .map(|i| format!("build step {:03}\n", i))
+            .collect();
+        std::fs::write(&path, &fixture).unwrap();
+
+        // Each line is 15 bytes; 40 bytes starts mid-way through line 98
+        let tail = read_tail(&path, 40).unwrap();
+        assert_eq!(tail, "build step 099\nbuild step 100\n");
+        assert_eq!(tail_lines(&tail, 1), "build step 100");
+    }
+
+    // === CachedCapture ===
+
+    #[test]
+    fn test_cached_capture_freshness() {
+        let captured_at = Instant::now();
+        let cached = CachedCapture {
+            captured_at,
+            content: None,
+        };
+        let ttl = Duration::from_millis(500);
+        assert!(cached.is_fresh(captured_at, ttl));
+        assert!(cached.is_fresh(captured_at + Duration::from_millis(499), ttl));
+        assert!(!cached.is_fresh(captured_at + ttl, ttl));
+    }
+
+    // === help_lists_action ===
+
+    #[test]
+    fn test_help_lists_action() {
+        let help = "Usage: zellij action <COMMAND>\n\nCommands:\n  focus-next-pane       Change focus to the next pane\n  focus-pane-with-id    Focus a pane by its id\n  help                  Print this message\n";
+        assert!(help_lists_action(help, "focus-pane-with-id"));
+        assert!(help_lists_action(help, "focus-next-pane"));
+    }
+
+    #[test]
+    fn test_help_lists_flag() {
+        let help = "Usage: zellij action rename-pane [OPTIONS] <NAME>\n\nOptions:\n  -p, --pane-id <PANE_ID>  Target pane\n  -h, --help";
+        assert!(help_lists_flag(help, "--pane-id"));
+        assert!(!help_lists_flag(
+            "Usage: zellij action rename-pane <NAME>",
+            "--pane-id"
+        ));
+        assert!(!help_lists_flag("  --pane-ids <IDS>", "--pane-id"));
+    }
+
+    #[test]
+    fn test_status_title_prefixes_icon() {
+        let icons = vec!["🤖".to_string(), "💬".to_string(), "✅".to_string()];
+        assert_eq!(status_title("claude", "🤖", &icons), "🤖 claude");
+        // Replaces a previous status rather than stacking icons
+        assert_eq!(status_title("🤖 claude", "💬", &icons), "💬 claude");
+        assert_eq!(status_title("🤖", "✅", &icons), "✅");
+        assert_eq!(status_title("", "🤖", &icons), "🤖");
+    }
+
+    #[test]
+    fn test_strip_status_icon() {
+        let icons = vec!["🤖".to_string(), "💬".to_string(), String::new()];
+        assert_eq!(strip_status_icon("💬 claude", &icons), "claude");
+        assert_eq!(strip_status_icon("claude", &icons), "claude");
+        assert_eq!(strip_status_icon("🤖", &icons), "");
+    }
+
+    #[test]
+    fn test_help_lists_action_missing() {
+        let help = "Commands:\n  focus-next-pane       Change focus to the next pane\n  focus-previous-pane   Change focus to the previous pane\n";
+        assert!(!help_lists_action(help, "focus-pane-with-id"));
+        // Prefix of a listed action does not count
+        assert!(!help_lists_action(help, "focus-next"));
+    }
+
+    // === paste_actions ===
+
+    fn actions(content: &str, bracketed: bool) -> Vec<String> {
+        paste_actions(content, bracketed)
+            .into_iter()
+            .map(|action| action.join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn test_paste_actions_bracketed_whole_buffer() {
+        assert_eq!(
+            actions("first\n\nsecond\n", true),
+            vec![
+                "write 27 91 50 48 48 126",
+                "write-chars first\n\nsecond",
+                "write 27 91 50 48 49 126",
+                "write 13",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paste_actions_crlf_and_trailing_newlines() {
+        assert_eq!(actions("a\r\nb\r\n\r\n", true)[1], "write-chars a\nb");
+        assert_eq!(
+            actions("a\r\n\r\nb\r\n", false),
+            vec![
+                "write-chars a",
+                "write 10",
+                "write 10",
+                "write-chars b",
+                "write 13"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paste_actions_line_by_line_submits_once() {
+        let actions = actions("one\ntwo\n\n", false);
+        assert_eq!(
+            actions,
+            vec!["write-chars one", "write 10", "write-chars two", "write 13"]
+        );
+        assert_eq!(actions.iter().filter(|a| *a == "write 13").count(), 1);
+    }
+
+    // === pane_cwd ===
+
+    fn panes_json(json: &str) -> Vec<PaneInfo> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_pane_cwd_uses_pane_id_not_focus() {
+        // A background pane resolves to its own cwd, not the focused pane's
+        let panes = panes_json(
+            r#"[
+                {"id": 0, "is_plugin": false, "is_focused": true, "terminal_command": null,
+                 "pane_cwd": "/repo", "tab_name": "main"},
+                {"id": 3, "is_plugin": true, "is_focused": false, "terminal_command": null,
+                 "tab_name": "feature"},
+                {"id": 3, "is_plugin": false, "is_focused": false, "terminal_command": null,
+                 "pane_cwd": "/repo__worktrees/feature", "tab_name": "feature"}
+            ]"#,
+        );
+        assert_eq!(
+            pane_cwd(&panes, 3).unwrap(),
+            PathBuf::from("/repo__worktrees/feature")
+        );
+        assert_eq!(pane_cwd(&panes, 0).unwrap(), PathBuf::from("/repo"));
+    }
+
+    #[test]
+    fn test_pane_cwd_missing_cwd_errors() {
+        let panes = panes_json(
+            r#"[{"id": 7, "is_plugin": false, "is_focused": true, "terminal_command": null}]"#,
+        );
+        let err = pane_cwd(&panes, 7).unwrap_err();
+        assert!(err.to_string().contains("no working directory"));
+    }
+
+    #[test]
+    fn test_pane_cwd_unknown_pane_errors() {
+        let panes = panes_json(
+            r#"[{"id": 1, "is_plugin": false, "is_focused": false, "terminal_command": null,
+                 "pane_cwd": "/work", "tab_name": "t"}]"#,
+        );
+        assert!(pane_cwd(&panes, 2).is_err());
+    }
+
+    // === ZellijSnapshot ===
+
+    #[test]
+    fn snapshot_reuses_fetched_state() {
+        // Pre-filled cells are served without spawning zellij
+        let backend = ZellijBackend::new(&Config::default());
+        let snapshot = ZellijSnapshot::new(&backend);
+        let _ = snapshot.panes.set(panes_json(
+            r#"[
+                {"id": 1, "is_plugin": false, "is_focused": true, "terminal_command": null,
+                 "pane_cwd": "/a", "tab_name": "one"},
+                {"id": 2, "is_plugin": false, "is_focused": true, "terminal_command": null,
+                 "pane_cwd": "/b", "tab_name": "two"}
+            ]"#,
+        ));
+        let _ = snapshot.tabs.set(
+            serde_json::from_str(
+                r#"[{"tab_id": 7, "position": 0, "name": "one", "active": false},
+                    {"tab_id": 9, "position": 1, "name": "two", "active": true}]"#,
+            )
+            .unwrap(),
+        );
+        let _ = snapshot.focused_tab.set(Some("two".to_string()));
+
+        assert_eq!(snapshot.focused_pane().unwrap().id, 2);
+        assert_eq!(snapshot.tab_named("one").unwrap().unwrap().tab_id(), 7);
+        assert!(snapshot.tab_named("missing").unwrap().is_none());
+    }
+
+    // === focus_to_restore ===
+
+    #[test]
+    fn test_focus_to_restore_skips_when_target_focused() {
+        let panes = panes_json(
+            r#"[
+                {"id": 1, "is_plugin": false, "is_focused": true, "terminal_command": null, "tab_name": "wm"},
+                {"id": 2, "is_plugin": false, "is_focused": false, "terminal_command": null, "tab_name": "wm"}
+            ]"#,
+        );
+        assert_eq!(focus_to_restore(&panes, Some("wm"), 1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_focus_to_restore_returns_prior_focus() {
+        let panes = panes_json(
+            r#"[
+                {"id": 1, "is_plugin": false, "is_focused": true, "terminal_command": null, "tab_name": "wm"},
+                {"id": 2, "is_plugin": false, "is_focused": false, "terminal_command": null, "tab_name": "wm"}
+            ]"#,
+        );
+        assert_eq!(focus_to_restore(&panes, Some("wm"), 2).unwrap(), Some(1));
+    }
+
+    // === parse_session_names ===
+
+    #[test]
+    fn test_parse_session_names() {
+        let output = "wm-feature [Created 2m ago] (current)\n\
+                      wm-bugfix [Created 1h ago]\n\
+                      old-session [Created 3d ago] (EXITED - attach to resurrect)\n";
+        let names = parse_session_names(output);
+        assert_eq!(names.len(), 2);
+        assert!(names.contains("wm-feature"));
+        assert!(names.contains("wm-bugfix"));
+        assert!(!names.contains("old-session"));
+    }
+
+    #[test]
+    fn test_parse_session_names_empty() {
+        assert!(parse_session_names("").is_empty());
+    }
+
+    // === PID resolution ===
+
+    #[test]
+    fn test_parse_pane_from_environ() {
+        let environ = b"HOME=/home/u\0ZELLIJ_PANE_ID=5\0ZELLIJ_SESSION_NAME=main\0";
+        assert_eq!(parse_pane_from_environ(environ, "main"), Some(5));
+        assert_eq!(parse_pane_from_environ(environ, "other"), None);
+        assert_eq!(parse_pane_from_environ(b"HOME=/home/u\0", "main"), None);
+    }
+
+    #[test]
+    fn test_parse_stat_ppid() {
+        assert_eq!(parse_stat_ppid("1234 (zsh) S 1200 1234 1234"), Some(1200));
+        // Command names may contain spaces and parentheses
+        assert_eq!(parse_stat_ppid("99 (my (odd) cmd) R 42 99 99"), Some(42));
+        assert_eq!(parse_stat_ppid("garbage"), None);
+    }
+
+    #[test]
+    fn test_pane_root_pids() {
+        let procs = vec![
+            // Pane 1: shell (parent is the zellij server) running node
+            PaneProcess {
+                pid: 100,
+                ppid: 10,
+                pane: 1,
+            },
+            PaneProcess {
+                pid: 150,
+                ppid: 100,
+                pane: 1,
+            },
+            // Pane 2: shell only
+            PaneProcess {
+                pid: 200,
+                ppid: 10,
+                pane: 2,
+            },
+            // Pane 2: daemonized child reparented to init
+            PaneProcess {
+                pid: 250,
+                ppid: 1,
+                pane: 2,
+            },
+        ];
+        let roots = pane_root_pids(&procs);
+        assert_eq!(roots.get(&1), Some(&100));
+        assert_eq!(roots.get(&2), Some(&200));
+        assert_eq!(roots.len(), 2);
+    }
+
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+        assert!(!process_alive(u32::MAX));
+    }
 }
@@ -56,6 +56,125 @@ fn validate_handle(handle: &str) -> Result<()> {
     Ok(())
 }
 
+/// Placeholders accepted in `window_name_template`.
+const WINDOW_NAME_PLACEHOLDERS: &[&str] = &["prefix", "handle", "branch", "pr"];
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into literal text and `{placeholder}` segments.
+fn parse_template(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let Some(len) = rest[start..].find('}') else {
+            bail!("window_name_template has an unclosed '{{': {}", template);
+        };
+        let name = &rest[start + 1..start + len];
+        if !WINDOW_NAME_PLACEHOLDERS.contains(&name) {
+            bail!(
+                "window_name_template has unknown placeholder '{{{}}}' (expected one of {{prefix}}, {{handle}}, {{branch}}, {{pr}})",
+                name
+            );
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+/// Validates a `window_name_template`.
+///
+/// The template must start with `{prefix}` so workmux can recognise its own
+/// windows, and must contain `{handle}` so names stay unique per worktree.
+/// `:` and `.` are rejected because tmux reads them as target separators.
+pub fn validate_window_name_template(template: &str) -> Result<()> {
+    let segments = parse_template(template)?;
+    if !matches!(segments.first(), Some(Segment::Placeholder("prefix"))) {
+        bail!(
+            "window_name_template must start with {{prefix}}: {}",
+            template
+        );
+    }
+    if !segments
+        .iter()
+        .any(|s| matches!(s, Segment::Placeholder("handle")))
+    {
+        bail!("window_name_template must contain {{handle}}: {}", template);
+    }
+    if segments
+        .iter()
+        .any(|s| matches!(s, Segment::Text(text) if text.contains([':', '.'])))
+    {
+        bail!(
+            "window_name_template must not contain ':' or '.': {}",
+            template
+        );
+    }
+    Ok(())
+}
+
+/// Renders the full window (or session) name for a worktree.
+///
+/// Without a template this is `{prefix}{handle}`. `{pr}` is empty for
+/// worktrees not created with `--pr`, and one separator next to it is dropped
+/// so `{prefix}{pr}-{handle}` doesn't leave a stray `-`.
+pub fn render_window_name(
+    template: Option<&str>,
+    prefix: &str,
+    handle: &str,
+    branch: &str,
+    pr: Option<u32>,
+) -> String {
+    let Some(segments) = template.and_then(|t| parse_template(t).ok()) else {
+        return format!("{}{}", prefix, handle);
+    };
+
+    let is_separator = |c: char| matches!(c, '-' | '_' | '/' | ' ');
+    let mut name = String::new();
+    let mut drop_separator = false;
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => {
+                let text = if drop_separator {
+                    text.strip_prefix(is_separator).unwrap_or(text)
+                } else {
+                    text
+                };
+                name.push_str(text);
+                drop_separator = false;
+            }
+            Segment::Placeholder("pr") if pr.is_none() => {
+                if name.ends_with(is_separator) && name.len() > prefix.len() {
+                    name.pop();
+                } else {
+                    drop_separator = true;
+                }
+            }
+            Segment::Placeholder(field) => {
+                drop_separator = false;
+                match field {
+                    "prefix" => name.push_str(prefix),
+                    "handle" => name.push_str(handle),
+                    // Keep tmux target separators out of the name
+                    "branch" => name.push_str(&branch.replace([':', '.'], "-")),
+                    "pr" => name.push_str(&pr.map(|n| n.to_string()).unwrap_or_default()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,4 +375,49 @@ mod tests {
     fn worktree_naming_basename_simple_branch() {
         assert_eq!(WorktreeNaming::Basename.derive_name("main"), "main");
     }
+
+    #[test]
+    fn render_window_name_defaults_to_prefix_and_handle() {
+        assert_eq!(
+            render_window_name(None, "wm-", "feature", "feat/feature", None),
+            "wm-feature"
+        );
+    }
+
+    #[test]
+    fn render_window_name_fills_placeholders() {
+        let template = Some("{prefix}{pr}-{handle}");
+        assert_eq!(
+            render_window_name(template, "wm-", "fix", "fix", Some(42)),
+            "wm-42-fix"
+        );
+        assert_eq!(
+            render_window_name(template, "wm-", "fix", "fix", None),
+            "wm-fix"
+        );
+        assert_eq!(
+            render_window_name(Some("{prefix}{handle}-{pr}"), "wm-", "fix", "fix", None),
+            "wm-fix"
+        );
+        assert_eq!(
+            render_window_name(Some("{prefix}{branch}_{handle}"), "", "x", "feat/x", None),
+            "feat/x_x"
+        );
+        assert_eq!(
+            render_window_name(Some("{prefix}{branch}"), "wm-", "x", "release/1.2", None),
+            "wm-release/1-2"
+        );
+    }
+
+    #[test]
+    fn validate_window_name_template_rules() {
+        assert!(validate_window_name_template("{prefix}{handle}").is_ok());
+        assert!(validate_window_name_template("{prefix}{pr}-{branch}-{handle}").is_ok());
+        assert!(validate_window_name_template("{handle}").is_err());
+        assert!(validate_window_name_template("{prefix}{branch}").is_err());
+        assert!(validate_window_name_template("{prefix}{handle}{user}").is_err());
+        assert!(validate_window_name_template("{prefix}{handle").is_err());
+        assert!(validate_window_name_template("{prefix}{branch}:{handle}").is_err());
+        assert!(validate_window_name_template("{prefix}{handle}.{pr}").is_err());
+    }
 }
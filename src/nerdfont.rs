@@ -128,6 +128,9 @@ pub fn contains_pua(s: &str) -> bool {
 /// This indicates the user has nerdfonts configured.
 pub fn config_has_pua(config: &crate::config::Config) -> bool {
     // Check status_icons
+    if config.status_icon_theme == Some(crate::config::StatusIconTheme::Nerdfont) {
+        return true;
+    }
     if let Some(ref working) = config.status_icons.working
         && contains_pua(working)
     {
@@ -273,8 +276,18 @@ fn save_nerdfont_preference(enabled: bool) -> Result<()> {
         String::new()
     };
 
-    // Check if nerdfont key already exists
-    if config_content.contains("nerdfont:") {
+    let is_toml = config_path.extension().is_some_and(|ext| ext == "toml");
+    if is_toml {
+        let re = regex::Regex::new(r"(?m)^nerdfont\s*=.*$")?;
+        if re.is_match(&config_content) {
+            config_content = re
+                .replace(&config_content, format!("nerdfont = {}", enabled))
+                .to_string();
+        } else {
+            // Top-level keys must come before any [table] header
+            config_content.insert_str(0, &format!("nerdfont = {}\n", enabled));
+        }
+    } else if config_content.contains("nerdfont:") {
         // Update existing value
         let re = regex::Regex::new(r"(?m)^nerdfont:.*$")?;
         config_content = re
@@ -335,6 +348,15 @@ mod tests {
         assert!(!contains_pua("●○◆×"));
     }
 
+    #[test]
+    fn config_has_pua_for_nerdfont_theme() {
+        let mut config = crate::config::Config::default();
+        assert!(!config_has_pua(&config));
+        config.status_icon_theme = Some(crate::config::StatusIconTheme::Nerdfont);
+        assert!(config_has_pua(&config));
+        assert!(contains_pua(config.status_icons().working()));
+    }
+
     #[test]
     fn contains_pua_handles_empty_string() {
         assert!(!contains_pua(""));
@@ -0,0 +1,132 @@
+//! Show desktop notifications on the host.
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::multiplexer::AgentStatus;
+
+/// AppleScript that shows `item 1 of argv` as title and `item 2` as body.
+/// Passing the text as arguments avoids quoting it into the script.
+const OSASCRIPT_LINES: &[&str] = &[
+    "on run argv",
+    "display notification (item 2 of argv) with title (item 1 of argv)",
+    "end run",
+];
+
+/// Notification tools to try, in order of preference.
+fn candidates(macos: bool) -> &'static [&'static str] {
+    if macos {
+        &["terminal-notifier", "osascript"]
+    } else {
+        &["notify-send"]
+    }
+}
+
+/// Arguments that make `tool` show a notification.
+fn tool_args(tool: &str, title: &str, body: &str) -> Vec<String> {
+    match tool {
+        "terminal-notifier" => vec![
+            "-title".to_string(),
+            title.to_string(),
+            "-message".to_string(),
+            body.to_string(),
+        ],
+        "osascript" => {
+            let mut args: Vec<String> = OSASCRIPT_LINES
+                .iter()
+                .flat_map(|line| ["-e".to_string(), line.to_string()])
+                .collect();
+            args.extend([title.to_string(), body.to_string()]);
+            args
+        }
+        // notify-send; `--` keeps a leading dash in the text from parsing as a flag
+        _ => vec!["--".to_string(), title.to_string(), body.to_string()],
+    }
+}
+
+/// Show a notification with the first available tool
+/// (`terminal-notifier` or `osascript` on macOS, `notify-send` on Linux).
+pub fn send(title: &str, body: &str) -> Result<()> {
+    crate::host_tool::spawn_first_available(
+        candidates(cfg!(target_os = "macos")),
+        |tool| tool_args(tool, title, body),
+        None,
+        "No notification tool found. Install terminal-notifier or notify-send.",
+    )
+}
+
+/// Notification body for a status change, or None for statuses that
+/// don't need the user's attention.
+fn status_body(status: AgentStatus, worktree: &str) -> Option<String> {
+    match status {
+        AgentStatus::Waiting => Some(format!("Agent is waiting for input in {}", worktree)),
+        AgentStatus::Done => Some(format!("Agent finished in {}", worktree)),
+        AgentStatus::Working => None,
+    }
+}
+
+/// Notify about an agent in `worktree_dir` reaching `status`, when
+/// `desktop_notifications` is enabled and the status changed from
+/// `previous`. Failures are logged, not returned, so a missing tool never
+/// breaks status updates.
+pub fn notify_status(
+    config: &Config,
+    previous: Option<AgentStatus>,
+    status: AgentStatus,
+    worktree_dir: &Path,
+) {
+    if !config.desktop_notifications() || previous == Some(status) {
+        return;
+    }
+    let worktree = worktree_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| worktree_dir.display().to_string());
+    let Some(body) = status_body(status, &worktree) else {
+        return;
+    };
+    if let Err(e) = send("workmux", &body) {
+        tracing::warn!(error = %e, "failed to show desktop notification");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_per_platform() {
+        assert_eq!(candidates(true), ["terminal-notifier", "osascript"]);
+        assert_eq!(candidates(false), ["notify-send"]);
+    }
+
+    #[test]
+    fn tool_args_pass_text_as_arguments() {
+        assert_eq!(
+            tool_args("notify-send", "-t", "done"),
+            vec!["--", "-t", "done"]
+        );
+        assert_eq!(
+            tool_args("terminal-notifier", "T", "B"),
+            vec!["-title", "T", "-message", "B"]
+        );
+        let args = tool_args("osascript", "Say \"hi\"", "B");
+        assert_eq!(args[args.len() - 2..], ["Say \"hi\"", "B"]);
+        assert_eq!(args.iter().filter(|a| *a == "-e").count(), 3);
+    }
+
+    #[test]
+    fn status_body_only_for_attention_statuses() {
+        assert_eq!(
+            status_body(AgentStatus::Done, "fix-bug").as_deref(),
+            Some("Agent finished in fix-bug")
+        );
+        assert!(
+            status_body(AgentStatus::Waiting, "fix-bug")
+                .unwrap()
+                .contains("waiting")
+        );
+        assert_eq!(status_body(AgentStatus::Working, "fix-bug"), None);
+    }
+}
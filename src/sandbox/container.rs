@@ -5,7 +5,8 @@ use std::process::Command;
 
 use anyhow::{Context, Result};
 
-use crate::config::{SandboxConfig, SandboxRuntime};
+use crate::config::{GpuMode, ImageCheck, SandboxBackend, SandboxConfig, SandboxRuntime};
+use crate::git::SandboxRecord;
 use crate::state::StateStore;
 
 /// Default image registry prefix.
@@ -18,6 +19,9 @@ pub const DOCKERFILE_CODEX: &str = include_str!("../../docker/Dockerfile.codex")
 pub const DOCKERFILE_GEMINI: &str = include_str!("../../docker/Dockerfile.gemini");
 pub const DOCKERFILE_OPENCODE: &str = include_str!("../../docker/Dockerfile.opencode");
 
+/// Image label holding the build context hash of a `workmux sandbox build` image.
+const BUILD_HASH_LABEL: &str = "dev.workmux.build-hash";
+
 /// Known agents that have pre-built images.
 pub const KNOWN_AGENTS: &[&str] = &["claude", "codex", "gemini", "opencode"];
 
@@ -65,12 +69,162 @@ pub fn ensure_sandbox_config_dirs() -> Result<SandboxPaths> {
     Ok(paths)
 }
 
-/// Build the sandbox Docker image locally (two-stage: base + agent).
-pub fn build_image(config: &SandboxConfig, agent: &str) -> Result<()> {
-    let runtime = match config.runtime() {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
+/// Content hash of the build context for `agent`'s image: the base and agent
+/// Dockerfiles, which copy no other files. None for agents without a
+/// Dockerfile.
+pub fn build_context_hash(agent: &str) -> Option<String> {
+    let agent_dockerfile = dockerfile_for_agent(agent)?;
+    let hash = crate::util::fnv1a(
+        DOCKERFILE_BASE
+            .bytes()
+            .chain(std::iter::once(0))
+            .chain(agent_dockerfile.bytes()),
+    );
+    Some(format!("{:016x}", hash)[..12].to_string())
+}
+
+/// Extra tag for an image built from the context with `hash`:
+/// `repo:claude` becomes `repo:claude-<hash>`, an untagged `repo` becomes
+/// `repo:<hash>`.
+fn hashed_tag(image: &str, hash: &str) -> String {
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    if image[name_start..].contains(':') {
+        format!("{}-{}", image, hash)
+    } else {
+        format!("{}:{}", image, hash)
+    }
+}
+
+/// Build context hash recorded in a local image, if it has one.
+fn image_build_hash(runtime: &str, image: &str) -> Option<String> {
+    let output = Command::new(runtime)
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            &format!("{{{{ index .Config.Labels \"{}\" }}}}", BUILD_HASH_LABEL),
+            image,
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!hash.is_empty() && hash != "<no value>").then_some(hash)
+}
+
+/// Message for an image built from other Dockerfiles than the current ones.
+fn stale_image_message(image: &str, actual: &str, expected: &str) -> Option<String> {
+    (actual != expected).then(|| {
+        format!(
+            "sandbox image '{}' was built from different Dockerfiles (build {}, current {}). \
+             Rebuild it with `workmux sandbox build`.",
+            image, actual, expected
+        )
+    })
+}
+
+/// Check that `agent`'s image, if it was built with `workmux sandbox build`,
+/// matches the current Dockerfiles. Pulled and custom images carry no build
+/// hash and are not checked. Warns or errors according to `image_check`.
+pub fn check_image_build_hash(config: &SandboxConfig, agent: &str) -> Result<()> {
+    let mode = config.image_check();
+    if mode == ImageCheck::Off {
+        return Ok(());
+    }
+    let Some(expected) = build_context_hash(agent) else {
+        return Ok(());
+    };
+    let image = config.resolved_image(agent);
+    let Some(actual) = image_build_hash(config.runtime()?.binary(), &image) else {
+        return Ok(());
+    };
+    if let Some(message) = stale_image_message(&image, &actual, &expected) {
+        if mode == ImageCheck::Error {
+            anyhow::bail!(
+                "{} (set sandbox.image_check: warn to continue anyway)",
+                message
+            );
+        }
+        eprintln!("Warning: {}", message);
+    }
+    Ok(())
+}
+
+/// Leading arguments for an image build. With a platform, Docker builds
+/// through buildx and loads the result into the local image store, so the
+/// agent stage can build on the local base image. Podman's builder takes
+/// `--platform` directly.
+fn build_command_args(runtime: &SandboxRuntime, platform: Option<&str>) -> Vec<String> {
+    let mut args: Vec<String> = match (runtime, platform) {
+        (SandboxRuntime::Docker, Some(_)) => vec!["buildx".into(), "build".into()],
+        _ => vec!["build".into()],
     };
+    if let Some(platform) = platform {
+        args.extend(["--platform".into(), platform.into()]);
+        if *runtime == SandboxRuntime::Docker {
+            args.push("--load".into());
+        }
+    }
+    args
+}
+
+/// Host architecture in container platform terms (e.g. `arm64`).
+fn host_platform_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => arch,
+    }
+}
+
+/// Whether binaries for `arch` can run on this host. Only checked on Linux,
+/// where emulation needs a registered binfmt_misc handler; Docker Desktop and
+/// Podman machines ship with it.
+fn has_emulation(arch: &str) -> bool {
+    if !cfg!(target_os = "linux") {
+        return true;
+    }
+    let qemu_arch = match arch {
+        "amd64" => "x86_64",
+        "arm64" => "aarch64",
+        arch => arch,
+    };
+    Path::new("/proc/sys/fs/binfmt_misc")
+        .join(format!("qemu-{}", qemu_arch))
+        .exists()
+}
+
+/// Warning for building `platform` on a host of another architecture with no
+/// emulator available, which fails on the first `RUN` step.
+fn cross_build_warning(platform: &str, host_arch: &str, emulated: bool) -> Option<String> {
+    let arch = platform.split('/').nth(1).unwrap_or_default();
+    if arch == host_arch || emulated {
+        return None;
+    }
+    Some(format!(
+        "building for {} on a {} host, but no emulator for {} is registered. \
+         Install QEMU user emulation (e.g. `docker run --privileged --rm tonistiigi/binfmt --install {}`).",
+        platform, host_arch, arch, arch
+    ))
+}
+
+/// Build the sandbox Docker image locally (two-stage: base + agent),
+/// optionally for another platform such as `linux/amd64`.
+pub fn build_image(config: &SandboxConfig, agent: &str, platform: Option<&str>) -> Result<()> {
+    let resolved_runtime = config.runtime()?;
+    let runtime = resolved_runtime.binary();
+    let build_args = build_command_args(&resolved_runtime, platform);
+
+    if let Some(platform) = platform {
+        let arch = platform.split('/').nth(1).unwrap_or_default();
+        if let Some(warning) =
+            cross_build_warning(platform, host_platform_arch(), has_emulation(arch))
+        {
+            eprintln!("Warning: {}", warning);
+        }
+    }
 
     let agent_dockerfile = dockerfile_for_agent(agent).ok_or_else(|| {
         anyhow::anyhow!(
@@ -90,7 +244,8 @@ pub fn build_image(config: &SandboxConfig, agent: &str) -> Result<()> {
     let status = Command::new(runtime)
         .env("DOCKER_BUILDKIT", "1")
         .env("DOCKER_CLI_HINTS", "false")
-        .args(["build", "-t", base_tag, "-f", "Dockerfile", "."])
+        .args(&build_args)
+        .args(["-t", base_tag, "-f", "Dockerfile", "."])
         .current_dir(tmp_dir.path())
         .status()
         .context("Failed to build base image")?;
@@ -99,8 +254,11 @@ pub fn build_image(config: &SandboxConfig, agent: &str) -> Result<()> {
         anyhow::bail!("Failed to build base image");
     }
 
-    // Stage 2: Build agent image on top of local base
+    // Stage 2: Build agent image on top of local base, labeled and tagged
+    // with the context hash so stale images can be detected later
     let image = config.resolved_image(agent);
+    let hash = build_context_hash(agent).unwrap_or_default();
+    let tagged = hashed_tag(&image, &hash);
     println!("Building {} image...", agent);
 
     let agent_tmp = tempfile::tempdir().context("Failed to create temp dir")?;
@@ -109,12 +267,16 @@ pub fn build_image(config: &SandboxConfig, agent: &str) -> Result<()> {
     let status = Command::new(runtime)
         .env("DOCKER_BUILDKIT", "1")
         .env("DOCKER_CLI_HINTS", "false")
+        .args(&build_args)
         .args([
-            "build",
             "--build-arg",
             &format!("BASE={}", base_tag),
+            "--label",
+            &format!("{}={}", BUILD_HASH_LABEL, hash),
             "-t",
             &image,
+            "-t",
+            &tagged,
             "-f",
             "Dockerfile",
             ".",
@@ -126,16 +288,14 @@ pub fn build_image(config: &SandboxConfig, agent: &str) -> Result<()> {
     if !status.success() {
         anyhow::bail!("Failed to build image '{}'", image);
     }
+    println!("Tagged {} (build {})", tagged, hash);
 
     Ok(())
 }
 
 /// Pull the sandbox image from the registry.
 pub fn pull_image(config: &SandboxConfig, image: &str) -> Result<()> {
-    let runtime = match config.runtime() {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
-    };
+    let runtime = config.runtime()?.binary();
 
     println!("Pulling image '{}'...", image);
 
@@ -188,11 +348,17 @@ pub fn build_docker_run_args(
     // On Linux Docker Engine (not Desktop), host.docker.internal doesn't resolve
     // unless we explicitly add it. The special "host-gateway" value maps to the
     // host's gateway IP. This is a harmless no-op on Docker Desktop.
-    if matches!(config.runtime(), SandboxRuntime::Docker) {
+    let runtime = config.runtime()?;
+    if matches!(runtime, SandboxRuntime::Docker) {
         args.push("--add-host".to_string());
         args.push("host.docker.internal:host-gateway".to_string());
     }
 
+    if let Some(mode) = config.network.mode() {
+        args.push("--network".to_string());
+        args.push(mode.to_string());
+    }
+
     if network_deny {
         // Deny mode: start as root for iptables setup, drop privileges via gosu.
         // Do NOT use --userns=keep-id (Podman) in deny mode since the container
@@ -207,13 +373,25 @@ pub fn build_docker_run_args(
         // Rootless Podman uses a user namespace that remaps UIDs. Without --userns=keep-id,
         // the host UID appears as root inside the container, making bind-mounted files
         // (credentials, config) inaccessible to the --user process.
-        if matches!(config.runtime(), SandboxRuntime::Podman) {
+        if matches!(runtime, SandboxRuntime::Podman) {
             args.push("--userns=keep-id".to_string());
         }
         args.push("--user".to_string());
         args.push(format!("{}:{}", uid, gid));
     }
 
+    args.extend(gpu_run_args(config.gpu(), &runtime));
+
+    // Resource limits (validated at config load, passed through verbatim)
+    if let Some(cpus) = &config.container.cpus {
+        args.push("--cpus".to_string());
+        args.push(cpus.clone());
+    }
+    if let Some(memory) = &config.container.memory {
+        args.push("--memory".to_string());
+        args.push(memory.clone());
+    }
+
     // Mirror mount worktree
     args.push("--mount".to_string());
     args.push(format!(
@@ -320,11 +498,9 @@ pub fn build_docker_run_args(
     }
 
     // Env passthrough
-    for var in config.env_passthrough() {
-        if std::env::var(var).is_ok() {
-            args.push("--env".to_string());
-            args.push(var.to_string());
-        }
+    for var in config.passthrough_env_vars() {
+        args.push("--env".to_string());
+        args.push(var);
     }
 
     // Extra env vars (RPC connection details)
@@ -381,18 +557,166 @@ pub fn deny_mode_run_flags() -> Vec<String> {
     ]
 }
 
+/// Whether an environment variable name looks like it holds an API credential.
+fn looks_like_api_key(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    name.ends_with("_KEY")
+        || name.ends_with("_TOKEN")
+        || name.contains("API_KEY")
+        || name.contains("SECRET")
+}
+
+/// Warning for `network.mode: none` when credentials are passed through,
+/// since nothing in the container can use them without a network.
+fn network_none_warning(config: &SandboxConfig) -> Option<String> {
+    if config.network.mode() != Some("none") {
+        return None;
+    }
+    let keys: Vec<&str> = config
+        .env_passthrough()
+        .into_iter()
+        .filter(|name| looks_like_api_key(name))
+        .collect();
+    if keys.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "sandbox.network.mode is 'none' but env_passthrough includes {}; \
+         the agent cannot reach any API without a network",
+        keys.join(", ")
+    ))
+}
+
+/// Error for `network.mode: none` with `host_commands` configured: the shims
+/// forward over host RPC, which the container cannot reach without a network.
+fn network_none_host_commands_error(config: &SandboxConfig) -> Option<String> {
+    if config.network.mode() != Some("none") || config.host_commands().is_empty() {
+        return None;
+    }
+    Some(format!(
+        "sandbox.network.mode is 'none' but host_commands is set ({}); \
+         host commands run over RPC and need a network. \
+         Remove host_commands or choose another network mode",
+        config.host_commands().join(", ")
+    ))
+}
+
+/// Docker/Podman run flags that expose the GPU, in the runtime's spelling.
+fn gpu_run_args(gpu: GpuMode, runtime: &SandboxRuntime) -> Vec<String> {
+    match (gpu, runtime) {
+        (GpuMode::Off, _) => Vec::new(),
+        // Podman exposes GPUs through CDI device names
+        (GpuMode::All, SandboxRuntime::Podman) => {
+            vec!["--device".into(), "nvidia.com/gpu=all".into()]
+        }
+        (GpuMode::All, _) => vec!["--gpus".into(), "all".into()],
+    }
+}
+
+/// Directories Podman searches for CDI device specs.
+const CDI_SPEC_DIRS: &[&str] = &["/etc/cdi", "/var/run/cdi"];
+
+/// Whether an NVIDIA CDI spec (e.g. from `nvidia-ctk cdi generate`) is installed.
+fn has_nvidia_cdi_spec() -> bool {
+    CDI_SPEC_DIRS.iter().any(|dir| {
+        std::fs::read_dir(dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.file_name().to_string_lossy().starts_with("nvidia"))
+        })
+    })
+}
+
+/// Explain why `runtime` can't provide GPU access, if it can't.
+///
+/// `run_help` is the output of `<runtime> run --help` (None if unavailable,
+/// in which case Docker is given the benefit of the doubt).
+fn gpu_support_error(
+    runtime: &SandboxRuntime,
+    run_help: Option<&str>,
+    has_cdi_spec: bool,
+) -> Option<String> {
+    match runtime {
+        SandboxRuntime::Podman if !has_cdi_spec => Some(
+            "sandbox.gpu is set but Podman has no NVIDIA CDI spec.\n\
+             Generate one with: sudo nvidia-ctk cdi generate --output=/etc/cdi/nvidia.yaml"
+                .to_string(),
+        ),
+        SandboxRuntime::Podman => None,
+        _ => match run_help {
+            Some(help) if !help.contains("--gpus") => Some(
+                "sandbox.gpu is set but this Docker version does not support --gpus.\n\
+                 Upgrade Docker (19.03 or newer) and install the NVIDIA Container Toolkit."
+                    .to_string(),
+            ),
+            _ => None,
+        },
+    }
+}
+
+/// Fail pane setup early when GPU access is requested but unavailable.
+fn check_gpu_support(config: &SandboxConfig) -> Result<()> {
+    if config.gpu() == GpuMode::Off {
+        return Ok(());
+    }
+    let runtime = config.runtime()?;
+    let (run_help, has_cdi_spec) = match runtime {
+        SandboxRuntime::Podman => (None, has_nvidia_cdi_spec()),
+        _ => {
+            let help = Command::new(runtime.binary())
+                .args(["run", "--help"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+            (help, false)
+        }
+    };
+    if let Some(error) = gpu_support_error(&runtime, run_help.as_deref(), has_cdi_spec) {
+        anyhow::bail!(error);
+    }
+    Ok(())
+}
+
 use crate::shell::shell_escape;
 
+/// Check that every `extra_mounts` source exists on the host, so a typo fails
+/// pane setup instead of the container start inside the pane.
+fn validate_extra_mounts(config: &SandboxConfig) -> Result<()> {
+    for mount in config.extra_mounts() {
+        let (host, _, _) = mount.resolve()?;
+        if !host.exists() {
+            anyhow::bail!(
+                "extra_mounts: host path '{}' does not exist",
+                host.display()
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Wrap a command to run inside a Docker/Podman container via the sandbox supervisor.
 ///
 /// Generates a `workmux sandbox run` command that starts an RPC server, then
 /// runs the command inside a container with RPC connection details as env vars.
 pub fn wrap_for_container(
     command: &str,
-    _config: &SandboxConfig,
+    config: &SandboxConfig,
     worktree_root: &Path,
     pane_cwd: &Path,
 ) -> Result<String> {
+    // Fail during pane setup, not inside the pane, when `auto` finds no runtime
+    config.runtime()?;
+
+    validate_extra_mounts(config)?;
+    check_gpu_support(config)?;
+    if let Some(error) = network_none_host_commands_error(config) {
+        anyhow::bail!(error);
+    }
+    if let Some(warning) = network_none_warning(config) {
+        eprintln!("Warning: {}", warning);
+    }
+
     // Strip the single leading space that rewrite_agent_command adds for
     // shell history prevention -- not needed for the supervisor.
     let command = command.strip_prefix(' ').unwrap_or(command);
@@ -416,6 +740,19 @@ pub fn wrap_for_container(
     Ok(format!(" {}", parts))
 }
 
+/// Resolve the image override for a sandbox run in `worktree`.
+///
+/// An explicit `--image` wins, then an image recorded with
+/// `workmux add --image`. None means `sandbox.image` from config applies.
+pub fn image_override(explicit: Option<String>, worktree: &Path) -> Option<String> {
+    explicit.or_else(|| {
+        crate::git::get_repo_root_for(worktree)
+            .ok()
+            .and_then(|root| root.file_name().map(|n| n.to_string_lossy().to_string()))
+            .and_then(|handle| crate::git::get_worktree_sandbox_image(&handle))
+    })
+}
+
 /// Stop any running containers associated with a worktree handle.
 ///
 /// Uses the state store to find registered containers instead of running
@@ -429,28 +766,85 @@ pub fn stop_containers_for_handle(handle: &str, config: &SandboxConfig) {
     };
 
     let containers = store.list_containers(handle);
+    stop_containers(&store, handle, &containers, config);
+}
+
+/// Stop the given containers of a worktree and unregister them.
+pub(crate) fn stop_planned_containers(handle: &str, containers: &[String], config: &SandboxConfig) {
+    if let Ok(store) = StateStore::new() {
+        stop_containers(&store, handle, containers, config);
+    }
+}
+
+fn stop_containers(
+    store: &StateStore,
+    handle: &str,
+    containers: &[String],
+    config: &SandboxConfig,
+) {
     if containers.is_empty() {
         return;
     }
 
-    let runtime = match config.runtime() {
-        SandboxRuntime::Podman => "podman",
-        SandboxRuntime::Docker => "docker",
+    let Ok(runtime) = config.runtime() else {
+        return;
     };
 
     tracing::debug!(?containers, handle, "stopping containers for worktree");
 
     // Stop all containers in one command
-    let _ = Command::new(runtime)
+    let _ = Command::new(runtime.binary())
         .arg("stop")
         .arg("-t")
         .arg("0")
-        .args(&containers)
+        .args(containers)
         .output();
 
     // Unregister containers from state store
     for name in containers {
-        store.unregister_container(handle, &name);
+        store.unregister_container(handle, name);
+    }
+}
+
+/// Stop containers for a worktree, gated on its recorded sandbox usage.
+///
+/// Worktrees launched without a sandbox (or with Lima, whose VM outlives the
+/// window) are skipped. Worktrees with no record predate sandbox tracking, so
+/// they fall back to checking for registered containers.
+pub fn stop_sandbox_for_handle(
+    handle: &str,
+    record: Option<&SandboxRecord>,
+    config: &SandboxConfig,
+) {
+    stop_sandbox_with(handle, record, config, stop_containers_for_handle);
+}
+
+/// Containers [`stop_sandbox_for_handle`] would stop, without stopping them.
+pub fn planned_container_stops(
+    handle: &str,
+    record: Option<&SandboxRecord>,
+    config: &SandboxConfig,
+) -> Vec<String> {
+    let mut planned = Vec::new();
+    stop_sandbox_with(handle, record, config, |handle, _| {
+        if let Ok(store) = StateStore::new() {
+            planned = store.list_containers(handle);
+        }
+    });
+    planned
+}
+
+fn stop_sandbox_with(
+    handle: &str,
+    record: Option<&SandboxRecord>,
+    config: &SandboxConfig,
+    stop: impl FnOnce(&str, &SandboxConfig),
+) {
+    match record {
+        Some(SandboxRecord::On(SandboxBackend::Container)) | None => stop(handle, config),
+        Some(SandboxRecord::On(SandboxBackend::Lima)) | Some(SandboxRecord::Off) => {
+            tracing::debug!(handle, ?record, "skipping container teardown");
+        }
     }
 }
 
@@ -464,6 +858,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Docker),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             env_passthrough: Some(vec!["TEST_KEY".to_string()]),
@@ -520,6 +915,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Docker),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             ..Default::default()
@@ -546,6 +942,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Podman),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             ..Default::default()
@@ -571,6 +968,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Podman),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             ..Default::default()
@@ -679,6 +1077,56 @@ mod tests {
         assert!(path_arg.starts_with("PATH=/tmp/.workmux-shims/bin:"));
     }
 
+    #[test]
+    fn test_build_command_args_for_platform() {
+        assert_eq!(build_command_args(&SandboxRuntime::Docker, None), ["build"]);
+        assert_eq!(
+            build_command_args(&SandboxRuntime::Docker, Some("linux/amd64")),
+            ["buildx", "build", "--platform", "linux/amd64", "--load"]
+        );
+        assert_eq!(
+            build_command_args(&SandboxRuntime::Podman, Some("linux/arm64")),
+            ["build", "--platform", "linux/arm64"]
+        );
+    }
+
+    #[test]
+    fn test_cross_build_warning_only_without_emulation() {
+        assert_eq!(cross_build_warning("linux/arm64", "arm64", false), None);
+        assert_eq!(cross_build_warning("linux/amd64", "arm64", true), None);
+        let warning = cross_build_warning("linux/amd64", "arm64", false).unwrap();
+        assert!(warning.contains("no emulator for amd64"), "{warning}");
+    }
+
+    #[test]
+    fn test_build_context_hash_is_per_agent() {
+        let claude = build_context_hash("claude").unwrap();
+        assert_eq!(claude.len(), 12);
+        assert_eq!(build_context_hash("claude").unwrap(), claude);
+        assert_ne!(build_context_hash("codex").unwrap(), claude);
+        assert_eq!(build_context_hash("unknown"), None);
+    }
+
+    #[test]
+    fn test_hashed_tag() {
+        assert_eq!(
+            hashed_tag("ghcr.io/raine/workmux-sandbox:claude", "abc123"),
+            "ghcr.io/raine/workmux-sandbox:claude-abc123"
+        );
+        assert_eq!(hashed_tag("my-image", "abc123"), "my-image:abc123");
+        assert_eq!(
+            hashed_tag("localhost:5000/my-image", "abc123"),
+            "localhost:5000/my-image:abc123"
+        );
+    }
+
+    #[test]
+    fn test_stale_image_message_only_on_mismatch() {
+        assert_eq!(stale_image_message("img", "aaa", "aaa"), None);
+        let message = stale_image_message("img", "aaa", "bbb").unwrap();
+        assert!(message.contains("workmux sandbox build"), "{message}");
+    }
+
     #[test]
     fn test_dockerfile_for_known_agents() {
         assert!(dockerfile_for_agent("claude").is_some());
@@ -715,6 +1163,17 @@ mod tests {
         assert_eq!(config.resolved_image("claude"), "my-image:latest");
     }
 
+    #[test]
+    fn test_image_override_prefers_explicit_image() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            image_override(Some("workmux-sandbox:exp".to_string()), dir.path()),
+            Some("workmux-sandbox:exp".to_string())
+        );
+        // Outside a git repo there is no recorded image to fall back to
+        assert_eq!(image_override(None, dir.path()), None);
+    }
+
     #[test]
     fn test_build_args_extra_mounts_readonly() {
         use crate::config::ExtraMount;
@@ -723,6 +1182,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Docker),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             extra_mounts: Some(vec![ExtraMount::Path("/tmp/notes".to_string())]),
@@ -752,6 +1212,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Docker),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             extra_mounts: Some(vec![ExtraMount::Spec {
@@ -779,6 +1240,240 @@ mod tests {
         assert!(!args_str.contains("/tmp/data,target=/mnt/data,readonly"));
     }
 
+    #[test]
+    fn test_build_args_extra_mounts_host_target_strings() {
+        use crate::config::ExtraMount;
+
+        let config = SandboxConfig {
+            enabled: Some(true),
+            container: ContainerConfig {
+                runtime: Some(SandboxRuntime::Docker),
+                ..Default::default()
+            },
+            image: Some("test-image:latest".to_string()),
+            extra_mounts: Some(vec![
+                ExtraMount::Path("/tmp/registry:/cargo/registry:ro".to_string()),
+                ExtraMount::Path("/tmp/cache:/tmp/cache:rw".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let args = build_docker_run_args(
+            "claude",
+            &config,
+            "claude",
+            Path::new("/tmp/project"),
+            Path::new("/tmp/project"),
+            &[],
+            None,
+            false,
+        )
+        .unwrap();
+
+        let args_str = args.join(" ");
+        assert!(
+            args_str
+                .contains("--mount type=bind,source=/tmp/registry,target=/cargo/registry,readonly")
+        );
+        assert!(args_str.contains("--mount type=bind,source=/tmp/cache,target=/tmp/cache "));
+    }
+
+    fn runtime_config(runtime: SandboxRuntime) -> SandboxConfig {
+        SandboxConfig {
+            enabled: Some(true),
+            container: ContainerConfig {
+                runtime: Some(runtime),
+                ..Default::default()
+            },
+            image: Some("test-image:latest".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn run_args(config: &SandboxConfig) -> Vec<String> {
+        build_docker_run_args(
+            "claude",
+            config,
+            "claude",
+            Path::new("/tmp/project"),
+            Path::new("/tmp/project"),
+            &[],
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn gpu_args_for(runtime: SandboxRuntime, gpu: Option<GpuMode>) -> String {
+        run_args(&SandboxConfig {
+            gpu,
+            ..runtime_config(runtime)
+        })
+        .join(" ")
+    }
+
+    #[test]
+    fn test_build_args_gpu_docker() {
+        let args_str = gpu_args_for(SandboxRuntime::Docker, Some(GpuMode::All));
+        assert!(args_str.contains("--gpus all"));
+        assert!(!args_str.contains("nvidia.com/gpu"));
+    }
+
+    #[test]
+    fn test_build_args_gpu_podman() {
+        let args_str = gpu_args_for(SandboxRuntime::Podman, Some(GpuMode::All));
+        assert!(args_str.contains("--device nvidia.com/gpu=all"));
+        assert!(!args_str.contains("--gpus"));
+    }
+
+    #[test]
+    fn test_build_args_gpu_off_by_default() {
+        for runtime in [SandboxRuntime::Docker, SandboxRuntime::Podman] {
+            for gpu in [None, Some(GpuMode::Off)] {
+                let args_str = gpu_args_for(runtime.clone(), gpu);
+                assert!(!args_str.contains("--gpus"));
+                assert!(!args_str.contains("nvidia.com/gpu"));
+            }
+        }
+    }
+
+    fn network_mode_config(mode: Option<&str>, env: &[&str]) -> SandboxConfig {
+        SandboxConfig {
+            env_passthrough: Some(env.iter().map(|s| s.to_string()).collect()),
+            network: crate::config::NetworkConfig {
+                mode: mode.map(str::to_string),
+                ..Default::default()
+            },
+            ..runtime_config(SandboxRuntime::Docker)
+        }
+    }
+
+    #[test]
+    fn test_build_args_network_mode() {
+        for mode in ["none", "host", "agents-bridge"] {
+            let args = run_args(&network_mode_config(Some(mode), &[]));
+            let idx = args.iter().position(|a| a == "--network").unwrap();
+            assert_eq!(args[idx + 1], mode);
+            // Must come before the image name to be a runtime flag
+            let image_idx = args.iter().position(|a| a == "test-image:latest").unwrap();
+            assert!(idx < image_idx);
+        }
+    }
+
+    #[test]
+    fn test_build_args_network_mode_unset() {
+        for mode in [None, Some("")] {
+            let args = run_args(&network_mode_config(mode, &[]));
+            assert!(!args.contains(&"--network".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_network_none_warns_about_api_keys() {
+        let config =
+            network_mode_config(Some("none"), &["ANTHROPIC_API_KEY", "GITHUB_TOKEN", "TERM"]);
+        let warning = network_none_warning(&config).unwrap();
+        assert!(warning.contains("ANTHROPIC_API_KEY, GITHUB_TOKEN"));
+        assert!(!warning.contains("TERM"));
+
+        assert_eq!(
+            network_none_warning(&network_mode_config(Some("none"), &["TERM"])),
+            None
+        );
+        assert_eq!(
+            network_none_warning(&network_mode_config(Some("host"), &["OPENAI_API_KEY"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_network_none_refuses_host_commands() {
+        let mut config = network_mode_config(Some("none"), &[]);
+        assert_eq!(network_none_host_commands_error(&config), None);
+
+        config.host_commands = Some(vec!["just".to_string(), "cargo".to_string()]);
+        let error = network_none_host_commands_error(&config).unwrap();
+        assert!(error.contains("just, cargo"));
+
+        config.network.mode = Some("host".to_string());
+        assert_eq!(network_none_host_commands_error(&config), None);
+    }
+
+    #[test]
+    fn test_resource_limits_only_when_configured() {
+        let mut config = network_mode_config(None, &[]);
+        let args = run_args(&config);
+        assert!(!args.contains(&"--cpus".to_string()));
+        assert!(!args.contains(&"--memory".to_string()));
+
+        config.container.cpus = Some("1.5".to_string());
+        config.container.memory = Some("4g".to_string());
+        let args = run_args(&config);
+        let cpus_idx = args.iter().position(|a| a == "--cpus").unwrap();
+        assert_eq!(args[cpus_idx + 1], "1.5");
+        let memory_idx = args.iter().position(|a| a == "--memory").unwrap();
+        assert_eq!(args[memory_idx + 1], "4g");
+        let image_idx = args.iter().position(|a| a == "test-image:latest").unwrap();
+        assert!(cpus_idx < image_idx && memory_idx < image_idx);
+    }
+
+    #[test]
+    fn test_gpu_support_error() {
+        // Podman needs a CDI spec
+        let err = gpu_support_error(&SandboxRuntime::Podman, None, false).unwrap();
+        assert!(err.contains("nvidia-ctk cdi generate"));
+        assert_eq!(gpu_support_error(&SandboxRuntime::Podman, None, true), None);
+
+        // Docker needs --gpus in `docker run --help`
+        let err =
+            gpu_support_error(&SandboxRuntime::Docker, Some("Usage: docker run"), false).unwrap();
+        assert!(err.contains("--gpus"));
+        assert_eq!(
+            gpu_support_error(
+                &SandboxRuntime::Docker,
+                Some("      --gpus gpu-request   GPU devices"),
+                false
+            ),
+            None
+        );
+        // Unknown help output is not treated as unsupported
+        assert_eq!(
+            gpu_support_error(&SandboxRuntime::Docker, None, false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wrap_rejects_missing_extra_mount_source() {
+        use crate::config::ExtraMount;
+
+        let existing = tempfile::tempdir().unwrap();
+        let mut config = make_config();
+        config.extra_mounts = Some(vec![ExtraMount::Path(format!(
+            "{}:/mnt/ok:rw",
+            existing.path().display()
+        ))]);
+        assert!(
+            wrap_for_container(
+                "claude",
+                &config,
+                Path::new("/tmp/project"),
+                Path::new("/tmp/project"),
+            )
+            .is_ok()
+        );
+
+        let missing = existing.path().join("missing");
+        config.extra_mounts = Some(vec![ExtraMount::Path(missing.display().to_string())]);
+        let err = wrap_for_container(
+            "claude",
+            &config,
+            Path::new("/tmp/project"),
+            Path::new("/tmp/project"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+
     #[test]
     fn test_build_args_gemini_agent_credential_mount() {
         let config = make_config();
@@ -1037,6 +1732,7 @@ mod tests {
             enabled: Some(true),
             container: ContainerConfig {
                 runtime: Some(SandboxRuntime::Podman),
+                ..Default::default()
             },
             image: Some("test-image:latest".to_string()),
             ..Default::default()
@@ -1087,4 +1783,37 @@ mod tests {
         assert!(flags.contains(&"--security-opt".to_string()));
         assert!(flags.contains(&"no-new-privileges".to_string()));
     }
+
+    fn stop_called_for(record: Option<&SandboxRecord>) -> bool {
+        let mut called = false;
+        stop_sandbox_with("feature", record, &make_config(), |handle, _| {
+            assert_eq!(handle, "feature");
+            called = true;
+        });
+        called
+    }
+
+    #[test]
+    fn test_stop_sandbox_runs_for_container_record() {
+        assert!(stop_called_for(Some(&SandboxRecord::On(
+            SandboxBackend::Container
+        ))));
+    }
+
+    #[test]
+    fn test_stop_sandbox_skips_when_recorded_off() {
+        assert!(!stop_called_for(Some(&SandboxRecord::Off)));
+    }
+
+    #[test]
+    fn test_stop_sandbox_skips_for_lima_record() {
+        assert!(!stop_called_for(Some(&SandboxRecord::On(
+            SandboxBackend::Lima
+        ))));
+    }
+
+    #[test]
+    fn test_stop_sandbox_falls_back_without_record() {
+        assert!(stop_called_for(None));
+    }
 }
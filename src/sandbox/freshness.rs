@@ -106,7 +106,7 @@ fn save_cache(image: &str, is_fresh: bool, local_image_id: Option<String>) -> Re
 ///
 /// This is a cheap local-only operation used to detect when the local image
 /// has changed since the last freshness check.
-fn get_local_image_id(runtime: &str, image: &str) -> Result<String> {
+pub(crate) fn get_local_image_id(runtime: &str, image: &str) -> Result<String> {
     let output = Command::new(runtime)
         .args(["image", "inspect", "--format", "{{.Id}}", image])
         .output()
@@ -182,10 +182,7 @@ fn get_remote_digest(image: &str) -> Result<String> {
 
 /// Perform the freshness check and print hint if stale.
 fn check_freshness(image: &str, runtime: SandboxRuntime) -> Result<bool> {
-    let runtime_bin = match runtime {
-        SandboxRuntime::Docker => "docker",
-        SandboxRuntime::Podman => "podman",
-    };
+    let runtime_bin = runtime.binary();
 
     // Get the digests the local image was pulled with (e.g. "registry/repo@sha256:abc...")
     let local_digests =
@@ -211,10 +208,7 @@ fn check_freshness(image: &str, runtime: SandboxRuntime) -> Result<bool> {
 /// Call this after a successful `sandbox pull` so the staleness hint
 /// is not shown until the next TTL window.
 pub fn mark_fresh(image: &str, runtime: SandboxRuntime) {
-    let runtime_bin = match runtime {
-        SandboxRuntime::Docker => "docker",
-        SandboxRuntime::Podman => "podman",
-    };
+    let runtime_bin = runtime.binary();
     let local_id = get_local_image_id(runtime_bin, image).ok();
     let _ = save_cache(image, true, local_id);
 }
@@ -236,10 +230,7 @@ pub fn check_in_background(image: String, runtime: SandboxRuntime) {
             return;
         }
 
-        let runtime_bin = match runtime {
-            SandboxRuntime::Docker => "docker",
-            SandboxRuntime::Podman => "podman",
-        };
+        let runtime_bin = runtime.binary();
 
         // Check cache first
         if let Some(cache) = load_cache(&image) {
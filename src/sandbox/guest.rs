@@ -1,12 +1,37 @@
 //! Guest-side detection helpers for sandbox environments.
 //!
-//! When `WM_SANDBOX_GUEST=1` is set, the workmux binary is running inside
-//! a sandbox (Lima VM or Docker container) and should use RPC instead of
+//! When `WM_SANDBOX_GUEST=1` is set, or the supervisor's RPC endpoint
+//! directory is mounted in `$HOME`, the workmux binary is running inside a
+//! sandbox (Lima VM or Docker container) and should use RPC instead of
 //! direct tmux/host operations.
 
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use super::rpc::ENDPOINT_DIR;
+
 /// Check if running inside a sandbox guest VM.
+///
+/// Processes that did not inherit the sandbox env vars (e.g. a separate
+/// `limactl shell`) are still guests when the endpoint directory exists.
 pub fn is_sandbox_guest() -> bool {
-    std::env::var_os("WM_SANDBOX_GUEST").is_some()
+    std::env::var_os("WM_SANDBOX_GUEST").is_some() || endpoint_dir().is_some()
+}
+
+/// Guest directory holding the supervisor's persisted RPC endpoints.
+///
+/// Lima mounts it under `$HOME`, and containers use `/tmp` as `HOME`. A
+/// shared path like `/tmp` is never checked on its own: any local user could
+/// create it and make host processes send their RPC calls elsewhere.
+pub fn endpoint_dir() -> Option<PathBuf> {
+    let dir = home::home_dir()?.join(ENDPOINT_DIR);
+    is_private_dir(&dir, unsafe { libc::geteuid() }).then_some(dir)
+}
+
+/// Whether `dir` is a directory owned by `uid` that no one else can write to.
+fn is_private_dir(dir: &Path, uid: u32) -> bool {
+    std::fs::metadata(dir)
+        .is_ok_and(|meta| meta.is_dir() && meta.uid() == uid && meta.mode() & 0o022 == 0)
 }
 
 /// Get the RPC endpoint from environment variables.
@@ -33,11 +58,29 @@ mod tests {
     fn test_is_sandbox_guest_when_unset() {
         // Should be false when env var is not set (default in test env)
         // Note: this test assumes WM_SANDBOX_GUEST is not set in the test runner
-        if std::env::var_os("WM_SANDBOX_GUEST").is_none() {
+        if std::env::var_os("WM_SANDBOX_GUEST").is_none() && endpoint_dir().is_none() {
             assert!(!is_sandbox_guest());
         }
     }
 
+    #[test]
+    fn test_is_private_dir_checks_owner_and_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(ENDPOINT_DIR);
+        let uid = unsafe { libc::geteuid() };
+        assert!(!is_private_dir(&dir, uid));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).unwrap();
+        assert!(is_private_dir(&dir, uid));
+        assert!(!is_private_dir(&dir, uid + 1));
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(!is_private_dir(&dir, uid));
+    }
+
     #[test]
     fn test_rpc_endpoint_when_unset() {
         // Should be None when env vars are not set
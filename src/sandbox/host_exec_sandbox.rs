@@ -86,37 +86,56 @@ pub fn spawn_sandboxed(
     worktree: &Path,
     envs: &HashMap<String, String>,
     allow_unsandboxed: bool,
+    own_process_group: bool,
 ) -> Result<Child> {
-    if allow_unsandboxed {
+    let mut cmd = if allow_unsandboxed {
         tracing::warn!(
             "dangerously_allow_unsandboxed_host_exec is set, skipping filesystem sandbox"
         );
-        return spawn_unsandboxed(program, args, worktree, envs);
+        unsandboxed_command(program, args, worktree, envs)
+    } else {
+        sandboxed_command(program, args, worktree, envs)?
+    };
+    // A separate group lets a timeout kill everything the command started.
+    // Otherwise the child stays in the supervisor's group, so closing the
+    // window takes it down too.
+    if own_process_group {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
     }
+    cmd.spawn()
+        .with_context(|| format!("Failed to spawn {}", cmd.get_program().to_string_lossy()))
+}
 
+fn sandboxed_command(
+    program: &str,
+    args: &[String],
+    worktree: &Path,
+    envs: &HashMap<String, String>,
+) -> Result<Command> {
     #[cfg(target_os = "macos")]
     {
-        spawn_macos(program, args, worktree, envs)
+        macos_command(program, args, worktree, envs)
     }
 
     #[cfg(target_os = "linux")]
     {
-        spawn_linux(program, args, worktree, envs)
+        linux_command(program, args, worktree, envs)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         tracing::warn!("host-exec sandboxing not supported on this OS, running unsandboxed");
-        spawn_unsandboxed(program, args, worktree, envs)
+        Ok(unsandboxed_command(program, args, worktree, envs))
     }
 }
 
-fn spawn_unsandboxed(
+fn unsandboxed_command(
     program: &str,
     args: &[String],
     worktree: &Path,
     envs: &HashMap<String, String>,
-) -> Result<Child> {
+) -> Command {
     let mut cmd = Command::new(program);
     cmd.args(args);
     cmd.current_dir(worktree);
@@ -125,18 +144,18 @@ fn spawn_unsandboxed(
     cmd.stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
-    cmd.spawn().context("Failed to spawn command")
+    cmd
 }
 
 // ── macOS: sandbox-exec ─────────────────────────────────────────────────
 
 #[cfg(target_os = "macos")]
-fn spawn_macos(
+fn macos_command(
     program: &str,
     args: &[String],
     worktree: &Path,
     envs: &HashMap<String, String>,
-) -> Result<Child> {
+) -> Result<Command> {
     let home = std::env::var("HOME").unwrap_or_else(|_| "/var/empty".to_string());
     let worktree_str = worktree
         .to_str()
@@ -159,7 +178,7 @@ fn spawn_macos(
         .stderr(Stdio::piped());
 
     debug!(program, "spawning under sandbox-exec");
-    cmd.spawn().context("Failed to spawn sandbox-exec")
+    Ok(cmd)
 }
 
 /// Generate the macOS Seatbelt profile string.
@@ -262,14 +281,14 @@ fn find_bwrap() -> Option<&'static str> {
 }
 
 #[cfg(target_os = "linux")]
-fn spawn_linux(
+fn linux_command(
     program: &str,
     args: &[String],
     worktree: &Path,
     envs: &HashMap<String, String>,
-) -> Result<Child> {
+) -> Result<Command> {
     if let Some(bwrap_path) = find_bwrap() {
-        spawn_bwrap(bwrap_path, program, args, worktree, envs)
+        bwrap_command(bwrap_path, program, args, worktree, envs)
     } else {
         anyhow::bail!(
             "bwrap (bubblewrap) not found at any trusted path ({}). \
@@ -281,13 +300,13 @@ fn spawn_linux(
 }
 
 #[cfg(target_os = "linux")]
-fn spawn_bwrap(
+fn bwrap_command(
     bwrap_path: &str,
     program: &str,
     args: &[String],
     worktree: &Path,
     envs: &HashMap<String, String>,
-) -> Result<Child> {
+) -> Result<Command> {
     let home = std::env::var("HOME").unwrap_or_else(|_| "/var/empty".to_string());
     let home_path = Path::new(&home);
 
@@ -356,7 +375,7 @@ fn spawn_bwrap(
         .stderr(Stdio::piped());
 
     debug!(program, "spawning under bwrap");
-    cmd.spawn().context("Failed to spawn bwrap")
+    Ok(cmd)
 }
 
 #[cfg(test)]
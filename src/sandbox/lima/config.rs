@@ -4,7 +4,74 @@ use anyhow::Result;
 use serde_yaml::Value;
 
 use super::mounts::Mount;
-use crate::config::SandboxConfig;
+use crate::config::{LimaMountType, SandboxConfig};
+
+/// Lima release as (major, minor, patch).
+pub type LimaVersion = (u32, u32, u32);
+
+/// Parse `limactl --version` output (e.g. "limactl version 1.0.3").
+pub fn parse_lima_version(output: &str) -> Option<LimaVersion> {
+    let version = output.split_whitespace().last()?.trim_start_matches('v');
+    // Drop pre-release/build suffixes like "1.1.0-beta.1"
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().flatten().unwrap_or(0);
+    let patch = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Why `mount_type` can't be used with this VM type and Lima version, if it can't.
+/// An unknown version is assumed to be recent enough.
+fn mount_type_unsupported(
+    mount_type: LimaMountType,
+    vz: bool,
+    version: Option<LimaVersion>,
+) -> Option<String> {
+    let requires = |min: LimaVersion| {
+        version
+            .filter(|v| *v < min)
+            .map(|_| format!("requires Lima {}.{} or newer", min.0, min.1))
+    };
+    match mount_type {
+        LimaMountType::ReverseSshfs => None,
+        LimaMountType::NineP if vz => Some("is not supported by the vz VM type".to_string()),
+        LimaMountType::NineP => requires((0, 10, 0)),
+        LimaMountType::Virtiofs if vz => requires((0, 14, 0)),
+        LimaMountType::Virtiofs => requires((1, 0, 0)),
+    }
+}
+
+/// Decide which `mountType` to write into the generated config.
+///
+/// Without an explicit choice, vz VMs (macOS) use virtiofs when the installed
+/// Lima supports it and everything else keeps Lima's default. An explicit
+/// choice that the VM type or Lima version can't handle falls back to Lima's
+/// default; the second value is the warning to show in that case.
+pub fn resolve_mount_type(
+    requested: Option<LimaMountType>,
+    vz: bool,
+    version: Option<LimaVersion>,
+) -> (Option<LimaMountType>, Option<String>) {
+    match requested {
+        Some(mount_type) => match mount_type_unsupported(mount_type, vz, version) {
+            None => (Some(mount_type), None),
+            Some(reason) => (
+                None,
+                Some(format!(
+                    "sandbox.lima.mount_type '{}' {}; falling back to Lima's default",
+                    mount_type.as_str(),
+                    reason
+                )),
+            ),
+        },
+        None if vz => {
+            let supported = mount_type_unsupported(LimaMountType::Virtiofs, vz, version).is_none();
+            (supported.then_some(LimaMountType::Virtiofs), None)
+        }
+        None => (None, None),
+    }
+}
 
 /// Generate the shell commands to install a specific agent in a Lima VM.
 ///
@@ -56,12 +123,14 @@ mkdir -p "$HOME/.local/bin"
 ///
 /// The `agent` parameter determines which CLI tool is installed during
 /// provisioning (e.g. "claude", "codex", "gemini", "opencode").
+/// `mount_type` comes from [`resolve_mount_type`]; None leaves it to Lima.
 pub fn generate_lima_config(
     _instance_name: &str,
     mounts: &[Mount],
     sandbox_config: &SandboxConfig,
     agent: &str,
     needs_nix: bool,
+    mount_type: Option<LimaMountType>,
 ) -> Result<String> {
     let mut config = serde_yaml::Mapping::new();
 
@@ -130,6 +199,9 @@ pub fn generate_lima_config(
     config.insert("containerd".into(), containerd.into());
 
     // Generate mounts
+    if let Some(mount_type) = mount_type {
+        config.insert("mountType".into(), mount_type.as_str().into());
+    }
     let mount_list: Vec<Value> = mounts
         .iter()
         .map(|m| {
@@ -140,6 +212,18 @@ pub fn generate_lima_config(
             );
             mount_config.insert("writable".into(), (!m.read_only).into());
 
+            // 9p needs mmap caching and mapped ownership for writes to behave
+            if mount_type == Some(LimaMountType::NineP) {
+                let mut nine_p = serde_yaml::Mapping::new();
+                if m.read_only {
+                    nine_p.insert("cache".into(), "fscache".into());
+                } else {
+                    nine_p.insert("securityModel".into(), "mapped-xattr".into());
+                    nine_p.insert("cache".into(), "mmap".into());
+                }
+                mount_config.insert("9p".into(), nine_p.into());
+            }
+
             if m.host_path != m.guest_path {
                 mount_config.insert(
                     "mountPoint".into(),
@@ -248,8 +332,8 @@ mod tests {
         ];
 
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         // Basic sanity checks
         assert!(yaml.contains("images:"));
@@ -266,8 +350,8 @@ mod tests {
     fn test_generate_lima_config_provision_scripts() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         // System provision installs dependencies
         assert!(yaml.contains("mode: system"));
@@ -295,8 +379,8 @@ mod tests {
     fn test_generate_lima_config_default_provision_count() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let provisions = parsed["provision"].as_sequence().unwrap();
@@ -313,8 +397,8 @@ mod tests {
             },
             ..Default::default()
         };
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let provisions = parsed["provision"].as_sequence().unwrap();
@@ -338,8 +422,8 @@ mod tests {
             image: Some("file:///Users/me/.lima/images/workmux-golden.qcow2".to_string()),
             ..Default::default()
         };
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let images = parsed["images"].as_sequence().unwrap();
@@ -356,8 +440,8 @@ mod tests {
     fn test_generate_lima_config_default_image() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let images = parsed["images"].as_sequence().unwrap();
@@ -377,8 +461,8 @@ mod tests {
             },
             ..Default::default()
         };
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let provisions = parsed["provision"].as_sequence().unwrap();
@@ -402,8 +486,8 @@ mod tests {
             },
             ..Default::default()
         };
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let provisions = parsed["provision"].as_sequence().unwrap();
@@ -436,8 +520,8 @@ mod tests {
         ];
 
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
         let mount_list = parsed["mounts"].as_sequence().unwrap();
@@ -461,7 +545,7 @@ mod tests {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
         let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "codex", true).unwrap();
+            generate_lima_config("test-vm", &mounts, &sandbox_config, "codex", true, None).unwrap();
 
         // Should install codex, not claude
         assert!(yaml.contains("codex"));
@@ -479,8 +563,8 @@ mod tests {
     fn test_generate_lima_config_gemini_agent() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "gemini", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "gemini", true, None)
+            .unwrap();
 
         // Should install Node.js and Gemini CLI
         assert!(yaml.contains("nodesource.com"));
@@ -494,7 +578,8 @@ mod tests {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
         let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "opencode", true).unwrap();
+            generate_lima_config("test-vm", &mounts, &sandbox_config, "opencode", true, None)
+                .unwrap();
 
         assert!(yaml.contains("opencode.ai/install"));
         assert!(!yaml.contains("claude.ai/install.sh"));
@@ -505,8 +590,15 @@ mod tests {
     fn test_generate_lima_config_unknown_agent() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "custom-agent", true)
-            .unwrap();
+        let yaml = generate_lima_config(
+            "test-vm",
+            &mounts,
+            &sandbox_config,
+            "custom-agent",
+            true,
+            None,
+        )
+        .unwrap();
 
         // Should have a comment about no built-in script
         assert!(yaml.contains("No built-in install script for agent: custom-agent"));
@@ -520,8 +612,8 @@ mod tests {
     fn test_generate_lima_config_claude_includes_config_symlink() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true, None)
+            .unwrap();
 
         // Claude agent should include config symlink
         assert!(
@@ -533,8 +625,8 @@ mod tests {
     fn test_generate_lima_config_no_nix_when_not_needed() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
         let sandbox_config = SandboxConfig::default();
-        let yaml =
-            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", false).unwrap();
+        let yaml = generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", false, None)
+            .unwrap();
 
         // Should NOT install Nix or Devbox
         assert!(!yaml.contains("install.determinate.systems/nix"));
@@ -578,4 +670,143 @@ mod tests {
         assert!(script.contains("No built-in install script"));
         assert!(script.contains("my-custom-agent"));
     }
+
+    #[test]
+    fn test_mount_type_omitted_by_default() {
+        let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
+        let yaml = generate_lima_config(
+            "test-vm",
+            &mounts,
+            &SandboxConfig::default(),
+            "claude",
+            true,
+            None,
+        )
+        .unwrap();
+        assert!(!yaml.contains("mountType"));
+        assert!(!yaml.contains("9p"));
+    }
+
+    #[test]
+    fn test_mount_type_virtiofs_emitted() {
+        let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
+        let yaml = generate_lima_config(
+            "test-vm",
+            &mounts,
+            &SandboxConfig::default(),
+            "claude",
+            true,
+            Some(LimaMountType::Virtiofs),
+        )
+        .unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed["mountType"].as_str(), Some("virtiofs"));
+        assert_eq!(parsed["mounts"][0]["writable"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn test_mount_type_9p_writable_flags() {
+        let mounts = vec![
+            Mount::rw(PathBuf::from("/tmp/rw")),
+            Mount {
+                host_path: PathBuf::from("/tmp/ro"),
+                guest_path: PathBuf::from("/tmp/ro"),
+                read_only: true,
+            },
+        ];
+        let yaml = generate_lima_config(
+            "test-vm",
+            &mounts,
+            &SandboxConfig::default(),
+            "claude",
+            true,
+            Some(LimaMountType::NineP),
+        )
+        .unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed["mountType"].as_str(), Some("9p"));
+
+        let rw = &parsed["mounts"][0];
+        assert_eq!(rw["writable"].as_bool(), Some(true));
+        assert_eq!(rw["9p"]["cache"].as_str(), Some("mmap"));
+        assert_eq!(rw["9p"]["securityModel"].as_str(), Some("mapped-xattr"));
+
+        let ro = &parsed["mounts"][1];
+        assert_eq!(ro["writable"].as_bool(), Some(false));
+        assert_eq!(ro["9p"]["cache"].as_str(), Some("fscache"));
+        assert!(ro["9p"]["securityModel"].is_null());
+    }
+
+    #[test]
+    fn test_parse_lima_version() {
+        assert_eq!(
+            parse_lima_version("limactl version 1.0.3\n"),
+            Some((1, 0, 3))
+        );
+        assert_eq!(
+            parse_lima_version("limactl version 0.23.2"),
+            Some((0, 23, 2))
+        );
+        assert_eq!(
+            parse_lima_version("limactl version 1.1.0-beta.1"),
+            Some((1, 1, 0))
+        );
+        assert_eq!(parse_lima_version("limactl version v2.0"), Some((2, 0, 0)));
+        assert_eq!(parse_lima_version("limactl version HEAD-abc"), None);
+        assert_eq!(parse_lima_version(""), None);
+    }
+
+    #[test]
+    fn test_resolve_mount_type_defaults() {
+        // vz defaults to virtiofs when Lima is new enough (or unknown)
+        assert_eq!(
+            resolve_mount_type(None, true, Some((1, 0, 0))),
+            (Some(LimaMountType::Virtiofs), None)
+        );
+        assert_eq!(
+            resolve_mount_type(None, true, None),
+            (Some(LimaMountType::Virtiofs), None)
+        );
+        // Old Lima on vz quietly keeps Lima's default
+        assert_eq!(
+            resolve_mount_type(None, true, Some((0, 13, 0))),
+            (None, None)
+        );
+        // QEMU keeps Lima's default
+        assert_eq!(
+            resolve_mount_type(None, false, Some((1, 0, 0))),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_resolve_mount_type_explicit() {
+        assert_eq!(
+            resolve_mount_type(Some(LimaMountType::ReverseSshfs), true, Some((0, 1, 0))),
+            (Some(LimaMountType::ReverseSshfs), None)
+        );
+        assert_eq!(
+            resolve_mount_type(Some(LimaMountType::NineP), false, Some((0, 20, 0))),
+            (Some(LimaMountType::NineP), None)
+        );
+        assert_eq!(
+            resolve_mount_type(Some(LimaMountType::Virtiofs), false, Some((1, 2, 0))),
+            (Some(LimaMountType::Virtiofs), None)
+        );
+    }
+
+    #[test]
+    fn test_resolve_mount_type_unsupported_falls_back() {
+        let (mount_type, warning) =
+            resolve_mount_type(Some(LimaMountType::NineP), true, Some((1, 0, 0)));
+        assert_eq!(mount_type, None);
+        let warning = warning.unwrap();
+        assert!(warning.contains("'9p'"));
+        assert!(warning.contains("vz"));
+
+        let (mount_type, warning) =
+            resolve_mount_type(Some(LimaMountType::Virtiofs), false, Some((0, 20, 1)));
+        assert_eq!(mount_type, None);
+        assert!(warning.unwrap().contains("requires Lima 1.0 or newer"));
+    }
 }
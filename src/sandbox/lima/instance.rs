@@ -2,9 +2,10 @@
 
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::config::Config;
 
@@ -57,6 +58,84 @@ pub(crate) fn check_vm_state(vm_name: &str) -> Result<VmState> {
     }
 }
 
+/// File name prefix of the Lima configs written when creating a VM.
+const CONFIG_TEMP_PREFIX: &str = "workmux-lima-";
+
+/// Path of the temporary Lima config used to create `vm_name`.
+pub fn config_temp_path(vm_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}{}.yaml", CONFIG_TEMP_PREFIX, vm_name))
+}
+
+/// Remove the temporary Lima config for `vm_name`, if present.
+pub fn remove_config_temp_file(vm_name: &str) {
+    let path = config_temp_path(vm_name);
+    if let Err(e) = std::fs::remove_file(&path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!(vm = %vm_name, error = %e, "failed to remove Lima config temp file");
+    }
+}
+
+/// Path of the lock file that serializes creating and starting `vm_name`.
+fn vm_lock_path(vm_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}{}.lock", CONFIG_TEMP_PREFIX, vm_name))
+}
+
+/// Take an exclusive lock on `path`, blocking until it is free. The lock is
+/// released when the returned file is dropped.
+///
+/// `on_wait` is called once if another process holds the lock.
+fn lock_file(path: &Path, on_wait: impl FnOnce()) -> Result<std::fs::File> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(std::fs::TryLockError::WouldBlock) => {
+            on_wait();
+            file.lock()
+                .with_context(|| format!("Failed to lock {}", path.display()))?;
+        }
+        Err(std::fs::TryLockError::Error(e)) => {
+            return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+        }
+    }
+    Ok(file)
+}
+
+/// Find temporary Lima configs in `dir` whose VM no longer exists.
+/// Returns each file's path and size in bytes.
+pub fn orphaned_config_files(dir: &Path, live_vms: &HashSet<&str>) -> Result<Vec<(PathBuf, u64)>> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+
+    let mut orphans = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(vm_name) = file_name
+            .to_str()
+            .and_then(|n| n.strip_prefix(CONFIG_TEMP_PREFIX))
+            .and_then(|n| n.strip_suffix(".yaml"))
+        else {
+            continue;
+        };
+        if live_vms.contains(vm_name) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_file() {
+            orphans.push((entry.path(), metadata.len()));
+        }
+    }
+    orphans.sort();
+    Ok(orphans)
+}
+
 /// Lima VM operations.
 pub struct LimaInstance;
 
@@ -70,6 +149,15 @@ impl LimaInstance {
             .unwrap_or(false)
     }
 
+    /// Installed Lima version, or None if it can't be determined.
+    pub fn version() -> Option<super::config::LimaVersion> {
+        let output = Command::new("limactl").arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        super::parse_lima_version(&String::from_utf8_lossy(&output.stdout))
+    }
+
     /// List all Lima instances.
     pub fn list() -> Result<Vec<LimaInstanceInfo>> {
         let output = Command::new("limactl")
@@ -85,6 +173,11 @@ impl LimaInstance {
         parse_lima_instances(&output.stdout)
     }
 
+    /// Check whether the named VM is currently running.
+    pub fn is_running(name: &str) -> Result<bool> {
+        Ok(matches!(check_vm_state(name)?, VmState::Running))
+    }
+
     /// Stop a Lima VM by name. This is idempotent -- succeeds if the VM is already stopped.
     pub fn stop_by_name(name: &str) -> Result<()> {
         let output = Command::new("limactl")
@@ -104,6 +197,46 @@ impl LimaInstance {
 
         Ok(())
     }
+
+    /// Delete a Lima VM by name, with its temp config and state directory.
+    pub fn delete_by_name(name: &str) -> Result<()> {
+        let output = Command::new("limactl")
+            .arg("delete")
+            .arg(name)
+            .arg("--force")
+            .output()
+            .with_context(|| format!("Failed to execute limactl delete for '{}'", name))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("{}", stderr.trim());
+        }
+
+        remove_config_temp_file(name);
+        if let Ok(state_dir) = super::mounts::lima_state_dir_path(name)
+            && state_dir.exists()
+            && let Err(e) = std::fs::remove_dir_all(&state_dir)
+        {
+            warn!(vm = %name, error = %e, "failed to clean up state dir");
+        }
+        Ok(())
+    }
+
+    /// Run `true` in the VM to confirm it accepts shell sessions.
+    pub fn probe_shell(name: &str) -> Result<()> {
+        let output = Command::new("limactl")
+            .args(["shell", name, "--", "true"])
+            .stdin(std::process::Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to execute limactl shell for '{}'", name))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("limactl shell failed: {}", stderr.trim());
+        }
+
+        Ok(())
+    }
 }
 
 /// Ensure a Lima VM is running for the given worktree.
@@ -122,9 +255,19 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
         );
     }
 
-    let isolation = config.sandbox.lima.isolation();
+    let isolation = super::resolve_isolation(worktree_path, config);
     let vm_name = super::instance_name(worktree_path, isolation.clone(), config)?;
 
+    // Supervisors sharing a VM would otherwise race on `limactl start`. The
+    // first one creates or starts the VM; the others wait here and then find
+    // it running.
+    let _lock = lock_file(&vm_lock_path(&vm_name), || {
+        eprintln!(
+            "Waiting for another workmux process to start Lima VM {}...",
+            vm_name
+        );
+    })?;
+
     debug!(vm_name = %vm_name, "checking Lima VM state");
     let vm_state = check_vm_state(&vm_name)?;
 
@@ -189,10 +332,25 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
                     != DetectedToolchain::None
             };
 
-            let lima_config =
-                super::generate_lima_config(&vm_name, &mounts, &config.sandbox, agent, needs_nix)?;
+            let (mount_type, warning) = super::resolve_mount_type(
+                config.sandbox.lima.mount_type,
+                cfg!(target_os = "macos"),
+                LimaInstance::version(),
+            );
+            if let Some(warning) = warning {
+                eprintln!("Warning: {}", warning);
+            }
+
+            let lima_config = super::generate_lima_config(
+                &vm_name,
+                &mounts,
+                &config.sandbox,
+                agent,
+                needs_nix,
+                mount_type,
+            )?;
 
-            let config_path = std::env::temp_dir().join(format!("workmux-lima-{}.yaml", vm_name));
+            let config_path = config_temp_path(&vm_name);
             std::fs::write(&config_path, &lima_config).with_context(|| {
                 format!("Failed to write Lima config to {}", config_path.display())
             })?;
@@ -228,3 +386,127 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
     info!(vm_name = %vm_name, "Lima VM ready");
     Ok(vm_name)
 }
+
+/// Seconds to wait after the agent exits before stopping its VM, so a quick
+/// restart reuses the running VM.
+const STOP_VM_DELAY_SECS: u64 = 60;
+
+/// Shell script that stops `vm_name` after `delay_secs`, unless a supervisor
+/// endpoint file or a [`VmLease`] is in `rpc_dir` by then.
+fn delayed_stop_script(vm_name: &str, rpc_dir: &Path, delay_secs: u64) -> String {
+    let rpc_dir = crate::shell::shell_quote(&rpc_dir.to_string_lossy());
+    format!(
+        "sleep {delay_secs}; [ -n \"$(ls -A {rpc_dir} 2>/dev/null)\" ] || limactl stop {vm} >/dev/null 2>&1",
+        vm = crate::shell::shell_quote(vm_name),
+    )
+}
+
+/// Stop `vm_name` in the background once the supervisor is gone.
+///
+/// Runs detached in its own process group so closing the pane doesn't kill
+/// it. Supervisors sharing the VM each keep an endpoint file in `rpc_dir`,
+/// and shell sessions a lease, so the VM keeps running while any of them is
+/// alive.
+pub fn schedule_vm_stop(vm_name: &str, rpc_dir: &Path) {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let result = Command::new("sh")
+        .arg("-c")
+        .arg(delayed_stop_script(vm_name, rpc_dir, STOP_VM_DELAY_SECS))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn();
+    match result {
+        Ok(_) => info!(vm = %vm_name, delay_secs = STOP_VM_DELAY_SECS, "scheduled VM stop"),
+        Err(e) => warn!(vm = %vm_name, error = %e, "failed to schedule VM stop"),
+    }
+}
+
+/// Marks `vm_name` as in use by a process that has no RPC endpoint, such as
+/// `workmux sandbox shell`, so a scheduled stop leaves the VM running. The
+/// lease is removed when dropped.
+pub struct VmLease {
+    path: PathBuf,
+}
+
+impl Drop for VmLease {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Take a lease on `vm_name` for the current process.
+pub fn lease_vm(vm_name: &str) -> Result<VmLease> {
+    let rpc_dir = super::mounts::lima_state_dir_path(vm_name)?.join("rpc");
+    lease_in(&rpc_dir)
+}
+
+fn lease_in(rpc_dir: &Path) -> Result<VmLease> {
+    std::fs::create_dir_all(rpc_dir)
+        .with_context(|| format!("Failed to create {}", rpc_dir.display()))?;
+    let path = rpc_dir.join(format!("shell-{}.lease", std::process::id()));
+    std::fs::write(&path, "").with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(VmLease { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lease_holds_rpc_dir_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpc_dir = dir.path().join("rpc");
+        let lease = lease_in(&rpc_dir).unwrap();
+        assert_eq!(std::fs::read_dir(&rpc_dir).unwrap().count(), 1);
+        drop(lease);
+        assert_eq!(std::fs::read_dir(&rpc_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn lock_file_waits_for_the_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workmux-lima-wm-test.lock");
+        let held = lock_file(&path, || panic!("lock was free")).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let waiter = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let _lock = lock_file(&path, || tx.send(()).unwrap()).unwrap();
+            })
+        };
+        rx.recv_timeout(std::time::Duration::from_secs(5))
+            .expect("second locker should wait");
+        drop(held);
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn orphaned_config_files_skips_live_vms_and_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("workmux-lima-wm-live.yaml"), "live").unwrap();
+        std::fs::write(dir.path().join("workmux-lima-wm-gone.yaml"), "gone!").unwrap();
+        std::fs::write(dir.path().join("workmux-lima-wm-gone.txt"), "other").unwrap();
+        std::fs::write(dir.path().join("unrelated.yaml"), "other").unwrap();
+
+        let live = HashSet::from(["wm-live"]);
+        let orphans = orphaned_config_files(dir.path(), &live).unwrap();
+        assert_eq!(
+            orphans,
+            vec![(dir.path().join("workmux-lima-wm-gone.yaml"), 5)]
+        );
+    }
+
+    #[test]
+    fn delayed_stop_script_checks_for_other_supervisors() {
+        let script = delayed_stop_script("wm-proj-1a2b", Path::new("/state/wm-proj-1a2b/rpc"), 60);
+        assert_eq!(
+            script,
+            "sleep 60; [ -n \"$(ls -A /state/wm-proj-1a2b/rpc 2>/dev/null)\" ] || limactl stop wm-proj-1a2b >/dev/null 2>&1"
+        );
+    }
+}
@@ -8,8 +8,11 @@ pub(crate) mod log_format;
 pub(crate) mod mounts;
 mod wrap;
 
-pub use config::generate_lima_config;
-pub use instance::{LimaInstance, LimaInstanceInfo, ensure_vm_running, parse_lima_instances};
+pub use config::{generate_lima_config, parse_lima_version, resolve_mount_type};
+pub use instance::{
+    LimaInstance, LimaInstanceInfo, ensure_vm_running, lease_vm, orphaned_config_files,
+    parse_lima_instances, remove_config_temp_file, schedule_vm_stop,
+};
 pub use mounts::{determine_project_root, generate_mounts};
 pub use wrap::wrap_for_lima;
 
@@ -18,7 +21,7 @@ pub const VM_PREFIX: &str = "wm-";
 
 use crate::config::{Config, IsolationLevel};
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 
 /// Sanitize a project name for use in a Lima VM instance name.
@@ -48,6 +51,9 @@ fn sanitize_name(name: &str, max_len: usize) -> String {
 }
 
 /// Hash a key and return the first `len` hex characters (zero-padded).
+///
+/// Existing VM names are derived from this hash, so it stays on
+/// `DefaultHasher`; new build-stable hashes use [`crate::util::fnv1a`].
 fn hash_key(key: &str, len: usize) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -59,10 +65,23 @@ fn hash_key(key: &str, len: usize) -> String {
     hex[..len].to_string()
 }
 
+/// Resolve the isolation level for a worktree.
+///
+/// A level recorded with `workmux add --isolation` takes precedence over
+/// `sandbox.lima.isolation`, so every operation on the worktree targets the
+/// VM it was created with.
+pub fn resolve_isolation(worktree: &Path, config: &Config) -> IsolationLevel {
+    crate::git::worktree_handle_for(worktree)
+        .and_then(|handle| crate::git::get_worktree_isolation(&handle))
+        .unwrap_or_else(|| config.sandbox.lima.isolation())
+}
+
 /// Generate a unique instance name for a worktree based on isolation level.
 ///
 /// For project isolation, the name includes the project directory name for
 /// human readability: `wm-<project>-<hash8>`.
+/// For worktree isolation, it is `wm-<worktree>-<hash8>`, hashed from the
+/// worktree path.
 /// For shared isolation, the name is a hash of "global": `wm-<hash8>`.
 pub fn instance_name(
     worktree: &Path,
@@ -75,35 +94,87 @@ pub fn instance_name(
             let hash = hash_key("global", 8);
             format!("{}{}", VM_PREFIX, hash)
         }
-        IsolationLevel::Project => {
-            let project_root = determine_project_root(worktree)?;
-            let canonical = project_root
-                .canonicalize()
-                .unwrap_or_else(|_| project_root.clone());
-            let key = canonical.to_string_lossy();
-
-            let hash = hash_key(&key, 8);
-
-            // Extract project directory name for human-readable prefix
-            // Budget: "wm-" (3) + project (up to 18) + "-" (1) + hash (8) = 30 max
-            let project_dir_name = canonical
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            let sanitized = sanitize_name(&project_dir_name, 18);
-
-            if sanitized.is_empty() {
-                format!("{}{}", VM_PREFIX, hash)
-            } else {
-                format!("{}{}-{}", VM_PREFIX, sanitized, hash)
-            }
-        }
+        IsolationLevel::Project => named_for_dir(&determine_project_root(worktree)?),
+        IsolationLevel::Worktree => named_for_dir(worktree),
     };
 
     debug!(isolation = ?isolation, vm_name = %name, "resolved Lima VM instance name");
     Ok(name)
 }
 
+/// `wm-<dir>-<hash8>`, hashed from the canonical path of `dir`.
+fn named_for_dir(dir: &Path) -> String {
+    let canonical = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let key = canonical.to_string_lossy();
+
+    let hash = hash_key(&key, 8);
+
+    // Extract directory name for human-readable prefix
+    // Budget: "wm-" (3) + dir (up to 18) + "-" (1) + hash (8) = 30 max
+    let dir_name = canonical
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let sanitized = sanitize_name(&dir_name, 18);
+
+    if sanitized.is_empty() {
+        format!("{}{}", VM_PREFIX, hash)
+    } else {
+        format!("{}{}-{}", VM_PREFIX, sanitized, hash)
+    }
+}
+
+/// Delete a worktree's throwaway VM (`isolation: worktree`) along with its
+/// state directory. Called when the worktree is removed.
+pub fn delete_worktree_vm(worktree: &Path, config: &Config) -> Result<()> {
+    let vm_name = instance_name(worktree, IsolationLevel::Worktree, config)?;
+    debug!(vm_name = %vm_name, "deleting worktree Lima VM");
+    LimaInstance::delete_by_name(&vm_name)
+}
+
+/// Release a worktree's hold on its Lima VM once its window is gone.
+///
+/// Killing the window skips the supervisor's exit path, so its RPC endpoint
+/// files stay behind and would keep the VM running indefinitely. This removes
+/// them and schedules the same delayed stop the supervisor would have, which
+/// still leaves the VM up while other worktrees use it.
+pub fn release_vm(release: &VmRelease) -> Result<()> {
+    let rpc_dir = mounts::lima_state_dir_path(&release.vm_name)?.join("rpc");
+    let removed = crate::sandbox::rpc::remove_endpoint_files(&rpc_dir, &release.worktree);
+    debug!(vm_name = %release.vm_name, removed, stop = release.stop, "releasing Lima VM for worktree");
+    if release.stop {
+        schedule_vm_stop(&release.vm_name, &rpc_dir);
+    }
+    Ok(())
+}
+
+/// The VM [`release_vm`] releases for a worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VmRelease {
+    pub worktree: PathBuf,
+    pub vm_name: String,
+    /// Whether releasing schedules the VM's delayed stop.
+    pub stop: bool,
+}
+
+/// Work out which VM a worktree holds, without releasing it.
+pub fn plan_release(
+    worktree: &Path,
+    isolation: IsolationLevel,
+    config: &Config,
+) -> Result<VmRelease> {
+    let worktree = worktree
+        .canonicalize()
+        .unwrap_or_else(|_| worktree.to_path_buf());
+    let stop = config.sandbox.lima.stop_vm_on_exit(&isolation);
+    let vm_name = instance_name(&worktree, isolation, config)?;
+    Ok(VmRelease {
+        worktree,
+        vm_name,
+        stop,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +242,14 @@ mod tests {
         let b = hash_key("bar", 8);
         assert_ne!(a, b);
     }
+
+    #[test]
+    fn test_worktree_isolation_names_vm_after_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let worktree = dir.path().join("feature-auth");
+        std::fs::create_dir(&worktree).unwrap();
+        let name = instance_name(&worktree, IsolationLevel::Worktree, &Config::default()).unwrap();
+        assert!(name.starts_with("wm-feature-auth-"), "{name}");
+        assert_eq!(name.len(), "wm-feature-auth-".len() + 8);
+    }
 }
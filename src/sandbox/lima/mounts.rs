@@ -228,6 +228,16 @@ pub fn generate_mounts(
                 }
             }
         }
+
+        IsolationLevel::Worktree => {
+            // The worktree itself, plus the git common dir its `.git` file
+            // points into
+            mounts.push(Mount::rw(worktree.to_path_buf()));
+            let git_common_dir = determine_git_common_dir(worktree)?;
+            if !git_common_dir.starts_with(worktree) {
+                mounts.push(Mount::rw(git_common_dir));
+            }
+        }
     }
 
     // Mount agent config directory
@@ -15,10 +15,80 @@ pub use container::DOCKERFILE_BASE;
 pub use container::KNOWN_AGENTS;
 pub(crate) use container::build_docker_run_args;
 pub use container::build_image;
+pub use container::check_image_build_hash;
 pub use container::dockerfile_for_agent;
 pub(crate) use container::ensure_sandbox_config_dirs;
+pub use container::image_override;
 pub use container::pull_image;
-pub use container::stop_containers_for_handle;
+pub(crate) use container::stop_planned_containers;
 pub use container::wrap_for_container;
+pub use container::{planned_container_stops, stop_sandbox_for_handle};
 pub use lima::ensure_vm_running as ensure_lima_vm;
 pub use lima::wrap_for_lima;
+
+use crate::config::{Config, IsolationLevel, SandboxBackend};
+use crate::git::{self, SandboxRecord};
+use anyhow::Result;
+
+/// What [`teardown_for_handle`] does for a worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TeardownPlan {
+    /// Stop these registered containers (possibly none).
+    Containers(Vec<String>),
+    /// Release the worktree's hold on a Lima VM.
+    Lima(lima::VmRelease),
+}
+
+/// Plan the teardown of the sandbox a worktree's last launch used, without
+/// doing it. `close --dry-run` reports this plan.
+pub fn plan_teardown(handle: &str, config: &Config) -> Result<TeardownPlan> {
+    let record = git::get_worktree_sandbox(handle);
+    if record != Some(SandboxRecord::On(SandboxBackend::Lima)) {
+        return Ok(TeardownPlan::Containers(planned_container_stops(
+            handle,
+            record.as_ref(),
+            &config.sandbox,
+        )));
+    }
+    let isolation =
+        git::get_worktree_isolation(handle).unwrap_or_else(|| config.sandbox.lima.isolation());
+    let (path, _) = git::find_worktree(handle)?;
+    Ok(TeardownPlan::Lima(lima::plan_release(
+        &path, isolation, config,
+    )?))
+}
+
+/// Tear down the sandbox a worktree's last launch used, before its window is
+/// killed: containers are stopped, a Lima VM is released for a delayed stop,
+/// and worktrees launched without a sandbox are left alone.
+pub fn teardown_for_handle(handle: &str, config: &Config) {
+    let done = plan_teardown(handle, config).and_then(|plan| match plan {
+        TeardownPlan::Containers(containers) => {
+            stop_planned_containers(handle, &containers, &config.sandbox);
+            Ok(())
+        }
+        TeardownPlan::Lima(release) => lima::release_vm(&release),
+    });
+    if let Err(e) = done {
+        tracing::warn!(handle, error = %e, "failed to tear down sandbox");
+    }
+}
+
+/// Delete the throwaway Lima VM of a worktree created with
+/// `isolation: worktree`. Called when the worktree is removed, after
+/// [`teardown_for_handle`]; other isolation levels keep their VM.
+pub fn delete_worktree_vm(handle: &str, config: &Config) {
+    if git::get_worktree_sandbox(handle) != Some(SandboxRecord::On(SandboxBackend::Lima)) {
+        return;
+    }
+    let isolation =
+        git::get_worktree_isolation(handle).unwrap_or_else(|| config.sandbox.lima.isolation());
+    if isolation != IsolationLevel::Worktree {
+        return;
+    }
+    let deleted =
+        git::find_worktree(handle).and_then(|(path, _)| lima::delete_worktree_vm(&path, config));
+    if let Err(e) = deleted {
+        tracing::warn!(handle, error = %e, "failed to delete worktree Lima VM");
+    }
+}
@@ -4,14 +4,15 @@
 //! workmux binary connects via a host-internal address and sends JSON-lines
 //! requests.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 use crate::config::Config;
@@ -38,6 +39,10 @@ pub enum RpcRequest {
     Exec {
         command: String,
         args: Vec<String>,
+        /// Per-command timeout in seconds from `WM_HOSTEXEC_TIMEOUT`;
+        /// overrides `sandbox.host_exec_timeout`, 0 disables it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
     },
     Merge {
         name: String,
@@ -50,6 +55,26 @@ pub enum RpcRequest {
         no_hooks: bool,
         notification: bool,
     },
+    /// Copy text to the host clipboard.
+    Clipboard {
+        text: String,
+    },
+    /// Open an http(s) URL in the host browser.
+    OpenUrl {
+        url: String,
+    },
+    /// Show a desktop notification on the host.
+    DesktopNotification {
+        title: String,
+        body: String,
+    },
+    /// Look up git credentials on the host (git credential helper protocol).
+    GitCredential {
+        operation: String,
+        host: String,
+    },
+    /// Record that the worktree's setup script succeeded.
+    SetupDone,
 }
 
 /// RPC response sent from host to guest.
@@ -57,11 +82,63 @@ pub enum RpcRequest {
 #[serde(tag = "type")]
 pub enum RpcResponse {
     Ok,
-    Error { message: String },
-    Output { message: String },
-    ExecOutput { data: String },
-    ExecError { data: String },
-    ExecExit { code: i32 },
+    Error {
+        message: String,
+        /// Missing from hosts that predate error codes.
+        #[serde(default)]
+        code: RpcErrorCode,
+    },
+    Output {
+        message: String,
+    },
+    ExecOutput {
+        data: String,
+    },
+    ExecError {
+        data: String,
+    },
+    ExecExit {
+        code: i32,
+    },
+    GitCredential {
+        username: String,
+        password: String,
+    },
+}
+
+impl RpcResponse {
+    pub fn error(code: RpcErrorCode, message: impl Into<String>) -> Self {
+        RpcResponse::Error {
+            message: message.into(),
+            code,
+        }
+    }
+}
+
+/// Category of an `RpcResponse::Error`, so the guest can tell failures that
+/// may succeed on retry from ones that never will.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RpcErrorCode {
+    /// Bad token, or the host config disallows the request.
+    Unauthorized,
+    /// The host does not understand the request (e.g. an older host binary).
+    Unsupported,
+    /// The request was understood but its arguments are invalid.
+    InvalidRequest,
+    /// The host failed while handling the request.
+    #[default]
+    Internal,
+}
+
+/// Wire form of a request or response. The guest numbers each request and
+/// the host echoes the number on every response to it. Peers that predate
+/// ids omit the field.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(flatten)]
+    body: T,
 }
 
 // ── Server ──────────────────────────────────────────────────────────────
@@ -82,6 +159,10 @@ pub struct RpcContext {
     pub detected_toolchain: crate::sandbox::toolchain::DetectedToolchain,
     /// Whether to allow host-exec without bwrap on Linux.
     pub allow_unsandboxed_host_exec: bool,
+    /// Whether to answer git credential requests from the guest.
+    pub git_credentials: bool,
+    /// Default timeout for host-exec commands.
+    pub exec_timeout: Option<Duration>,
 }
 
 /// TCP RPC server that accepts guest connections.
@@ -109,15 +190,22 @@ impl RpcServer {
     }
 
     /// Spawn a background thread that accepts connections and dispatches handlers.
-    pub fn spawn(self, ctx: Arc<RpcContext>) -> thread::JoinHandle<()> {
+    pub fn spawn(self, ctx: Arc<RpcContext>) -> RpcServerHandle {
         /// Max concurrent RPC connections. One sandbox session typically uses a
         /// single connection, so 16 is generous while still preventing thread
         /// exhaustion from malicious connection floods.
         const MAX_CONNECTIONS: usize = 16;
 
         let active = Arc::new(AtomicUsize::new(0));
-        thread::spawn(move || {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let port = self.port;
+        let stop = Arc::clone(&shutdown);
+        let thread = thread::spawn(move || {
             for stream in self.listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    debug!("RPC server shutting down");
+                    break;
+                }
                 match stream {
                     Ok(stream) => {
                         let current = active.load(Ordering::Relaxed);
@@ -142,7 +230,34 @@ impl RpcServer {
                     }
                 }
             }
-        })
+        });
+
+        RpcServerHandle {
+            shutdown,
+            port,
+            thread,
+        }
+    }
+}
+
+/// Handle to a running RPC acceptor thread.
+pub struct RpcServerHandle {
+    shutdown: Arc<AtomicBool>,
+    port: u16,
+    thread: thread::JoinHandle<()>,
+}
+
+impl RpcServerHandle {
+    /// Stop accepting connections and wait for the acceptor thread to exit,
+    /// closing the listener. Connections already open finish on their own
+    /// threads.
+    pub fn shutdown(self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the blocking accept with a throwaway connection
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+        if self.thread.join().is_err() {
+            warn!("RPC acceptor thread panicked");
+        }
     }
 }
 
@@ -241,10 +356,8 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
         serde_json::from_str(auth_line.trim()).context("Failed to parse auth header")?;
 
     if !constant_time_eq(auth.token.as_bytes(), ctx.token.as_bytes()) {
-        let resp = RpcResponse::Error {
-            message: "Invalid token".to_string(),
-        };
-        write_response(&mut writer, &resp)?;
+        let resp = RpcResponse::error(RpcErrorCode::Unauthorized, "Invalid token");
+        write_response(&mut writer, None, &resp)?;
         return Ok(());
     }
 
@@ -264,8 +377,23 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
             continue;
         }
 
-        let request: RpcRequest = serde_json::from_str(line.trim())
+        let value: serde_json::Value = serde_json::from_str(line.trim())
             .with_context(|| format!("Failed to parse RPC request: {}", line.trim()))?;
+        let id = value.get("id").and_then(|id| id.as_u64());
+        // Valid JSON that isn't a known request most likely comes from a newer
+        // guest binary; answer instead of dropping the connection.
+        let request = match serde_json::from_value::<Envelope<RpcRequest>>(value) {
+            Ok(envelope) => envelope.body,
+            Err(e) => {
+                warn!(error = %e, "unsupported RPC request");
+                let resp = RpcResponse::error(
+                    RpcErrorCode::Unsupported,
+                    format!("Unsupported RPC request: {}", e),
+                );
+                write_response(&mut writer, id, &resp)?;
+                continue;
+            }
+        };
 
         info!(?request, "RPC request received");
 
@@ -273,9 +401,15 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
         if let RpcRequest::Exec {
             ref command,
             ref args,
+            timeout_secs,
         } = request
         {
-            handle_exec(command, args, ctx, &mut writer)?;
+            let timeout = match timeout_secs {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => ctx.exec_timeout,
+            };
+            handle_exec(command, args, timeout, ctx, &mut writer, id)?;
             continue;
         }
 
@@ -306,21 +440,27 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
                 notification,
                 &ctx.worktree_path,
                 &mut writer,
+                id,
             )?;
             continue;
         }
 
         let response = dispatch_request(&request, ctx);
-        debug!(?response, "RPC response");
+        // Never log credential values
+        if matches!(response, RpcResponse::GitCredential { .. }) {
+            debug!("RPC response: GitCredential (redacted)");
+        } else {
+            debug!(?response, "RPC response");
+        }
 
-        write_response(&mut writer, &response)?;
+        write_response(&mut writer, id, &response)?;
     }
 
     Ok(())
 }
 
-fn write_response(writer: &mut impl Write, response: &RpcResponse) -> Result<()> {
-    let mut json = serde_json::to_string(response)?;
+fn write_response(writer: &mut impl Write, id: Option<u64>, response: &RpcResponse) -> Result<()> {
+    let mut json = serde_json::to_string(&Envelope { id, body: response })?;
     json.push('\n');
     writer.write_all(json.as_bytes())?;
     writer.flush()?;
@@ -331,7 +471,10 @@ fn write_response(writer: &mut impl Write, response: &RpcResponse) -> Result<()>
 
 fn dispatch_request(request: &RpcRequest, ctx: &RpcContext) -> RpcResponse {
     match request {
-        RpcRequest::Heartbeat => RpcResponse::Ok,
+        RpcRequest::Heartbeat => {
+            crate::state::record_heartbeat(&*ctx.mux, &ctx.pane_id);
+            RpcResponse::Ok
+        }
         RpcRequest::SetStatus { status } => handle_set_status(status, ctx),
         RpcRequest::SetTitle { title } => handle_set_title(title, ctx),
         RpcRequest::SpawnAgent {
@@ -344,6 +487,24 @@ fn dispatch_request(request: &RpcRequest, ctx: &RpcContext) -> RpcResponse {
             *background,
             &ctx.worktree_path,
         ),
+        RpcRequest::Clipboard { text } => match crate::clipboard::copy(text) {
+            Ok(()) => RpcResponse::Ok,
+            Err(e) => RpcResponse::error(RpcErrorCode::Internal, e.to_string()),
+        },
+        RpcRequest::OpenUrl { url } => match crate::browser::open(url) {
+            Ok(()) => RpcResponse::Ok,
+            Err(e) => RpcResponse::error(RpcErrorCode::Internal, e.to_string()),
+        },
+        RpcRequest::DesktopNotification { title, body } => {
+            match crate::notification::send(title, body) {
+                Ok(()) => RpcResponse::Ok,
+                Err(e) => RpcResponse::error(RpcErrorCode::Internal, e.to_string()),
+            }
+        }
+        RpcRequest::GitCredential { operation, host } => {
+            handle_git_credential(operation, host, ctx)
+        }
+        RpcRequest::SetupDone => handle_setup_done(ctx),
         RpcRequest::Exec { .. } => {
             // Handled in handle_connection before dispatch
             unreachable!("Exec is handled directly in handle_connection")
@@ -358,67 +519,54 @@ fn dispatch_request(request: &RpcRequest, ctx: &RpcContext) -> RpcResponse {
 // ── Handlers ────────────────────────────────────────────────────────────
 
 fn handle_set_status(status: &str, ctx: &RpcContext) -> RpcResponse {
-    // Reuse the same logic as set_window_status command
     let config = match Config::load(None) {
         Ok(c) => c,
         Err(e) => {
-            return RpcResponse::Error {
-                message: format!("Failed to load config: {}", e),
-            };
+            return RpcResponse::error(
+                RpcErrorCode::Internal,
+                format!("Failed to load config: {}", e),
+            );
         }
     };
 
-    let (agent_status, icon, auto_clear) = match status.to_lowercase().as_str() {
-        "working" => (
-            Some(AgentStatus::Working),
-            config.status_icons.working().to_string(),
-            false,
-        ),
-        "waiting" => (
-            Some(AgentStatus::Waiting),
-            config.status_icons.waiting().to_string(),
-            true,
-        ),
-        "done" => (
-            Some(AgentStatus::Done),
-            config.status_icons.done().to_string(),
-            true,
-        ),
+    let agent_status = match status.to_lowercase().as_str() {
+        "working" => AgentStatus::Working,
+        "waiting" => AgentStatus::Waiting,
+        "done" => AgentStatus::Done,
         "clear" => {
             if let Err(e) = ctx.mux.clear_status(&ctx.pane_id) {
-                return RpcResponse::Error {
-                    message: format!("Failed to clear status: {}", e),
-                };
+                return RpcResponse::error(
+                    RpcErrorCode::Internal,
+                    format!("Failed to clear status: {}", e),
+                );
             }
             return RpcResponse::Ok;
         }
         _ => {
-            return RpcResponse::Error {
-                message: format!("Unknown status: {}", status),
-            };
+            return RpcResponse::error(
+                RpcErrorCode::InvalidRequest,
+                format!("Unknown status: {}", status),
+            );
         }
     };
 
-    if config.status_format.unwrap_or(true) {
-        let _ = ctx.mux.ensure_status_format(&ctx.pane_id);
-    }
-
-    match ctx.mux.set_status(&ctx.pane_id, &icon, auto_clear) {
-        Ok(()) => {
-            // Persist agent state to StateStore so the dashboard sees this agent
-            if let Some(agent_status) = agent_status {
-                crate::state::persist_agent_update(
-                    &*ctx.mux,
-                    &ctx.pane_id,
-                    Some(agent_status),
-                    None,
-                );
-            }
-            RpcResponse::Ok
-        }
-        Err(e) => RpcResponse::Error {
-            message: format!("Failed to set status: {}", e),
-        },
+    // Same path as `workmux set-window-status` on the host, so notifications
+    // and status hooks fire for sandboxed agents too.
+    // SECURITY: status hooks run unsandboxed on the host. `Config::merge`
+    // keeps them global-only, so a guest editing the worktree's
+    // .workmux.yaml cannot choose what runs here.
+    match crate::command::set_window_status::apply_status(
+        &config,
+        &*ctx.mux,
+        &ctx.pane_id,
+        agent_status,
+        Some(&ctx.worktree_path),
+    ) {
+        Ok(()) => RpcResponse::Ok,
+        Err(e) => RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("Failed to set status: {}", e),
+        ),
     }
 }
 
@@ -440,10 +588,122 @@ fn handle_set_title(title: &str, ctx: &RpcContext) -> RpcResponse {
             );
             RpcResponse::Ok
         }
-        Err(e) => RpcResponse::Error {
-            message: format!("Failed to set title: {}", e),
-        },
+        Err(e) => RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("Failed to set title: {}", e),
+        ),
+    }
+}
+
+/// Mark the supervised worktree as set up. The handle comes from the
+/// supervisor's worktree, so a guest can only mark its own worktree.
+fn handle_setup_done(ctx: &RpcContext) -> RpcResponse {
+    let Some(handle) = crate::git::worktree_handle_for(&ctx.worktree_path) else {
+        return RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("Not in a worktree: {}", ctx.worktree_path.display()),
+        );
+    };
+    match crate::git::set_worktree_setup_done(&handle) {
+        Ok(()) => RpcResponse::Ok,
+        Err(e) => RpcResponse::error(RpcErrorCode::Internal, format!("{:#}", e)),
+    }
+}
+
+fn handle_git_credential(operation: &str, host: &str, ctx: &RpcContext) -> RpcResponse {
+    if !ctx.git_credentials {
+        return RpcResponse::error(
+            RpcErrorCode::Unauthorized,
+            "Git credential proxying is disabled (sandbox.git_credentials: false)",
+        );
+    }
+    if let Err(e) = validate_credential_host(host) {
+        return RpcResponse::error(RpcErrorCode::InvalidRequest, e.to_string());
+    }
+
+    match operation {
+        "get" => {}
+        // The guest never writes to the host's credential store
+        "store" | "erase" => return RpcResponse::Ok,
+        _ => {
+            return RpcResponse::error(
+                RpcErrorCode::InvalidRequest,
+                format!("Unknown git credential operation: {}", operation),
+            );
+        }
+    }
+
+    match git_credential_fill(host) {
+        Ok(Some((username, password))) => RpcResponse::GitCredential { username, password },
+        Ok(None) => RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("No git credentials found for {}", host),
+        ),
+        Err(e) => RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("git credential fill failed: {}", e),
+        ),
+    }
+}
+
+/// Reject hosts that could inject extra fields into the credential protocol.
+fn validate_credential_host(host: &str) -> Result<()> {
+    if host.is_empty() {
+        anyhow::bail!("Git credential request is missing a host");
+    }
+    if host
+        .chars()
+        .any(|c| c.is_control() || c == '=' || c.is_whitespace())
+    {
+        anyhow::bail!("Invalid host in git credential request: {:?}", host);
+    }
+    Ok(())
+}
+
+/// Ask the host's git credential helpers for an HTTPS credential.
+///
+/// Runs outside the worktree: its `.git/config` is writable from the guest,
+/// so a planted `credential.helper` there would execute on the host.
+/// Terminal prompts are disabled since nobody is there to answer them.
+fn git_credential_fill(host: &str) -> Result<Option<(String, String)>> {
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .current_dir(std::env::temp_dir())
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_CONFIG_PARAMETERS")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run git credential fill")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        write!(stdin, "protocol=https\nhost={}\n\n", host)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(parse_credential_output(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Extract username and password from `git credential fill` output.
+fn parse_credential_output(output: &str) -> Option<(String, String)> {
+    let mut username = String::new();
+    let mut password = None;
+    for line in output.lines() {
+        match line.split_once('=') {
+            Some(("username", value)) => username = value.to_string(),
+            Some(("password", value)) => password = Some(value.to_string()),
+            _ => {}
+        }
     }
+    password.map(|password| (username, password))
 }
 
 /// Disable git native hooks for a spawned command and all its children.
@@ -504,13 +764,15 @@ fn handle_spawn_agent(
         Ok(output) if output.status.success() => RpcResponse::Ok,
         Ok(output) => {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            RpcResponse::Error {
-                message: format!("workmux add failed: {}", stderr.trim()),
-            }
+            RpcResponse::error(
+                RpcErrorCode::Internal,
+                format!("workmux add failed: {}", stderr.trim()),
+            )
         }
-        Err(e) => RpcResponse::Error {
-            message: format!("Failed to run workmux add: {}", e),
-        },
+        Err(e) => RpcResponse::error(
+            RpcErrorCode::Internal,
+            format!("Failed to run workmux add: {}", e),
+        ),
     }
 }
 
@@ -525,6 +787,7 @@ fn handle_merge(
     notification: bool,
     worktree_path: &PathBuf,
     writer: &mut impl Write,
+    id: Option<u64>,
 ) -> Result<()> {
     use std::process::{Command, Stdio};
 
@@ -570,9 +833,11 @@ fn handle_merge(
         Err(e) => {
             write_response(
                 writer,
-                &RpcResponse::Error {
-                    message: format!("Failed to run workmux merge: {}", e),
-                },
+                id,
+                &RpcResponse::error(
+                    RpcErrorCode::Internal,
+                    format!("Failed to run workmux merge: {}", e),
+                ),
             )?;
             return Ok(());
         }
@@ -623,7 +888,7 @@ fn handle_merge(
     // Stream responses; kill child on write failure (mirrors handle_exec pattern)
     let stream_result = (|| -> Result<()> {
         for chunk in rx {
-            write_response(writer, &RpcResponse::Output { message: chunk })?;
+            write_response(writer, id, &RpcResponse::Output { message: chunk })?;
         }
         Ok(())
     })();
@@ -639,16 +904,18 @@ fn handle_merge(
 
     let status = child.wait()?;
     if status.success() {
-        write_response(writer, &RpcResponse::Ok)?;
+        write_response(writer, id, &RpcResponse::Ok)?;
     } else {
         write_response(
             writer,
-            &RpcResponse::Error {
-                message: format!(
+            id,
+            &RpcResponse::error(
+                RpcErrorCode::Internal,
+                format!(
                     "workmux merge exited with code {}",
                     status.code().unwrap_or(1)
                 ),
-            },
+            ),
         )?;
     }
 
@@ -693,31 +960,89 @@ fn sanitized_env() -> std::collections::HashMap<String, String> {
     envs
 }
 
+/// Translate an `afplay` request for a host without `afplay` into the
+/// host's own audio player. The sound file is afplay's last argument.
+/// Returns None when the command should run unchanged.
+fn sound_command(
+    command: &str,
+    args: &[String],
+    is_installed: impl Fn(&str) -> bool,
+) -> Result<Option<(String, Vec<String>)>> {
+    if command != "afplay" || is_installed("afplay") {
+        return Ok(None);
+    }
+    let Some(path) = args.last() else {
+        return Ok(None);
+    };
+    crate::sound::player_command(path).map(Some)
+}
+
+/// Why the host refuses to run `command`, or None if it is allowed.
+fn exec_rejection(
+    command: &str,
+    allowed_commands: &std::collections::HashSet<String>,
+) -> Option<String> {
+    if !crate::sandbox::shims::validate_command_name(command) {
+        return Some(format!(
+            "workmux host-exec: invalid command name '{}'\n",
+            command
+        ));
+    }
+    if !allowed_commands.contains(command) {
+        return Some(format!(
+            "workmux host-exec: '{}' is not allowed on the host. \
+             Add it to sandbox.host_commands in your global config.\n",
+            command
+        ));
+    }
+    None
+}
+
 fn handle_exec(
     command: &str,
     args: &[String],
+    timeout: Option<Duration>,
     ctx: &RpcContext,
     writer: &mut impl Write,
+    id: Option<u64>,
 ) -> Result<()> {
     info!(command, ?args, "host-exec request");
 
-    // Validate command name format (strict alphanumeric + dash/underscore/dot)
-    if !crate::sandbox::shims::validate_command_name(command) {
-        let resp = RpcResponse::ExecExit { code: 127 };
-        write_response(writer, &resp)?;
+    // Validate command name format (strict alphanumeric + dash/underscore/dot),
+    // then the allowlist: `host_commands` from global config plus built-ins
+    if let Some(reason) = exec_rejection(command, &ctx.allowed_commands) {
+        warn!(command, "host-exec request rejected");
+        write_response(writer, id, &RpcResponse::ExecError { data: reason })?;
+        write_response(writer, id, &RpcResponse::ExecExit { code: 127 })?;
         return Ok(());
     }
 
-    // Validate command is in allowlist
-    if !ctx.allowed_commands.contains(command) {
-        let resp = RpcResponse::ExecExit { code: 127 };
-        write_response(writer, &resp)?;
-        return Ok(());
-    }
+    // afplay only exists on macOS; elsewhere play the sound with the host's own player
+    let sound_command = match sound_command(command, args, |program| which::which(program).is_ok())
+    {
+        Ok(sound_command) => sound_command,
+        Err(e) => {
+            warn!(command, error = %e, "no audio backend for sound playback");
+            write_response(
+                writer,
+                id,
+                &RpcResponse::ExecError {
+                    data: format!("{e}\n"),
+                },
+            )?;
+            write_response(writer, id, &RpcResponse::ExecExit { code: 127 })?;
+            return Ok(());
+        }
+    };
+    let (command, args) = match &sound_command {
+        Some((program, player_args)) => (program.as_str(), player_args.as_slice()),
+        None => (command, args),
+    };
 
     // Skip toolchain wrapping for built-in host commands (e.g., afplay) since they
     // exist outside the project's devbox/nix environment
-    let is_builtin = crate::sandbox::shims::BUILTIN_HOST_COMMANDS.contains(&command);
+    let is_builtin =
+        sound_command.is_some() || crate::sandbox::shims::BUILTIN_HOST_COMMANDS.contains(&command);
     let wrapper_script = if !is_builtin {
         crate::sandbox::toolchain::toolchain_wrapper_script(&ctx.detected_toolchain)
     } else {
@@ -749,6 +1074,7 @@ fn handle_exec(
         &ctx.worktree_path,
         &envs,
         ctx.allow_unsandboxed_host_exec,
+        timeout.is_some(),
     );
 
     let mut child = match spawn_result {
@@ -757,11 +1083,12 @@ fn handle_exec(
             warn!(command, error = %e, "failed to spawn command");
             write_response(
                 writer,
+                id,
                 &RpcResponse::ExecError {
                     data: format!("host-exec spawn failed: {e}\n"),
                 },
             )?;
-            write_response(writer, &RpcResponse::ExecExit { code: 126 })?;
+            write_response(writer, id, &RpcResponse::ExecExit { code: 126 })?;
             return Ok(());
         }
     };
@@ -774,43 +1101,46 @@ fn handle_exec(
 
     let tx_out = tx.clone();
     let stdout_thread = thread::spawn(move || {
-        use std::io::Read;
-        let mut buf = [0u8; 8192];
-        loop {
-            match stdout.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    let data = String::from_utf8_lossy(&buf[..n]).into_owned();
-                    let _ = tx_out.send(RpcResponse::ExecOutput { data });
-                }
-                Err(_) => break,
-            }
-        }
+        pump_exec_output(&mut stdout, &tx_out, |data| RpcResponse::ExecOutput {
+            data,
+        })
     });
 
     let tx_err = tx.clone();
     let stderr_thread = thread::spawn(move || {
-        use std::io::Read;
-        let mut buf = [0u8; 8192];
-        loop {
-            match stderr.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    let data = String::from_utf8_lossy(&buf[..n]).into_owned();
-                    let _ = tx_err.send(RpcResponse::ExecError { data });
-                }
-                Err(_) => break,
-            }
-        }
+        pump_exec_output(&mut stderr, &tx_err, |data| RpcResponse::ExecError { data })
     });
 
     // Drop our sender so rx closes when threads finish
     drop(tx);
 
-    // Stream responses as they arrive; kill child on write failure
+    // Stream responses as they arrive; kill child on write failure. On timeout,
+    // kill the process group and keep draining so output already produced
+    // still reaches the guest.
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let pid = child.id();
+    let mut timed_out = false;
     let stream_result = (|| -> Result<()> {
-        for response in rx {
-            write_response(writer, &response)?;
+        loop {
+            let response = match deadline {
+                Some(deadline) if !timed_out => {
+                    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                        Ok(response) => response,
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                            warn!(command, pid, "host-exec timed out, killing process group");
+                            kill_process_group(pid);
+                            timed_out = true;
+                            continue;
+                        }
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                _ => match rx.recv() {
+                    Ok(response) => response,
+                    Err(_) => break,
+                },
+            };
+            write_response(writer, id, &response)?;
         }
         Ok(())
     })();
@@ -825,13 +1155,92 @@ fn handle_exec(
     stderr_thread.join().ok();
 
     let status = child.wait()?;
+    if let (true, Some(timeout)) = (timed_out, timeout) {
+        write_response(
+            writer,
+            id,
+            &RpcResponse::ExecError {
+                data: timeout_message(command, timeout),
+            },
+        )?;
+        write_response(
+            writer,
+            id,
+            &RpcResponse::ExecExit {
+                code: EXEC_TIMEOUT_EXIT_CODE,
+            },
+        )?;
+        return Ok(());
+    }
     let code = status.code().unwrap_or(1);
     info!(command, code, "host-exec finished");
 
-    write_response(writer, &RpcResponse::ExecExit { code })?;
+    write_response(writer, id, &RpcResponse::ExecExit { code })?;
     Ok(())
 }
 
+/// Forward a child's output as it is read, without waiting for newlines, so
+/// progress bars and `\r` updates show up live in the guest.
+fn pump_exec_output(
+    reader: &mut impl std::io::Read,
+    tx: &std::sync::mpsc::Sender<RpcResponse>,
+    wrap: impl Fn(String) -> RpcResponse,
+) {
+    let mut buf = [0u8; 8192];
+    let mut pending = Vec::new();
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                pending.extend_from_slice(&buf[..n]);
+                let data = drain_utf8(&mut pending);
+                if !data.is_empty() && tx.send(wrap(data)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let _ = tx.send(wrap(String::from_utf8_lossy(&pending).into_owned()));
+    }
+}
+
+/// Decode the complete UTF-8 prefix of `buf`, keeping a character cut off by
+/// the read boundary for the next read instead of turning it into U+FFFD.
+fn drain_utf8(buf: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(buf) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => buf.len(),
+    };
+    let rest = buf.split_off(complete);
+    let data = String::from_utf8_lossy(buf).into_owned();
+    *buf = rest;
+    data
+}
+
+/// Exit code reported for a host-exec command killed by its timeout,
+/// matching coreutils `timeout`.
+const EXEC_TIMEOUT_EXIT_CODE: i32 = 124;
+
+fn timeout_message(command: &str, timeout: Duration) -> String {
+    format!(
+        "workmux host-exec: '{}' timed out after {}s and was killed \
+         (set WM_HOSTEXEC_TIMEOUT to change the limit)\n",
+        command,
+        timeout.as_secs()
+    )
+}
+
+/// Kill a host-exec child spawned in its own process group, along with
+/// anything it started.
+fn kill_process_group(pid: u32) {
+    // SAFETY: kill(2) with a negative pid signals the process group; the
+    // child was spawned as the group leader, so this can't hit other processes.
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGKILL);
+    }
+}
+
 // ── Client ──────────────────────────────────────────────────────────────
 
 /// RPC client for guest-side use. Connects to the host supervisor.
@@ -842,11 +1251,33 @@ fn handle_exec(
 pub struct RpcClient {
     reader: BufReader<TcpStream>,
     writer: TcpStream,
+    /// Id for the next request.
+    next_id: u64,
+    /// Id of the last request sent, which responses must echo.
+    pending_id: Option<u64>,
 }
 
 impl RpcClient {
-    /// Connect using WM_RPC_HOST, WM_RPC_PORT, and WM_RPC_TOKEN env vars.
+    /// Connect to the host supervisor.
+    ///
+    /// Lookup order:
+    /// 1. `WM_RPC_HOST`, `WM_RPC_PORT`, and `WM_RPC_TOKEN` env vars
+    /// 2. The endpoint file for the current directory (or its nearest
+    ///    ancestor) in the guest's endpoint directory, for guest processes
+    ///    that did not inherit the env vars
     pub fn from_env() -> Result<Self> {
+        if std::env::var_os("WM_RPC_HOST").is_none()
+            && std::env::var_os("WM_RPC_PORT").is_none()
+            && std::env::var_os("WM_RPC_TOKEN").is_none()
+            && let Some(endpoint) = super::guest::endpoint_dir().and_then(|dir| {
+                let cwd = std::env::current_dir().ok()?;
+                find_endpoint(&dir, &cwd)
+            })
+        {
+            debug!(host = %endpoint.host, port = endpoint.port, "using RPC endpoint file");
+            return Self::connect(&endpoint.host, endpoint.port, &endpoint.token);
+        }
+
         let host = std::env::var("WM_RPC_HOST").context("WM_RPC_HOST not set")?;
         let port: u16 = std::env::var("WM_RPC_PORT")
             .context("WM_RPC_PORT not set")?
@@ -875,7 +1306,12 @@ impl RpcClient {
         (&*writer_ref).write_all(auth_json.as_bytes())?;
         (&*writer_ref).flush()?;
 
-        Ok(Self { reader, writer })
+        Ok(Self {
+            reader,
+            writer,
+            next_id: 1,
+            pending_id: None,
+        })
     }
 
     /// Send a request and receive a response.
@@ -884,24 +1320,161 @@ impl RpcClient {
         self.recv()
     }
 
+    /// Connect to the host and send a request it answers with `Ok`. An
+    /// error response fails with "Host failed to `action`".
+    pub fn call_host(request: &RpcRequest, action: &str) -> Result<()> {
+        match Self::from_env()?.call(request)? {
+            RpcResponse::Ok => Ok(()),
+            RpcResponse::Error {
+                code: RpcErrorCode::Unsupported,
+                ..
+            } => bail!(
+                "Host workmux is too old to {}; update it on the host",
+                action
+            ),
+            RpcResponse::Error { message, .. } => {
+                bail!("Host failed to {}: {}", action, message)
+            }
+            other => bail!("Unexpected RPC response: {:?}", other),
+        }
+    }
+
     /// Send a request without waiting for a response.
     pub fn send(&mut self, request: &RpcRequest) -> Result<()> {
-        let mut req_json = serde_json::to_string(request)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut req_json = serde_json::to_string(&Envelope {
+            id: Some(id),
+            body: request,
+        })?;
         req_json.push('\n');
         (&self.writer).write_all(req_json.as_bytes())?;
         (&self.writer).flush()?;
+        self.pending_id = Some(id);
         Ok(())
     }
 
-    /// Receive a single response line.
+    /// Receive a single response line for the last request sent.
+    ///
+    /// Fails if the response echoes a different request id. Responses
+    /// without an id (from older hosts) are accepted.
     pub fn recv(&mut self) -> Result<RpcResponse> {
         let mut line = String::new();
         self.reader.read_line(&mut line)?;
-        serde_json::from_str(&line)
-            .with_context(|| format!("Failed to parse RPC response: {}", line))
+        let envelope: Envelope<RpcResponse> = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse RPC response: {}", line))?;
+        check_response_id(self.pending_id, envelope.id)?;
+        Ok(envelope.body)
     }
 }
 
+/// Verify that a response belongs to the request that is waiting for it.
+fn check_response_id(expected: Option<u64>, received: Option<u64>) -> Result<()> {
+    match (expected, received) {
+        (Some(expected), Some(received)) if expected != received => anyhow::bail!(
+            "RPC response id {} does not match request id {}",
+            received,
+            expected
+        ),
+        _ => Ok(()),
+    }
+}
+
+// ── Endpoint file ───────────────────────────────────────────────────────
+
+/// Guest directory (relative to `$HOME`) holding persisted RPC endpoints.
+pub const ENDPOINT_DIR: &str = ".workmux-state/rpc";
+
+/// RPC connection details persisted for guest processes without the env vars.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RpcEndpoint {
+    pub host: String,
+    pub port: u16,
+    pub token: String,
+}
+
+/// An endpoint file on the host, removed when dropped.
+pub struct EndpointFile {
+    path: PathBuf,
+}
+
+impl Drop for EndpointFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// One file per worktree so concurrent supervisors sharing a state dir
+/// (Lima shared isolation) don't overwrite each other.
+fn endpoint_file_name(worktree: &Path) -> String {
+    format!("{}.json", crate::util::path_file_stem(worktree))
+}
+
+/// An endpoint file's content: the endpoint plus the path it was written
+/// for, so [`remove_endpoint_files`] can find files for subdirectories.
+#[derive(Serialize)]
+struct StoredEndpoint<'a> {
+    #[serde(flatten)]
+    endpoint: &'a RpcEndpoint,
+    worktree: &'a Path,
+}
+
+#[derive(Deserialize)]
+struct EndpointOwner {
+    worktree: PathBuf,
+}
+
+/// Write the endpoint for `worktree` into `dir`, readable only by the owner.
+pub fn write_endpoint_file(
+    dir: &Path,
+    worktree: &Path,
+    endpoint: &RpcEndpoint,
+) -> Result<EndpointFile> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+
+    let path = dir.join(endpoint_file_name(worktree));
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // The mode only applies on create; tighten a pre-existing file too
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    serde_json::to_writer(&mut file, &StoredEndpoint { endpoint, worktree })?;
+    Ok(EndpointFile { path })
+}
+
+/// Remove endpoint files left in `dir` for `worktree` or any path beneath it,
+/// e.g. by supervisors killed with their window. Returns how many were removed.
+pub fn remove_endpoint_files(dir: &Path, worktree: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            std::fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| serde_json::from_str::<EndpointOwner>(&content).ok())
+                .is_some_and(|owner| owner.worktree.starts_with(worktree))
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Find the endpoint for `cwd` or its nearest ancestor worktree in `dir`.
+fn find_endpoint(dir: &Path, cwd: &Path) -> Option<RpcEndpoint> {
+    cwd.ancestors().find_map(|candidate| {
+        let content = std::fs::read_to_string(dir.join(endpoint_file_name(candidate))).ok()?;
+        serde_json::from_str(&content).ok()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -914,6 +1487,12 @@ mod tests {
         assert!(json.contains("\"type\":\"Heartbeat\""));
     }
 
+    #[test]
+    fn test_request_serialization_setup_done() {
+        let json = serde_json::to_string(&RpcRequest::SetupDone).unwrap();
+        assert!(json.contains("\"type\":\"SetupDone\""));
+    }
+
     #[test]
     fn test_request_serialization_set_status() {
         let req = RpcRequest::SetStatus {
@@ -953,12 +1532,51 @@ mod tests {
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"Ok\""));
 
-        let resp = RpcResponse::Error {
-            message: "oops".to_string(),
-        };
+        let resp = RpcResponse::error(RpcErrorCode::Unsupported, "oops");
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"Error\""));
         assert!(json.contains("\"message\":\"oops\""));
+        assert!(json.contains("\"code\":\"Unsupported\""));
+    }
+
+    #[test]
+    fn test_error_without_code_defaults_to_internal() {
+        // Older hosts send errors without a code
+        let resp: RpcResponse =
+            serde_json::from_str(r#"{"type":"Error","message":"oops"}"#).unwrap();
+        match resp {
+            RpcResponse::Error { code, .. } => assert_eq!(code, RpcErrorCode::Internal),
+            other => panic!("Expected Error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_write_response_echoes_id() {
+        let mut buf = Vec::new();
+        write_response(&mut buf, Some(7), &RpcResponse::Ok).unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert_eq!(line, "{\"id\":7,\"type\":\"Ok\"}\n");
+
+        let envelope: Envelope<RpcResponse> = serde_json::from_str(&line).unwrap();
+        assert_eq!(envelope.id, Some(7));
+        assert!(matches!(envelope.body, RpcResponse::Ok));
+    }
+
+    #[test]
+    fn test_request_envelope_without_id() {
+        let envelope: Envelope<RpcRequest> =
+            serde_json::from_str(r#"{"type":"SetTitle","title":"x"}"#).unwrap();
+        assert_eq!(envelope.id, None);
+        assert!(matches!(envelope.body, RpcRequest::SetTitle { .. }));
+    }
+
+    #[test]
+    fn test_check_response_id() {
+        assert!(check_response_id(Some(2), Some(2)).is_ok());
+        assert!(check_response_id(Some(2), None).is_ok());
+        assert!(check_response_id(None, Some(5)).is_ok());
+        let err = check_response_id(Some(2), Some(1)).unwrap_err();
+        assert!(err.to_string().contains("does not match request id 2"));
     }
 
     #[test]
@@ -999,7 +1617,7 @@ mod tests {
         let port = server.port();
         let token = generate_token();
 
-        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux);
+        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default());
         let ctx = Arc::new(RpcContext {
             pane_id: "%0".to_string(),
             worktree_path: PathBuf::from("/tmp/test"),
@@ -1008,6 +1626,8 @@ mod tests {
             allowed_commands: std::collections::HashSet::new(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: false,
+            git_credentials: true,
+            exec_timeout: None,
         });
 
         let _handle = server.spawn(ctx);
@@ -1023,11 +1643,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_server_shutdown_closes_listener() {
+        let server = RpcServer::bind().unwrap();
+        let port = server.port();
+
+        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default());
+        let ctx = Arc::new(RpcContext {
+            pane_id: "%0".to_string(),
+            worktree_path: PathBuf::from("/tmp/test"),
+            mux,
+            token: generate_token(),
+            allowed_commands: std::collections::HashSet::new(),
+            detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
+            allow_unsandboxed_host_exec: false,
+            git_credentials: false,
+            exec_timeout: None,
+        });
+
+        server.spawn(ctx).shutdown();
+        assert!(TcpStream::connect(("127.0.0.1", port)).is_err());
+    }
+
+    #[test]
+    fn test_request_serialization_desktop_notification() {
+        let json = r#"{"type":"DesktopNotification","title":"workmux","body":"done"}"#;
+        match serde_json::from_str(json).unwrap() {
+            RpcRequest::DesktopNotification { title, body } => {
+                assert_eq!(title, "workmux");
+                assert_eq!(body, "done");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_request_serialization_exec() {
         let req = RpcRequest::Exec {
             command: "just".to_string(),
             args: vec!["check".to_string()],
+            timeout_secs: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"type\":\"Exec\""));
@@ -1035,7 +1690,7 @@ mod tests {
 
         let parsed: RpcRequest = serde_json::from_str(&json).unwrap();
         match parsed {
-            RpcRequest::Exec { command, args } => {
+            RpcRequest::Exec { command, args, .. } => {
                 assert_eq!(command, "just");
                 assert_eq!(args, vec!["check"]);
             }
@@ -1137,7 +1792,7 @@ mod tests {
         let port = server.port();
         let token = generate_token();
 
-        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux);
+        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default());
         let ctx = Arc::new(RpcContext {
             pane_id: "%0".to_string(),
             worktree_path: PathBuf::from("/tmp/test"),
@@ -1146,6 +1801,8 @@ mod tests {
             allowed_commands: std::collections::HashSet::new(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: false,
+            git_credentials: true,
+            exec_timeout: None,
         });
 
         let _handle = server.spawn(ctx);
@@ -1154,11 +1811,211 @@ mod tests {
         let mut client = RpcClient::connect("127.0.0.1", port, "wrong-token").unwrap();
         let resp = client.call(&RpcRequest::Heartbeat).unwrap();
         match resp {
-            RpcResponse::Error { message } => assert!(message.contains("Invalid token")),
+            RpcResponse::Error { message, code } => {
+                assert!(message.contains("Invalid token"));
+                assert_eq!(code, RpcErrorCode::Unauthorized);
+            }
             other => panic!("Expected Error, got {:?}", other),
         }
     }
 
+    #[test]
+    fn test_client_server_unknown_request_is_unsupported() {
+        let server = RpcServer::bind().unwrap();
+        let port = server.port();
+        let token = generate_token();
+
+        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default());
+        let ctx = Arc::new(RpcContext {
+            pane_id: "%0".to_string(),
+            worktree_path: PathBuf::from("/tmp/test"),
+            mux,
+            token: token.clone(),
+            allowed_commands: std::collections::HashSet::new(),
+            detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
+            allow_unsandboxed_host_exec: false,
+            git_credentials: true,
+            exec_timeout: None,
+        });
+
+        let _handle = server.spawn(ctx);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let mut client = RpcClient::connect("127.0.0.1", port, &token).unwrap();
+        (&client.writer)
+            .write_all(b"{\"id\":9,\"type\":\"FromTheFuture\"}\n")
+            .unwrap();
+        client.pending_id = Some(9);
+        match client.recv().unwrap() {
+            RpcResponse::Error { code, .. } => assert_eq!(code, RpcErrorCode::Unsupported),
+            other => panic!("Expected Error, got {:?}", other),
+        }
+
+        // The connection stays usable
+        assert!(matches!(
+            client.call(&RpcRequest::Heartbeat).unwrap(),
+            RpcResponse::Ok
+        ));
+    }
+
+    // ── Endpoint file tests ─────────────────────────────────────────────
+
+    #[test]
+    fn test_endpoint_file_roundtrip_from_subdirectory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("rpc");
+        let endpoint = RpcEndpoint {
+            host: "host.lima.internal".to_string(),
+            port: 4242,
+            token: "secret".to_string(),
+        };
+        let file =
+            write_endpoint_file(&dir, Path::new("/work/proj__worktrees/feat"), &endpoint).unwrap();
+
+        let mode = std::fs::metadata(&file.path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        assert_eq!(
+            find_endpoint(&dir, Path::new("/work/proj__worktrees/feat/src/bin")),
+            Some(endpoint)
+        );
+        assert_eq!(
+            find_endpoint(&dir, Path::new("/work/proj__worktrees/other")),
+            None
+        );
+
+        // Dropping the handle removes the file
+        let path = file.path.clone();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_remove_endpoint_files_for_worktree_and_subdirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("rpc");
+        let endpoint = RpcEndpoint {
+            host: "host.lima.internal".to_string(),
+            port: 4242,
+            token: "secret".to_string(),
+        };
+        for path in [
+            "/work/proj__worktrees/feat",
+            "/work/proj__worktrees/feat/apps/web",
+            "/work/proj__worktrees/feat-2",
+        ] {
+            std::mem::forget(write_endpoint_file(&dir, Path::new(path), &endpoint).unwrap());
+        }
+
+        let removed = remove_endpoint_files(&dir, Path::new("/work/proj__worktrees/feat"));
+        assert_eq!(removed, 2);
+        assert!(find_endpoint(&dir, Path::new("/work/proj__worktrees/feat-2")).is_some());
+        assert!(find_endpoint(&dir, Path::new("/work/proj__worktrees/feat")).is_none());
+    }
+
+    #[test]
+    fn test_endpoint_file_name_is_unique_per_path() {
+        let a = endpoint_file_name(Path::new("/a/b-c"));
+        let b = endpoint_file_name(Path::new("/a-b/c"));
+        assert_ne!(a, b);
+        assert!(!a.contains('/'));
+    }
+
+    #[test]
+    fn test_endpoint_file_name_stays_short_for_deep_paths() {
+        let deep = format!("/{}/{}", "dir/".repeat(100), "é".repeat(200));
+        let name = endpoint_file_name(Path::new(&deep));
+        assert!(name.len() < 100, "{} bytes", name.len());
+    }
+
+    // ── Git credential tests ────────────────────────────────────────────
+
+    fn credential_ctx(git_credentials: bool) -> RpcContext {
+        RpcContext {
+            pane_id: "%0".to_string(),
+            worktree_path: PathBuf::from("/tmp/test"),
+            mux: multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default()),
+            token: generate_token(),
+            allowed_commands: std::collections::HashSet::new(),
+            detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
+            allow_unsandboxed_host_exec: false,
+            git_credentials,
+            exec_timeout: None,
+        }
+    }
+
+    #[test]
+    fn test_git_credential_disabled_by_config() {
+        let resp = handle_git_credential("get", "github.com", &credential_ctx(false));
+        match resp {
+            RpcResponse::Error { message, code } => {
+                assert!(message.contains("disabled"));
+                assert_eq!(code, RpcErrorCode::Unauthorized);
+            }
+            other => panic!("Expected Error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_git_credential_store_and_erase_are_noops() {
+        let ctx = credential_ctx(true);
+        for operation in ["store", "erase"] {
+            assert!(matches!(
+                handle_git_credential(operation, "github.com", &ctx),
+                RpcResponse::Ok
+            ));
+        }
+        assert!(matches!(
+            handle_git_credential("approve", "github.com", &ctx),
+            RpcResponse::Error { .. }
+        ));
+    }
+
+    #[test]
+    fn test_git_credential_rejects_injected_host() {
+        let ctx = credential_ctx(true);
+        for host in ["", "github.com\nprotocol=http", "a=b", "git hub.com"] {
+            assert!(
+                matches!(
+                    handle_git_credential("get", host, &ctx),
+                    RpcResponse::Error { .. }
+                ),
+                "{host:?}"
+            );
+        }
+        assert!(validate_credential_host("github.com:8443").is_ok());
+    }
+
+    #[test]
+    fn test_open_url_rejects_non_http_schemes() {
+        let ctx = credential_ctx(true);
+        for url in ["file:///etc/passwd", "vscode://open", "javascript:alert(1)"] {
+            let request = RpcRequest::OpenUrl {
+                url: url.to_string(),
+            };
+            match dispatch_request(&request, &ctx) {
+                RpcResponse::Error { message, .. } => assert!(message.contains("http"), "{url}"),
+                other => panic!("Expected Error for {url}, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_credential_output() {
+        let output = "protocol=https\nhost=github.com\nusername=octo\npassword=tok=en\n";
+        assert_eq!(
+            parse_credential_output(output),
+            Some(("octo".to_string(), "tok=en".to_string()))
+        );
+        assert_eq!(
+            parse_credential_output("password=secret\n"),
+            Some((String::new(), "secret".to_string()))
+        );
+        assert_eq!(parse_credential_output("username=octo\n"), None);
+    }
+
     // ── Host-exec integration tests ─────────────────────────────────────
 
     /// Start an RPC server with the given allowed commands and return a
@@ -1171,13 +2028,13 @@ mod tests {
     fn start_exec_server(
         allowed: &[&str],
         allow_unsandboxed: bool,
-    ) -> (RpcClient, tempfile::TempDir, thread::JoinHandle<()>) {
+    ) -> (RpcClient, tempfile::TempDir, RpcServerHandle) {
         let server = RpcServer::bind().unwrap();
         let port = server.port();
         let token = generate_token();
         let tmp = tempfile::tempdir().unwrap();
 
-        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux);
+        let mux = multiplexer::create_backend(multiplexer::BackendType::Tmux, &Config::default());
         let ctx = Arc::new(RpcContext {
             pane_id: "%0".to_string(),
             worktree_path: tmp.path().to_path_buf(),
@@ -1186,6 +2043,8 @@ mod tests {
             allowed_commands: allowed.iter().map(|s| s.to_string()).collect(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: allow_unsandboxed,
+            git_credentials: false,
+            exec_timeout: None,
         });
 
         let handle = server.spawn(ctx);
@@ -1198,10 +2057,20 @@ mod tests {
     /// Send an exec request and collect all streaming responses into
     /// (stdout, stderr, exit_code).
     fn exec_collect(client: &mut RpcClient, command: &str, args: &[&str]) -> (String, String, i32) {
+        exec_collect_with_timeout(client, command, args, None)
+    }
+
+    fn exec_collect_with_timeout(
+        client: &mut RpcClient,
+        command: &str,
+        args: &[&str],
+        timeout_secs: Option<u64>,
+    ) -> (String, String, i32) {
         client
             .send(&RpcRequest::Exec {
                 command: command.to_string(),
                 args: args.iter().map(|s| s.to_string()).collect(),
+                timeout_secs,
             })
             .unwrap();
 
@@ -1225,11 +2094,96 @@ mod tests {
         assert_eq!(stdout.trim(), "hello world");
     }
 
+    #[test]
+    fn test_exec_timeout_kills_process_group_and_keeps_output() {
+        let (mut client, _tmp, _handle) = start_exec_server(&["sh"], true);
+        let started = Instant::now();
+        // The backgrounded sleep shares the group, so it must die too or the
+        // pipes would stay open and the request would hang
+        let (stdout, stderr, code) = exec_collect_with_timeout(
+            &mut client,
+            "sh",
+            &["-c", "echo partial; sleep 30 & wait"],
+            Some(1),
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(code, EXEC_TIMEOUT_EXIT_CODE);
+        assert_eq!(stdout.trim(), "partial");
+        assert!(stderr.contains("'sh' timed out after 1s"), "{stderr}");
+    }
+
+    #[test]
+    fn test_exec_timeout_zero_disables_default() {
+        let (mut client, _tmp, _handle) = start_exec_server(&["echo"], true);
+        let (stdout, _stderr, code) =
+            exec_collect_with_timeout(&mut client, "echo", &["done"], Some(0));
+        assert_eq!(code, 0);
+        assert_eq!(stdout.trim(), "done");
+    }
+
+    #[test]
+    fn test_drain_utf8_keeps_split_character() {
+        // "█" is three bytes; split it across two reads
+        let bar = "█".as_bytes();
+        let mut pending = b"50% ".to_vec();
+        pending.extend_from_slice(&bar[..2]);
+        assert_eq!(drain_utf8(&mut pending), "50% ");
+        assert_eq!(pending, &bar[..2]);
+
+        pending.extend_from_slice(&bar[2..]);
+        pending.extend_from_slice(b"\r");
+        assert_eq!(drain_utf8(&mut pending), "█\r");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_drain_utf8_replaces_invalid_bytes() {
+        let mut pending = vec![b'a', 0xff, b'b'];
+        assert_eq!(drain_utf8(&mut pending), "a\u{fffd}b");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_pump_exec_output_reassembles_chunks() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        // Two reads that split "▌" down the middle
+        let text = "progress ▏▎▍▌\rdone\n".as_bytes();
+        let split = "progress ▏▎▍".len() + 1;
+        let mut reader = std::io::Read::chain(&text[..split], &text[split..]);
+        pump_exec_output(&mut reader, &tx, |data| RpcResponse::ExecOutput { data });
+        drop(tx);
+        let out: String = rx
+            .into_iter()
+            .map(|r| match r {
+                RpcResponse::ExecOutput { data } => data,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(out, "progress ▏▎▍▌\rdone\n");
+    }
+
     #[test]
     fn test_exec_disallowed_command() {
         let (mut client, _tmp, _handle) = start_exec_server(&["echo"], true);
-        let (_stdout, _stderr, code) = exec_collect(&mut client, "ls", &[]);
+        let (_stdout, stderr, code) = exec_collect(&mut client, "ls", &[]);
         assert_eq!(code, 127, "disallowed command should return 127");
+        assert!(stderr.contains("'ls' is not allowed"), "{stderr}");
+        assert!(stderr.contains("sandbox.host_commands"), "{stderr}");
+    }
+
+    #[test]
+    fn test_sound_command_only_translates_missing_afplay() {
+        let args = vec![
+            "-v".to_string(),
+            "0.5".to_string(),
+            "/tmp/done.aiff".to_string(),
+        ];
+        // afplay present on the host runs unchanged
+        assert!(sound_command("afplay", &args, |_| true).unwrap().is_none());
+        // Other commands are never translated
+        assert!(sound_command("echo", &args, |_| false).unwrap().is_none());
+        // No sound file to hand to another player
+        assert!(sound_command("afplay", &[], |_| false).unwrap().is_none());
     }
 
     #[test]
@@ -1237,8 +2191,9 @@ mod tests {
         let (mut client, _tmp, _handle) = start_exec_server(&["echo"], true);
 
         // Shell metacharacters in command name
-        let (_stdout, _stderr, code) = exec_collect(&mut client, "echo;whoami", &[]);
+        let (_stdout, stderr, code) = exec_collect(&mut client, "echo;whoami", &[]);
         assert_eq!(code, 127);
+        assert!(stderr.contains("invalid command name"), "{stderr}");
 
         // Path traversal
         let (_stdout, _stderr, code) = exec_collect(&mut client, "/bin/echo", &[]);
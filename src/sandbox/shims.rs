@@ -4,6 +4,7 @@
 //! and route them to `workmux host-exec`.
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::os::unix::fs::symlink;
 use std::path::{Path, PathBuf};
@@ -106,6 +107,121 @@ pub fn create_shim_directory(state_dir: &Path, commands: &[String]) -> Result<Pa
     Ok(shim_bin)
 }
 
+/// Shell script that prints each of `commands` found on the guest's PATH,
+/// one per line. Run inside the guest before the shim directory is put on PATH.
+pub fn guest_lookup_script(commands: &[String]) -> String {
+    let names: Vec<&str> = commands
+        .iter()
+        .map(String::as_str)
+        .filter(|cmd| validate_command_name(cmd))
+        .collect();
+    format!(
+        "for c in {}; do command -v \"$c\" >/dev/null 2>&1 && echo \"$c\"; done; true",
+        names.join(" ")
+    )
+}
+
+/// Commands from `commands` listed in the output of `guest_lookup_script`.
+pub fn shadowed_commands(commands: &[String], lookup_output: &str) -> Vec<String> {
+    let found: Vec<&str> = lookup_output.lines().map(str::trim).collect();
+    commands
+        .iter()
+        .filter(|cmd| found.contains(&cmd.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Result of a guest PATH probe, cached per guest (VM or image ID).
+#[derive(Debug, Serialize, Deserialize)]
+struct ShadowCache {
+    /// Commands that were probed.
+    commands: Vec<String>,
+    /// Commands found on the guest PATH.
+    shadowed: Vec<String>,
+}
+
+/// Configured host commands that shadow a guest binary.
+///
+/// Reuses `cache_file` when it was written for the same commands, so the
+/// guest is only probed once per VM or image. Otherwise `probe` must run the
+/// given lookup script with `sh -c` inside the guest. Returns `None` when the
+/// probe fails.
+fn probe_shadowed_commands(
+    commands: &[String],
+    cache_file: &Path,
+    probe: impl FnOnce(&str) -> std::process::Command,
+) -> Option<Vec<String>> {
+    if let Ok(content) = fs::read_to_string(cache_file)
+        && let Ok(cache) = serde_json::from_str::<ShadowCache>(&content)
+        && cache.commands == commands
+    {
+        return Some(cache.shadowed);
+    }
+
+    let output = match probe(&guest_lookup_script(commands)).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            tracing::debug!(status = ?output.status, "guest PATH probe failed");
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "failed to run guest PATH probe");
+            return None;
+        }
+    };
+    let shadowed = shadowed_commands(commands, &String::from_utf8_lossy(&output.stdout));
+
+    let cache = ShadowCache {
+        commands: commands.to_vec(),
+        shadowed: shadowed.clone(),
+    };
+    let written = cache_file
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| {
+            fs::write(
+                cache_file,
+                serde_json::to_string(&cache).unwrap_or_default(),
+            )
+        });
+    if let Err(e) = written {
+        tracing::debug!(path = ?cache_file, error = %e, "failed to cache guest PATH probe");
+    }
+
+    Some(shadowed)
+}
+
+/// Warn about configured host commands that shadow a guest binary.
+///
+/// Built-in commands are skipped since they are meant to replace guest
+/// binaries. See [`probe_shadowed_commands`] for `cache_file` and `probe`.
+/// Probe failures are logged and otherwise ignored.
+pub fn warn_shadowed_commands(
+    user_commands: &[String],
+    cache_file: &Path,
+    probe: impl FnOnce(&str) -> std::process::Command,
+) {
+    let commands: Vec<String> = user_commands
+        .iter()
+        .filter(|cmd| !BUILTIN_HOST_COMMANDS.contains(&cmd.as_str()))
+        .cloned()
+        .collect();
+    if commands.is_empty() {
+        return;
+    }
+
+    for cmd in probe_shadowed_commands(&commands, cache_file, probe).unwrap_or_default() {
+        eprintln!(
+            "Warning: host_commands entry '{}' also exists in the sandbox; the host version takes precedence",
+            cmd
+        );
+        tracing::warn!(
+            command = %cmd,
+            "host_commands entry also exists on the guest PATH; the host version takes precedence"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +257,50 @@ mod tests {
         assert!(!shim_bin.join("/bin/evil").exists());
     }
 
+    #[test]
+    fn test_guest_lookup_script_skips_invalid_names() {
+        let script = guest_lookup_script(&["just".to_string(), "a;b".to_string()]);
+        assert!(script.starts_with("for c in just; do command -v"));
+        assert!(!script.contains("a;b"));
+    }
+
+    #[test]
+    fn test_shadowed_commands() {
+        let commands = vec!["just".to_string(), "cargo".to_string(), "npm".to_string()];
+        assert_eq!(
+            shadowed_commands(&commands, "cargo\nnpm\n"),
+            vec!["cargo", "npm"]
+        );
+        assert!(shadowed_commands(&commands, "").is_empty());
+    }
+
+    #[test]
+    fn test_probe_shadowed_commands_caches_result() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_file = tmp.path().join("cache/shadowed.json");
+        let commands = vec!["sh".to_string(), "wm-no-such-command".to_string()];
+        let run_locally = |script: &str| {
+            let mut cmd = std::process::Command::new("sh");
+            cmd.args(["-c", script]);
+            cmd
+        };
+
+        let shadowed = probe_shadowed_commands(&commands, &cache_file, run_locally);
+        assert_eq!(shadowed, Some(vec!["sh".to_string()]));
+        assert!(cache_file.exists());
+
+        // Cache hit: the probe is not run again
+        let shadowed = probe_shadowed_commands(&commands, &cache_file, |_| {
+            panic!("probe should not run on a cache hit")
+        });
+        assert_eq!(shadowed, Some(vec!["sh".to_string()]));
+
+        // Different commands invalidate the cache
+        let other = vec!["wm-no-such-command".to_string()];
+        let shadowed = probe_shadowed_commands(&other, &cache_file, run_locally);
+        assert_eq!(shadowed, Some(vec![]));
+    }
+
     #[test]
     fn test_effective_host_commands_includes_builtins() {
         let result = effective_host_commands(&[]);
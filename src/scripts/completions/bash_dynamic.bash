@@ -9,6 +9,11 @@ _workmux_git_branches() {
     workmux _complete-git-branches 2>/dev/null
 }
 
+# Dynamic Lima VM completion for sandbox stop
+_workmux_vms() {
+    workmux _complete-vms 2>/dev/null
+}
+
 # Wrapper that adds dynamic completion
 _workmux_dynamic() {
     local cur prev words cword
@@ -40,13 +45,20 @@ _workmux_dynamic() {
                     return
                 fi
                 ;;
-            open|remove|rm|path|close|send|capture|status|wait|run)
+            open|remove|rm|path|close|attach|switch|send|capture|status|wait|run)
                 # Positional arg: handles
                 if [[ "$cur" != -* ]]; then
                     COMPREPLY=($(compgen -W "$(_workmux_handles)" -- "$cur"))
                     return
                 fi
                 ;;
+            sandbox)
+                # Positional arg of `sandbox stop`: running VMs
+                if [[ "${words[2]}" == "stop" && ${cword} -ge 3 && "$cur" != -* ]]; then
+                    COMPREPLY=($(compgen -W "$(_workmux_vms)" -- "$cur"))
+                    return
+                fi
+                ;;
             add)
                 # Handle flags that take specific argument types
                 case "$prev" in
@@ -9,8 +9,15 @@ function __workmux_git_branches
     workmux _complete-git-branches 2>/dev/null
 end
 
+# Dynamic Lima VM completion for sandbox stop
+function __workmux_vms
+    workmux _complete-vms 2>/dev/null
+end
+
 # Add dynamic completions for commands that take worktree handles or branch names
 # (handles are the primary identifier shown in completions)
-complete -c workmux -n '__fish_seen_subcommand_from open remove rm path merge close send capture status wait run' -f -a '(__workmux_handles)'
+complete -c workmux -n '__fish_seen_subcommand_from open remove rm path merge close attach switch send capture status wait run' -f -a '(__workmux_handles)'
 # Add dynamic completions for add command (uses git branches)
 complete -c workmux -n '__fish_seen_subcommand_from add' -f -a '(__workmux_git_branches)'
+# Add dynamic completions for sandbox stop (running workmux VMs)
+complete -c workmux -n '__fish_seen_subcommand_from sandbox; and __fish_seen_subcommand_from stop' -f -a '(__workmux_vms)'
@@ -0,0 +1,32 @@
+//! Play notification sounds with whichever audio player the host has.
+
+use anyhow::{Result, anyhow};
+
+/// Audio players to try on `os`, in order of preference.
+fn candidates(os: &str) -> &'static [&'static str] {
+    match os {
+        "macos" => &["afplay"],
+        _ => &["paplay", "aplay"],
+    }
+}
+
+/// Pick the first installed audio player (`afplay` on macOS, `paplay` or
+/// `aplay` on Linux) and return the program and arguments that play `path`.
+pub fn player_command(path: &str) -> Result<(String, Vec<String>)> {
+    let player =
+        crate::host_tool::first_available(candidates(std::env::consts::OS)).ok_or_else(|| {
+            anyhow!("No audio backend found. Install afplay (macOS), or paplay or aplay (Linux).")
+        })?;
+    Ok((player.to_string(), vec![path.to_string()]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_per_platform() {
+        assert_eq!(candidates("macos"), ["afplay"]);
+        assert_eq!(candidates("linux"), ["paplay", "aplay"]);
+    }
+}
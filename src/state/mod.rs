@@ -14,7 +14,28 @@ use tracing::warn;
 use crate::multiplexer::{AgentStatus, Multiplexer};
 
 pub use store::StateStore;
-pub use types::{AgentState, PaneKey};
+pub use types::{AgentState, DoneEntry, PaneKey};
+
+/// Record a heartbeat for the agent in `pane_id` (best-effort).
+///
+/// Only agents that already have stored state are updated; the first status
+/// update creates the entry.
+pub fn record_heartbeat(mux: &dyn Multiplexer, pane_id: &str) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let result = StateStore::new().and_then(|store| store.record_heartbeat(&pane_key, now));
+    if let Err(e) = result {
+        warn!(error = %e, "failed to record heartbeat");
+    }
+}
 
 /// Persist an agent state update to the StateStore.
 ///
@@ -24,12 +45,14 @@ pub use types::{AgentState, PaneKey};
 ///   falling back to the live pane title.
 ///
 /// Logs warnings on failure without propagating errors (best-effort persistence).
+/// Returns the status stored before this update, so callers can act only on
+/// transitions.
 pub fn persist_agent_update(
     mux: &dyn Multiplexer,
     pane_id: &str,
     status: Option<AgentStatus>,
     title_override: Option<String>,
-) {
+) -> Option<AgentStatus> {
     let pane_key = PaneKey {
         backend: mux.name().to_string(),
         instance: mux.instance_id(),
@@ -40,11 +63,11 @@ pub fn persist_agent_update(
         Ok(Some(info)) => info,
         Ok(None) => {
             warn!(%pane_id, "pane not found, skipping state persist");
-            return;
+            return None;
         }
         Err(e) => {
             warn!(error = %e, "failed to get live pane info, skipping state persist");
-            return;
+            return None;
         }
     };
 
@@ -53,21 +76,32 @@ pub fn persist_agent_update(
         .map(|d| d.as_secs())
         .unwrap_or(0);
 
+    let Ok(store) = StateStore::new() else {
+        return None;
+    };
+    // Hold the agent's lock across the merge so a concurrent heartbeat or
+    // status update isn't lost
+    let lock = store.lock_agent(&pane_key);
+    if let Err(e) = &lock {
+        warn!(error = %e, "failed to lock agent state");
+    }
+
     // Load existing state to merge with
-    let existing = StateStore::new()
-        .ok()
-        .and_then(|store| store.get_agent(&pane_key).ok().flatten());
+    let existing = store.get_agent(&pane_key).ok().flatten();
 
     // Resolve status: explicit update wins, otherwise preserve existing
-    let final_status = status.or(existing.as_ref().and_then(|e| e.status));
+    let previous_status = existing.as_ref().and_then(|e| e.status);
+    let final_status = status.or(previous_status);
 
     // Preserve existing status_ts if status hasn't changed (avoids resetting timer)
-    let status_ts = if final_status == existing.as_ref().and_then(|e| e.status) {
+    let status_ts = if final_status == previous_status {
         existing.as_ref().and_then(|e| e.status_ts).unwrap_or(now)
     } else {
         now
     };
 
+    let last_heartbeat = existing.as_ref().and_then(|e| e.last_heartbeat);
+
     // Resolve title: explicit override wins, then existing stored title, then live
     let pane_title = title_override
         .or(existing.and_then(|e| e.pane_title))
@@ -84,11 +118,22 @@ pub fn persist_agent_update(
         updated_ts: now,
         window_name: live_info.window,
         session_name: live_info.session,
+        last_heartbeat,
     };
 
-    if let Ok(store) = StateStore::new()
-        && let Err(e) = store.upsert_agent(&state)
-    {
+    if let Err(e) = store.upsert_agent(&state) {
         warn!(error = %e, "failed to persist agent state");
     }
+    drop(lock);
+
+    // Track completion order for last-done cycling
+    let stack_result = match status {
+        Some(AgentStatus::Done) => store.push_done_pane(&state.pane_key),
+        Some(_) => store.remove_done_pane(&state.pane_key),
+        None => Ok(()),
+    };
+    if let Err(e) = stack_result {
+        warn!(error = %e, "failed to update done-stack");
+    }
+    previous_status
 }
@@ -1,12 +1,14 @@
 //! Filesystem-based state persistence for agent state.
 
 use anyhow::{Context, Result};
+use nix::fcntl::{Flock, FlockArg};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{info, warn};
 
-use super::types::{AgentState, GlobalSettings, PaneKey};
+use super::types::{AgentState, DoneEntry, GlobalSettings, PaneKey};
 
 /// Manages filesystem-based state persistence for workmux agents.
 ///
@@ -14,9 +16,11 @@ use super::types::{AgentState, GlobalSettings, PaneKey};
 /// ```text
 /// $XDG_STATE_HOME/workmux/           # ~/.local/state/workmux/
 /// ├── settings.json                   # Global dashboard settings
-/// └── agents/
-///     ├── tmux__default__%1.json     # {backend}__{instance}__{pane_id}.json
-///     └── wezterm__main__3.json
+/// ├── agents/
+/// │   ├── tmux__default__%1.json     # {backend}__{instance}__{pane_id}.json
+/// │   └── wezterm__main__3.json
+/// └── done/
+///     └── tmux__default.json         # Done-stack per {backend}__{instance}
 /// ```
 pub struct StateStore {
     base_path: PathBuf,
@@ -51,6 +55,11 @@ impl StateStore {
         self.base_path.join("containers")
     }
 
+    /// Path to done-stacks directory.
+    fn done_dir(&self) -> PathBuf {
+        self.base_path.join("done")
+    }
+
     /// Path to settings file.
     fn settings_path(&self) -> PathBuf {
         self.base_path.join("settings.json")
@@ -61,6 +70,11 @@ impl StateStore {
         self.agents_dir().join(key.to_filename())
     }
 
+    /// Path to a specific agent's lock file, next to its state file.
+    fn agent_lock_path(&self, key: &PaneKey) -> PathBuf {
+        self.agent_path(key).with_extension("lock")
+    }
+
     /// Create or update agent state.
     ///
     /// Uses atomic write (temp file + rename) for crash safety.
@@ -78,6 +92,34 @@ impl StateStore {
         read_agent_file(&self.agent_path(key))
     }
 
+    /// Take an exclusive lock on one agent's state, held until the guard is
+    /// dropped. Serializes read-modify-write updates of the same pane, such
+    /// as a status change racing a heartbeat.
+    pub fn lock_agent(&self, key: &PaneKey) -> Result<Flock<fs::File>> {
+        let lock_file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.agent_lock_path(key))
+            .context("Failed to open agent lock file")?;
+        Flock::lock(lock_file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| errno)
+            .context("Failed to lock agent state")
+    }
+
+    /// Set `last_heartbeat` on a stored agent.
+    ///
+    /// Returns false if the agent has no stored state yet.
+    pub fn record_heartbeat(&self, key: &PaneKey, ts: u64) -> Result<bool> {
+        let _lock = self.lock_agent(key)?;
+        let Some(mut state) = self.get_agent(key)? else {
+            return Ok(false);
+        };
+        state.last_heartbeat = Some(ts);
+        self.upsert_agent(&state)?;
+        Ok(true)
+    }
+
     /// List all agent states.
     ///
     /// Used for reconciliation and dashboard display.
@@ -104,16 +146,18 @@ impl StateStore {
         Ok(agents)
     }
 
-    /// Delete agent state.
+    /// Delete agent state and its lock file.
     ///
-    /// No-op if the file doesn't exist.
+    /// No-op for files that don't exist.
     pub fn delete_agent(&self, key: &PaneKey) -> Result<()> {
-        let path = self.agent_path(key);
-        match fs::remove_file(&path) {
-            Ok(()) => Ok(()),
-            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
-            Err(e) => Err(e).context("Failed to delete agent state"),
+        for path in [self.agent_path(key), self.agent_lock_path(key)] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).context("Failed to delete agent state"),
+            }
         }
+        Ok(())
     }
 
     /// Load global settings.
@@ -143,6 +187,79 @@ impl StateStore {
         write_atomic(&path, content.as_bytes())
     }
 
+    // ── Done-stack management ───────────────────────────────────────────────
+
+    /// Atomically read-modify-write the done-stack for a backend instance.
+    ///
+    /// The stack holds panes with their completion time, most recently
+    /// completed last. An exclusive
+    /// lock on a sidecar file serializes concurrent updates from agents
+    /// finishing at the same time, so no push is lost. The `pane_id` of `key`
+    /// is ignored; only backend and instance select the stack.
+    pub fn update_done_stack<F>(&self, key: &PaneKey, update: F) -> Result<Vec<DoneEntry>>
+    where
+        F: FnOnce(&mut Vec<DoneEntry>),
+    {
+        let dir = self.done_dir();
+        fs::create_dir_all(&dir).context("Failed to create done-stack directory")?;
+        let path = dir.join(key.instance_filename());
+
+        let lock_file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("lock"))
+            .context("Failed to open done-stack lock file")?;
+        let _lock = Flock::lock(lock_file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| errno)
+            .context("Failed to lock done-stack")?;
+
+        let mut stack = read_done_stack(&path)?;
+        update(&mut stack);
+        let content = serde_json::to_string(&stack)?;
+        write_atomic(&path, content.as_bytes())?;
+        Ok(stack)
+    }
+
+    /// Push a pane onto its instance's done-stack, moving it to the top if present.
+    ///
+    /// The entry is stamped with the current time for `last_done_window`.
+    pub fn push_done_pane(&self, key: &PaneKey) -> Result<()> {
+        let done_ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.update_done_stack(key, |stack| {
+            stack.retain(|entry| entry.pane_id != key.pane_id);
+            stack.push(DoneEntry {
+                pane_id: key.pane_id.clone(),
+                done_ts,
+            });
+        })?;
+        Ok(())
+    }
+
+    /// Pop the most recently completed pane from the instance's done-stack.
+    #[allow(dead_code)] // Used in tests, may be used in future features
+    pub fn pop_done_pane(&self, key: &PaneKey) -> Result<Option<String>> {
+        let mut popped = None;
+        self.update_done_stack(key, |stack| popped = stack.pop().map(|entry| entry.pane_id))?;
+        Ok(popped)
+    }
+
+    /// Remove a pane from its instance's done-stack (e.g. when it resumes work).
+    pub fn remove_done_pane(&self, key: &PaneKey) -> Result<()> {
+        self.update_done_stack(key, |stack| {
+            stack.retain(|entry| entry.pane_id != key.pane_id)
+        })?;
+        Ok(())
+    }
+
+    /// Read the done-stack entries with their completion times, most recent last.
+    pub fn done_entries(&self, key: &PaneKey) -> Result<Vec<DoneEntry>> {
+        read_done_stack(&self.done_dir().join(key.instance_filename()))
+    }
+
     // ── Container state management ──────────────────────────────────────────
 
     /// Register a running container for a worktree handle.
@@ -195,12 +312,23 @@ impl StateStore {
         &self,
         mux: &dyn crate::multiplexer::Multiplexer,
     ) -> Result<Vec<crate::multiplexer::AgentPane>> {
+        Ok(self.reconcile_agents(mux)?.kept)
+    }
+
+    /// Reconcile stored agents against live multiplexer state, removing the
+    /// state files of dead agents. Agents of other backends/instances are
+    /// left alone.
+    pub fn reconcile_agents(
+        &self,
+        mux: &dyn crate::multiplexer::Multiplexer,
+    ) -> Result<Reconciled> {
         let all_agents = self.list_all_agents()?;
 
         // Fetch all live pane info in a single batched query
         let live_panes = mux.get_all_live_pane_info()?;
 
         let mut valid_agents = Vec::new();
+        let mut pruned = Vec::new();
         let backend = mux.name();
         let instance = mux.instance_id();
 
@@ -217,7 +345,7 @@ impl StateStore {
             match live_pane {
                 None => {
                     // Pane not in batched result - use backend-specific validation
-                    if mux.validate_agent_alive(&state)? {
+                    if mux.validate_agent_alive(&state, Some(&live_panes))? {
                         let agent_pane = state.to_agent_pane(
                             state.session_name.clone().unwrap_or_default(),
                             state.window_name.clone().unwrap_or_default(),
@@ -227,10 +355,14 @@ impl StateStore {
                         info!(pane_id, "reconcile: removing agent, pane no longer exists");
                         self.delete_agent(&state.pane_key)?;
                         let _ = mux.clear_status(&state.pane_key.pane_id);
+                        pruned.push(state);
                     }
                 }
-                Some(live) if live.pid.is_some_and(|pid| pid != state.pane_pid) => {
-                    // PID mismatch - pane ID was recycled by a new process
+                Some(live)
+                    if state.pane_pid != 0 && live.pid.is_some_and(|pid| pid != state.pane_pid) =>
+                {
+                    // PID mismatch - pane ID was recycled by a new process.
+                    // A stored PID of 0 means it was unknown at registration.
                     info!(
                         pane_id,
                         stored_pid = state.pane_pid,
@@ -239,6 +371,7 @@ impl StateStore {
                     );
                     self.delete_agent(&state.pane_key)?;
                     let _ = mux.clear_status(&state.pane_key.pane_id);
+                    pruned.push(state);
                 }
                 Some(live)
                     if live
@@ -255,6 +388,7 @@ impl StateStore {
                     );
                     self.delete_agent(&state.pane_key)?;
                     let _ = mux.clear_status(&state.pane_key.pane_id);
+                    pruned.push(state);
                 }
                 Some(live) => {
                     // Valid - include in dashboard
@@ -271,10 +405,21 @@ impl StateStore {
             }
         }
 
-        Ok(valid_agents)
+        Ok(Reconciled {
+            kept: valid_agents,
+            pruned,
+        })
     }
 }
 
+/// Outcome of [`StateStore::reconcile_agents`].
+pub struct Reconciled {
+    /// Agents that are still alive
+    pub kept: Vec<crate::multiplexer::AgentPane>,
+    /// Agents whose state was removed
+    pub pruned: Vec<AgentState>,
+}
+
 /// Write content atomically using temp file + rename.
 ///
 /// This ensures the target file is never partially written.
@@ -285,6 +430,18 @@ fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Read a done-stack file, treating a missing or corrupted file as empty.
+fn read_done_stack(path: &Path) -> Result<Vec<DoneEntry>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(?path, error = %e, "corrupted done-stack, resetting");
+            Vec::new()
+        })),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).context("Failed to read done-stack"),
+    }
+}
+
 /// Get the XDG state directory.
 ///
 /// Checks XDG_STATE_HOME first, falls back to ~/.local/state.
@@ -323,6 +480,7 @@ fn read_agent_file(path: &Path) -> Result<Option<AgentState>> {
 mod tests {
     use super::*;
     use crate::multiplexer::AgentStatus;
+    use crate::state::types::HEARTBEAT_FRESH_INTERVALS;
     use tempfile::TempDir;
 
     fn test_store() -> (StateStore, TempDir) {
@@ -342,15 +500,14 @@ mod tests {
     fn test_agent_state(key: PaneKey) -> AgentState {
         AgentState {
             pane_key: key,
-            workdir: PathBuf::from("/home/user/project"),
             status: Some(AgentStatus::Working),
             status_ts: Some(1234567890),
             pane_title: Some("Implementing feature X".to_string()),
             pane_pid: 12345,
             command: "node".to_string(),
             updated_ts: 1234567890,
-            window_name: Some("wm-test".to_string()),
             session_name: Some("main".to_string()),
+            ..AgentState::for_test("%1", "/home/user/project", Some("wm-test"))
         }
     }
 
@@ -369,6 +526,28 @@ mod tests {
         assert_eq!(retrieved.pane_pid, state.pane_pid);
     }
 
+    #[test]
+    fn test_record_heartbeat() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+
+        // No stored agent yet: nothing to update
+        assert!(!store.record_heartbeat(&key, 1000).unwrap());
+        assert!(store.get_agent(&key).unwrap().is_none());
+
+        store.upsert_agent(&test_agent_state(key.clone())).unwrap();
+        assert!(store.record_heartbeat(&key, 1000).unwrap());
+
+        let retrieved = store.get_agent(&key).unwrap().unwrap();
+        assert_eq!(retrieved.last_heartbeat, Some(1000));
+        let max_age = 30 * HEARTBEAT_FRESH_INTERVALS;
+        assert!(retrieved.has_fresh_heartbeat(1000 + max_age, 30));
+        assert!(!retrieved.has_fresh_heartbeat(1001 + max_age, 30));
+        assert!(retrieved.has_fresh_heartbeat(1000 + max_age, 60));
+        // Other fields are untouched
+        assert_eq!(retrieved.status, Some(AgentStatus::Working));
+    }
+
     #[test]
     fn test_get_nonexistent_agent() {
         let (store, _dir) = test_store();
@@ -407,10 +586,13 @@ mod tests {
         let state = test_agent_state(key.clone());
 
         store.upsert_agent(&state).unwrap();
+        assert!(store.record_heartbeat(&key, 1).unwrap());
         assert!(store.get_agent(&key).unwrap().is_some());
+        assert!(store.agent_lock_path(&key).exists());
 
         store.delete_agent(&key).unwrap();
         assert!(store.get_agent(&key).unwrap().is_none());
+        assert!(!store.agent_lock_path(&key).exists());
     }
 
     #[test]
@@ -513,4 +695,121 @@ mod tests {
         let agents = store.list_all_agents().unwrap();
         assert_eq!(agents.len(), 1);
     }
+
+    /// Pane IDs on the done-stack, most recent last.
+    fn done_stack(store: &StateStore, key: &PaneKey) -> Vec<String> {
+        store
+            .done_entries(key)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.pane_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_done_stack_push_pop_order() {
+        let (store, _dir) = test_store();
+        let mut key = test_pane_key();
+
+        for pane in ["%1", "%2", "%3"] {
+            key.pane_id = pane.to_string();
+            store.push_done_pane(&key).unwrap();
+        }
+        // Re-pushing moves the pane to the top
+        key.pane_id = "%1".to_string();
+        store.push_done_pane(&key).unwrap();
+
+        assert_eq!(done_stack(&store, &key), vec!["%2", "%3", "%1"]);
+        assert_eq!(store.pop_done_pane(&key).unwrap().as_deref(), Some("%1"));
+        assert_eq!(done_stack(&store, &key), vec!["%2", "%3"]);
+    }
+
+    #[test]
+    fn test_done_stack_remove() {
+        let (store, _dir) = test_store();
+        let mut key = test_pane_key();
+        store.push_done_pane(&key).unwrap();
+        key.pane_id = "%2".to_string();
+        store.push_done_pane(&key).unwrap();
+
+        key.pane_id = "%1".to_string();
+        store.remove_done_pane(&key).unwrap();
+        assert_eq!(done_stack(&store, &key), vec!["%2"]);
+    }
+
+    #[test]
+    fn test_done_stack_keyed_by_instance() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+        let other = PaneKey {
+            instance: "/tmp/tmux-1000/other".to_string(),
+            ..test_pane_key()
+        };
+
+        store.push_done_pane(&key).unwrap();
+        assert_eq!(done_stack(&store, &key), vec!["%1"]);
+        assert!(done_stack(&store, &other).is_empty());
+        assert_eq!(store.pop_done_pane(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_done_stack_concurrent_pushes_not_lost() {
+        let dir = TempDir::new().unwrap();
+        let threads = 16;
+
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let path = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    // Separate store per thread, like separate processes
+                    let store = StateStore::with_path(path).unwrap();
+                    let key = PaneKey {
+                        pane_id: format!("%{}", i),
+                        ..test_pane_key()
+                    };
+                    store.push_done_pane(&key).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let store = StateStore::with_path(dir.path().to_path_buf()).unwrap();
+        let mut stack = done_stack(&store, &test_pane_key());
+        assert_eq!(stack.len(), threads);
+        stack.sort();
+        stack.dedup();
+        assert_eq!(stack.len(), threads);
+    }
+
+    #[test]
+    fn test_corrupted_done_stack_resets() {
+        let (store, dir) = test_store();
+        let key = test_pane_key();
+        let done_dir = dir.path().join("done");
+        fs::create_dir_all(&done_dir).unwrap();
+        fs::write(done_dir.join(key.instance_filename()), "not json").unwrap();
+
+        assert!(done_stack(&store, &key).is_empty());
+        store.push_done_pane(&key).unwrap();
+        assert_eq!(done_stack(&store, &key), vec!["%1"]);
+    }
+
+    #[test]
+    fn test_done_stack_records_completion_time() {
+        let (store, _dir) = test_store();
+        let mut key = test_pane_key();
+        key.pane_id = "%1".to_string();
+        store.push_done_pane(&key).unwrap();
+        key.pane_id = "%2".to_string();
+        store.push_done_pane(&key).unwrap();
+
+        let entries = store.done_entries(&key).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].pane_id, "%1");
+        assert_eq!(entries[1].pane_id, "%2");
+        assert!(entries[0].done_ts > 0);
+        assert!(entries[1].done_ts >= entries[0].done_ts);
+    }
 }
@@ -42,6 +42,14 @@ impl PaneKey {
         format!("{}__{}__{}.json", self.backend, safe_instance, safe_pane_id)
     }
 
+    /// Generate filename for state shared by all panes of this pane's instance.
+    ///
+    /// Format: `{backend}__{instance}.json`
+    pub fn instance_filename(&self) -> String {
+        let safe_instance = utf8_percent_encode(&self.instance, FILENAME_ENCODE_SET).to_string();
+        format!("{}__{}.json", self.backend, safe_instance)
+    }
+
     /// Parse a PaneKey from a filename.
     ///
     /// Returns None if the filename doesn't match the expected format.
@@ -108,9 +116,50 @@ pub struct AgentState {
     /// Stored here for consistency with window_name.
     #[serde(default)]
     pub session_name: Option<String>,
+
+    /// Unix timestamp of the last heartbeat from a sandboxed agent.
+    /// Sent periodically over RPC while the guest agent runs.
+    #[serde(default)]
+    pub last_heartbeat: Option<u64>,
+}
+
+#[cfg(test)]
+impl AgentState {
+    /// An idle tmux agent running claude in `workdir`, for tests.
+    pub(crate) fn for_test(pane_id: &str, workdir: &str, window: Option<&str>) -> Self {
+        AgentState {
+            pane_key: PaneKey {
+                backend: "tmux".to_string(),
+                instance: "default".to_string(),
+                pane_id: pane_id.to_string(),
+            },
+            workdir: std::path::PathBuf::from(workdir),
+            status: None,
+            status_ts: None,
+            pane_title: None,
+            pane_pid: 1,
+            command: "claude".to_string(),
+            updated_ts: 0,
+            window_name: window.map(str::to_string),
+            session_name: None,
+            last_heartbeat: None,
+        }
+    }
 }
 
+/// Heartbeats older than this many intervals are ignored by liveness checks,
+/// so a late or dropped heartbeat doesn't mark a running agent dead.
+pub const HEARTBEAT_FRESH_INTERVALS: u64 = 3;
+
 impl AgentState {
+    /// Whether a heartbeat arrived within [`HEARTBEAT_FRESH_INTERVALS`] of
+    /// `now`, for heartbeats sent every `interval_secs`.
+    pub fn has_fresh_heartbeat(&self, now: u64, interval_secs: u64) -> bool {
+        let max_age = interval_secs.saturating_mul(HEARTBEAT_FRESH_INTERVALS);
+        self.last_heartbeat
+            .is_some_and(|ts| now.saturating_sub(ts) <= max_age)
+    }
+
     /// Convert to AgentPane for dashboard display.
     ///
     /// The caller is responsible for providing the best available session/window names
@@ -124,10 +173,19 @@ impl AgentState {
             pane_title: self.pane_title.clone(),
             status: self.status,
             status_ts: self.status_ts,
+            command: self.command.clone(),
         }
     }
 }
 
+/// A pane on the done-stack and when it completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoneEntry {
+    pub pane_id: String,
+    /// Unix timestamp when the pane was pushed onto the stack
+    pub done_ts: u64,
+}
+
 /// Dashboard preferences stored globally.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GlobalSettings {
@@ -0,0 +1,350 @@
+//! Shared interactive prompts.
+
+use anyhow::{Context, Result, bail};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Ask a yes/no question on the terminal.
+///
+/// Returns true without prompting when `assume_yes` is set (`--yes`). Without
+/// a TTY there is nobody to answer, so it errors instead of guessing.
+/// `default_no` selects the answer for an empty reply and the `[y/N]` hint.
+pub fn confirm(prompt: &str, default_no: bool, assume_yes: bool) -> Result<bool> {
+    let stdin = io::stdin();
+    let is_tty = stdin.is_terminal();
+    confirm_with(
+        &mut stdin.lock(),
+        &mut io::stdout(),
+        prompt,
+        default_no,
+        assume_yes,
+        is_tty,
+    )
+}
+
+/// Show a numbered list and let the user pick any number of entries.
+///
+/// Accepts numbers, ranges like `1-3`, comma or space separated lists, or
+/// `all`. Returns the chosen zero-based indices in list order; an empty
+/// answer selects nothing. `prompt` is shown above the `> ` input line.
+pub fn select_many(labels: &[String], prompt: &str) -> Result<Vec<usize>> {
+    let stdin = io::stdin();
+    select_many_with(&mut stdin.lock(), &mut io::stdout(), labels, prompt)
+}
+
+/// Testable core of [`select_many`].
+fn select_many_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    labels: &[String],
+    prompt: &str,
+) -> Result<Vec<usize>> {
+    for (idx, label) in labels.iter().enumerate() {
+        writeln!(output, "  {}. {}", idx + 1, label)?;
+    }
+    writeln!(output)?;
+    writeln!(output, "{}", prompt)?;
+    write!(output, "> ")?;
+    output.flush().context("Failed to flush stdout")?;
+
+    let mut answer = String::new();
+    input
+        .read_line(&mut answer)
+        .context("Failed to read input")?;
+    parse_selection(&answer, labels.len())
+}
+
+/// Show a numbered list and let the user pick one entry, by number or by
+/// typing part of its label.
+///
+/// A query keeps the labels that contain its characters in order (a
+/// case-insensitive subsequence match); when several remain the narrowed list
+/// is shown again. An empty answer or EOF cancels and returns None.
+pub fn pick(labels: &[String], prompt: &str) -> Result<Option<usize>> {
+    let stdin = io::stdin();
+    pick_with(&mut stdin.lock(), &mut io::stdout(), labels, prompt)
+}
+
+/// Testable core of [`pick`].
+fn pick_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    labels: &[String],
+    prompt: &str,
+) -> Result<Option<usize>> {
+    let mut candidates: Vec<usize> = (0..labels.len()).collect();
+    loop {
+        for (n, &idx) in candidates.iter().enumerate() {
+            writeln!(output, "  {}. {}", n + 1, labels[idx])?;
+        }
+        writeln!(output)?;
+        writeln!(output, "{}", prompt)?;
+        write!(output, "> ")?;
+        output.flush().context("Failed to flush stdout")?;
+
+        let mut answer = String::new();
+        input
+            .read_line(&mut answer)
+            .context("Failed to read input")?;
+        let query = answer.trim();
+        if query.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(n) = query.parse::<usize>()
+            && (1..=candidates.len()).contains(&n)
+        {
+            return Ok(Some(candidates[n - 1]));
+        }
+
+        let matches: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&idx| fuzzy_match(query, &labels[idx]))
+            .collect();
+        match matches.len() {
+            0 => writeln!(output, "No match for '{}'\n", query)?,
+            1 => return Ok(Some(matches[0])),
+            _ => {
+                writeln!(output)?;
+                candidates = matches;
+            }
+        }
+    }
+}
+
+/// Whether the characters of `query` appear in `text` in order, ignoring case.
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text.any(|t| t == q))
+}
+
+/// Parse a selection like "1", "2-4", "1, 3 5" or "all" against `len` items.
+fn parse_selection(input: &str, len: usize) -> Result<Vec<usize>> {
+    let invalid = |token: &str| {
+        anyhow::anyhow!(
+            "Invalid selection '{}'. Enter numbers between 1 and {}, ranges like 1-3, or 'all'.",
+            token,
+            len
+        )
+    };
+
+    let mut selected = vec![false; len];
+    for token in input
+        .split([',', ' ', '\t', '\n'])
+        .filter(|t| !t.is_empty())
+    {
+        if token.eq_ignore_ascii_case("all") {
+            selected.fill(true);
+            continue;
+        }
+        let (start, end) = match token.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (token, token),
+        };
+        let start: usize = start.parse().map_err(|_| invalid(token))?;
+        let end: usize = end.parse().map_err(|_| invalid(token))?;
+        if start < 1 || end > len || start > end {
+            return Err(invalid(token));
+        }
+        selected[start - 1..end].fill(true);
+    }
+
+    Ok(selected
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &chosen)| chosen.then_some(idx))
+        .collect())
+}
+
+/// Testable core of [`confirm`].
+fn confirm_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+    default_no: bool,
+    assume_yes: bool,
+    is_tty: bool,
+) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !is_tty {
+        bail!("Confirmation required but stdin is not a terminal. Use --yes to skip it.");
+    }
+
+    let hint = if default_no { "[y/N]" } else { "[Y/n]" };
+    write!(output, "{} {} ", prompt, hint)?;
+    output.flush().context("Failed to flush stdout")?;
+
+    let mut answer = String::new();
+    input
+        .read_line(&mut answer)
+        .context("Failed to read input")?;
+
+    Ok(match answer.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => !default_no,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ask(input: &str, default_no: bool, assume_yes: bool, is_tty: bool) -> Result<bool> {
+        let mut input = Cursor::new(input.as_bytes().to_vec());
+        let mut output = Vec::new();
+        confirm_with(
+            &mut input,
+            &mut output,
+            "Continue?",
+            default_no,
+            assume_yes,
+            is_tty,
+        )
+    }
+
+    #[test]
+    fn assume_yes_skips_prompt() {
+        // Input is never read, with or without a TTY
+        assert!(ask("n\n", true, true, true).unwrap());
+        assert!(ask("", true, true, false).unwrap());
+    }
+
+    #[test]
+    fn non_tty_without_yes_errors() {
+        let err = ask("y\n", true, false, false).unwrap_err();
+        assert!(err.to_string().contains("--yes"));
+        assert!(ask("y\n", false, false, false).is_err());
+    }
+
+    #[test]
+    fn explicit_answers() {
+        for input in ["y\n", "Y\n", "yes\n", " YES \n"] {
+            assert!(ask(input, true, false, true).unwrap(), "{input:?}");
+        }
+        for input in ["n\n", "N\n", "no\n"] {
+            assert!(!ask(input, false, false, true).unwrap(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn empty_or_unknown_answer_uses_default() {
+        assert!(!ask("\n", true, false, true).unwrap());
+        assert!(!ask("maybe\n", true, false, true).unwrap());
+        assert!(ask("\n", false, false, true).unwrap());
+        // EOF counts as an empty answer
+        assert!(!ask("", true, false, true).unwrap());
+    }
+
+    #[test]
+    fn parse_selection_numbers_ranges_and_all() {
+        assert_eq!(parse_selection("2\n", 3).unwrap(), vec![1]);
+        assert_eq!(parse_selection("1-3", 5).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_selection("5, 1 3-4", 5).unwrap(), vec![0, 2, 3, 4]);
+        assert_eq!(parse_selection("2,2,1-2", 3).unwrap(), vec![0, 1]);
+        assert_eq!(parse_selection("ALL", 3).unwrap(), vec![0, 1, 2]);
+        assert!(parse_selection("  \n", 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_selection_rejects_out_of_range() {
+        for input in ["0", "4", "3-1", "2-4", "x", "1-", "-2"] {
+            let err = parse_selection(input, 3).unwrap_err();
+            assert!(err.to_string().contains("between 1 and 3"), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn select_many_lists_labels_and_reads_answer() {
+        let mut output = Vec::new();
+        let labels = vec!["first".to_string(), "second".to_string()];
+        let picked = select_many_with(
+            &mut Cursor::new(b"2\n".to_vec()),
+            &mut output,
+            &labels,
+            "Pick:",
+        )
+        .unwrap();
+        assert_eq!(picked, vec![1]);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "  1. first\n  2. second\n\nPick:\n> "
+        );
+    }
+
+    #[test]
+    fn prompt_shows_default_hint() {
+        let mut output = Vec::new();
+        confirm_with(
+            &mut Cursor::new(b"\n".to_vec()),
+            &mut output,
+            "Go?",
+            true,
+            false,
+            true,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "Go? [y/N] ");
+
+        let mut output = Vec::new();
+        confirm_with(
+            &mut Cursor::new(b"\n".to_vec()),
+            &mut output,
+            "Go?",
+            false,
+            false,
+            true,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "Go? [Y/n] ");
+    }
+
+    fn pick_from(input: &str, labels: &[&str]) -> (Option<usize>, String) {
+        let labels: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        let mut output = Vec::new();
+        let picked = pick_with(
+            &mut Cursor::new(input.as_bytes().to_vec()),
+            &mut output,
+            &labels,
+            "Pick:",
+        )
+        .unwrap();
+        (picked, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn fuzzy_match_is_ordered_subsequence() {
+        assert!(fuzzy_match("fa", "wm-feature-auth"));
+        assert!(fuzzy_match("FA", "wm-feature-auth"));
+        assert!(fuzzy_match("", "anything"));
+        assert!(!fuzzy_match("af", "fa"));
+        assert!(!fuzzy_match("xyz", "wm-feature"));
+    }
+
+    #[test]
+    fn pick_by_number_or_unique_query() {
+        let labels = ["wm-auth", "wm-billing", "wm-search"];
+        assert_eq!(pick_from("2\n", &labels).0, Some(1));
+        assert_eq!(pick_from("srch\n", &labels).0, Some(2));
+        assert_eq!(pick_from("\n", &labels).0, None);
+        assert_eq!(pick_from("", &labels).0, None);
+    }
+
+    #[test]
+    fn pick_narrows_ambiguous_query() {
+        let labels = ["wm-auth", "wm-billing", "wm-auth-2"];
+        // "auth" keeps two entries, renumbered; "2" then picks the second of them
+        let (picked, output) = pick_from("auth\n2\n", &labels);
+        assert_eq!(picked, Some(2));
+        assert!(output.contains("  2. wm-auth-2"));
+        let (picked, output) = pick_from("zzz\n1\n", &labels);
+        assert_eq!(picked, Some(0));
+        assert!(output.contains("No match for 'zzz'"));
+    }
+}
@@ -6,6 +6,38 @@ pub fn canon_or_self(p: &Path) -> PathBuf {
     p.canonicalize().unwrap_or_else(|_| p.to_path_buf())
 }
 
+/// Longest readable part of a [`path_file_stem`], well under NAME_MAX.
+const PATH_STEM_MAX: usize = 64;
+
+/// A file name stem for `path`: its last component for readability, then a
+/// hash of the full path so paths with the same last component don't clash.
+pub fn path_file_stem(path: &Path) -> String {
+    let stem: String = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(PATH_STEM_MAX)
+        .collect();
+    format!("{}-{:016x}", stem, fnv1a(path.to_string_lossy().bytes()))
+}
+
+/// 64-bit FNV-1a hash. Unlike `DefaultHasher`, it is stable across workmux
+/// builds, so names and tags derived from it survive upgrades and match
+/// between host and guest binaries.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
 /// Format a duration as a human-readable elapsed time string.
 /// Used by `status` and `wait` commands.
 pub fn format_elapsed_secs(secs: u64) -> String {
@@ -41,6 +73,33 @@ pub fn format_elapsed_duration(d: Duration) -> String {
     }
 }
 
+/// Parse an age like "30m", "12h", "7d", or "2w". A bare number is days.
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (expected e.g. 12h, 7d, 2w)", s))?;
+    let secs_per_unit = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{}' (use s, m, h, d, or w)",
+                s
+            ));
+        }
+    };
+    value
+        .checked_mul(secs_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{}' is too large", s))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +152,17 @@ mod tests {
         assert_eq!(format_elapsed_duration(Duration::from_secs(3661)), "1h 01m");
         assert_eq!(format_elapsed_duration(Duration::from_secs(7260)), "2h 01m");
     }
+
+    #[test]
+    fn parse_age_units() {
+        const DAY: u64 = 24 * 60 * 60;
+        assert_eq!(parse_age("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(parse_age("7d"), Ok(Duration::from_secs(7 * DAY)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * DAY)));
+        assert_eq!(parse_age("3"), Ok(Duration::from_secs(3 * DAY)));
+        assert!(parse_age("7y").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("99999999999999999w").is_err());
+    }
 }
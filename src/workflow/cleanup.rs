@@ -5,7 +5,7 @@ use std::time::SystemTime;
 use std::{thread, time::Duration};
 
 use crate::config::MuxMode;
-use crate::multiplexer::{Multiplexer, util::prefixed};
+use crate::multiplexer::Multiplexer;
 use crate::shell::shell_quote;
 use crate::{cmd, git};
 use tracing::{debug, info, warn};
@@ -42,11 +42,10 @@ fn remove_dir_contents(path: &Path) {
 }
 
 /// Find all windows matching the base handle pattern (including duplicates).
-/// Matches: {prefix}{handle} and {prefix}{handle}-{N}
-fn find_matching_windows(mux: &dyn Multiplexer, prefix: &str, handle: &str) -> Result<Vec<String>> {
+/// Matches: {base_name} and {base_name}-{N}
+fn find_matching_windows(mux: &dyn Multiplexer, base_name: &str) -> Result<Vec<String>> {
     let all_windows = mux.get_all_window_names()?;
-    let base_name = prefixed(prefix, handle);
-    let escaped_base = regex::escape(&base_name);
+    let escaped_base = regex::escape(base_name);
     let pattern = format!(r"^{}(-\d+)?$", escaped_base);
     let re = Regex::new(&pattern).expect("Invalid regex pattern");
 
@@ -58,8 +57,7 @@ fn find_matching_windows(mux: &dyn Multiplexer, prefix: &str, handle: &str) -> R
 /// Check if the current window/session matches the base handle pattern (including duplicates).
 fn is_inside_matching_target(
     mux: &dyn Multiplexer,
-    prefix: &str,
-    handle: &str,
+    base_name: &str,
     mode: MuxMode,
 ) -> Result<Option<String>> {
     let current_name = if mode == MuxMode::Session {
@@ -73,8 +71,7 @@ fn is_inside_matching_target(
         None => return Ok(None),
     };
 
-    let base_name = prefixed(prefix, handle);
-    let escaped_base = regex::escape(&base_name);
+    let escaped_base = regex::escape(base_name);
     let pattern = format!(r"^{}(-\d+)?$", escaped_base);
     let re = Regex::new(&pattern).expect("Invalid regex pattern");
 
@@ -117,10 +114,11 @@ pub fn cleanup(
     context.chdir_to_main_worktree()?;
 
     let mux_running = context.mux.is_running().unwrap_or(false);
+    let full_name = context.full_name(handle);
 
     // Check if we're running inside ANY matching target (original or duplicate)
     let current_matching_target = if mux_running {
-        is_inside_matching_target(context.mux.as_ref(), &context.prefix, handle, mode)?
+        is_inside_matching_target(context.mux.as_ref(), &full_name, mode)?
     } else {
         None
     };
@@ -301,8 +299,7 @@ pub fn cleanup(
         // Find and kill all OTHER matching windows (not the current one)
         // Note: Sessions don't have duplicates like windows, so skip for session mode
         if mux_running && !is_session_mode {
-            let matching_windows =
-                find_matching_windows(context.mux.as_ref(), &context.prefix, handle)?;
+            let matching_windows = find_matching_windows(context.mux.as_ref(), &full_name)?;
             let mut killed_count = 0;
             for window in &matching_windows {
                 if window != &current_target {
@@ -414,9 +411,9 @@ pub fn cleanup(
         if mux_running {
             if is_session_mode {
                 // For session mode, kill the session directly
-                let session_name = prefixed(&context.prefix, handle);
-                if context.mux.session_exists(&session_name)? {
-                    if let Err(e) = context.mux.kill_session(&session_name) {
+                let session_name = full_name.as_str();
+                if context.mux.session_exists(session_name)? {
+                    if let Err(e) = context.mux.kill_session(session_name) {
                         warn!(session = session_name, error = %e, "cleanup:failed to kill session");
                     } else {
                         result.tmux_window_killed = true;
@@ -426,7 +423,7 @@ pub fn cleanup(
                         const MAX_RETRIES: u32 = 20;
                         const RETRY_DELAY: Duration = Duration::from_millis(50);
                         for _ in 0..MAX_RETRIES {
-                            if !context.mux.session_exists(&session_name)? {
+                            if !context.mux.session_exists(session_name)? {
                                 break;
                             }
                             thread::sleep(RETRY_DELAY);
@@ -435,8 +432,7 @@ pub fn cleanup(
                 }
             } else {
                 // For window mode, find and kill all matching windows (including duplicates)
-                let matching_windows =
-                    find_matching_windows(context.mux.as_ref(), &context.prefix, handle)?;
+                let matching_windows = find_matching_windows(context.mux.as_ref(), &full_name)?;
                 let mut killed_count = 0;
                 for window in &matching_windows {
                     if let Err(e) = context.mux.kill_window(window) {
@@ -458,8 +454,7 @@ pub fn cleanup(
                     const MAX_RETRIES: u32 = 20;
                     const RETRY_DELAY: Duration = Duration::from_millis(50);
                     for _ in 0..MAX_RETRIES {
-                        let remaining =
-                            find_matching_windows(context.mux.as_ref(), &context.prefix, handle)?;
+                        let remaining = find_matching_windows(context.mux.as_ref(), &full_name)?;
                         if remaining.is_empty() {
                             break;
                         }
@@ -530,8 +525,7 @@ fn build_deferred_cleanup_script(dc: &DeferredCleanup) -> String {
 /// `target_window_name` is the window name of the merge target.
 /// `source_handle` is the window name of the branch being merged/removed.
 pub fn navigate_to_target_and_close(
-    mux: &dyn Multiplexer,
-    prefix: &str,
+    context: &WorkflowContext,
     target_window_name: &str,
     source_handle: &str,
     cleanup_result: &CleanupResult,
@@ -539,10 +533,13 @@ pub fn navigate_to_target_and_close(
 ) -> Result<()> {
     use crate::multiplexer::MuxHandle;
 
+    let mux = context.mux.as_ref();
+    let prefix = context.prefix.as_str();
+
     // Check if target window/session exists (probe both modes since target
     // may be a different mode than source, e.g. session worktree -> window main)
     let mux_running = mux.is_running()?;
-    let target_full = prefixed(prefix, target_window_name);
+    let target_full = context.full_name(target_window_name);
     let (target_exists, target_mode) = if mux_running {
         let is_session = mux.session_exists(&target_full).unwrap_or(false);
         let is_window = mux
@@ -562,11 +559,11 @@ pub fn navigate_to_target_and_close(
 
     // Prepare window names for shell commands
     // Use the actual window name from window_to_close_later when available (includes -N suffix),
-    // otherwise fall back to the base prefixed name
+    // otherwise fall back to the base window name
     let source_full = cleanup_result
         .window_to_close_later
         .clone()
-        .unwrap_or_else(|| prefixed(prefix, source_handle));
+        .unwrap_or_else(|| context.full_name(source_handle));
 
     // Generate backend-specific shell commands for deferred scripts.
     // Kill uses source mode, select uses target's detected mode.
@@ -703,7 +700,7 @@ pub fn navigate_to_target_and_close(
     } else if !cleanup_result.tmux_window_killed {
         // Running outside and targets weren't killed yet (shouldn't happen normally)
         // but handle it for completeness
-        let target = MuxHandle::new(mux, target_mode, prefix, target_window_name);
+        let target = MuxHandle::new(mux, target_mode, target_full);
         target.select()?;
         info!(
             handle = source_handle,
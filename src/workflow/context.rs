@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::multiplexer::Multiplexer;
+use crate::multiplexer::util::WindowNames;
 use crate::{config, git};
 use tracing::debug;
 
@@ -10,11 +11,14 @@ use tracing::debug;
 ///
 /// This struct centralizes pre-flight checks and holds essential data
 /// needed by workflow modules, reducing code duplication.
+#[derive(Clone)]
 pub struct WorkflowContext {
     pub main_worktree_root: PathBuf,
     pub git_common_dir: PathBuf,
     pub main_branch: String,
     pub prefix: String,
+    /// Stored window/session names that differ from `{prefix}{handle}`
+    pub window_names: WindowNames,
     pub config: config::Config,
     pub mux: Arc<dyn Multiplexer>,
     /// Relative path from repo root to config directory.
@@ -53,6 +57,7 @@ impl WorkflowContext {
         };
 
         let prefix = config.window_prefix().to_string();
+        let window_names = WindowNames::load();
 
         let (config_rel_dir, config_source_dir) = match config_location {
             Some(loc) => (loc.rel_dir, loc.config_dir),
@@ -75,6 +80,7 @@ impl WorkflowContext {
             git_common_dir,
             main_branch,
             prefix,
+            window_names,
             config,
             mux,
             config_rel_dir,
@@ -82,6 +88,11 @@ impl WorkflowContext {
         })
     }
 
+    /// Full window/session name for a worktree handle.
+    pub fn full_name(&self, handle: &str) -> String {
+        self.window_names.full_name(&self.prefix, handle)
+    }
+
     /// Ensure the terminal multiplexer is running, returning an error if not
     ///
     /// Call this at the start of workflows that require a multiplexer.
@@ -2,7 +2,9 @@ use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 
 use crate::config::MuxMode;
+use crate::git::MetaValue;
 use crate::multiplexer::MuxHandle;
+use crate::multiplexer::util::{self, WindowNames};
 use crate::{git, spinner};
 use tracing::{debug, info, warn};
 
@@ -76,17 +78,32 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
     context.ensure_mux_running()?;
 
     // Validate backend supports session mode before creating any git state
-    if options.mode == MuxMode::Session && context.mux.name() != "tmux" {
+    if options.mode == MuxMode::Session && !matches!(context.mux.name(), "tmux" | "zellij") {
         return Err(anyhow!(
-            "Session mode (--session) is only supported with tmux.\n\
+            "Session mode (--session) is only supported with tmux and zellij.\n\
              Current backend: {}. Use window mode instead.",
             context.mux.name()
         ));
     }
 
+    // Render a templated window name up front so the existence checks below
+    // use it. A name already stored for this handle wins.
+    let mut full_target_name = match (
+        &context.config.window_name_template,
+        context.window_names.get(handle),
+    ) {
+        (Some(template), None) => crate::naming::render_window_name(
+            Some(template),
+            &context.prefix,
+            handle,
+            branch_name,
+            options.pr_number,
+        ),
+        _ => context.full_name(handle),
+    };
+
     // Check if worktree or target (window/session) already exists
-    let target = MuxHandle::new(context.mux.as_ref(), options.mode, &context.prefix, handle);
-    let full_target_name = target.full_name();
+    let target = MuxHandle::new(context.mux.as_ref(), options.mode, &full_target_name);
     let target_exists = target.exists()?;
     let worktree_exists = git::worktree_exists(branch_name)?;
 
@@ -113,13 +130,33 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
             config_root: options.config_root.clone(),
             open_if_exists: false,
             mode: options.mode,
+            isolation: options.isolation.clone(),
+            pr_number: options.pr_number,
+            sandbox_image: options.sandbox_image.clone(),
+            start_dir: options.start_dir.clone(),
+            setup_script: options.setup_script.clone(),
+            rerun_setup: options.rerun_setup,
+            window_name: None,
         };
 
+        if let Some(isolation) = &options.isolation {
+            git::set_worktree_isolation(handle, isolation).with_context(|| {
+                format!("Failed to store isolation level for worktree '{}'", handle)
+            })?;
+        }
+        if let Some(image) = &options.sandbox_image {
+            git::set_worktree_sandbox_image(handle, image).with_context(|| {
+                format!("Failed to store sandbox image for worktree '{}'", handle)
+            })?;
+        }
+
         return super::open::open(branch_name, context, open_options, false);
     }
 
-    // Check target using handle (the display name)
-    if target_exists {
+    // A live window with this name (a same-named worktree in another repo,
+    // or a templated name) gets a unique suffix below. Sessions can't be
+    // told apart on cleanup, so a taken session name is an error.
+    if target_exists && target.is_session() {
         return Err(anyhow!(
             "A {} {} named '{}' already exists",
             context.mux.name(),
@@ -137,6 +174,20 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         ));
     }
 
+    // A window already uses this name: pick a free suffixed name so later
+    // lookups for this handle don't hit the other window
+    let deduplicated = target_exists;
+    if deduplicated {
+        let unique = util::unique_name(&context.mux.get_all_window_names()?, &full_target_name);
+        info!(
+            handle = handle,
+            taken = full_target_name,
+            window = unique,
+            "create:window name taken, using unique name"
+        );
+        full_target_name = unique;
+    }
+
     // Auto-detect: create branch if it doesn't exist
     let branch_exists = git::branch_exists(branch_name)?;
     if branch_exists && remote_branch.is_some() {
@@ -303,7 +354,7 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
     // Store the tmux mode in git config for cleanup operations
     // This allows remove/close/merge to know whether to kill a window or session
     if options.mode == MuxMode::Session {
-        git::set_worktree_meta(handle, "mode", "session")
+        git::set_worktree_meta_value(handle, &MuxMode::Session)
             .with_context(|| format!("Failed to store tmux mode for worktree '{}'", handle))?;
         debug!(
             handle = handle,
@@ -312,6 +363,53 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         );
     }
 
+    // Store an --isolation override so later sandbox runs resolve the same Lima VM
+    if let Some(isolation) = &options.isolation {
+        git::set_worktree_isolation(handle, isolation).with_context(|| {
+            format!("Failed to store isolation level for worktree '{}'", handle)
+        })?;
+        debug!(
+            handle = handle,
+            isolation = %isolation.as_meta_value(),
+            "create:stored isolation level in git config"
+        );
+    }
+
+    // Store an --image override so later sandbox runs use the same image
+    if let Some(image) = &options.sandbox_image {
+        git::set_worktree_sandbox_image(handle, image)
+            .with_context(|| format!("Failed to store sandbox image for worktree '{}'", handle))?;
+        debug!(
+            handle = handle,
+            image = image.as_str(),
+            "create:stored sandbox image in git config"
+        );
+    }
+
+    // Store an --agent override so the status watcher matches its patterns
+    if let Some(agent) = agent {
+        git::set_worktree_agent(handle, agent)
+            .with_context(|| format!("Failed to store agent for worktree '{}'", handle))?;
+    }
+
+    // Store the PR number so `rename` can render `{pr}` again
+    if let Some(pr) = options.pr_number {
+        git::set_worktree_pr(handle, pr)
+            .with_context(|| format!("Failed to store PR number for worktree '{}'", handle))?;
+    }
+
+    // Store a templated or deduplicated window name so lookups and cleanup in
+    // later invocations resolve the same name
+    if context.config.window_name_template.is_some() || deduplicated {
+        git::set_worktree_meta(handle, "window", &full_target_name)
+            .with_context(|| format!("Failed to store window name for worktree '{}'", handle))?;
+        debug!(
+            handle = handle,
+            window = full_target_name,
+            "create:stored window name in git config"
+        );
+    }
+
     // Setup the rest of the environment (tmux, files, hooks)
     let prompt_file_path = if let Some(p) = prompt {
         Some(setup::write_prompt_file(
@@ -352,6 +450,7 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         prompt_file_path,
         working_dir,
         config_root,
+        window_name: Some(full_target_name),
         ..options
     };
     let mut result = setup::setup_environment(
@@ -467,6 +566,12 @@ pub fn create_with_changes(
             // 5. Failure: Rollback
             warn!(error = %e, "create_with_changes: failed to apply stash, rolling back");
 
+            // The new worktree's window name was stored after the context was made
+            let context = &WorkflowContext {
+                window_names: WindowNames::load(),
+                ..context.clone()
+            };
+
             let cleanup_result = cleanup::cleanup(
                 context,
                 branch_name,
@@ -482,8 +587,7 @@ pub fn create_with_changes(
 
             // Handle window navigation/closing based on whether we're inside the source window
             cleanup::navigate_to_target_and_close(
-                context.mux.as_ref(),
-                &context.prefix,
+                context,
                 &context.main_branch,
                 handle,
                 &cleanup_result,
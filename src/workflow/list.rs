@@ -6,7 +6,7 @@ use crate::config::MuxMode;
 use crate::multiplexer::{Multiplexer, util};
 use crate::state::StateStore;
 use crate::util::canon_or_self;
-use crate::{config, git, github, spinner};
+use crate::{config, git, github, gitlab, spinner};
 
 use super::types::{AgentStatusSummary, WorktreeInfo};
 
@@ -54,7 +54,7 @@ fn filter_worktrees(
 pub fn list(
     config: &config::Config,
     mux: &dyn Multiplexer,
-    fetch_pr_status: bool,
+    pr_forge: Option<git::Forge>,
     filter: &[String],
 ) -> Result<Vec<WorktreeInfo>> {
     if !git::is_git_repo()? {
@@ -98,10 +98,17 @@ pub fn list(
         .and_then(|base| git::get_unmerged_branches(&base).ok())
         .unwrap_or_default(); // Use an empty set on failure
 
-    // Batch fetch all PRs if requested (single API call)
-    let pr_map = if fetch_pr_status {
+    // Batch fetch all PRs (or GitLab MRs) if requested (single API call)
+    let pr_map = if let Some(forge) = pr_forge {
         spinner::with_spinner("Fetching PR status", || {
-            Ok(github::list_prs().unwrap_or_default())
+            Ok(match forge {
+                git::Forge::GitHub => github::list_prs().unwrap_or_default(),
+                git::Forge::GitLab => gitlab::list_mrs()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(branch, mr)| (branch, mr.into()))
+                    .collect(),
+            })
         })?
     } else {
         std::collections::HashMap::new()
@@ -125,8 +132,10 @@ pub fn list(
 
     // Batch-load all worktree modes in a single git config call
     let worktree_modes = git::get_all_worktree_modes();
+    let mut worktree_sandboxes = git::get_all_worktree_sandboxes();
 
     let prefix = config.window_prefix();
+    let window_names = util::WindowNames::load();
     let worktrees: Vec<WorktreeInfo> = worktrees_data
         .into_iter()
         .map(|(path, branch)| {
@@ -138,7 +147,7 @@ pub fn list(
                 .to_string();
 
             // Check if mux target exists (window or session based on stored mode)
-            let prefixed_name = util::prefixed(prefix, &handle);
+            let prefixed_name = window_names.full_name(prefix, &handle);
             let mode = worktree_modes
                 .get(&handle)
                 .copied()
@@ -190,6 +199,7 @@ pub fn list(
                 has_unmerged,
                 pr_info,
                 agent_status,
+                sandbox: worktree_sandboxes.remove(&handle),
             }
         })
         .collect();
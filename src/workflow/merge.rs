@@ -330,8 +330,7 @@ pub fn merge(
 
     // Navigate to the target branch window/session and close the source
     cleanup::navigate_to_target_and_close(
-        context.mux.as_ref(),
-        &context.prefix,
+        context,
         &target_window_name,
         handle,
         &cleanup_result,
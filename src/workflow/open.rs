@@ -3,7 +3,6 @@ use regex::Regex;
 
 use crate::git;
 use crate::multiplexer::MuxHandle;
-use crate::multiplexer::util::prefixed;
 use tracing::info;
 
 use super::cleanup::get_worktree_mode;
@@ -67,8 +66,7 @@ pub fn open(
     let target = MuxHandle::new(
         context.mux.as_ref(),
         stored_mode,
-        &context.prefix,
-        &base_handle,
+        context.full_name(&base_handle),
     );
     let target_exists = target.exists()?;
 
@@ -88,6 +86,7 @@ pub fn open(
             post_create_hooks_run: 0,
             base_branch: None,
             did_switch: true,
+            window_name: target.full_name().to_string(),
         });
     }
 
@@ -104,7 +103,7 @@ pub fn open(
         // Insert after the last window in the base handle group (base or -N suffixes)
         let after = context
             .mux
-            .find_last_window_with_base_handle(&context.prefix, &base_handle)
+            .find_last_window_with_base_handle("", target.full_name())
             .unwrap_or(None);
         (unique_handle, after)
     } else {
@@ -134,6 +133,7 @@ pub fn open(
         working_dir,
         config_root,
         mode: stored_mode,
+        window_name: Some(context.full_name(&handle)),
         ..options
     };
 
@@ -169,8 +169,7 @@ pub fn open(
 /// Note: Only called in window mode (session mode rejects --new).
 fn resolve_unique_handle(context: &WorkflowContext, base_handle: &str) -> Result<String> {
     let all_names = context.mux.get_all_window_names()?;
-    let prefix = &context.prefix;
-    let full_base = prefixed(prefix, base_handle);
+    let full_base = context.full_name(base_handle);
 
     // If base name doesn't exist, use it directly
     if !all_names.contains(&full_base) {
@@ -194,7 +193,13 @@ fn resolve_unique_handle(context: &WorkflowContext, base_handle: &str) -> Result
         }
     }
 
-    let new_handle = format!("{}-{}", base_handle, max_suffix + 1);
+    // A suffixed name can already belong to a deduplicated window of another
+    // worktree, so skip past any that are taken
+    let mut suffix = max_suffix + 1;
+    while all_names.contains(&format!("{}-{}", full_base, suffix)) {
+        suffix += 1;
+    }
+    let new_handle = format!("{}-{}", base_handle, suffix);
 
     info!(
         base_handle = base_handle,
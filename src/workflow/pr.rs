@@ -35,6 +35,40 @@ impl RemoteDetectionContext for RealRemoteDetectionContext {
     }
 }
 
+/// Abstraction for the GitHub and git operations used to resolve a PR as a base
+trait PrBaseContext {
+    fn get_pr_details(&self, pr_number: u32) -> Result<github::PrDetails>;
+    fn get_repo_owner(&self) -> Result<String>;
+    fn ensure_fork_remote(&self, fork_owner: &str) -> Result<String>;
+    fn fetch_remote(&self, remote: &str) -> Result<()>;
+    fn branch_exists(&self, ref_name: &str) -> Result<bool>;
+}
+
+/// Real implementation using the github and git modules
+struct RealPrBaseContext;
+
+impl PrBaseContext for RealPrBaseContext {
+    fn get_pr_details(&self, pr_number: u32) -> Result<github::PrDetails> {
+        github::get_pr_details(pr_number)
+    }
+
+    fn get_repo_owner(&self) -> Result<String> {
+        git::get_repo_owner()
+    }
+
+    fn ensure_fork_remote(&self, fork_owner: &str) -> Result<String> {
+        git::ensure_fork_remote(fork_owner)
+    }
+
+    fn fetch_remote(&self, remote: &str) -> Result<()> {
+        git::fetch_remote(remote)
+    }
+
+    fn branch_exists(&self, ref_name: &str) -> Result<bool> {
+        git::branch_exists(ref_name)
+    }
+}
+
 /// Result of resolving a PR checkout.
 pub struct PrCheckoutResult {
     pub local_branch: String,
@@ -96,6 +130,64 @@ pub fn resolve_pr_ref(
     })
 }
 
+/// Resolve a PR number to its head branch for use as a base (`--base-pr`).
+///
+/// Fetches the PR's head branch (adding a fork remote if needed) and returns
+/// the remote ref, e.g. "origin/feature-a". The create workflow records it as
+/// the branch's workmux-base, so merge and remove checks compare against it.
+pub fn resolve_pr_base(pr_number: u32) -> Result<String> {
+    resolve_pr_base_internal(pr_number, &RealPrBaseContext)
+}
+
+/// Internal logic using the context trait for testability.
+fn resolve_pr_base_internal(pr_number: u32, ctx: &dyn PrBaseContext) -> Result<String> {
+    let pr_details = spinner::with_spinner(&format!("Fetching PR #{}", pr_number), || {
+        ctx.get_pr_details(pr_number)
+    })
+    .with_context(|| format!("PR #{} does not exist or is not accessible", pr_number))?;
+
+    println!(
+        "Base: PR #{} ({}): {}",
+        pr_number, pr_details.head_ref_name, pr_details.title
+    );
+    if pr_details.state != "OPEN" {
+        eprintln!(
+            "⚠️  Warning: PR #{} is {}. Stacking on it anyway...",
+            pr_number, pr_details.state
+        );
+    }
+
+    let current_repo_owner = ctx
+        .get_repo_owner()
+        .context("Failed to determine repository owner from origin remote")?;
+
+    let remote_name = if pr_details.is_fork(&current_repo_owner) {
+        ctx.ensure_fork_remote(&pr_details.head_repository_owner.login)?
+    } else {
+        "origin".to_string()
+    };
+
+    let remote_ref = format!("{}/{}", remote_name, pr_details.head_ref_name);
+    let full_ref = format!("refs/remotes/{}", remote_ref);
+
+    // Always fetch so the base reflects the PR's latest pushed commits
+    spinner::with_spinner(&format!("Fetching from '{}'", remote_name), || {
+        ctx.fetch_remote(&remote_name)
+    })
+    .with_context(|| format!("Failed to fetch from remote '{}'", remote_name))?;
+
+    if !ctx.branch_exists(&full_ref)? {
+        return Err(anyhow!(
+            "Head branch '{}' of PR #{} was not found on remote '{}'",
+            pr_details.head_ref_name,
+            pr_number,
+            remote_name
+        ));
+    }
+
+    Ok(remote_ref)
+}
+
 /// Result of resolving a fork branch.
 pub struct ForkBranchResult {
     pub remote_ref: String,
@@ -496,4 +588,105 @@ mod tests {
         assert!(err.to_string().contains("Failed to fetch"));
         assert!(err.to_string().contains("origin"));
     }
+
+    // === resolve_pr_base ===
+
+    /// Mock PR provider and git state for --base-pr resolution
+    struct MockPrBaseContext {
+        prs: Vec<(u32, &'static str, &'static str)>,
+        repo_owner: &'static str,
+        remote_refs: HashSet<String>,
+        fetched: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl MockPrBaseContext {
+        /// `prs` entries are (number, head branch, head repository owner)
+        fn new(prs: &[(u32, &'static str, &'static str)], remote_refs: &[&str]) -> Self {
+            Self {
+                prs: prs.to_vec(),
+                repo_owner: "owner",
+                remote_refs: remote_refs.iter().map(|s| s.to_string()).collect(),
+                fetched: std::cell::RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl PrBaseContext for MockPrBaseContext {
+        fn get_pr_details(&self, pr_number: u32) -> Result<github::PrDetails> {
+            let (_, head, head_owner) = self
+                .prs
+                .iter()
+                .find(|(n, _, _)| *n == pr_number)
+                .ok_or_else(|| anyhow!("Could not resolve to a PullRequest"))?;
+            Ok(github::PrDetails {
+                head_ref_name: head.to_string(),
+                head_repository_owner: github::RepositoryOwner {
+                    login: head_owner.to_string(),
+                },
+                state: "OPEN".to_string(),
+                is_draft: false,
+                title: format!("PR {}", pr_number),
+                author: github::Author {
+                    login: head_owner.to_string(),
+                },
+            })
+        }
+
+        fn get_repo_owner(&self) -> Result<String> {
+            Ok(self.repo_owner.to_string())
+        }
+
+        fn ensure_fork_remote(&self, fork_owner: &str) -> Result<String> {
+            Ok(format!("fork-{}", fork_owner))
+        }
+
+        fn fetch_remote(&self, remote: &str) -> Result<()> {
+            self.fetched.borrow_mut().push(remote.to_string());
+            Ok(())
+        }
+
+        fn branch_exists(&self, ref_name: &str) -> Result<bool> {
+            Ok(self.remote_refs.contains(ref_name))
+        }
+    }
+
+    #[test]
+    fn test_base_pr_resolves_head_branch() {
+        let ctx = MockPrBaseContext::new(
+            &[(42, "feature-a", "owner")],
+            &["refs/remotes/origin/feature-a"],
+        );
+        let base = resolve_pr_base_internal(42, &ctx).unwrap();
+        assert_eq!(base, "origin/feature-a");
+        assert_eq!(*ctx.fetched.borrow(), vec!["origin".to_string()]);
+    }
+
+    #[test]
+    fn test_base_pr_from_fork_uses_fork_remote() {
+        let ctx = MockPrBaseContext::new(
+            &[(7, "fix-bug", "contributor")],
+            &["refs/remotes/fork-contributor/fix-bug"],
+        );
+        let base = resolve_pr_base_internal(7, &ctx).unwrap();
+        assert_eq!(base, "fork-contributor/fix-bug");
+        assert_eq!(*ctx.fetched.borrow(), vec!["fork-contributor".to_string()]);
+    }
+
+    #[test]
+    fn test_base_pr_missing_pr_errors() {
+        let ctx = MockPrBaseContext::new(&[(42, "feature-a", "owner")], &[]);
+        let err = resolve_pr_base_internal(99, &ctx).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("PR #99 does not exist or is not accessible")
+        );
+        assert!(ctx.fetched.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_base_pr_head_branch_missing_on_remote_errors() {
+        let ctx = MockPrBaseContext::new(&[(42, "deleted-branch", "owner")], &[]);
+        let err = resolve_pr_base_internal(42, &ctx).unwrap_err();
+        assert!(err.to_string().contains("'deleted-branch' of PR #42"));
+    }
 }
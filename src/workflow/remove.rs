@@ -83,11 +83,12 @@ pub fn remove(
     // Note: Unmerged branch check removed - git branch -d/D handles this natively
     // The CLI provides a user-friendly confirmation prompt before calling this function
 
-    // Stop any running containers for this worktree before killing the window.
+    // Tear down the sandbox before killing the window.
     // This is necessary because tmux kill-window sends SIGHUP which doesn't allow
-    // the supervisor's Drop handler to run. We try unconditionally since sandbox
-    // may have been enabled via --sandbox flag even if disabled in config.
-    sandbox::stop_containers_for_handle(actual_handle, &context.config.sandbox);
+    // the supervisor's Drop handler to run. Gated on the recorded sandbox usage
+    // rather than config, since sandbox may have been enabled via --sandbox flag.
+    sandbox::teardown_for_handle(actual_handle, &context.config);
+    sandbox::delete_worktree_vm(actual_handle, &context.config);
 
     info!(branch = %branch_name, keep_branch, "remove:cleanup start");
     let cleanup_result = cleanup::cleanup(
@@ -102,8 +103,7 @@ pub fn remove(
 
     // Navigate to the main branch window/session and close the source
     cleanup::navigate_to_target_and_close(
-        context.mux.as_ref(),
-        &context.prefix,
+        context,
         &context.main_branch,
         actual_handle,
         &cleanup_result,
@@ -8,7 +8,7 @@ use crate::multiplexer::{
     PaneSetupOptions,
 };
 use crate::{cmd, config, git, prompt::Prompt};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use fs_extra::dir as fs_dir;
 use fs_extra::file as fs_file;
@@ -47,6 +47,10 @@ pub fn setup_environment(
         "setup_environment:start"
     );
     let prefix = config.window_prefix();
+    let full_name = options
+        .window_name
+        .clone()
+        .unwrap_or_else(|| crate::multiplexer::util::prefixed(prefix, handle));
     // Use main worktree root for file operations since source files live there
     let repo_root = git::get_main_worktree_root()?;
 
@@ -114,6 +118,14 @@ pub fn setup_environment(
         );
     }
 
+    // Panes start in the `--cwd` subdirectory when given. Resolved after hooks
+    // so post_create can still create it.
+    let pane_working_dir = match &options.start_dir {
+        Some(subdir) => crate::multiplexer::util::resolve_start_dir(worktree_path, subdir)?,
+        None => effective_working_dir.to_path_buf(),
+    };
+    let pane_working_dir = pane_working_dir.as_path();
+
     // Build window plans: normalize windows/panes config into a list of window configs.
     // In window mode, we always use a single window from panes config.
     // In session mode, we can use multiple windows from windows config.
@@ -146,17 +158,45 @@ pub fn setup_environment(
         mux,
         config,
         &all_resolved_panes,
-        effective_working_dir,
+        pane_working_dir,
         worktree_path,
         options,
         agent,
     )?;
 
-    let pane_setup_options = PaneSetupOptions {
+    // Record sandbox usage for this launch so close/remove only tear down
+    // containers when they apply, and list can show a sandbox indicator.
+    let sandbox_record = if config.sandbox.is_enabled() {
+        git::SandboxRecord::On(config.sandbox.backend())
+    } else {
+        git::SandboxRecord::Off
+    };
+    if let Err(e) = git::set_worktree_sandbox(handle, &sandbox_record) {
+        warn!(handle, error = %e, "setup_environment:failed to record sandbox usage");
+    }
+    // Pin the Lima isolation level so later runs and teardown resolve the same VM
+    if sandbox_record == git::SandboxRecord::On(config::SandboxBackend::Lima)
+        && git::get_worktree_isolation(handle).is_none()
+        && let Err(e) = git::set_worktree_isolation(handle, &config.sandbox.lima.isolation())
+    {
+        warn!(handle, error = %e, "setup_environment:failed to record isolation level");
+    }
+
+    // The setup script runs once per worktree, unless forced with --rerun-setup
+    let setup_script = options
+        .setup_script
+        .as_deref()
+        .or(config.setup_script.as_deref())
+        .filter(|script| !script.trim().is_empty())
+        .filter(|_| options.rerun_setup || !git::is_worktree_setup_done(handle));
+
+    let mut pane_setup_options = PaneSetupOptions {
         run_commands: options.run_pane_commands,
         prompt_file_path: options.prompt_file_path.as_deref(),
         worktree_root: Some(worktree_path),
         lima_vm_name: lima_vm_name.as_deref(),
+        setup_script,
+        handle,
     };
 
     // Track the focus pane across all windows
@@ -173,10 +213,11 @@ pub fn setup_environment(
 
             let initial_pane_id = mux
                 .create_window(CreateWindowParams {
-                    prefix,
-                    name: handle,
-                    cwd: effective_working_dir,
+                    prefix: "",
+                    name: &full_name,
+                    cwd: pane_working_dir,
                     after_window: last_wm_window.as_deref(),
+                    panes: &resolved_panes,
                 })
                 .context("Failed to create window")?;
             info!(
@@ -190,7 +231,7 @@ pub fn setup_environment(
                 .setup_panes(
                     &initial_pane_id,
                     &resolved_panes,
-                    effective_working_dir,
+                    pane_working_dir,
                     pane_setup_options,
                     config,
                     agent,
@@ -200,8 +241,6 @@ pub fn setup_environment(
             focus_pane_id = Some(result.focus_pane_id);
         }
         MuxMode::Session => {
-            let session_full_name = crate::multiplexer::util::prefixed(prefix, handle);
-
             for (i, window_plan) in window_plans.iter().enumerate() {
                 let panes = window_plan.panes.as_deref().unwrap_or(&[]);
                 let resolved_panes = resolve_pane_configuration(panes, agent);
@@ -210,9 +249,9 @@ pub fn setup_environment(
                     // First window: create the session
                     let pane_id = mux
                         .create_session(CreateSessionParams {
-                            prefix,
-                            name: handle,
-                            cwd: effective_working_dir,
+                            prefix: "",
+                            name: &full_name,
+                            cwd: pane_working_dir,
                             initial_window_name: window_plan.name.as_deref(),
                         })
                         .context("Failed to create session")?;
@@ -228,9 +267,9 @@ pub fn setup_environment(
                     // Subsequent windows: create within the existing session
                     let pane_id = mux
                         .create_window_in_session(CreateWindowInSessionParams {
-                            session_name: &session_full_name,
+                            session_name: &full_name,
                             name: window_plan.name.as_deref(),
-                            cwd: effective_working_dir,
+                            cwd: pane_working_dir,
                         })
                         .context("Failed to create window in session")?;
                     info!(
@@ -248,13 +287,18 @@ pub fn setup_environment(
                     .setup_panes(
                         &initial_pane_id,
                         &resolved_panes,
-                        effective_working_dir,
+                        pane_working_dir,
                         pane_setup_options.clone(),
                         config,
                         agent,
                     )
                     .context("Failed to setup panes")?;
 
+                // Only the first window with a command pane runs the setup script
+                if result.setup_started {
+                    pane_setup_options.setup_script = None;
+                }
+
                 // Track focus: last window with a focus: true pane wins.
                 // If no pane has focus: true, use the first window's default.
                 let has_explicit_focus = resolved_panes.iter().any(|p| p.focus);
@@ -278,10 +322,10 @@ pub fn setup_environment(
         mux.select_pane(&focus_pane_id)?;
         match options.mode {
             MuxMode::Window => {
-                mux.select_window(prefix, handle)?;
+                mux.select_window("", &full_name)?;
             }
             MuxMode::Session => {
-                mux.switch_to_session(prefix, handle)?;
+                mux.switch_to_session("", &full_name)?;
             }
         }
     }
@@ -292,6 +336,7 @@ pub fn setup_environment(
         post_create_hooks_run: hooks_run,
         base_branch: None,
         did_switch: false,
+        window_name: full_name,
     })
 }
 
@@ -391,6 +436,7 @@ pub fn resolve_pane_configuration(
         size: None,
         percentage: None,
         target: None,
+        cwd: None,
     }]
 }
 
@@ -664,6 +710,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
 
         let result = resolve_pane_configuration(&original_panes, None);
@@ -680,6 +727,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
 
         let result = resolve_pane_configuration(&original_panes, Some("claude"));
@@ -697,6 +745,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             config::PaneConfig {
                 command: Some("npm run dev".to_string()),
@@ -705,6 +754,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
         ];
 
@@ -722,6 +772,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
 
         let result = resolve_pane_configuration(&original_panes, Some("claude"));
@@ -756,6 +807,13 @@ mod tests {
             config_root: None,
             open_if_exists: false,
             mode: crate::config::MuxMode::default(),
+            isolation: None,
+            sandbox_image: None,
+            start_dir: None,
+            pr_number: None,
+            setup_script: None,
+            rerun_setup: false,
+            window_name: None,
         }
     }
 
@@ -768,6 +826,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(false); // pane commands disabled
@@ -791,6 +850,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(None); // no agent
         let options = make_options_with_prompt(true);
@@ -815,6 +875,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             config::PaneConfig {
                 command: Some("clear".to_string()),
@@ -823,6 +884,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
         ];
         let config = make_config_with_agent(Some("claude"));
@@ -844,6 +906,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(true);
@@ -861,6 +924,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(true);
@@ -878,6 +942,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(Some("claude")); // config says claude
         let options = make_options_with_prompt(true);
@@ -902,6 +967,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             config::PaneConfig {
                 command: Some("claude --verbose".to_string()), // matches
@@ -910,6 +976,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
         ];
         let config = make_config_with_agent(Some("claude"));
@@ -928,6 +995,7 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            cwd: None,
         }];
         let config = make_config_with_agent(None); // no global agent
         let options = make_options_with_prompt(true);
@@ -947,6 +1015,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
             config::PaneConfig {
                 command: Some("codex --yolo".to_string()),
@@ -955,6 +1024,7 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                cwd: None,
             },
         ];
 
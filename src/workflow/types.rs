@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
-use crate::config::MuxMode;
+use crate::config::{IsolationLevel, MuxMode};
+use crate::git::SandboxRecord;
 use crate::github::PrSummary;
 use crate::multiplexer::AgentStatus;
 use crate::prompt::Prompt;
@@ -24,6 +25,8 @@ pub struct CreateResult {
     pub base_branch: Option<String>,
     /// True if we switched to an existing window instead of creating a new one
     pub did_switch: bool,
+    /// Full name of the window or session
+    pub window_name: String,
 }
 
 /// Result of merging a worktree
@@ -81,6 +84,20 @@ pub struct SetupOptions {
     pub open_if_exists: bool,
     /// Mode for tmux operations: window (default) or session
     pub mode: MuxMode,
+    /// Lima isolation level from `add --isolation`, recorded in worktree metadata
+    pub isolation: Option<IsolationLevel>,
+    /// PR number from `add --pr`, for the `{pr}` window name placeholder
+    pub pr_number: Option<u32>,
+    /// Sandbox image from `add --image`, recorded in worktree metadata
+    pub sandbox_image: Option<String>,
+    /// Pane start directory from `add --cwd`, relative to the worktree root
+    pub start_dir: Option<PathBuf>,
+    /// Setup script from `--setup`, overriding `setup_script` in config
+    pub setup_script: Option<String>,
+    /// Run the setup script even if it already ran for this worktree
+    pub rerun_setup: bool,
+    /// Full window or session name. If None, uses `{prefix}{handle}`.
+    pub window_name: Option<String>,
 }
 
 impl SetupOptions {
@@ -97,6 +114,13 @@ impl SetupOptions {
             config_root: None,
             open_if_exists: false,
             mode: MuxMode::default(),
+            isolation: None,
+            pr_number: None,
+            sandbox_image: None,
+            start_dir: None,
+            setup_script: None,
+            rerun_setup: false,
+            window_name: None,
         }
     }
 
@@ -112,6 +136,13 @@ impl SetupOptions {
             config_root: None,
             open_if_exists: false,
             mode: MuxMode::default(),
+            isolation: None,
+            pr_number: None,
+            sandbox_image: None,
+            start_dir: None,
+            setup_script: None,
+            rerun_setup: false,
+            window_name: None,
         }
     }
 
@@ -133,6 +164,13 @@ impl SetupOptions {
             config_root: None,
             open_if_exists: false,
             mode: MuxMode::default(),
+            isolation: None,
+            pr_number: None,
+            sandbox_image: None,
+            start_dir: None,
+            setup_script: None,
+            rerun_setup: false,
+            window_name: None,
         }
     }
 }
@@ -150,4 +188,6 @@ pub struct WorktreeInfo {
     pub has_unmerged: bool,
     pub pr_info: Option<PrSummary>,
     pub agent_status: Option<AgentStatusSummary>,
+    /// Sandbox usage recorded at the last launch (None if never recorded)
+    pub sandbox: Option<SandboxRecord>,
 }